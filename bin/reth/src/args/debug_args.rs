@@ -57,6 +57,11 @@ pub struct DebugArgs {
         conflicts_with = "hook_transaction"
     )]
     pub hook_all: bool,
+
+    /// Persist blocks that failed validation or execution to disk, so they are still returned by
+    /// `debug_getBadBlocks` after a restart.
+    #[arg(long = "debug.persist-bad-blocks", help_heading = "Debug")]
+    pub persist_bad_blocks: bool,
 }
 
 #[cfg(test)]
@@ -10,8 +10,8 @@ use humantime::parse_duration;
 pub struct DevArgs {
     /// Start the node in dev mode
     ///
-    /// This mode uses a local proof-of-authority consensus engine with either fixed block times
-    /// or automatically mined blocks.
+    /// This mode uses a local proof-of-authority consensus engine with either fixed block times,
+    /// automatically mined blocks or blocks that are only mined on request.
     /// Disables network discovery and enables local http server.
     /// Prefunds 20 accounts derived by mnemonic "test test test test test test test test test test
     /// test junk" with 10 000 ETH each.
@@ -22,7 +22,7 @@ pub struct DevArgs {
     #[arg(
         long = "dev.block-max-transactions",
         help_heading = "Dev testnet",
-        conflicts_with = "block_time"
+        conflicts_with_all = ["block_time", "manual"]
     )]
     pub block_max_transactions: Option<usize>,
 
@@ -33,11 +33,20 @@ pub struct DevArgs {
     #[arg(
         long = "dev.block-time",
         help_heading = "Dev testnet",
-        conflicts_with = "block_max_transactions",
+        conflicts_with_all = ["block_max_transactions", "manual"],
         value_parser = parse_duration,
         verbatim_doc_comment
     )]
     pub block_time: Option<Duration>,
+
+    /// Seal a block as soon as the ready transactions use at least this much gas, even if the
+    /// block time has not elapsed yet.
+    #[arg(long = "dev.gas-threshold", help_heading = "Dev testnet", requires = "block_time")]
+    pub gas_threshold: Option<u64>,
+
+    /// Only mine blocks when requested via the `anvil` RPC namespace, e.g. `evm_mine`.
+    #[arg(long = "dev.manual", help_heading = "Dev testnet")]
+    pub manual: bool,
 }
 
 #[cfg(test)]
@@ -55,13 +64,13 @@ mod tests {
     #[test]
     fn test_parse_dev_args() {
         let args = CommandParser::<DevArgs>::parse_from(["reth"]).args;
-        assert_eq!(args, DevArgs { dev: false, block_max_transactions: None, block_time: None });
+        assert_eq!(args, DevArgs { dev: false, ..Default::default() });
 
         let args = CommandParser::<DevArgs>::parse_from(["reth", "--dev"]).args;
-        assert_eq!(args, DevArgs { dev: true, block_max_transactions: None, block_time: None });
+        assert_eq!(args, DevArgs { dev: true, ..Default::default() });
 
         let args = CommandParser::<DevArgs>::parse_from(["reth", "--auto-mine"]).args;
-        assert_eq!(args, DevArgs { dev: true, block_max_transactions: None, block_time: None });
+        assert_eq!(args, DevArgs { dev: true, ..Default::default() });
 
         let args = CommandParser::<DevArgs>::parse_from([
             "reth",
@@ -70,7 +79,10 @@ mod tests {
             "2",
         ])
         .args;
-        assert_eq!(args, DevArgs { dev: true, block_max_transactions: Some(2), block_time: None });
+        assert_eq!(
+            args,
+            DevArgs { dev: true, block_max_transactions: Some(2), ..Default::default() }
+        );
 
         let args =
             CommandParser::<DevArgs>::parse_from(["reth", "--dev", "--dev.block-time", "1s"]).args;
@@ -78,10 +90,32 @@ mod tests {
             args,
             DevArgs {
                 dev: true,
-                block_max_transactions: None,
-                block_time: Some(std::time::Duration::from_secs(1))
+                block_time: Some(std::time::Duration::from_secs(1)),
+                ..Default::default()
+            }
+        );
+
+        let args = CommandParser::<DevArgs>::parse_from([
+            "reth",
+            "--dev",
+            "--dev.block-time",
+            "12s",
+            "--dev.gas-threshold",
+            "15000000",
+        ])
+        .args;
+        assert_eq!(
+            args,
+            DevArgs {
+                dev: true,
+                block_time: Some(std::time::Duration::from_secs(12)),
+                gas_threshold: Some(15_000_000),
+                ..Default::default()
             }
         );
+
+        let args = CommandParser::<DevArgs>::parse_from(["reth", "--dev", "--dev.manual"]).args;
+        assert_eq!(args, DevArgs { dev: true, manual: true, ..Default::default() });
     }
 
     #[test]
@@ -95,6 +129,20 @@ mod tests {
             "1s",
         ]);
         assert!(args.is_err());
+
+        let args = CommandParser::<DevArgs>::try_parse_from([
+            "reth",
+            "--dev",
+            "--dev.manual",
+            "--dev.block-time",
+            "1s",
+        ]);
+        assert!(args.is_err());
+
+        // the gas threshold only applies to interval mining
+        let args =
+            CommandParser::<DevArgs>::try_parse_from(["reth", "--dev", "--dev.gas-threshold", "1"]);
+        assert!(args.is_err());
     }
 
     #[test]
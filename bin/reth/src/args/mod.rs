@@ -37,7 +37,7 @@ pub use gas_price_oracle_args::GasPriceOracleArgs;
 
 /// TxPoolArgs for configuring the transaction pool
 mod txpool_args;
-pub use txpool_args::TxPoolArgs;
+pub use txpool_args::{TxPoolArgs, TxPoolOrdering};
 
 /// DevArgs for configuring the dev testnet
 mod dev_args;
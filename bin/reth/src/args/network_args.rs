@@ -1,14 +1,27 @@
 //! clap [Args](clap::Args) for network related arguments.
 
-use crate::version::P2P_CLIENT_VERSION;
+use crate::{
+    args::{
+        get_secret_key,
+        utils::{fork_hash_value_parser, parse_nonzero_duration_from_secs},
+    },
+    version::P2P_CLIENT_VERSION,
+};
 use clap::Args;
 use reth_config::Config;
 use reth_discv4::{DEFAULT_DISCOVERY_ADDR, DEFAULT_DISCOVERY_PORT};
+use reth_discv5::{discv5::Enr, Discv5Config, DEFAULT_DISCOVERY_V5_PORT, DEFAULT_LOOKUP_INTERVAL};
+use reth_dns_discovery::{DnsPublishConfig, EnrFilter};
 use reth_net_nat::NatResolver;
 use reth_network::{HelloMessageWithProtocols, NetworkConfigBuilder};
-use reth_primitives::{mainnet_nodes, ChainSpec, NodeRecord};
+use reth_primitives::{mainnet_nodes, ChainSpec, ForkHash, NodeRecord};
 use secp256k1::SecretKey;
-use std::{net::Ipv4Addr, path::PathBuf, sync::Arc};
+use std::{
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
 
 /// Parameters for configuring the network more granularity via CLI
 #[derive(Debug, Args, PartialEq, Eq)]
@@ -74,6 +87,16 @@ pub struct NetworkArgs {
     /// Maximum number of inbound requests. default: 30
     #[arg(long)]
     pub max_inbound_peers: Option<usize>,
+
+    /// Publish the discovered nodes as a DNS tree.
+    #[command(flatten)]
+    pub dns_publish: DnsPublishArgs,
+
+    /// Serve the `snap/1` protocol to peers that sync the state.
+    ///
+    /// The state is served from the hashed state and trie tables of the last fully synced block.
+    #[arg(long = "enable-snap-server", verbatim_doc_comment)]
+    pub enable_snap_server: bool,
 }
 
 impl NetworkArgs {
@@ -141,6 +164,8 @@ impl Default for NetworkArgs {
             port: DEFAULT_DISCOVERY_PORT,
             max_outbound_peers: None,
             max_inbound_peers: None,
+            dns_publish: DnsPublishArgs::default(),
+            enable_snap_server: false,
         }
     }
 }
@@ -167,6 +192,33 @@ pub struct DiscoveryArgs {
     /// The UDP port to use for P2P discovery/networking
     #[arg(long = "discovery.port", name = "discovery.port", value_name = "DISCOVERY_PORT", default_value_t = DEFAULT_DISCOVERY_PORT)]
     pub port: u16,
+
+    /// Enable Discv5 discovery.
+    ///
+    /// Discv5 runs alongside discv4 on its own UDP port.
+    #[arg(long, conflicts_with = "disable_discovery")]
+    pub enable_discv5_discovery: bool,
+
+    /// The UDP address to use for devp2p peer discovery version 5.
+    #[arg(long = "discovery.v5.addr", name = "discovery.v5.addr", value_name = "DISCOVERY_V5_ADDR", default_value_t = DEFAULT_DISCOVERY_ADDR)]
+    pub discv5_addr: Ipv4Addr,
+
+    /// The UDP port to use for devp2p peer discovery version 5.
+    #[arg(long = "discovery.v5.port", name = "discovery.v5.port", value_name = "DISCOVERY_V5_PORT", default_value_t = DEFAULT_DISCOVERY_V5_PORT)]
+    pub discv5_port: u16,
+
+    /// The interval in seconds at which to look up random nodes over discv5.
+    #[arg(long = "discovery.v5.lookup-interval", name = "discovery.v5.lookup-interval", value_name = "SECONDS", value_parser = parse_nonzero_duration_from_secs, default_value = "20")]
+    pub discv5_lookup_interval: Duration,
+
+    /// Comma separated `enr:` records of the nodes to bootstrap discv5 with.
+    #[arg(
+        long = "discovery.v5.bootnodes",
+        name = "discovery.v5.bootnodes",
+        value_name = "ENRS",
+        value_delimiter = ','
+    )]
+    pub discv5_bootnodes: Vec<Enr>,
 }
 
 impl DiscoveryArgs {
@@ -182,6 +234,15 @@ impl DiscoveryArgs {
         if self.disable_discovery || self.disable_discv4_discovery {
             network_config_builder = network_config_builder.disable_discv4_discovery();
         }
+
+        if self.enable_discv5_discovery && !self.disable_discovery {
+            let mut builder = Discv5Config::builder();
+            builder
+                .listen_addr(SocketAddr::V4(SocketAddrV4::new(self.discv5_addr, self.discv5_port)))
+                .lookup_interval(self.discv5_lookup_interval)
+                .add_boot_nodes(self.discv5_bootnodes.iter().cloned());
+            network_config_builder = network_config_builder.discovery_v5(builder);
+        }
         network_config_builder
     }
 }
@@ -194,10 +255,103 @@ impl Default for DiscoveryArgs {
             disable_discv4_discovery: false,
             addr: DEFAULT_DISCOVERY_ADDR,
             port: DEFAULT_DISCOVERY_PORT,
+            enable_discv5_discovery: false,
+            discv5_addr: DEFAULT_DISCOVERY_ADDR,
+            discv5_port: DEFAULT_DISCOVERY_V5_PORT,
+            discv5_lookup_interval: DEFAULT_LOOKUP_INTERVAL,
+            discv5_bootnodes: Vec::new(),
         }
     }
 }
 
+/// Arguments to publish the discovered nodes as an [EIP-1459](https://eips.ethereum.org/EIPS/eip-1459) DNS tree
+#[derive(Debug, Args, PartialEq, Eq, Default)]
+pub struct DnsPublishArgs {
+    /// The domain the DNS tree of the discovered nodes is published under.
+    ///
+    /// The tree is written to the zone file given by `--dns.publish.zone-file`, and is only
+    /// rebuilt if discv4 discovery is enabled.
+    #[arg(
+        long = "dns.publish.domain",
+        name = "dns.publish.domain",
+        value_name = "DOMAIN",
+        requires = "dns.publish.zone-file"
+    )]
+    pub domain: Option<String>,
+
+    /// The path to the zone file the DNS tree is written to.
+    #[arg(
+        long = "dns.publish.zone-file",
+        name = "dns.publish.zone-file",
+        value_name = "FILE",
+        requires = "dns.publish.domain"
+    )]
+    pub zone_file: Option<PathBuf>,
+
+    /// Secret key to sign the DNS tree with.
+    ///
+    /// Defaults to the p2p secret key of the node.
+    #[arg(
+        long = "dns.publish.key",
+        name = "dns.publish.key",
+        value_name = "PATH",
+        requires = "dns.publish.domain"
+    )]
+    pub key: Option<PathBuf>,
+
+    /// The interval at which the DNS tree is rebuilt, in seconds.
+    #[arg(
+        long = "dns.publish.interval",
+        name = "dns.publish.interval",
+        value_name = "SECONDS",
+        value_parser = parse_nonzero_duration_from_secs,
+        requires = "dns.publish.domain"
+    )]
+    pub interval: Option<Duration>,
+
+    /// Only publish nodes that advertise this fork hash in their `eth` ENR entry, e.g.
+    /// `0xfc64ec04`.
+    #[arg(
+        long = "dns.publish.fork-hash",
+        name = "dns.publish.fork-hash",
+        value_name = "FORK_HASH",
+        value_parser = fork_hash_value_parser,
+        requires = "dns.publish.domain"
+    )]
+    pub fork_hash: Option<ForkHash>,
+
+    /// Only publish nodes that have these ENR keys, e.g. `snap`.
+    #[arg(
+        long = "dns.publish.enr-key",
+        name = "dns.publish.enr-key",
+        value_name = "KEY",
+        value_delimiter = ',',
+        requires = "dns.publish.domain"
+    )]
+    pub enr_keys: Vec<String>,
+}
+
+impl DnsPublishArgs {
+    /// Returns the [DnsPublishConfig] if publishing is enabled.
+    ///
+    /// The tree is signed with the key at `--dns.publish.key` or the given default key.
+    pub fn publish_config(&self, default_key: SecretKey) -> eyre::Result<Option<DnsPublishConfig>> {
+        let Some(domain) = &self.domain else { return Ok(None) };
+        let key = match &self.key {
+            Some(path) => get_secret_key(path)?,
+            None => default_key,
+        };
+
+        let mut config = DnsPublishConfig::new(domain.clone(), key);
+        if let Some(interval) = self.interval {
+            config.interval = interval;
+        }
+        config.filter =
+            EnrFilter { fork_hash: self.fork_hash, required_keys: self.enr_keys.clone() };
+        Ok(Some(config))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -257,6 +411,90 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_dns_publish_args() {
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--dns.publish.domain",
+            "nodes.example.org",
+            "--dns.publish.zone-file",
+            "nodes.zone",
+            "--dns.publish.interval",
+            "600",
+            "--dns.publish.fork-hash",
+            "0xfc64ec04",
+            "--dns.publish.enr-key",
+            "snap",
+        ])
+        .args;
+        assert_eq!(args.dns_publish.domain.as_deref(), Some("nodes.example.org"));
+        assert_eq!(args.dns_publish.interval, Some(Duration::from_secs(600)));
+        assert_eq!(args.dns_publish.fork_hash, Some(ForkHash([0xfc, 0x64, 0xec, 0x04])));
+        assert_eq!(args.dns_publish.enr_keys, vec!["snap".to_string()]);
+
+        // a zone file is required
+        assert!(CommandParser::<NetworkArgs>::try_parse_from([
+            "reth",
+            "--dns.publish.domain",
+            "nodes.example.org",
+        ])
+        .is_err());
+
+        // the tree is rebuilt at a non-zero interval
+        assert!(CommandParser::<NetworkArgs>::try_parse_from([
+            "reth",
+            "--dns.publish.domain",
+            "nodes.example.org",
+            "--dns.publish.zone-file",
+            "nodes.zone",
+            "--dns.publish.interval",
+            "0",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parse_discv5_args() {
+        let args = CommandParser::<NetworkArgs>::parse_from(["reth"]).args;
+        assert!(!args.discovery.enable_discv5_discovery);
+        assert_eq!(args.discovery.discv5_port, DEFAULT_DISCOVERY_V5_PORT);
+        assert_eq!(args.discovery.discv5_lookup_interval, DEFAULT_LOOKUP_INTERVAL);
+
+        let enr = "enr:-IS4QHCYrYZbAKWCBRlAy5zzaDZXJBGkcnh4MHcBFZntXNFrdvJjX04jRzjzCBOonrkTfj499SZuOh8R33Ls8RRcy5wBgmlkgnY0gmlwhH8AAAGJc2VjcDI1NmsxoQPKY0yuDUmstAHYpMa2_oxVtw0RW_QAdpzBQA8yWM0xOIN1ZHCCdl8";
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--enable-discv5-discovery",
+            "--discovery.v5.port",
+            "9200",
+            "--discovery.v5.lookup-interval",
+            "5",
+            "--discovery.v5.bootnodes",
+            enr,
+        ])
+        .args;
+        assert!(args.discovery.enable_discv5_discovery);
+        assert_eq!(args.discovery.discv5_port, 9200);
+        assert_eq!(args.discovery.discv5_lookup_interval, Duration::from_secs(5));
+        assert_eq!(args.discovery.discv5_bootnodes, vec![enr.parse::<Enr>().unwrap()]);
+
+        // lookups need a non-zero interval
+        assert!(CommandParser::<NetworkArgs>::try_parse_from([
+            "reth",
+            "--enable-discv5-discovery",
+            "--discovery.v5.lookup-interval",
+            "0",
+        ])
+        .is_err());
+
+        // discv5 can't be enabled if discovery is disabled
+        assert!(CommandParser::<NetworkArgs>::try_parse_from([
+            "reth",
+            "--disable-discovery",
+            "--enable-discv5-discovery",
+        ])
+        .is_err());
+    }
+
     #[test]
     fn network_args_default_sanity_test() {
         let default_args = NetworkArgs::default();
@@ -19,9 +19,12 @@ pub struct PayloadBuilderArgs {
     #[arg(long = "builder.extradata", value_parser=ExtradataValueParser::default(),  default_value_t = default_extradata())]
     pub extradata: String,
 
-    /// Target gas ceiling for built blocks.
+    /// Target gas limit for built blocks.
+    ///
+    /// Built blocks move the gas limit of their parent toward this target by at most 1/1024 of
+    /// the parent's gas limit.
     #[arg(long = "builder.gaslimit", default_value = "30000000", value_name = "GAS_LIMIT")]
-    pub max_gas_limit: u64,
+    pub desired_gas_limit: u64,
 
     /// The interval at which the job should build a new payload after the last (in seconds).
     #[arg(long = "builder.interval", value_parser = parse_duration_from_secs, default_value = "1", value_name = "SECONDS")]
@@ -52,7 +55,7 @@ impl Default for PayloadBuilderArgs {
     fn default() -> Self {
         Self {
             extradata: default_extradata(),
-            max_gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
+            desired_gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
             interval: Duration::from_secs(1),
             deadline: SLOT_DURATION,
             max_payload_tasks: 3,
@@ -75,8 +78,8 @@ impl PayloadBuilderConfig for PayloadBuilderArgs {
         self.deadline
     }
 
-    fn max_gas_limit(&self) -> u64 {
-        self.max_gas_limit
+    fn desired_gas_limit(&self) -> u64 {
+        self.desired_gas_limit
     }
 
     fn max_payload_tasks(&self) -> usize {
@@ -169,6 +172,17 @@ mod tests {
         assert!(args.is_err());
     }
 
+    #[test]
+    fn test_args_with_gas_limit() {
+        let args = CommandParser::<PayloadBuilderArgs>::parse_from([
+            "reth",
+            "--builder.gaslimit",
+            "36000000",
+        ])
+        .args;
+        assert_eq!(args.desired_gas_limit, 36_000_000);
+    }
+
     #[test]
     fn payload_builder_args_default_sanity_check() {
         let default_args = PayloadBuilderArgs::default();
@@ -17,13 +17,19 @@ use clap::{
     Arg, Args, Command,
 };
 use futures::TryFutureExt;
+use reth_auto_seal_consensus::AutoSealHandle;
 use reth_network_api::{NetworkInfo, Peers};
+use reth_primitives::fs;
 use reth_provider::{
     AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader,
-    EvmEnvProvider, HeaderProvider, StateProviderFactory,
+    EvmEnvProvider, HeaderProvider, HistoryReader, InvalidBlockReader, PlainStateReader,
+    StateProviderFactory,
 };
 use reth_rpc::{
-    eth::{cache::EthStateCacheConfig, gas_oracle::GasPriceOracleConfig, RPC_DEFAULT_GAS_CAP},
+    eth::{
+        cache::EthStateCacheConfig, gas_oracle::GasPriceOracleConfig, KeystoreSigner,
+        RPC_DEFAULT_GAS_CAP,
+    },
     JwtError, JwtSecret,
 };
 use reth_rpc_builder::{
@@ -41,7 +47,7 @@ use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
     path::PathBuf,
 };
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 /// Default max number of subscriptions per connection.
 pub(crate) const RPC_DEFAULT_MAX_SUBS_PER_CONN: u32 = 1024;
@@ -169,6 +175,35 @@ pub struct RpcServerArgs {
     )]
     pub rpc_gas_cap: u64,
 
+    /// Maximum number of blocks behind the tip for which `eth_getProof` is served.
+    #[arg(
+        long = "rpc.eth-proof-window",
+        value_name = "COUNT",
+        value_parser = RangedU64ValueParser::<u64>::new().range(..=constants::MAX_ETH_PROOF_WINDOW),
+        default_value_t = constants::DEFAULT_ETH_PROOF_WINDOW
+    )]
+    pub rpc_eth_proof_window: u64,
+
+    /// Directory of encrypted JSON keystore files whose accounts can be used by `eth_sign`,
+    /// `eth_signTransaction` and `eth_sendTransaction`.
+    ///
+    /// Anyone who can reach the `eth` namespace can sign with these accounts, so they are only
+    /// loaded if the HTTP and WS servers listen on a loopback address, see
+    /// `--allow-insecure-unlock`.
+    #[arg(long, value_name = "DIR", requires = "password")]
+    pub keystore: Option<PathBuf>,
+
+    /// File containing the password to decrypt the keystore files.
+    ///
+    /// Only the first line of the file is used.
+    #[arg(long, value_name = "FILE", requires = "keystore")]
+    pub password: Option<PathBuf>,
+
+    /// Allow loading the keystore accounts while the HTTP or WS server listens on a non-loopback
+    /// address.
+    #[arg(long = "allow-insecure-unlock", requires = "keystore")]
+    pub allow_insecure_unlock: bool,
+
     /// State cache configuration.
     #[clap(flatten)]
     pub rpc_state_cache: RpcStateCacheArgs,
@@ -184,12 +219,19 @@ impl RpcServerArgs {
     /// Returns the handles for the launched regular RPC server(s) (if any) and the server handle
     /// for the auth server that handles the `engine_` API that's accessed by the consensus
     /// layer.
+    ///
+    /// The `anvil` namespace is only available if a handle to the mining task of a dev chain is
+    /// provided.
+    ///
+    /// Profiles requested over the `debug` namespace are written to the given `profiles_dir`.
     #[allow(clippy::too_many_arguments)]
     pub async fn start_servers<Reth, Engine, Conf>(
         &self,
         components: &Reth,
         engine_api: Engine,
         jwt_secret: JwtSecret,
+        auto_seal: Option<AutoSealHandle>,
+        profiles_dir: PathBuf,
         conf: &mut Conf,
     ) -> eyre::Result<RethRpcServerHandles>
     where
@@ -200,15 +242,30 @@ impl RpcServerArgs {
         let auth_config = self.auth_server_config(jwt_secret)?;
 
         let module_config = self.transport_rpc_module_config();
+        let rpc_config = module_config
+            .config()
+            .cloned()
+            .unwrap_or_else(|| RpcModuleConfig::new(self.eth_config()))
+            .with_profiles_dir(profiles_dir);
+        let module_config = module_config.with_config(rpc_config);
         debug!(target: "reth::cli", http=?module_config.http(), ws=?module_config.ws(), "Using RPC module config");
 
-        let (mut modules, auth_module, mut registry) = RpcModuleBuilder::default()
+        let mut builder = RpcModuleBuilder::default()
             .with_provider(components.provider())
             .with_pool(components.pool())
             .with_network(components.network())
             .with_events(components.events())
-            .with_executor(components.task_executor())
-            .build_with_auth_server(module_config, engine_api);
+            .with_executor(components.task_executor());
+        if let Some(handle) = auto_seal {
+            builder = builder.with_auto_seal_handle(handle);
+        }
+        let (mut modules, auth_module, mut registry) =
+            builder.build_with_auth_server(module_config, engine_api);
+
+        if let Some(signer) = self.keystore_signer()? {
+            info!(target: "reth::cli", accounts=?signer.addresses(), "Loaded keystore accounts");
+            registry.eth_api().add_keystore_signer(signer);
+        }
 
         let rpc_components = RethRpcComponents { registry: &mut registry, modules: &mut modules };
         // apply configured customization
@@ -262,6 +319,9 @@ impl RpcServerArgs {
             + EvmEnvProvider
             + ChainSpecProvider
             + ChangeSetReader
+            + HistoryReader
+            + InvalidBlockReader
+            + PlainStateReader
             + Clone
             + Unpin
             + 'static,
@@ -318,6 +378,36 @@ impl RpcServerArgs {
         )
         .await
     }
+
+    /// Loads the accounts of the configured keystore, if any.
+    ///
+    /// Fails if the HTTP or WS server listens on a non-loopback address, unless
+    /// `--allow-insecure-unlock` is set.
+    fn keystore_signer(&self) -> eyre::Result<Option<KeystoreSigner>> {
+        let (Some(keystore), Some(password_file)) = (&self.keystore, &self.password) else {
+            return Ok(None)
+        };
+        self.ensure_keystore_unlock_allowed()?;
+
+        warn!(target: "reth::cli", ?keystore, "Keystore accounts are unlocked, anyone who can reach the eth namespace can sign with them");
+        let password = fs::read_to_string(password_file)?;
+        let password = password.lines().next().unwrap_or_default();
+        Ok(Some(KeystoreSigner::load(keystore, password)?))
+    }
+
+    /// Returns an error if the HTTP or WS server is exposed on a non-loopback address and
+    /// `--allow-insecure-unlock` is not set.
+    fn ensure_keystore_unlock_allowed(&self) -> eyre::Result<()> {
+        let exposed = (self.http && !self.http_addr.is_loopback()) ||
+            (self.ws && !self.ws_addr.is_loopback());
+        if exposed && !self.allow_insecure_unlock {
+            eyre::bail!(
+                "refusing to load keystore accounts while the HTTP or WS server listens on a \
+                 non-loopback address, use --allow-insecure-unlock to load them anyway"
+            )
+        }
+        Ok(())
+    }
 }
 
 impl RethRpcConfig for RpcServerArgs {
@@ -336,6 +426,7 @@ impl RethRpcConfig for RpcServerArgs {
             .max_blocks_per_filter(self.rpc_max_blocks_per_filter.unwrap_or_max())
             .max_logs_per_response(self.rpc_max_logs_per_response.unwrap_or_max() as usize)
             .rpc_gas_cap(self.rpc_gas_cap)
+            .eth_proof_window(self.rpc_eth_proof_window)
             .state_cache(self.state_cache_config())
             .gpo_config(self.gas_price_oracle_config())
     }
@@ -477,6 +568,10 @@ impl Default for RpcServerArgs {
             rpc_max_blocks_per_filter: constants::DEFAULT_MAX_BLOCKS_PER_FILTER.into(),
             rpc_max_logs_per_response: (constants::DEFAULT_MAX_LOGS_PER_RESPONSE as u64).into(),
             rpc_gas_cap: RPC_DEFAULT_GAS_CAP.into(),
+            rpc_eth_proof_window: constants::DEFAULT_ETH_PROOF_WINDOW,
+            keystore: None,
+            password: None,
+            allow_insecure_unlock: false,
             gas_price_oracle: GasPriceOracleArgs::default(),
             rpc_state_cache: RpcStateCacheArgs::default(),
         }
@@ -544,6 +639,67 @@ mod tests {
         assert!(args.is_err());
     }
 
+    #[test]
+    fn test_rpc_eth_proof_window() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        let config = args.eth_config();
+        assert_eq!(config.eth_proof_window, constants::DEFAULT_ETH_PROOF_WINDOW);
+
+        let args =
+            CommandParser::<RpcServerArgs>::parse_from(["reth", "--rpc.eth-proof-window", "1000"])
+                .args;
+        let config = args.eth_config();
+        assert_eq!(config.eth_proof_window, 1000);
+
+        let window = (constants::MAX_ETH_PROOF_WINDOW + 1).to_string();
+        let args = CommandParser::<RpcServerArgs>::try_parse_from([
+            "reth",
+            "--rpc.eth-proof-window",
+            window.as_str(),
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_rpc_keystore_args() {
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--keystore",
+            "keys",
+            "--password",
+            "password.txt",
+        ])
+        .args;
+        assert_eq!(args.keystore, Some(PathBuf::from("keys")));
+        assert_eq!(args.password, Some(PathBuf::from("password.txt")));
+
+        let err = CommandParser::<RpcServerArgs>::try_parse_from(["reth", "--keystore", "keys"])
+            .unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn test_rpc_keystore_requires_loopback() {
+        let keystore_args = ["--keystore", "keys", "--password", "password.txt"];
+        let parse = |args: &[&str]| {
+            CommandParser::<RpcServerArgs>::parse_from(
+                ["reth"].iter().chain(&keystore_args).chain(args),
+            )
+            .args
+        };
+
+        assert!(parse(&["--http", "--ws"]).ensure_keystore_unlock_allowed().is_ok());
+        assert!(parse(&["--http", "--http.addr", "0.0.0.0"])
+            .ensure_keystore_unlock_allowed()
+            .is_err());
+        assert!(parse(&["--ws", "--ws.addr", "0.0.0.0"]).ensure_keystore_unlock_allowed().is_err());
+        assert!(parse(&["--http", "--http.addr", "0.0.0.0", "--allow-insecure-unlock"])
+            .ensure_keystore_unlock_allowed()
+            .is_ok());
+        // a non-loopback address is fine if the server isn't enabled
+        assert!(parse(&["--http.addr", "0.0.0.0"]).ensure_keystore_unlock_allowed().is_ok());
+    }
+
     #[test]
     fn test_rpc_server_args_parser() {
         let args =
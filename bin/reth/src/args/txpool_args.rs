@@ -1,11 +1,14 @@
 //! Transaction pool arguments
 
-use clap::Args;
+use crate::args::utils::parse_nonzero_duration_from_secs;
+use clap::{Args, ValueEnum};
 use reth_transaction_pool::{
-    LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP,
-    REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+    maintain::{LocalTransactionBackupConfig, DEFAULT_LOCAL_TRANSACTIONS_BACKUP_INTERVAL},
+    LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, TransactionOrderingKind,
+    DEFAULT_PRICE_BUMP, REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
     TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT, TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
 };
+use std::{path::PathBuf, time::Duration};
 
 /// Parameters for debugging purposes
 #[derive(Debug, Args, PartialEq)]
@@ -43,9 +46,33 @@ pub struct TxPoolArgs {
     /// Price bump percentage to replace an already existing blob transaction
     #[arg(long = "blobpool.pricebump", default_value_t = REPLACE_BLOB_PRICE_BUMP)]
     pub blob_transaction_price_bump: u128,
+    /// The order in which pending transactions are returned for block building.
+    #[arg(long = "txpool.ordering", value_enum, default_value_t = TxPoolOrdering::CoinbaseTip)]
+    pub ordering: TxPoolOrdering,
+    /// The maximum tip per gas (in wei) that is taken into account by the `capped-tip` ordering.
+    #[arg(
+        long = "txpool.ordering.max-tip",
+        value_name = "WEI",
+        required_if_eq("ordering", "capped-tip")
+    )]
+    pub max_tip: Option<u128>,
+
     /// Flag to disable local transaction exemptions.
     #[arg(long = "txpool.nolocals")]
     pub no_locals: bool,
+
+    /// Path to the file local transactions are backed up to, so they survive restarts.
+    ///
+    /// Defaults to `txpool-transactions-backup.rlp` in the chain's data directory.
+    #[arg(long = "txpool.journal", value_name = "PATH")]
+    pub journal: Option<PathBuf>,
+    /// Flag to disable the backup of local transactions across restarts.
+    #[arg(long = "txpool.nojournal", conflicts_with = "journal")]
+    pub no_journal: bool,
+    /// The interval at which local transactions are backed up (in seconds), in addition to on
+    /// shutdown.
+    #[arg(long = "txpool.rejournal", value_parser = parse_nonzero_duration_from_secs, default_value = "3600", value_name = "SECONDS")]
+    pub rejournal: Duration,
 }
 
 impl Default for TxPoolArgs {
@@ -60,7 +87,12 @@ impl Default for TxPoolArgs {
             max_account_slots: TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
             price_bump: DEFAULT_PRICE_BUMP,
             blob_transaction_price_bump: REPLACE_BLOB_PRICE_BUMP,
+            ordering: TxPoolOrdering::CoinbaseTip,
+            max_tip: None,
             no_locals: false,
+            journal: None,
+            no_journal: false,
+            rejournal: DEFAULT_LOCAL_TRANSACTIONS_BACKUP_INTERVAL,
         }
     }
 }
@@ -93,6 +125,45 @@ impl TxPoolArgs {
             },
         }
     }
+
+    /// Returns the configured transaction ordering.
+    pub fn ordering(&self) -> TransactionOrderingKind {
+        match self.ordering {
+            TxPoolOrdering::CoinbaseTip => TransactionOrderingKind::CoinbaseTip,
+            TxPoolOrdering::ArrivalTime => TransactionOrderingKind::ArrivalTime,
+            TxPoolOrdering::CappedTip => TransactionOrderingKind::CappedTip {
+                max_tip_per_gas: self.max_tip.unwrap_or_default(),
+            },
+        }
+    }
+
+    /// Returns the configuration for backing up local transactions.
+    ///
+    /// The given path is used unless a different file is configured.
+    pub fn local_transactions_backup_config(
+        &self,
+        default_path: PathBuf,
+    ) -> LocalTransactionBackupConfig {
+        if self.no_journal {
+            return LocalTransactionBackupConfig::default()
+        }
+        LocalTransactionBackupConfig::with_local_txs_backup(
+            self.journal.clone().unwrap_or(default_path),
+        )
+        .with_interval(self.rejournal)
+    }
+}
+
+/// The transaction orderings that can be selected for the pending pool.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum TxPoolOrdering {
+    /// Order by the tip paid to the block's beneficiary.
+    #[default]
+    CoinbaseTip,
+    /// Order by the time transactions were added to the pool, first in, first out.
+    ArrivalTime,
+    /// Order by effective gas price, ignoring tips above `--txpool.ordering.max-tip`.
+    CappedTip,
 }
 
 #[cfg(test)]
@@ -113,4 +184,33 @@ mod tests {
         let args = CommandParser::<TxPoolArgs>::parse_from(["reth"]).args;
         assert_eq!(args, default_args);
     }
+
+    #[test]
+    fn txpool_args_ordering() {
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.ordering",
+            "capped-tip",
+            "--txpool.ordering.max-tip",
+            "2000000000",
+        ])
+        .args;
+        assert_eq!(
+            args.ordering(),
+            TransactionOrderingKind::CappedTip { max_tip_per_gas: 2_000_000_000 }
+        );
+
+        let res = CommandParser::<TxPoolArgs>::try_parse_from([
+            "reth",
+            "--txpool.ordering",
+            "capped-tip",
+        ]);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn txpool_args_rejects_zero_rejournal() {
+        let res = CommandParser::<TxPoolArgs>::try_parse_from(["reth", "--txpool.rejournal", "0"]);
+        assert!(res.is_err());
+    }
 }
@@ -1,6 +1,6 @@
 //! Clap parser utilities
 
-use reth_primitives::{fs, AllGenesisFormats, BlockHashOrNumber, ChainSpec, B256};
+use reth_primitives::{fs, hex, AllGenesisFormats, BlockHashOrNumber, ChainSpec, ForkHash, B256};
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr, ToSocketAddrs},
     path::PathBuf,
@@ -28,6 +28,31 @@ pub fn parse_duration_from_secs(arg: &str) -> eyre::Result<Duration, std::num::P
     Ok(Duration::from_secs(seconds))
 }
 
+/// Helper to parse a non-zero [Duration] from seconds, for intervals that drive a timer
+pub fn parse_nonzero_duration_from_secs(arg: &str) -> eyre::Result<Duration> {
+    let duration = parse_duration_from_secs(arg)?;
+    if duration.is_zero() {
+        eyre::bail!("interval must be at least one second")
+    }
+    Ok(duration)
+}
+
+/// Helper to parse a `KEY=VALUE` pair
+pub fn parse_key_value(arg: &str) -> eyre::Result<(String, String)> {
+    let (key, value) =
+        arg.split_once('=').ok_or_else(|| eyre::eyre!("invalid KEY=VALUE pair: {arg}"))?;
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// Helper to parse a ratio between 0 and 1
+pub fn parse_ratio(arg: &str) -> eyre::Result<f64> {
+    let ratio: f64 = arg.parse()?;
+    if !(0.0..=1.0).contains(&ratio) {
+        eyre::bail!("ratio {ratio} is not between 0 and 1")
+    }
+    Ok(ratio)
+}
+
 /// Clap value parser for [ChainSpec]s that takes either a built-in chainspec or the path
 /// to a custom one.
 pub fn chain_spec_value_parser(s: &str) -> eyre::Result<Arc<ChainSpec>, eyre::Error> {
@@ -109,6 +134,15 @@ pub fn hash_or_num_value_parser(value: &str) -> eyre::Result<BlockHashOrNumber,
     }
 }
 
+/// Parse a [ForkHash] from its hex representation, e.g. `0xfc64ec04`.
+pub fn fork_hash_value_parser(value: &str) -> eyre::Result<ForkHash, eyre::Error> {
+    let bytes = hex::decode(value)?;
+    let hash: [u8; 4] = bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| eyre::eyre!("fork hash must be 4 bytes, got {}", bytes.len()))?;
+    Ok(ForkHash(hash))
+}
+
 /// Error thrown while parsing a socket address.
 #[derive(thiserror::Error, Debug)]
 pub enum SocketAddressParsingError {
@@ -158,12 +192,18 @@ pub fn parse_socket_address(value: &str) -> eyre::Result<SocketAddr, SocketAddre
 mod tests {
     use super::*;
     use proptest::prelude::Rng;
-    use reth_primitives::{
-        hex, Address, ChainConfig, ChainSpecBuilder, Genesis, GenesisAccount, U256,
-    };
+    use reth_primitives::{Address, ChainConfig, ChainSpecBuilder, Genesis, GenesisAccount, U256};
     use secp256k1::rand::thread_rng;
     use std::collections::HashMap;
 
+    #[test]
+    fn parse_fork_hash() {
+        let hash = fork_hash_value_parser("0xfc64ec04").unwrap();
+        assert_eq!(hash, ForkHash([0xfc, 0x64, 0xec, 0x04]));
+        assert_eq!(fork_hash_value_parser("fc64ec04").unwrap(), hash);
+        assert!(fork_hash_value_parser("0xfc64ec").is_err());
+    }
+
     #[test]
     fn parse_known_chain_spec() {
         for chain in SUPPORTED_CHAINS {
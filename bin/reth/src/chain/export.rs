@@ -0,0 +1,216 @@
+use crate::{
+    args::{
+        utils::{chain_help, genesis_value_parser, SUPPORTED_CHAINS},
+        DatabaseArgs,
+    },
+    dirs::{DataDirPath, MaybePlatformPath},
+};
+use alloy_rlp::Encodable;
+use clap::{Parser, ValueEnum};
+use reth_db::{database::Database, open_db_read_only};
+use reth_era::{era1_file_name, Era1Writer, MAX_BLOCKS_PER_ERA1};
+use reth_primitives::{BlockNumber, ChainSpec};
+use reth_provider::{
+    BlockNumReader, BlockReader, DatabaseProviderRO, HeaderProvider, ProviderError,
+    ProviderFactory, ReceiptProvider,
+};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::info;
+
+/// Exports blocks to RLP encoded files or Era1 archives.
+#[derive(Debug, Parser)]
+pub struct ExportCommand {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        long_help = chain_help(),
+        default_value = SUPPORTED_CHAINS[0],
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    #[clap(flatten)]
+    db: DatabaseArgs,
+
+    /// The format to export the blocks in.
+    #[arg(long, value_enum, default_value_t = ExportFormat::Rlp)]
+    format: ExportFormat,
+
+    /// The first block to export.
+    ///
+    /// Defaults to the first block after genesis for RLP, and to genesis for Era1.
+    #[arg(long, verbatim_doc_comment)]
+    from: Option<BlockNumber>,
+
+    /// The last block to export. Defaults to the latest block.
+    #[arg(long)]
+    to: Option<BlockNumber>,
+
+    /// The path to export the blocks to.
+    ///
+    /// RLP encoded blocks are written one after another to this file, Era1 files of up to 8192
+    /// blocks are written to this directory.
+    #[arg(value_name = "EXPORT_PATH", verbatim_doc_comment)]
+    path: PathBuf,
+}
+
+/// The format of exported blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// RLP encoded blocks, as read by `reth import`.
+    Rlp,
+    /// Era1 archives with headers, bodies, receipts and total difficulty.
+    Era1,
+}
+
+impl ExportCommand {
+    /// Execute `export` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        info!(target: "reth::cli", "reth export starting");
+
+        // add network name to data dir
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let db_path = data_dir.db_path();
+        info!(target: "reth::cli", path = ?db_path, "Opening database");
+        let db = open_db_read_only(&db_path, self.db.log_level)?;
+        info!(target: "reth::cli", "Database opened");
+
+        // blocks that were moved to snapshots are read from there
+        let mut factory = ProviderFactory::new(db, self.chain.clone());
+        let snapshotter = reth_snapshot::Snapshotter::new(
+            factory.clone(),
+            data_dir.snapshots_path(),
+            self.chain.snapshot_block_interval,
+        )?;
+        factory = factory
+            .with_snapshots(data_dir.snapshots_path(), snapshotter.highest_snapshot_receiver());
+        let provider = factory.provider()?;
+
+        let from = self.from.unwrap_or(match self.format {
+            ExportFormat::Rlp => 1,
+            ExportFormat::Era1 => 0,
+        });
+        let to = match self.to {
+            Some(to) => to,
+            None => provider.last_block_number()?,
+        };
+        if from > to {
+            eyre::bail!("invalid block range {from}..={to}");
+        }
+
+        match self.format {
+            ExportFormat::Rlp => export_rlp(&provider, from..=to, &self.path)?,
+            ExportFormat::Era1 => {
+                export_era1(&provider, from..=to, &self.path, &self.chain.chain.to_string())?
+            }
+        }
+
+        info!(target: "reth::cli", from, to, path = ?self.path, "Blocks exported");
+        Ok(())
+    }
+}
+
+/// Writes the RLP encoded blocks of the range one after another to the file at `path`.
+fn export_rlp<DB: Database>(
+    provider: &DatabaseProviderRO<DB>,
+    range: RangeInclusive<BlockNumber>,
+    path: &Path,
+) -> eyre::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    let mut buf = Vec::new();
+    for number in range {
+        let block =
+            provider.block(number.into())?.ok_or(ProviderError::BlockNotFound(number.into()))?;
+
+        buf.clear();
+        block.encode(&mut buf);
+        writer.write_all(&buf)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes the blocks of the range to Era1 files in the directory at `path`.
+///
+/// Every file holds the blocks of one epoch of [`MAX_BLOCKS_PER_ERA1`] blocks, the first and last
+/// files only hold the part of their epoch within the range.
+fn export_era1<DB: Database>(
+    provider: &DatabaseProviderRO<DB>,
+    range: RangeInclusive<BlockNumber>,
+    path: &Path,
+    network: &str,
+) -> eyre::Result<()> {
+    std::fs::create_dir_all(path)?;
+
+    let epoch_size = MAX_BLOCKS_PER_ERA1 as u64;
+    let mut start = *range.start();
+    while start <= *range.end() {
+        let end = (start / epoch_size * epoch_size + epoch_size - 1).min(*range.end());
+
+        // the file name depends on the accumulator root, so the file is renamed once written
+        let tmp_path = path.join(format!("{network}-{:05}.era1.tmp", start / epoch_size));
+        let mut writer = Era1Writer::new(BufWriter::new(File::create(&tmp_path)?))?;
+        for number in start..=end {
+            let block = provider
+                .block(number.into())?
+                .ok_or(ProviderError::BlockNotFound(number.into()))?;
+            let receipts = provider
+                .receipts_by_block(number.into())?
+                .ok_or(ProviderError::BlockNotFound(number.into()))?;
+            if receipts.len() != block.body.len() {
+                eyre::bail!("receipts of block {number} are missing, they might have been pruned");
+            }
+            let total_difficulty = provider
+                .header_td_by_number(number)?
+                .ok_or(ProviderError::TotalDifficultyNotFound(number))?;
+
+            writer.append_block(block.seal_slow(), &receipts, total_difficulty)?;
+        }
+        let (_, root) = writer.finish()?;
+
+        let file_path = path.join(era1_file_name(network, start, root));
+        std::fs::rename(&tmp_path, &file_path)?;
+        info!(target: "reth::cli", start, end, path = ?file_path, "Era1 file written");
+
+        start = end + 1;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_export_command() {
+        let args: ExportCommand =
+            ExportCommand::parse_from(["reth", "--format", "era1", "--to", "8191", "."]);
+        assert_eq!(args.format, ExportFormat::Era1);
+        assert_eq!(args.from, None);
+        assert_eq!(args.to, Some(8191));
+
+        let args: ExportCommand = ExportCommand::parse_from(["reth", "--from", "1", "."]);
+        assert_eq!(args.format, ExportFormat::Rlp);
+        assert_eq!(args.from, Some(1));
+    }
+}
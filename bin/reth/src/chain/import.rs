@@ -25,11 +25,14 @@ use reth_stages::{
     prelude::*,
     stages::{ExecutionStage, ExecutionStageThresholds, SenderRecoveryStage, TotalDifficultyStage},
 };
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use tokio::sync::watch;
 use tracing::{debug, info};
 
-/// Syncs RLP encoded blocks from a file.
+/// Syncs RLP encoded blocks or Era1 archives from a file.
 #[derive(Debug, Parser)]
 pub struct ImportCommand {
     /// The path to the configuration file to use.
@@ -65,6 +68,9 @@ pub struct ImportCommand {
     ///
     /// The online stages (headers and bodies) are replaced by a file import, after which the
     /// remaining stages are executed.
+    ///
+    /// Files with the `era1` extension are read as Era1 archives, and directories are searched
+    /// for Era1 archives which are imported in the order of their names.
     #[arg(value_name = "IMPORT_PATH", verbatim_doc_comment)]
     path: PathBuf,
 }
@@ -95,34 +101,64 @@ impl ImportCommand {
         let consensus = Arc::new(BeaconConsensus::new(self.chain.clone()));
         info!(target: "reth::cli", "Consensus engine initialized");
 
-        // create a new FileClient
-        info!(target: "reth::cli", "Importing chain file");
-        let file_client = Arc::new(FileClient::new(&self.path).await?);
+        // Era1 archives are imported one file at a time, so only one of them is held in memory
+        let era1 = self.path.is_dir() || is_era1_file(&self.path);
+        let files = if era1 { era1_files(&self.path)? } else { vec![self.path.clone()] };
+
+        for file in files {
+            // create a new FileClient
+            info!(target: "reth::cli", path = ?file, "Importing chain file");
+            let file_client = if era1 {
+                FileClient::from_era1(&file).await?
+            } else {
+                FileClient::new(&file).await?
+            };
+            let file_client = Arc::new(file_client);
 
-        // override the tip
-        let tip = file_client.tip().expect("file client has no tip");
-        info!(target: "reth::cli", "Chain file imported");
+            if !file_client.has_canonical_blocks() {
+                eyre::bail!("unable to import non canonical blocks");
+            }
+            let (Some(first_block), Some(tip)) = (file_client.min_block(), file_client.tip())
+            else {
+                eyre::bail!("no blocks found in {}", file.display());
+            };
 
-        let (mut pipeline, events) = self
-            .build_import_pipeline(config, provider_factory.clone(), &consensus, file_client)
-            .await?;
+            // the blocks of the file must connect to the blocks that are already imported
+            let provider = provider_factory.provider()?;
+            let latest_block_number =
+                provider.get_stage_checkpoint(StageId::Finish)?.map(|ch| ch.block_number);
+            drop(provider);
+            let next_block = latest_block_number.unwrap_or_default() + 1;
+            if first_block > next_block {
+                eyre::bail!(
+                    "{} starts at block {first_block}, expected block {next_block}",
+                    file.display()
+                );
+            }
+            info!(target: "reth::cli", "Chain file imported");
 
-        // override the tip
-        pipeline.set_tip(tip);
-        debug!(target: "reth::cli", ?tip, "Tip manually set");
+            let (mut pipeline, events) = self
+                .build_import_pipeline(
+                    config.clone(),
+                    provider_factory.clone(),
+                    &consensus,
+                    file_client,
+                )
+                .await?;
 
-        let provider = provider_factory.provider()?;
+            // override the tip
+            pipeline.set_tip(tip);
+            debug!(target: "reth::cli", ?tip, "Tip manually set");
 
-        let latest_block_number =
-            provider.get_stage_checkpoint(StageId::Finish)?.map(|ch| ch.block_number);
-        tokio::spawn(handle_events(None, latest_block_number, events, db.clone()));
+            tokio::spawn(handle_events(None, latest_block_number, events, db.clone()));
 
-        // Run pipeline
-        info!(target: "reth::cli", "Starting sync pipeline");
-        tokio::select! {
-            res = pipeline.run() => res?,
-            _ = tokio::signal::ctrl_c() => {},
-        };
+            // Run pipeline
+            info!(target: "reth::cli", "Starting sync pipeline");
+            tokio::select! {
+                res = pipeline.run() => res?,
+                _ = tokio::signal::ctrl_c() => break,
+            };
+        }
 
         info!(target: "reth::cli", "Finishing up");
         Ok(())
@@ -139,10 +175,6 @@ impl ImportCommand {
         DB: Database + Clone + Unpin + 'static,
         C: Consensus + 'static,
     {
-        if !file_client.has_canonical_blocks() {
-            eyre::bail!("unable to import non canonical blocks");
-        }
-
         let header_downloader = ReverseHeadersDownloaderBuilder::from(config.stages.headers)
             .build(file_client.clone(), consensus.clone())
             .into_task();
@@ -205,6 +237,29 @@ impl ImportCommand {
     }
 }
 
+/// Returns `true` if the path has the `era1` extension.
+fn is_era1_file(path: &Path) -> bool {
+    path.extension().map_or(false, |ext| ext == "era1")
+}
+
+/// Returns the Era1 files of the directory at `path` sorted by name, or `path` if it is a file.
+fn era1_files(path: &Path) -> eyre::Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()])
+    }
+
+    let mut files = std::fs::read_dir(path)?
+        .map(|entry| Ok(entry?.path()))
+        .filter(|path| path.as_ref().map_or(true, |path| is_era1_file(path)))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    if files.is_empty() {
+        eyre::bail!("no era1 files found in {}", path.display());
+    }
+    // era1 file names start with the zero padded epoch, after the network name
+    files.sort_unstable();
+    Ok(files)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -216,4 +271,26 @@ mod tests {
             assert_eq!(args.chain.chain, chain.parse().unwrap());
         }
     }
+
+    #[test]
+    fn finds_era1_files() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["mainnet-00001-bbbbbbbb.era1", "mainnet-00000-aaaaaaaa.era1", "blocks.rlp"] {
+            std::fs::write(dir.path().join(name), []).unwrap();
+        }
+
+        let files = era1_files(dir.path()).unwrap();
+        assert_eq!(
+            files,
+            vec![
+                dir.path().join("mainnet-00000-aaaaaaaa.era1"),
+                dir.path().join("mainnet-00001-bbbbbbbb.era1")
+            ]
+        );
+
+        let file = dir.path().join("mainnet-00001-bbbbbbbb.era1");
+        assert!(is_era1_file(&file));
+        assert_eq!(era1_files(&file).unwrap(), vec![file]);
+        assert!(!is_era1_file(&dir.path().join("blocks.rlp")));
+    }
 }
@@ -5,7 +5,7 @@ use reth_network_api::{NetworkInfo, Peers};
 use reth_primitives::ChainSpec;
 use reth_provider::{
     AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader,
-    EvmEnvProvider, StateProviderFactory,
+    EvmEnvProvider, HistoryReader, InvalidBlockReader, PlainStateReader, StateProviderFactory,
 };
 use reth_rpc_builder::{
     auth::AuthServerHandle, RethModuleRegistry, RpcServerHandle, TransportRpcModules,
@@ -22,6 +22,9 @@ pub trait FullProvider:
     + EvmEnvProvider
     + ChainSpecProvider
     + ChangeSetReader
+    + HistoryReader
+    + InvalidBlockReader
+    + PlainStateReader
     + Clone
     + Unpin
     + 'static
@@ -35,6 +38,9 @@ impl<T> FullProvider for T where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + HistoryReader
+        + InvalidBlockReader
+        + PlainStateReader
         + Clone
         + Unpin
         + 'static
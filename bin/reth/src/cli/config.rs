@@ -99,8 +99,8 @@ pub trait PayloadBuilderConfig {
     /// The deadline for when the payload builder job should resolve.
     fn deadline(&self) -> Duration;
 
-    /// Target gas ceiling for built blocks.
-    fn max_gas_limit(&self) -> u64;
+    /// Target gas limit for built blocks, that the gas limit of each built block moves toward.
+    fn desired_gas_limit(&self) -> u64;
 
     /// Maximum number of tasks to spawn for building a payload.
     fn max_payload_tasks(&self) -> usize;
@@ -138,7 +138,7 @@ pub trait RethNodeCommandConfig: fmt::Debug {
             .deadline(conf.deadline())
             .max_payload_tasks(conf.max_payload_tasks())
             .extradata(conf.extradata_rlp_bytes())
-            .max_gas_limit(conf.max_gas_limit());
+            .desired_gas_limit(conf.desired_gas_limit());
 
         #[cfg(feature = "optimism")]
         let payload_job_config =
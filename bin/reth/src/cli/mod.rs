@@ -1,11 +1,13 @@
 //! CLI definition and entrypoint to executable
 use crate::{
-    args::utils::{chain_help, genesis_value_parser, SUPPORTED_CHAINS},
+    args::utils::{
+        chain_help, genesis_value_parser, parse_key_value, parse_ratio, SUPPORTED_CHAINS,
+    },
     chain,
     cli::ext::RethCliExt,
     db, debug_cmd,
     dirs::{LogsDir, PlatformPath},
-    node, p2p, recover,
+    dns_tree, node, p2p, recover,
     runner::CliRunner,
     stage, test_vectors,
     version::{LONG_VERSION, SHORT_VERSION},
@@ -15,8 +17,10 @@ use reth_primitives::ChainSpec;
 use reth_tracing::{
     tracing::{metadata::LevelFilter, Level, Subscriber},
     tracing_subscriber::{filter::Directive, registry::LookupSpan, EnvFilter},
-    BoxedLayer, FileWorkerGuard,
+    BoxedLayer, FileWorkerGuard, OtlpProtocol,
 };
+#[cfg(feature = "otlp")]
+use reth_tracing::{OtlpConfig, OtlpGuard};
 use std::{fmt, fmt::Display, sync::Arc};
 
 pub mod components;
@@ -81,13 +85,14 @@ impl<Ext: RethCliExt> Cli<Ext> {
         self.logs.log_file_directory =
             self.logs.log_file_directory.join(self.chain.chain.to_string());
 
-        let _guard = self.init_tracing()?;
+        let _guards = self.init_tracing()?;
 
         let runner = CliRunner;
         match self.command {
             Commands::Node(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
             Commands::Init(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Import(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+            Commands::Export(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Db(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Stage(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::P2P(command) => runner.run_until_ctrl_c(command.execute()),
@@ -95,22 +100,23 @@ impl<Ext: RethCliExt> Cli<Ext> {
             Commands::Config(command) => runner.run_until_ctrl_c(command.execute()),
             Commands::Debug(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
             Commands::Recover(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
+            Commands::DnsTree(command) => runner.run_until_ctrl_c(command.execute()),
         }
     }
 
     /// Initializes tracing with the configured options.
     ///
-    /// If file logging is enabled, this function returns a guard that must be kept alive to ensure
-    /// that all logs are flushed to disk.
-    pub fn init_tracing(&self) -> eyre::Result<Option<FileWorkerGuard>> {
+    /// If file logging or OTLP export is enabled, this function returns guards that must be kept
+    /// alive to ensure that all logs are flushed to disk and all spans are exported.
+    pub fn init_tracing(&self) -> eyre::Result<LogGuards> {
         let mut layers =
             vec![reth_tracing::stdout(self.verbosity.directive(), &self.logs.color.to_string())];
 
-        let (additional_layers, guard) = self.logs.layers()?;
+        let (additional_layers, guards) = self.logs.layers()?;
         layers.extend(additional_layers);
 
         reth_tracing::init(layers);
-        Ok(guard)
+        Ok(guards)
     }
 
     /// Configures the given node extension.
@@ -138,9 +144,12 @@ pub enum Commands<Ext: RethCliExt = ()> {
     /// Initialize the database from a genesis file.
     #[command(name = "init")]
     Init(chain::InitCommand),
-    /// This syncs RLP encoded blocks from a file.
+    /// This syncs RLP encoded blocks or Era1 archives from a file.
     #[command(name = "import")]
     Import(chain::ImportCommand),
+    /// Export blocks to an RLP encoded file or Era1 archives.
+    #[command(name = "export")]
+    Export(chain::ExportCommand),
     /// Database debugging utilities
     #[command(name = "db")]
     Db(db::Command),
@@ -162,6 +171,9 @@ pub enum Commands<Ext: RethCliExt = ()> {
     /// Scripts for node recovery
     #[command(name = "recover")]
     Recover(recover::Command),
+    /// Build and sign an EIP-1459 DNS tree from a list of node records
+    #[command(name = "dns-tree")]
+    DnsTree(dns_tree::Command),
 }
 
 impl<Ext: RethCliExt> Commands<Ext> {
@@ -209,6 +221,69 @@ pub struct Logs {
     )]
     journald_filter: String,
 
+    /// Export spans to an OpenTelemetry collector over OTLP.
+    ///
+    /// Requires reth to be built with the `otlp` feature.
+    #[arg(long = "log.otlp", global = true)]
+    otlp: bool,
+
+    /// The endpoint of the OpenTelemetry collector.
+    ///
+    /// Defaults to `http://localhost:4317` for gRPC and `http://localhost:4318` for HTTP.
+    #[arg(long = "log.otlp.endpoint", value_name = "URL", global = true)]
+    otlp_endpoint: Option<String>,
+
+    /// The protocol used to export spans to the OpenTelemetry collector, either `grpc` or `http`.
+    #[arg(
+        long = "log.otlp.protocol",
+        value_name = "PROTOCOL",
+        global = true,
+        default_value_t = OtlpProtocol::Grpc
+    )]
+    otlp_protocol: OtlpProtocol,
+
+    /// The filter to use for spans exported to the OpenTelemetry collector.
+    ///
+    /// The default also exports the span of every RPC call. The spans of the exporter itself
+    /// (`h2`, `tonic`, `tower`, `hyper` and `reqwest`) are never exported.
+    #[arg(
+        long = "log.otlp.filter",
+        value_name = "FILTER",
+        global = true,
+        default_value = "debug,jsonrpsee_server::transport=trace"
+    )]
+    otlp_filter: String,
+
+    /// The fraction of traces exported to the OpenTelemetry collector, between 0 and 1.
+    #[arg(
+        long = "log.otlp.sample-ratio",
+        value_name = "RATIO",
+        global = true,
+        default_value_t = 1.0,
+        value_parser = parse_ratio
+    )]
+    otlp_sample_ratio: f64,
+
+    /// The service name of the spans exported to the OpenTelemetry collector.
+    #[arg(
+        long = "log.otlp.service-name",
+        value_name = "NAME",
+        global = true,
+        default_value = "reth"
+    )]
+    otlp_service_name: String,
+
+    /// Additional resource attributes of the spans exported to the OpenTelemetry collector,
+    /// separated by commas.
+    #[arg(
+        long = "log.otlp.resource-attributes",
+        value_name = "KEY=VALUE",
+        global = true,
+        value_delimiter = ',',
+        value_parser = parse_key_value
+    )]
+    otlp_resource_attributes: Vec<(String, String)>,
+
     /// Sets whether or not the formatter emits ANSI terminal escape codes for colors and other
     /// text formatting.
     #[arg(
@@ -220,12 +295,24 @@ pub struct Logs {
     color: ColorMode,
 }
 
+/// Guards of the tracing layers built by [Logs::layers].
+///
+/// When dropped, all logs are flushed to disk and all spans are exported.
+#[derive(Debug, Default)]
+pub struct LogGuards {
+    /// The guard of the log file layer.
+    _file: Option<FileWorkerGuard>,
+    /// The guard of the OTLP layer.
+    #[cfg(feature = "otlp")]
+    _otlp: Option<OtlpGuard>,
+}
+
 /// Constant to convert megabytes to bytes
 const MB_TO_BYTES: u64 = 1024 * 1024;
 
 impl Logs {
     /// Builds tracing layers from the current log options.
-    pub fn layers<S>(&self) -> eyre::Result<(Vec<BoxedLayer<S>>, Option<FileWorkerGuard>)>
+    pub fn layers<S>(&self) -> eyre::Result<(Vec<BoxedLayer<S>>, LogGuards)>
     where
         S: Subscriber,
         for<'a> S: LookupSpan<'a>,
@@ -257,7 +344,45 @@ impl Logs {
             None
         };
 
-        Ok((layers, file_guard))
+        #[cfg(not(feature = "otlp"))]
+        if self.otlp {
+            eyre::bail!("--log.otlp requires reth to be built with the `otlp` feature")
+        }
+
+        #[cfg(feature = "otlp")]
+        let otlp_guard = if self.otlp {
+            let endpoint = self.otlp_endpoint.clone().unwrap_or_else(|| {
+                match self.otlp_protocol {
+                    OtlpProtocol::Grpc => "http://localhost:4317",
+                    OtlpProtocol::Http => "http://localhost:4318",
+                }
+                .to_string()
+            });
+            let (layer, guard) = reth_tracing::otlp(
+                EnvFilter::try_new(DEFAULT_ENV_FILTER_DIRECTIVE)?
+                    .add_directive(self.otlp_filter.parse()?),
+                OtlpConfig {
+                    endpoint,
+                    protocol: self.otlp_protocol,
+                    service_name: self.otlp_service_name.clone(),
+                    sample_ratio: self.otlp_sample_ratio,
+                    resource_attributes: self.otlp_resource_attributes.clone(),
+                },
+            )?;
+            layers.push(layer);
+            Some(guard)
+        } else {
+            None
+        };
+
+        Ok((
+            layers,
+            LogGuards {
+                _file: file_guard,
+                #[cfg(feature = "otlp")]
+                _otlp: otlp_guard,
+            },
+        ))
     }
 }
 
@@ -333,6 +458,37 @@ mod tests {
         assert_eq!(reth.logs.color, ColorMode::Always);
     }
 
+    #[test]
+    fn parse_otlp_args() {
+        let reth = Cli::<()>::try_parse_from([
+            "reth",
+            "node",
+            "--log.otlp",
+            "--log.otlp.protocol",
+            "http",
+            "--log.otlp.sample-ratio",
+            "0.1",
+            "--log.otlp.resource-attributes",
+            "deployment.environment=prod,host.name=rpc-1",
+        ])
+        .unwrap();
+        assert!(reth.logs.otlp);
+        assert_eq!(reth.logs.otlp_protocol, OtlpProtocol::Http);
+        assert_eq!(reth.logs.otlp_sample_ratio, 0.1);
+        assert_eq!(reth.logs.otlp_service_name, "reth");
+        assert_eq!(
+            reth.logs.otlp_resource_attributes,
+            vec![
+                ("deployment.environment".to_string(), "prod".to_string()),
+                ("host.name".to_string(), "rpc-1".to_string())
+            ]
+        );
+
+        assert!(
+            Cli::<()>::try_parse_from(["reth", "node", "--log.otlp.sample-ratio", "2"]).is_err()
+        );
+    }
+
     /// Tests that the help message is parsed correctly. This ensures that clap args are configured
     /// correctly and no conflicts are introduced via attributes that would result in a panic at
     /// runtime
@@ -30,6 +30,7 @@ mod list;
 mod snapshots;
 /// DB List TUI
 mod tui;
+mod verify;
 
 /// `reth db` command
 #[derive(Debug, Parser)]
@@ -85,6 +86,8 @@ pub enum Subcommands {
     Clear(clear::Command),
     /// Snapshots tables from database
     Snapshot(snapshots::Command),
+    /// Verifies the consistency of the database tables
+    Verify(verify::Command),
     /// Lists current and local database versions
     Version,
     /// Returns the full database path
@@ -226,6 +229,14 @@ impl Command {
             Subcommands::Snapshot(command) => {
                 command.execute(&db_path, self.db.log_level, self.chain.clone())?;
             }
+            Subcommands::Verify(command) => {
+                let db = if command.repair {
+                    open_db(&db_path, self.db.log_level)?
+                } else {
+                    open_db_read_only(&db_path, self.db.log_level)?
+                };
+                command.execute(&db)?;
+            }
             Subcommands::Version => {
                 let local_db_version = match get_db_version(&db_path) {
                     Ok(version) => Some(version),
@@ -110,6 +110,16 @@ impl Command {
                             factory.clone(),
                             snap_segments::Receipts::new(*compression, filters),
                         )?,
+                        SnapshotSegment::AccountChangeSets => self
+                            .generate_snapshot::<DatabaseEnv>(
+                                factory.clone(),
+                                snap_segments::AccountChangeSets::new(*compression, filters),
+                            )?,
+                        SnapshotSegment::StorageChangeSets => self
+                            .generate_snapshot::<DatabaseEnv>(
+                                factory.clone(),
+                                snap_segments::StorageChangeSets::new(*compression, filters),
+                            )?,
                     }
                 }
             }
@@ -142,6 +152,9 @@ impl Command {
                         InclusionFilter::Cuckoo,
                         *phf,
                     )?,
+                    SnapshotSegment::AccountChangeSets | SnapshotSegment::StorageChangeSets => {
+                        println!("Benchmarks are not available for {mode} snapshots, skipping.")
+                    }
                 }
             }
         }
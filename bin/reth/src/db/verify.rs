@@ -0,0 +1,775 @@
+use clap::{Parser, ValueEnum};
+use reth_db::{
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO},
+    database::Database,
+    models::{sharded_key::ShardedKey, storage_sharded_key::StorageShardedKey, BlockNumberAddress},
+    table::Table,
+    tables,
+    transaction::{DbTx, DbTxMut},
+};
+use reth_primitives::{
+    keccak256, stage::StageId, Account, Address, BlockNumber, PruneCheckpoint, PruneSegment,
+    StorageEntry, TxNumber, B256,
+};
+use std::{fmt, ops::RangeInclusive};
+use tracing::info;
+
+/// The arguments for the `reth db verify` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The first block to verify.
+    #[arg(long, default_value_t = 0)]
+    pub from: BlockNumber,
+
+    /// The last block to verify. Defaults to the highest canonical block.
+    #[arg(long)]
+    pub to: Option<BlockNumber>,
+
+    /// The checks to run, separated by commas. All checks are run if not specified.
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub checks: Vec<Check>,
+
+    /// Repairs the findings which can be derived from the other tables.
+    ///
+    /// The state trie is not updated when the hashed state is repaired, and has to be rebuilt
+    /// afterwards.
+    #[arg(long)]
+    pub repair: bool,
+}
+
+/// A group of invariants checked by the `reth db verify` command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum Check {
+    /// `CanonicalHeaders`, `HeaderNumbers`, `Headers` and `HeaderTD` agree
+    Headers,
+    /// `BlockBodyIndices` are contiguous and match `Transactions`, `TransactionBlock`,
+    /// `TxSenders` and `Receipts`
+    Bodies,
+    /// `AccountHistory` and `StorageHistory` shards cover the changesets
+    History,
+    /// `HashedAccount` and `HashedStorage` match the plain state
+    HashedState,
+    /// Stage checkpoints are consistent with each other and with the tables
+    StageCheckpoints,
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.to_possible_value().expect("no skipped variants");
+        f.write_str(name.get_name())
+    }
+}
+
+/// A broken invariant found by the `reth db verify` command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// The check which found the broken invariant.
+    pub check: Check,
+    /// Description of the broken invariant.
+    pub message: String,
+    /// The repair of the broken invariant, if it can be derived from the other tables.
+    pub repair: Option<Repair>,
+}
+
+/// The repair of a broken invariant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Repair {
+    /// Writes the number of a canonical header to `HeaderNumbers`.
+    HeaderNumber(B256, BlockNumber),
+    /// Writes the block of the last transaction of a block to `TransactionBlock`.
+    TransactionBlock(TxNumber, BlockNumber),
+    /// Writes the recovered sender of a transaction to `TxSenders`.
+    TxSender(TxNumber, Address),
+    /// Writes the hashed plain state account to `HashedAccount`.
+    ///
+    /// The state trie is not updated, see [Repair::invalidates_trie].
+    HashedAccount(B256, Account),
+    /// Writes the hashed plain state storage slot to `HashedStorage`.
+    ///
+    /// The state trie is not updated, see [Repair::invalidates_trie].
+    HashedStorage(B256, StorageEntry),
+}
+
+impl Repair {
+    /// Returns `true` if the repair changes the hashed state, after which the state trie no longer
+    /// matches it and has to be rebuilt.
+    fn invalidates_trie(&self) -> bool {
+        matches!(self, Repair::HashedAccount(..) | Repair::HashedStorage(..))
+    }
+
+    /// Applies the repair.
+    fn apply<TX: DbTxMut + DbTx>(&self, tx: &TX) -> eyre::Result<()> {
+        match self {
+            Repair::HeaderNumber(hash, number) => {
+                tx.put::<tables::HeaderNumbers>(*hash, *number)?
+            }
+            Repair::TransactionBlock(tx_number, number) => {
+                tx.put::<tables::TransactionBlock>(*tx_number, *number)?
+            }
+            Repair::TxSender(tx_number, sender) => {
+                tx.put::<tables::TxSenders>(*tx_number, *sender)?
+            }
+            Repair::HashedAccount(hashed_address, account) => {
+                tx.put::<tables::HashedAccount>(*hashed_address, *account)?
+            }
+            Repair::HashedStorage(hashed_address, entry) => {
+                let mut cursor = tx.cursor_dup_write::<tables::HashedStorage>()?;
+                if cursor
+                    .seek_by_key_subkey(*hashed_address, entry.key)?
+                    .filter(|e| e.key == entry.key)
+                    .is_some()
+                {
+                    cursor.delete_current()?;
+                }
+                cursor.upsert(*hashed_address, *entry)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Command {
+    /// Execute `db verify` command
+    pub fn execute<DB: Database>(self, db: &DB) -> eyre::Result<()> {
+        let findings = self.verify(&db.tx()?)?;
+        for finding in &findings {
+            let repairable = if finding.repair.is_some() { " (repairable)" } else { "" };
+            println!("[{}] {}{repairable}", finding.check, finding.message);
+        }
+
+        let mut repaired = 0;
+        let mut trie_invalidated = false;
+        if self.repair {
+            let tx = db.tx_mut()?;
+            for repair in findings.iter().filter_map(|finding| finding.repair.as_ref()) {
+                repair.apply(&tx)?;
+                repaired += 1;
+                trie_invalidated |= repair.invalidates_trie();
+            }
+            tx.commit()?;
+        }
+
+        println!("{} findings, {repaired} repaired", findings.len());
+        if trie_invalidated {
+            println!(
+                "The hashed state was repaired, so the state trie is outdated. \
+                 Rebuild it with `reth stage drop merkle` before starting the node."
+            );
+        }
+        if findings.len() > repaired {
+            eyre::bail!("database verification failed");
+        }
+        Ok(())
+    }
+
+    /// Runs the selected checks, returning the broken invariants.
+    pub fn verify<TX: DbTx>(&self, tx: &TX) -> eyre::Result<Vec<Finding>> {
+        let to = match self.to {
+            Some(to) => to,
+            None => tx
+                .cursor_read::<tables::CanonicalHeaders>()?
+                .last()?
+                .map_or(0, |(number, _)| number),
+        };
+        if self.from > to {
+            eyre::bail!("invalid block range {}..={to}", self.from);
+        }
+        let range = self.from..=to;
+
+        let mut checks = if self.checks.is_empty() {
+            Check::value_variants().to_vec()
+        } else {
+            self.checks.clone()
+        };
+        checks.sort_unstable();
+        checks.dedup();
+
+        let mut verifier = Verifier { tx, findings: Vec::new() };
+        for check in checks {
+            info!(target: "reth::cli", %check, ?range, "Verifying");
+            match check {
+                Check::Headers => verifier.verify_headers(range.clone())?,
+                Check::Bodies => verifier.verify_bodies(range.clone())?,
+                Check::History => verifier.verify_history(range.clone())?,
+                Check::HashedState => verifier.verify_hashed_state()?,
+                Check::StageCheckpoints => verifier.verify_stage_checkpoints()?,
+            }
+        }
+        Ok(verifier.findings)
+    }
+}
+
+/// Collects the findings of the checks run on a transaction.
+struct Verifier<'a, TX> {
+    tx: &'a TX,
+    findings: Vec<Finding>,
+}
+
+impl<'a, TX: DbTx> Verifier<'a, TX> {
+    fn report(&mut self, check: Check, message: String, repair: Option<Repair>) {
+        self.findings.push(Finding { check, message, repair });
+    }
+
+    fn stage_checkpoint(&self, id: StageId) -> eyre::Result<BlockNumber> {
+        Ok(self
+            .tx
+            .get::<tables::SyncStage>(id.to_string())?
+            .map(|checkpoint| checkpoint.block_number)
+            .unwrap_or_default())
+    }
+
+    fn prune_checkpoint(&self, segment: PruneSegment) -> eyre::Result<Option<PruneCheckpoint>> {
+        Ok(self.tx.get::<tables::PruneCheckpoints>(segment)?)
+    }
+
+    fn verify_headers(&mut self, range: RangeInclusive<BlockNumber>) -> eyre::Result<()> {
+        // snapshotted headers are pruned from the database, so they're not checked
+        let pruned = self
+            .prune_checkpoint(PruneSegment::Headers)?
+            .and_then(|checkpoint| checkpoint.block_number);
+        let range = match pruned {
+            Some(pruned) if pruned >= *range.start() => {
+                info!(target: "reth::cli", to = pruned, "Skipping snapshotted headers");
+                pruned + 1..=*range.end()
+            }
+            _ => range,
+        };
+
+        let mut expected = *range.start();
+        for entry in self.tx.cursor_read::<tables::CanonicalHeaders>()?.walk_range(range.clone())? {
+            let (number, hash) = entry?;
+            if number != expected {
+                self.report(
+                    Check::Headers,
+                    format!("canonical hashes of blocks {expected}..{number} are missing"),
+                    None,
+                );
+            }
+            expected = number + 1;
+
+            match self.tx.get::<tables::Headers>(number)? {
+                Some(header) => {
+                    let header_hash = header.hash_slow();
+                    if header_hash != hash {
+                        self.report(
+                            Check::Headers,
+                            format!(
+                                "header {number} hashes to {header_hash}, canonical hash is {hash}"
+                            ),
+                            None,
+                        );
+                    }
+                }
+                None => self.report(Check::Headers, format!("header {number} is missing"), None),
+            }
+
+            let header_number = self.tx.get::<tables::HeaderNumbers>(hash)?;
+            if header_number != Some(number) {
+                self.report(
+                    Check::Headers,
+                    format!("number of header {hash} is {header_number:?}, expected {number}"),
+                    Some(Repair::HeaderNumber(hash, number)),
+                );
+            }
+
+            if self.tx.get::<tables::HeaderTD>(number)?.is_none() {
+                self.report(
+                    Check::Headers,
+                    format!("total difficulty of block {number} is missing"),
+                    None,
+                );
+            }
+        }
+
+        if expected <= *range.end() {
+            self.report(
+                Check::Headers,
+                format!("canonical hashes of blocks {expected}..={} are missing", range.end()),
+                None,
+            );
+        }
+        Ok(())
+    }
+
+    fn verify_bodies(&mut self, range: RangeInclusive<BlockNumber>) -> eyre::Result<()> {
+        let sender_recovery = self.stage_checkpoint(StageId::SenderRecovery)?;
+        let execution = self.stage_checkpoint(StageId::Execution)?;
+
+        // pruned senders and receipts, as well as snapshotted transactions, are not reported
+        let prune_checkpoint = |segment| -> eyre::Result<_> {
+            Ok(self.prune_checkpoint(segment)?.and_then(|c| c.tx_number))
+        };
+        let transactions_pruned = prune_checkpoint(PruneSegment::Transactions)?;
+        let senders_pruned = prune_checkpoint(PruneSegment::SenderRecovery)?;
+        let receipts_pruned = prune_checkpoint(PruneSegment::Receipts)?;
+        let contract_logs_pruned = self.prune_checkpoint(PruneSegment::ContractLogs)?.is_some();
+        let is_pruned = |tx_number, checkpoint: Option<TxNumber>| {
+            checkpoint.map_or(false, |checkpoint| tx_number <= checkpoint)
+        };
+
+        let mut expected = *range.start();
+        let mut next_tx_num = match expected.checked_sub(1) {
+            Some(parent) => {
+                self.tx.get::<tables::BlockBodyIndices>(parent)?.map(|body| body.next_tx_num())
+            }
+            None => Some(0),
+        };
+        for entry in self.tx.cursor_read::<tables::BlockBodyIndices>()?.walk_range(range.clone())? {
+            let (number, body) = entry?;
+            if number != expected {
+                self.report(
+                    Check::Bodies,
+                    format!("body indices of blocks {expected}..{number} are missing"),
+                    None,
+                );
+                next_tx_num = None;
+            }
+            expected = number + 1;
+
+            if let Some(next_tx_num) = next_tx_num.filter(|next| *next != body.first_tx_num) {
+                self.report(
+                    Check::Bodies,
+                    format!(
+                        "first transaction of block {number} is {}, expected {next_tx_num}",
+                        body.first_tx_num
+                    ),
+                    None,
+                );
+            }
+            next_tx_num = Some(body.next_tx_num());
+
+            if body.is_empty() {
+                continue
+            }
+
+            let last_tx_block = self.tx.get::<tables::TransactionBlock>(body.last_tx_num())?;
+            if last_tx_block != Some(number) {
+                self.report(
+                    Check::Bodies,
+                    format!(
+                        "block of transaction {} is {last_tx_block:?}, expected {number}",
+                        body.last_tx_num()
+                    ),
+                    Some(Repair::TransactionBlock(body.last_tx_num(), number)),
+                );
+            }
+
+            for tx_number in body.tx_num_range() {
+                let Some(transaction) = self.tx.get::<tables::Transactions>(tx_number)? else {
+                    if !is_pruned(tx_number, transactions_pruned) {
+                        self.report(
+                            Check::Bodies,
+                            format!("transaction {tx_number} of block {number} is missing"),
+                            None,
+                        );
+                    }
+                    continue
+                };
+
+                if number <= sender_recovery &&
+                    !is_pruned(tx_number, senders_pruned) &&
+                    self.tx.get::<tables::TxSenders>(tx_number)?.is_none()
+                {
+                    self.report(
+                        Check::Bodies,
+                        format!("sender of transaction {tx_number} is missing"),
+                        transaction
+                            .recover_signer()
+                            .map(|sender| Repair::TxSender(tx_number, sender)),
+                    );
+                }
+
+                if number <= execution &&
+                    !contract_logs_pruned &&
+                    !is_pruned(tx_number, receipts_pruned) &&
+                    self.tx.get::<tables::Receipts>(tx_number)?.is_none()
+                {
+                    self.report(
+                        Check::Bodies,
+                        format!("receipt of transaction {tx_number} is missing"),
+                        None,
+                    );
+                }
+            }
+        }
+
+        // transactions after the last body don't belong to any block
+        let last_body = self.tx.cursor_read::<tables::BlockBodyIndices>()?.last()?;
+        if let Some((last_number, body)) = last_body.filter(|(number, _)| range.contains(number)) {
+            let last_tx = self.tx.cursor_read::<tables::Transactions>()?.last()?;
+            if let Some((tx_number, _)) = last_tx.filter(|(tx, _)| *tx >= body.next_tx_num()) {
+                self.report(
+                    Check::Bodies,
+                    format!(
+                        "transactions {}..={tx_number} are after the last block {last_number}",
+                        body.next_tx_num()
+                    ),
+                    None,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn verify_history(&mut self, range: RangeInclusive<BlockNumber>) -> eyre::Result<()> {
+        let account_range = *range.start()..=
+            (*range.end()).min(self.stage_checkpoint(StageId::IndexAccountHistory)?);
+        let mut history = self.tx.cursor_read::<tables::AccountHistory>()?;
+        for entry in self.tx.cursor_read::<tables::AccountChangeSet>()?.walk_range(account_range)? {
+            let (number, change) = entry?;
+            let indexed = history
+                .seek(ShardedKey::new(change.address, number))?
+                .filter(|(key, _)| key.key == change.address)
+                .map_or(false, |(_, blocks)| blocks.iter(0).any(|block| block as u64 == number));
+            if !indexed {
+                self.report(
+                    Check::History,
+                    format!("account history of {} is missing block {number}", change.address),
+                    None,
+                );
+            }
+        }
+
+        let storage_range = *range.start()..=
+            (*range.end()).min(self.stage_checkpoint(StageId::IndexStorageHistory)?);
+        let mut history = self.tx.cursor_read::<tables::StorageHistory>()?;
+        for entry in self
+            .tx
+            .cursor_read::<tables::StorageChangeSet>()?
+            .walk_range(BlockNumberAddress::range(storage_range))?
+        {
+            let (BlockNumberAddress((number, address)), change) = entry?;
+            let indexed = history
+                .seek(StorageShardedKey::new(address, change.key, number))?
+                .filter(|(key, _)| key.address == address && key.sharded_key.key == change.key)
+                .map_or(false, |(_, blocks)| blocks.iter(0).any(|block| block as u64 == number));
+            if !indexed {
+                self.report(
+                    Check::History,
+                    format!(
+                        "storage history of {address} slot {} is missing block {number}",
+                        change.key
+                    ),
+                    None,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn verify_hashed_state(&mut self) -> eyre::Result<()> {
+        // the plain state is only hashed up to the hashing checkpoints
+        let execution = self.stage_checkpoint(StageId::Execution)?;
+        for id in [StageId::AccountHashing, StageId::StorageHashing] {
+            if self.stage_checkpoint(id)? != execution {
+                info!(target: "reth::cli", stage = %id, "Hashing is behind execution, skipping");
+                return Ok(())
+            }
+        }
+
+        for entry in self.tx.cursor_read::<tables::PlainAccountState>()?.walk(None)? {
+            let (address, account) = entry?;
+            let hashed_address = keccak256(address);
+            if self.tx.get::<tables::HashedAccount>(hashed_address)? != Some(account) {
+                self.report(
+                    Check::HashedState,
+                    format!("hashed account of {address} doesn't match the plain state"),
+                    Some(Repair::HashedAccount(hashed_address, account)),
+                );
+            }
+        }
+
+        let mut hashed_storage = self.tx.cursor_dup_read::<tables::HashedStorage>()?;
+        for entry in self.tx.cursor_read::<tables::PlainStorageState>()?.walk(None)? {
+            let (address, StorageEntry { key, value }) = entry?;
+            let (hashed_address, hashed_key) = (keccak256(address), keccak256(key));
+            let hashed_value = hashed_storage
+                .seek_by_key_subkey(hashed_address, hashed_key)?
+                .filter(|entry| entry.key == hashed_key)
+                .map(|entry| entry.value);
+            if hashed_value != Some(value) {
+                self.report(
+                    Check::HashedState,
+                    format!("hashed storage of {address} slot {key} doesn't match the plain state"),
+                    Some(Repair::HashedStorage(
+                        hashed_address,
+                        StorageEntry { key: hashed_key, value },
+                    )),
+                );
+            }
+        }
+
+        // every hashed entry has been matched, so extra entries show up in the counts
+        let (plain, hashed) = (
+            self.tx.entries::<tables::PlainAccountState>()?,
+            self.tx.entries::<tables::HashedAccount>()?,
+        );
+        if hashed > plain {
+            self.report(
+                Check::HashedState,
+                format!("{} hashed accounts are not in the plain state", hashed - plain),
+                None,
+            );
+        }
+        let (plain, hashed) = (
+            self.tx.entries::<tables::PlainStorageState>()?,
+            self.tx.entries::<tables::HashedStorage>()?,
+        );
+        if hashed > plain {
+            self.report(
+                Check::HashedState,
+                format!("{} hashed storage slots are not in the plain state", hashed - plain),
+                None,
+            );
+        }
+        Ok(())
+    }
+
+    fn verify_stage_checkpoints(&mut self) -> eyre::Result<()> {
+        // every stage runs after the previous one, so it can't be ahead of it
+        let mut previous: Option<(StageId, BlockNumber)> = None;
+        for id in StageId::ALL {
+            // the unwind part of the merkle stage is always skipped during execution
+            if id == StageId::MerkleUnwind {
+                continue
+            }
+            let checkpoint = self.stage_checkpoint(id)?;
+            if let Some((previous_id, previous_checkpoint)) = previous {
+                if checkpoint > previous_checkpoint {
+                    self.report(
+                        Check::StageCheckpoints,
+                        format!(
+                            "checkpoint of stage {id} is {checkpoint}, \
+                             ahead of stage {previous_id} at {previous_checkpoint}"
+                        ),
+                        None,
+                    );
+                }
+            }
+            previous = Some((id, checkpoint));
+        }
+
+        // stages can't be ahead of the data they've written
+        let last_header = self.tx.cursor_read::<tables::CanonicalHeaders>()?.last()?.map(|e| e.0);
+        let last_td = self.tx.cursor_read::<tables::HeaderTD>()?.last()?.map(|e| e.0);
+        let last_body = self.tx.cursor_read::<tables::BlockBodyIndices>()?.last()?.map(|e| e.0);
+        for (id, last, table) in [
+            (StageId::Headers, last_header, tables::CanonicalHeaders::NAME),
+            (StageId::TotalDifficulty, last_td, tables::HeaderTD::NAME),
+            (StageId::Bodies, last_body, tables::BlockBodyIndices::NAME),
+        ] {
+            let checkpoint = self.stage_checkpoint(id)?;
+            if checkpoint > last.unwrap_or_default() {
+                self.report(
+                    Check::StageCheckpoints,
+                    format!(
+                        "checkpoint of stage {id} is {checkpoint}, but {table} ends at {last:?}"
+                    ),
+                    None,
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{models::AccountBeforeTx, test_utils::create_test_rw_db, BlockNumberList};
+    use reth_interfaces::test_utils::{generators, generators::random_block_range};
+    use reth_primitives::{stage::StageCheckpoint, PruneMode, MAINNET, U256};
+    use reth_provider::{BlockWriter, ProviderFactory};
+
+    fn set_stage_checkpoint<TX: DbTxMut>(tx: &TX, id: StageId, block_number: BlockNumber) {
+        tx.put::<tables::SyncStage>(id.to_string(), StageCheckpoint::new(block_number)).unwrap();
+    }
+
+    fn messages(findings: &[Finding]) -> Vec<&str> {
+        findings.iter().map(|finding| finding.message.as_str()).collect()
+    }
+
+    #[test]
+    fn parse_checks() {
+        let cmd = Command::parse_from(["reth", "--checks", "headers,bodies", "--repair"]);
+        assert_eq!(cmd.checks, vec![Check::Headers, Check::Bodies]);
+        assert!(cmd.repair);
+        assert_eq!(Check::HashedState.to_string(), "hashed-state");
+    }
+
+    #[test]
+    fn finds_and_repairs_broken_indices() {
+        let db = create_test_rw_db();
+        let factory = ProviderFactory::new(db.clone(), MAINNET.clone());
+
+        let mut rng = generators::rng();
+        let blocks = random_block_range(&mut rng, 0..=10, B256::ZERO, 1..3);
+        let provider = factory.provider_rw().unwrap();
+        for block in blocks.clone() {
+            provider.insert_block(block, None, None).unwrap();
+        }
+        provider.commit().unwrap();
+
+        let cmd = Command::parse_from(["reth", "--from", "1", "--checks", "headers,bodies"]);
+        assert_eq!(cmd.verify(&db.tx().unwrap()).unwrap(), vec![]);
+
+        // break the indices of block 5
+        let body = db.tx().unwrap().get::<tables::BlockBodyIndices>(5).unwrap().unwrap();
+        let tx = db.tx_mut().unwrap();
+        tx.delete::<tables::HeaderNumbers>(blocks[5].hash(), None).unwrap();
+        tx.delete::<tables::TransactionBlock>(body.last_tx_num(), None).unwrap();
+        tx.commit().unwrap();
+
+        let findings = cmd.verify(&db.tx().unwrap()).unwrap();
+        assert_eq!(
+            findings.iter().map(|f| f.repair.clone()).collect::<Vec<_>>(),
+            vec![
+                Some(Repair::HeaderNumber(blocks[5].hash(), 5)),
+                Some(Repair::TransactionBlock(body.last_tx_num(), 5)),
+            ]
+        );
+
+        Command::parse_from(["reth", "--from", "1", "--checks", "headers,bodies", "--repair"])
+            .execute(db.as_ref())
+            .unwrap();
+        assert_eq!(cmd.verify(&db.tx().unwrap()).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn skips_snapshotted_headers_and_transactions() {
+        let db = create_test_rw_db();
+        let factory = ProviderFactory::new(db.clone(), MAINNET.clone());
+
+        let mut rng = generators::rng();
+        let blocks = random_block_range(&mut rng, 0..=10, B256::ZERO, 1..3);
+        let provider = factory.provider_rw().unwrap();
+        for block in blocks {
+            provider.insert_block(block, None, None).unwrap();
+        }
+        provider.commit().unwrap();
+
+        // prune the headers and transactions of blocks 0..=4, as if they were snapshotted
+        let body = db.tx().unwrap().get::<tables::BlockBodyIndices>(4).unwrap().unwrap();
+        let tx = db.tx_mut().unwrap();
+        for number in 0..=4 {
+            tx.delete::<tables::CanonicalHeaders>(number, None).unwrap();
+            tx.delete::<tables::Headers>(number, None).unwrap();
+            tx.delete::<tables::HeaderTD>(number, None).unwrap();
+        }
+        for tx_number in 0..=body.last_tx_num() {
+            tx.delete::<tables::Transactions>(tx_number, None).unwrap();
+        }
+        let cmd = Command::parse_from(["reth", "--from", "1", "--checks", "headers,bodies"]);
+        assert!(!cmd.verify(&tx).unwrap().is_empty());
+
+        let prune_mode = PruneMode::Before(5);
+        for (segment, tx_number) in
+            [(PruneSegment::Headers, None), (PruneSegment::Transactions, Some(body.last_tx_num()))]
+        {
+            let checkpoint = PruneCheckpoint { block_number: Some(4), tx_number, prune_mode };
+            tx.put::<tables::PruneCheckpoints>(segment, checkpoint).unwrap();
+        }
+        assert_eq!(cmd.verify(&tx).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn finds_missing_history() {
+        let db = create_test_rw_db();
+        let (first, second) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let slot = B256::with_last_byte(1);
+
+        let tx = db.tx_mut().unwrap();
+        for (number, address) in [(3, first), (4, second)] {
+            tx.put::<tables::AccountChangeSet>(number, AccountBeforeTx { address, info: None })
+                .unwrap();
+            tx.put::<tables::StorageChangeSet>(
+                BlockNumberAddress((number, address)),
+                StorageEntry { key: slot, value: U256::ZERO },
+            )
+            .unwrap();
+        }
+        tx.put::<tables::AccountHistory>(
+            ShardedKey::new(first, u64::MAX),
+            BlockNumberList::new([3]).unwrap(),
+        )
+        .unwrap();
+        tx.put::<tables::StorageHistory>(
+            StorageShardedKey::new(first, slot, u64::MAX),
+            BlockNumberList::new([3]).unwrap(),
+        )
+        .unwrap();
+        set_stage_checkpoint(&tx, StageId::IndexAccountHistory, 4);
+        set_stage_checkpoint(&tx, StageId::IndexStorageHistory, 4);
+
+        // the changesets of block 4 are not indexed
+        let cmd = Command::parse_from(["reth", "--to", "4", "--checks", "history"]);
+        assert_eq!(
+            messages(&cmd.verify(&tx).unwrap()),
+            vec![
+                format!("account history of {second} is missing block 4"),
+                format!("storage history of {second} slot {slot} is missing block 4"),
+            ]
+        );
+
+        // changesets after the history checkpoints are not indexed yet
+        set_stage_checkpoint(&tx, StageId::IndexAccountHistory, 3);
+        set_stage_checkpoint(&tx, StageId::IndexStorageHistory, 3);
+        assert_eq!(cmd.verify(&tx).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn finds_and_repairs_hashed_state() {
+        let db = create_test_rw_db();
+        let address = Address::with_last_byte(1);
+        let account = Account { nonce: 1, balance: U256::from(10), bytecode_hash: None };
+        let slot = StorageEntry { key: B256::with_last_byte(1), value: U256::from(1) };
+        let hashed_slot = StorageEntry { key: keccak256(slot.key), value: slot.value };
+
+        let tx = db.tx_mut().unwrap();
+        tx.put::<tables::PlainAccountState>(address, account).unwrap();
+        tx.put::<tables::PlainStorageState>(address, slot).unwrap();
+        tx.put::<tables::HashedAccount>(keccak256(address), Account { nonce: 0, ..account })
+            .unwrap();
+        tx.commit().unwrap();
+
+        let cmd = Command::parse_from(["reth", "--checks", "hashed-state"]);
+        let findings = cmd.verify(&db.tx().unwrap()).unwrap();
+        assert_eq!(
+            findings.iter().map(|f| f.repair.clone()).collect::<Vec<_>>(),
+            vec![
+                Some(Repair::HashedAccount(keccak256(address), account)),
+                Some(Repair::HashedStorage(keccak256(address), hashed_slot)),
+            ]
+        );
+        assert!(findings.iter().all(|f| f.repair.as_ref().unwrap().invalidates_trie()));
+
+        Command::parse_from(["reth", "--checks", "hashed-state", "--repair"])
+            .execute(db.as_ref())
+            .unwrap();
+        assert_eq!(cmd.verify(&db.tx().unwrap()).unwrap(), vec![]);
+
+        // hashing behind execution is not checked
+        let tx = db.tx_mut().unwrap();
+        tx.delete::<tables::HashedAccount>(keccak256(address), None).unwrap();
+        set_stage_checkpoint(&tx, StageId::Execution, 1);
+        assert_eq!(cmd.verify(&tx).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn finds_inconsistent_stage_checkpoints() {
+        let db = create_test_rw_db();
+        let tx = db.tx_mut().unwrap();
+        set_stage_checkpoint(&tx, StageId::Headers, 10);
+        set_stage_checkpoint(&tx, StageId::TotalDifficulty, 11);
+
+        let cmd = Command::parse_from(["reth", "--checks", "stage-checkpoints"]);
+        assert_eq!(
+            messages(&cmd.verify(&tx).unwrap()),
+            vec![
+                "checkpoint of stage TotalDifficulty is 11, ahead of stage Headers at 10",
+                "checkpoint of stage Headers is 10, but CanonicalHeaders ends at None",
+                "checkpoint of stage TotalDifficulty is 11, but HeaderTD ends at None",
+            ]
+        );
+    }
+}
@@ -241,6 +241,8 @@ impl Command {
         let payload_config = PayloadConfig::new(
             Arc::clone(&best_block),
             Bytes::default(),
+            // keep the gas limit of the parent
+            best_block.gas_limit,
             PayloadBuilderAttributes::try_new(best_block.hash, payload_attrs)?,
             self.chain.clone(),
             #[cfg(feature = "optimism")]
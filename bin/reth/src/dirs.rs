@@ -285,6 +285,29 @@ impl<D> ChainPath<D> {
         self.0.join("known-peers.json").into()
     }
 
+    /// Returns the path to the file that blocks which failed validation or execution are
+    /// persisted to.
+    ///
+    /// `<DIR>/<CHAIN_ID>/bad-blocks.json`
+    pub fn bad_blocks_path(&self) -> PathBuf {
+        self.0.join("bad-blocks.json").into()
+    }
+
+    /// Returns the path to the file the transaction pool's local transactions are backed up to.
+    ///
+    /// `<DIR>/<CHAIN_ID>/txpool-transactions-backup.rlp`
+    pub fn txpool_transactions_path(&self) -> PathBuf {
+        self.0.join("txpool-transactions-backup.rlp").into()
+    }
+
+    /// Returns the path to the directory that profiles requested over the `debug` RPC namespace
+    /// are written to.
+    ///
+    /// `<DIR>/<CHAIN_ID>/profiles`
+    pub fn profiles_path(&self) -> PathBuf {
+        self.0.join("profiles").into()
+    }
+
     /// Returns the path to the blobstore directory for this chain where blobs of unfinalized
     /// transactions are stored.
     ///
@@ -0,0 +1,103 @@
+//! CLI command to build an [EIP-1459](https://eips.ethereum.org/EIPS/eip-1459) DNS tree offline
+use crate::args::{get_secret_key, utils::fork_hash_value_parser};
+use clap::Parser;
+use eyre::WrapErr;
+use reth_dns_discovery::{tree::LinkEntry, DnsTree, Enr, EnrFilter, ZoneFilePublisher};
+use reth_primitives::{fs, ForkHash};
+use secp256k1::SecretKey;
+use std::{
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tracing::info;
+
+/// `reth dns-tree` command
+///
+/// Builds and signs a DNS tree from a list of node records and writes it as a zone file.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The path to a file with the node records to include, one `enr:` record per line.
+    ///
+    /// Empty lines and lines starting with `#` are ignored.
+    #[arg(value_name = "ENR_FILE", verbatim_doc_comment)]
+    enrs: PathBuf,
+
+    /// The domain the tree is published under.
+    #[arg(long, value_name = "DOMAIN")]
+    domain: String,
+
+    /// The path to the secret key the tree is signed with.
+    ///
+    /// A new key is generated and written to the path if it does not exist.
+    #[arg(long, value_name = "PATH")]
+    key: PathBuf,
+
+    /// The sequence number of the tree. Must be greater than the sequence number of the currently
+    /// published tree.
+    ///
+    /// Defaults to the current unix timestamp.
+    #[arg(long, value_name = "SEQ")]
+    seq: Option<u64>,
+
+    /// Links to other trees to include, e.g. `enrtree://<key>@nodes.example.org`.
+    #[arg(long = "link", value_name = "LINK", value_delimiter = ',')]
+    links: Vec<LinkEntry>,
+
+    /// Only include nodes that advertise this fork hash in their `eth` ENR entry, e.g.
+    /// `0xfc64ec04`.
+    #[arg(long, value_name = "FORK_HASH", value_parser = fork_hash_value_parser)]
+    fork_hash: Option<ForkHash>,
+
+    /// Only include nodes that have these ENR keys, e.g. `snap`.
+    #[arg(long = "enr-key", value_name = "KEY", value_delimiter = ',')]
+    enr_keys: Vec<String>,
+
+    /// The path to write the zone file to. Prints the zone file if not set.
+    #[arg(long, short, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// The TTL of the records in seconds.
+    #[arg(long, value_name = "SECONDS", default_value_t = ZoneFilePublisher::DEFAULT_TTL)]
+    ttl: u32,
+}
+
+impl Command {
+    /// Execute `dns-tree` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        let key = get_secret_key(&self.key)?;
+        let filter = EnrFilter { fork_hash: self.fork_hash, required_keys: self.enr_keys };
+
+        let content = fs::read_to_string(&self.enrs)?;
+        let mut nodes = Vec::new();
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue
+            }
+            let enr = line
+                .parse::<Enr<SecretKey>>()
+                .map_err(|err| eyre::eyre!(err))
+                .wrap_err_with(|| format!("Invalid node record: {line}"))?;
+            if filter.matches(&enr) {
+                nodes.push(enr);
+            }
+        }
+
+        let seq = match self.seq {
+            Some(seq) => seq,
+            None => SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        };
+        let num_nodes = nodes.len();
+        let tree = DnsTree::new(&key, seq, nodes, self.links)?;
+        let zone = tree.to_zone_file(&self.domain, self.ttl);
+
+        match self.output {
+            Some(path) => {
+                fs::write(&path, zone)?;
+                info!(target: "reth::cli", link = %tree.link(&self.domain), seq, nodes = num_nodes, ?path, "Wrote DNS tree");
+            }
+            None => print!("{zone}"),
+        }
+
+        Ok(())
+    }
+}
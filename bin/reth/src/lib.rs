@@ -8,7 +8,9 @@
 //! - `jemalloc-prof`: Enables [jemallocator's](https://github.com/tikv/jemallocator) heap profiling
 //!   and leak detection functionality. See [jemalloc's opt.prof](https://jemalloc.net/jemalloc.3.html#opt.prof)
 //!   documentation for usage details. This is **not recommended on Windows**. See [here](https://rust-lang.github.io/rfcs/1974-global-allocators.html#jemalloc)
-//!   for more info.
+//!   for more info. Heap profiles can be written with `debug_writeMemProfile`.
+//! - `cpu-profiling`: Enables collecting CPU profiles in the pprof format with
+//!   `debug_startCPUProfile`, `debug_stopCPUProfile` and `debug_cpuProfile`.
 //! - `min-error-logs`: Disables all logs below `error` level.
 //! - `min-warn-logs`: Disables all logs below `warn` level.
 //! - `min-info-logs`: Disables all logs below `info` level. This can speed up the node, since fewer
@@ -35,6 +37,7 @@ pub mod config;
 pub mod db;
 pub mod debug_cmd;
 pub mod dirs;
+pub mod dns_tree;
 pub mod init;
 pub mod node;
 pub mod p2p;
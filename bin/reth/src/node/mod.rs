@@ -39,6 +39,7 @@ use reth_config::{
     Config,
 };
 use reth_db::{database::Database, init_db, DatabaseEnv};
+use reth_dns_discovery::{DnsPublishConfig, DnsTreePublisher, ZoneFilePublisher};
 use reth_downloaders::{
     bodies::bodies::BodiesDownloaderBuilder,
     headers::reverse_headers::ReverseHeadersDownloaderBuilder,
@@ -52,7 +53,10 @@ use reth_interfaces::{
     },
     RethResult,
 };
-use reth_network::{NetworkBuilder, NetworkConfig, NetworkEvents, NetworkHandle, NetworkManager};
+use reth_network::{
+    snap_requests::{SnapProtocolHandler, SnapRequestHandler, SNAP_REQUEST_CHANNEL_CAPACITY},
+    NetworkBuilder, NetworkConfig, NetworkEvents, NetworkHandle, NetworkManager,
+};
 use reth_network_api::{NetworkInfo, PeersInfo};
 use reth_primitives::{
     constants::eip4844::{LoadKzgSettingsError, MAINNET_KZG_TRUSTED_SETUP},
@@ -62,7 +66,8 @@ use reth_primitives::{
 };
 use reth_provider::{
     providers::BlockchainProvider, BlockHashReader, BlockReader, CanonStateSubscriptions,
-    HeaderProvider, HeaderSyncMode, ProviderFactory, StageCheckpointReader,
+    HeaderProvider, HeaderSyncMode, ProviderFactory, StageCheckpointReader, StateProviderFactory,
+    StateRangeReader,
 };
 use reth_prune::{segments::SegmentSet, Pruner};
 use reth_revm::EvmProcessorFactory;
@@ -79,7 +84,8 @@ use reth_stages::{
 };
 use reth_tasks::TaskExecutor;
 use reth_transaction_pool::{
-    blobstore::InMemoryBlobStore, TransactionPool, TransactionValidationTaskExecutor,
+    blobstore::InMemoryBlobStore, DynTransactionOrdering, TransactionPool,
+    TransactionValidationTaskExecutor,
 };
 use secp256k1::SecretKey;
 use std::{
@@ -87,7 +93,10 @@ use std::{
     path::PathBuf,
     sync::Arc,
 };
-use tokio::sync::{mpsc::unbounded_channel, oneshot, watch};
+use tokio::sync::{
+    mpsc::{self, unbounded_channel},
+    oneshot, watch,
+};
 use tracing::*;
 
 pub mod cl_events;
@@ -293,12 +302,15 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
             EvmProcessorFactory::new(self.chain.clone()),
         );
         let tree_config = BlockchainTreeConfig::default();
-        let tree = BlockchainTree::new(
+        let mut tree = BlockchainTree::new(
             tree_externals,
             tree_config,
             prune_config.clone().map(|config| config.segments),
         )?
         .with_sync_metrics_tx(sync_metrics_tx.clone());
+        if self.debug.persist_bad_blocks {
+            tree = tree.with_persisted_invalid_blocks(data_dir.bad_blocks_path());
+        }
         let canon_state_notification_sender = tree.canon_state_notification_sender();
         let blockchain_tree = ShareableBlockchainTree::new(tree);
         debug!(target: "reth::cli", "configured blockchain tree");
@@ -316,8 +328,12 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
             .with_additional_tasks(1)
             .build_with_tasks(blockchain_db.clone(), ctx.task_executor.clone(), blob_store.clone());
 
-        let transaction_pool =
-            reth_transaction_pool::Pool::eth_pool(validator, blob_store, self.txpool.pool_config());
+        let transaction_pool = reth_transaction_pool::Pool::new(
+            validator,
+            DynTransactionOrdering::from(self.txpool.ordering()),
+            blob_store,
+            self.txpool.pool_config(),
+        );
         info!(target: "reth::cli", "Transaction pool initialized");
 
         // spawn txpool maintenance task
@@ -338,6 +354,22 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
             debug!(target: "reth::cli", "Spawned txpool maintenance task");
         }
 
+        // spawn the task that restores and backs up local transactions
+        {
+            let pool = transaction_pool.clone();
+            let config =
+                self.txpool.local_transactions_backup_config(data_dir.txpool_transactions_path());
+            ctx.task_executor.spawn_critical_with_graceful_shutdown_signal(
+                "local transactions backup task",
+                |shutdown| {
+                    reth_transaction_pool::maintain::backup_local_transactions_task(
+                        shutdown, pool, config,
+                    )
+                },
+            );
+            debug!(target: "reth::cli", "Spawned local transactions backup task");
+        }
+
         info!(target: "reth::cli", "Connecting to P2P network");
         let network_secret_path =
             self.network.p2p_secret_key.clone().unwrap_or_else(|| data_dir.p2p_secret_path());
@@ -353,6 +385,8 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
             default_peers_path.clone(),
         );
 
+        let dns_publish = self.network.dns_publish.publish_config(secret_key)?;
+
         let network_client = network_config.client.clone();
         let mut network_builder = NetworkManager::builder(network_config).await?;
 
@@ -374,6 +408,7 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
             transaction_pool.clone(),
             network_client,
             default_peers_path,
+            dns_publish,
         );
 
         info!(target: "reth::cli", peer_id = %network.peer_id(), local_addr = %network.local_addr(), enode = %network.local_node_record(), "Connected to P2P network");
@@ -395,11 +430,20 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
         };
 
         // Configure the pipeline
-        let (mut pipeline, client) = if self.dev.dev {
+        let (mut pipeline, client, auto_seal_handle) = if self.dev.dev {
             info!(target: "reth::cli", "Starting Reth in dev mode");
 
-            let mining_mode = if let Some(interval) = self.dev.block_time {
-                MiningMode::interval(interval)
+            let mining_mode = if self.dev.manual {
+                MiningMode::manual()
+            } else if let Some(interval) = self.dev.block_time {
+                match self.dev.gas_threshold {
+                    Some(gas_threshold) => MiningMode::hybrid(
+                        interval,
+                        gas_threshold,
+                        transaction_pool.pending_transactions_listener(),
+                    ),
+                    None => MiningMode::interval(interval),
+                }
             } else if let Some(max_transactions) = self.dev.block_max_transactions {
                 MiningMode::instant(
                     max_transactions,
@@ -435,10 +479,11 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
 
             let pipeline_events = pipeline.events();
             task.set_pipeline_events(pipeline_events);
+            let auto_seal_handle = task.handle();
             debug!(target: "reth::cli", "Spawning auto mine task");
             ctx.task_executor.spawn(Box::pin(task));
 
-            (pipeline, EitherDownloader::Left(client))
+            (pipeline, EitherDownloader::Left(client), Some(auto_seal_handle))
         } else {
             let pipeline = self
                 .build_networked_pipeline(
@@ -453,7 +498,7 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
                 )
                 .await?;
 
-            (pipeline, EitherDownloader::Right(network_client))
+            (pipeline, EitherDownloader::Right(network_client), None)
         };
 
         let pipeline_events = pipeline.events();
@@ -544,8 +589,17 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
         self.adjust_instance_ports();
 
         // Start RPC servers
-        let _rpc_server_handles =
-            self.rpc.start_servers(&components, engine_api, jwt_secret, &mut self.ext).await?;
+        let _rpc_server_handles = self
+            .rpc
+            .start_servers(
+                &components,
+                engine_api,
+                jwt_secret,
+                auto_seal_handle,
+                data_dir.profiles_path(),
+                &mut self.ext,
+            )
+            .await?;
 
         // Run consensus engine to completion
         let (tx, rx) = oneshot::channel();
@@ -721,17 +775,49 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
         pool: Pool,
         client: C,
         default_peers_path: PathBuf,
+        dns_publish: Option<DnsPublishConfig>,
     ) -> NetworkHandle
     where
-        C: BlockReader + HeaderProvider + Clone + Unpin + 'static,
+        C: BlockReader
+            + HeaderProvider
+            + StateProviderFactory
+            + StateRangeReader
+            + Clone
+            + Unpin
+            + 'static,
         Pool: TransactionPool + Unpin + 'static,
     {
-        let (handle, network, txpool, eth) =
-            builder.transactions(pool).request_handler(client).split_with_handle();
+        let (handle, mut network, txpool, eth) =
+            builder.transactions(pool).request_handler(client.clone()).split_with_handle();
 
         task_executor.spawn_critical("p2p txpool", txpool);
         task_executor.spawn_critical("p2p eth request handler", eth);
 
+        if self.network.enable_snap_server {
+            let (tx, rx) = mpsc::channel(SNAP_REQUEST_CHANNEL_CAPACITY);
+            network.add_rlpx_sub_protocol(SnapProtocolHandler::new(tx));
+            task_executor
+                .spawn_critical("p2p snap request handler", SnapRequestHandler::new(client, rx));
+            info!(target: "reth::cli", "Serving snap/1");
+        }
+
+        if let (Some(config), Some(zone_file)) = (dns_publish, &self.network.dns_publish.zone_file)
+        {
+            if let Some(discv4) = network.discv4() {
+                // publish the nodes of the discv4 table that responded to an ENR request
+                let source = move || {
+                    let discv4 = discv4.clone();
+                    async move { discv4.enrs().await.unwrap_or_default() }
+                };
+                let publisher =
+                    DnsTreePublisher::new(config, source, ZoneFilePublisher::new(zone_file));
+                info!(target: "reth::cli", link = %publisher.link(), ?zone_file, "Publishing DNS tree");
+                task_executor.spawn(publisher.run());
+            } else {
+                warn!(target: "reth::cli", "Discv4 discovery is disabled, not publishing DNS tree");
+            }
+        }
+
         let known_peers_file = self.network.persistent_peers_file(default_peers_path);
         task_executor
             .spawn_critical_with_graceful_shutdown_signal("p2p network task", |shutdown| {
@@ -954,7 +1040,10 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
                     config.storage_hashing.clean_threshold,
                     config.storage_hashing.commit_threshold,
                 ))
-                .set(MerkleStage::new_execution(config.merkle.clean_threshold))
+                .set(MerkleStage::new_execution(
+                    config.merkle.clean_threshold,
+                    config.merkle.parallel,
+                ))
                 .set(TransactionLookupStage::new(
                     config.transaction_lookup.commit_threshold,
                     prune_modes.transaction_lookup,
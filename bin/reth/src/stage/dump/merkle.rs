@@ -122,6 +122,7 @@ async fn dry_run<DB: Database>(
     let mut stage = MerkleStage::Execution {
         // Forces updating the root instead of calculating from scratch
         clean_threshold: u64::MAX,
+        parallel: false,
     };
 
     loop {
@@ -4,13 +4,14 @@ use crate::{
     chain::BlockKind,
     metrics::{MakeCanonicalAction, MakeCanonicalDurationsRecorder, TreeMetrics},
     state::{BlockChainId, TreeState},
-    AppendableChain, BlockIndices, BlockchainTreeConfig, BundleStateData, TreeExternals,
+    AppendableChain, BlockIndices, BlockchainTreeConfig, BundleStateData, InvalidBlockStore,
+    TreeExternals,
 };
 use reth_db::{database::Database, DatabaseError};
 use reth_interfaces::{
     blockchain_tree::{
         error::{BlockchainTreeError, CanonicalError, InsertBlockError, InsertBlockErrorKind},
-        BlockStatus, BlockValidationKind, CanonicalOutcome, InsertPayloadOk,
+        BlockStatus, BlockValidationKind, CanonicalOutcome, InsertPayloadOk, InvalidBlock,
     },
     consensus::{Consensus, ConsensusError},
     executor::{BlockExecutionError, BlockValidationError},
@@ -30,6 +31,7 @@ use reth_provider::{
 use reth_stages::{MetricEvent, MetricEventsSender};
 use std::{
     collections::{BTreeMap, HashSet},
+    path::PathBuf,
     sync::Arc,
 };
 use tracing::{debug, error, info, instrument, trace, warn};
@@ -89,6 +91,8 @@ pub struct BlockchainTree<DB: Database, EF: ExecutorFactory> {
     /// Metrics for sync stages.
     sync_metrics_tx: Option<MetricEventsSender>,
     prune_modes: Option<PruneModes>,
+    /// The most recent blocks that failed validation or execution.
+    invalid_blocks: InvalidBlockStore,
 }
 
 impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
@@ -132,6 +136,7 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
             metrics: Default::default(),
             sync_metrics_tx: None,
             prune_modes,
+            invalid_blocks: InvalidBlockStore::default(),
         })
     }
 
@@ -141,6 +146,24 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
         self
     }
 
+    /// Persist the blocks that failed validation or execution to the given file, so they survive
+    /// restarts.
+    pub fn with_persisted_invalid_blocks(mut self, path: PathBuf) -> Self {
+        self.invalid_blocks = self.invalid_blocks.with_persistence(path);
+        self
+    }
+
+    /// Returns the most recent blocks that failed validation or execution, ordered from oldest to
+    /// newest.
+    pub fn invalid_blocks(&self) -> Vec<InvalidBlock> {
+        self.invalid_blocks.blocks().cloned().collect()
+    }
+
+    /// Records a block that was rejected before it was inserted into the tree.
+    pub fn record_invalid_block(&mut self, block: SealedBlock, error: String) {
+        self.invalid_blocks.insert(block, error);
+    }
+
     /// Check if the block is known to blockchain tree or database and return its status.
     ///
     /// Function will check:
@@ -705,7 +728,9 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
     pub fn buffer_block(&mut self, block: SealedBlockWithSenders) -> Result<(), InsertBlockError> {
         // validate block consensus rules
         if let Err(err) = self.validate_block(&block) {
-            return Err(InsertBlockError::consensus_error(err, block.block))
+            let err = InsertBlockError::consensus_error(err, block.block);
+            self.invalid_blocks.insert(err.block().clone(), err.kind().to_string());
+            return Err(err)
         }
 
         self.state.buffered_blocks.insert_block(block);
@@ -784,6 +809,21 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
         &mut self,
         block: SealedBlockWithSenders,
         block_validation_kind: BlockValidationKind,
+    ) -> Result<InsertPayloadOk, InsertBlockError> {
+        let res = self.try_insert_block(block, block_validation_kind);
+        if let Err(err) = &res {
+            if err.kind().is_invalid_block() {
+                self.invalid_blocks.insert(err.block().clone(), err.kind().to_string());
+            }
+        }
+        res
+    }
+
+    /// See [BlockchainTree::insert_block].
+    fn try_insert_block(
+        &mut self,
+        block: SealedBlockWithSenders,
+        block_validation_kind: BlockValidationKind,
     ) -> Result<InsertPayloadOk, InsertBlockError> {
         // check if we already have this block
         match self.is_block_known(block.num_hash()) {
@@ -1176,10 +1216,7 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
         let hashed_state = chain.state().hash_state_slow();
         let (state_root, trie_updates) = chain
             .state()
-            .state_root_calculator(
-                self.externals.provider_factory.provider()?.tx_ref(),
-                &hashed_state,
-            )
+            .parallel_state_root_calculator(self.externals.provider_factory.db_ref(), &hashed_state)
             .root_with_updates()
             .map_err(Into::<DatabaseError>::into)?;
         let tip = chain.tip();
@@ -11,6 +11,7 @@ use reth_interfaces::{
         BlockValidationKind,
     },
     consensus::{Consensus, ConsensusError},
+    provider::ProviderError,
     RethResult,
 };
 use reth_primitives::{
@@ -18,7 +19,7 @@ use reth_primitives::{
 };
 use reth_provider::{
     providers::BundleStateProvider, BundleStateDataProvider, BundleStateWithReceipts, Chain,
-    ExecutorFactory, StateRootProvider,
+    ExecutorFactory,
 };
 use std::{
     collections::BTreeMap,
@@ -219,8 +220,15 @@ impl AppendableChain {
         // check state root if the block extends the canonical chain __and__ if state root
         // validation was requested.
         if block_kind.extends_canonical_head() && block_validation_kind.is_exhaustive() {
-            // check state root
-            let state_root = provider.state_root(&bundle_state)?;
+            // check state root, computing the storage roots of the changed accounts in parallel
+            // on top of the canonical state
+            let mut state = provider.bundle_state_data_provider().state().clone();
+            state.extend(bundle_state.clone());
+            let hashed_state = state.hash_state_slow();
+            let state_root = state
+                .parallel_state_root_calculator(externals.provider_factory.db_ref(), &hashed_state)
+                .root()
+                .map_err(|err| ProviderError::Database(err.into()))?;
             if block.state_root != state_root {
                 return Err(ConsensusError::BodyStateRootDiff(
                     GotExpected { got: state_root, expected: block.state_root }.into(),
@@ -0,0 +1,229 @@
+//! A bounded store of blocks that were rejected by the tree.
+
+use reth_interfaces::blockchain_tree::InvalidBlock;
+use reth_primitives::{
+    fs::{self, FsPathError},
+    SealedBlock,
+};
+use std::{
+    collections::VecDeque,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread::JoinHandle,
+};
+use tracing::{debug, warn};
+
+/// The default number of invalid blocks that are retained by the [InvalidBlockStore].
+pub const DEFAULT_MAX_INVALID_BLOCKS: usize = 10;
+
+/// Keeps track of the most recent blocks that failed validation or execution.
+///
+/// Once the store is full, the oldest block is evicted. If a persistence file is configured, the
+/// store is written to it on every insert and loaded from it on startup, so the rejected blocks
+/// survive restarts and can be inspected after the fact.
+///
+/// The file is written by a background thread, so inserting never blocks on disk IO.
+#[derive(Debug)]
+pub struct InvalidBlockStore {
+    /// The rejected blocks, ordered from oldest to newest.
+    blocks: VecDeque<InvalidBlock>,
+    /// The maximum number of blocks to retain.
+    limit: usize,
+    /// The writer of the persistence file, if any.
+    writer: Option<InvalidBlockWriter>,
+}
+
+impl Default for InvalidBlockStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_INVALID_BLOCKS)
+    }
+}
+
+impl InvalidBlockStore {
+    /// Create a new in-memory store that retains at most `limit` blocks.
+    pub fn new(limit: usize) -> Self {
+        Self { blocks: VecDeque::with_capacity(limit), limit, writer: None }
+    }
+
+    /// Persist the store to the given file.
+    ///
+    /// Blocks that were previously persisted to the file are loaded into the store. If the file
+    /// can not be read, the store starts out empty and the file is overwritten on the next insert.
+    ///
+    /// Pending writes are flushed when the store is dropped.
+    pub fn with_persistence(mut self, path: PathBuf) -> Self {
+        if path.exists() {
+            match Self::load(&path) {
+                Ok(blocks) => {
+                    debug!(target: "blockchain_tree", ?path, count = blocks.len(), "Loaded invalid blocks");
+                    self.blocks = blocks;
+                    self.truncate();
+                }
+                Err(err) => {
+                    warn!(target: "blockchain_tree", %err, "Failed to load invalid blocks");
+                }
+            }
+        }
+        match InvalidBlockWriter::spawn(path) {
+            Ok(writer) => self.writer = Some(writer),
+            Err(err) => {
+                warn!(target: "blockchain_tree", %err, "Failed to spawn invalid blocks writer");
+            }
+        }
+        self
+    }
+
+    /// Returns the number of blocks in the store.
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Returns `true` if the store is empty.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Returns all blocks in the store, ordered from oldest to newest.
+    pub fn blocks(&self) -> impl Iterator<Item = &InvalidBlock> + '_ {
+        self.blocks.iter()
+    }
+
+    /// Inserts a rejected block into the store, evicting the oldest block if the store is full.
+    ///
+    /// Blocks that are already in the store are ignored.
+    pub fn insert(&mut self, block: SealedBlock, error: String) {
+        if self.limit == 0 || self.blocks.iter().any(|entry| entry.block.hash == block.hash) {
+            return
+        }
+
+        self.blocks.push_back(InvalidBlock { block, error });
+        self.truncate();
+
+        if let Some(writer) = &self.writer {
+            writer.write(self.blocks.iter().cloned().collect());
+        }
+    }
+
+    /// Evicts the oldest blocks until the store is within its limit.
+    fn truncate(&mut self) {
+        while self.blocks.len() > self.limit {
+            self.blocks.pop_front();
+        }
+    }
+
+    /// Reads the persisted blocks from the given file.
+    fn load(path: &Path) -> Result<VecDeque<InvalidBlock>, FsPathError> {
+        let json = fs::read(path)?;
+        serde_json::from_slice(&json)
+            .map_err(|source| FsPathError::ReadJson { source, path: path.to_path_buf() })
+    }
+}
+
+/// Writes snapshots of the [InvalidBlockStore] to its persistence file on a background thread.
+///
+/// Dropping the writer waits for the last snapshot to be written.
+#[derive(Debug)]
+struct InvalidBlockWriter {
+    /// Sends snapshots to the writer thread.
+    tx: Option<mpsc::Sender<Vec<InvalidBlock>>>,
+    /// The handle of the writer thread.
+    handle: Option<JoinHandle<()>>,
+}
+
+impl InvalidBlockWriter {
+    /// Spawns the writer thread for the given file.
+    fn spawn(path: PathBuf) -> std::io::Result<Self> {
+        let (tx, rx) = mpsc::channel::<Vec<InvalidBlock>>();
+        let handle = std::thread::Builder::new().name("invalid-blocks-writer".to_string()).spawn(
+            move || {
+                while let Ok(mut blocks) = rx.recv() {
+                    // only the most recent snapshot is worth writing
+                    while let Ok(next) = rx.try_recv() {
+                        blocks = next;
+                    }
+                    if let Err(err) = persist(&path, &blocks) {
+                        warn!(target: "blockchain_tree", %err, "Failed to persist invalid blocks");
+                    }
+                }
+            },
+        )?;
+        Ok(Self { tx: Some(tx), handle: Some(handle) })
+    }
+
+    /// Queues a snapshot of the store to be written.
+    fn write(&self, blocks: Vec<InvalidBlock>) {
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(blocks);
+        }
+    }
+}
+
+impl Drop for InvalidBlockWriter {
+    fn drop(&mut self) {
+        // closing the channel stops the thread once the queued snapshots are written
+        self.tx.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Writes the blocks to the given file.
+///
+/// The blocks are written to a temporary file first, which then replaces the given file, so a
+/// crash while writing never leaves a truncated file behind.
+fn persist(path: &Path, blocks: &[InvalidBlock]) -> Result<(), FsPathError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_vec(blocks)
+        .map_err(|source| FsPathError::WriteJson { source, path: path.to_path_buf() })?;
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, json)?;
+    fs::rename(&tmp_path, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::Header;
+
+    fn block(number: u64) -> SealedBlock {
+        SealedBlock {
+            header: Header { number, ..Default::default() }.seal_slow(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn evicts_oldest() {
+        let mut store = InvalidBlockStore::new(2);
+        store.insert(block(1), "1".to_string());
+        store.insert(block(2), "2".to_string());
+        store.insert(block(2), "2".to_string());
+        assert_eq!(store.len(), 2);
+
+        store.insert(block(3), "3".to_string());
+        assert_eq!(store.blocks().map(|b| b.block.number).collect::<Vec<_>>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn persists_across_restarts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("invalid-blocks.json");
+
+        let mut store = InvalidBlockStore::new(2).with_persistence(path.clone());
+        store.insert(block(1), "bad state root".to_string());
+        store.insert(block(2), "bad receipts root".to_string());
+
+        // dropping the store flushes the pending writes
+        drop(store);
+        assert!(!path.with_extension("tmp").exists());
+
+        let store = InvalidBlockStore::new(1).with_persistence(path);
+        let blocks = store.blocks().collect::<Vec<_>>();
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].block, block(2));
+        assert_eq!(blocks[0].error, "bad receipts root");
+    }
+}
@@ -42,6 +42,10 @@ pub use bundle::{BundleStateData, BundleStateDataRef};
 
 /// Buffer of not executed blocks.
 pub mod block_buffer;
+
+pub mod invalid_blocks;
+pub use invalid_blocks::InvalidBlockStore;
+
 mod canonical_chain;
 
 /// Common blockchain tree metrics.
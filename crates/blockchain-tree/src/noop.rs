@@ -2,7 +2,7 @@ use reth_interfaces::{
     blockchain_tree::{
         error::{BlockchainTreeError, InsertBlockError},
         BlockValidationKind, BlockchainTreeEngine, BlockchainTreeViewer, CanonicalOutcome,
-        InsertPayloadOk,
+        InsertPayloadOk, InvalidBlock,
     },
     RethResult,
 };
@@ -59,6 +59,8 @@ impl BlockchainTreeEngine for NoopBlockchainTree {
     fn unwind(&self, _unwind_to: BlockNumber) -> RethResult<()> {
         Ok(())
     }
+
+    fn record_invalid_block(&self, _block: SealedBlock, _error: String) {}
 }
 
 impl BlockchainTreeViewer for NoopBlockchainTree {
@@ -86,6 +88,10 @@ impl BlockchainTreeViewer for NoopBlockchainTree {
         None
     }
 
+    fn invalid_blocks(&self) -> Vec<InvalidBlock> {
+        Vec::new()
+    }
+
     fn canonical_blocks(&self) -> BTreeMap<BlockNumber, BlockHash> {
         Default::default()
     }
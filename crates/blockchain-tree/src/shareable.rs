@@ -5,7 +5,7 @@ use reth_db::database::Database;
 use reth_interfaces::{
     blockchain_tree::{
         error::InsertBlockError, BlockValidationKind, BlockchainTreeEngine, BlockchainTreeViewer,
-        CanonicalOutcome, InsertPayloadOk,
+        CanonicalOutcome, InsertPayloadOk, InvalidBlock,
     },
     RethResult,
 };
@@ -99,6 +99,11 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTreeEngine for ShareableBlockc
         tree.update_chains_metrics();
         res
     }
+
+    fn record_invalid_block(&self, block: SealedBlock, error: String) {
+        trace!(target: "blockchain_tree", hash=?block.hash, number=block.number, "Recording invalid block");
+        self.tree.write().record_invalid_block(block, error);
+    }
 }
 
 impl<DB: Database, EF: ExecutorFactory> BlockchainTreeViewer for ShareableBlockchainTree<DB, EF> {
@@ -130,6 +135,11 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTreeViewer for ShareableBlockc
         self.tree.read().get_buffered_block(&block_hash).map(|b| b.header.clone())
     }
 
+    fn invalid_blocks(&self) -> Vec<InvalidBlock> {
+        trace!(target: "blockchain_tree", "Returning invalid blocks");
+        self.tree.read().invalid_blocks()
+    }
+
     fn canonical_blocks(&self) -> BTreeMap<BlockNumber, BlockHash> {
         trace!(target: "blockchain_tree", "Returning canonical blocks in tree");
         self.tree.read().block_indices().canonical_chain().inner().clone()
@@ -249,11 +249,14 @@ pub struct MerkleConfig {
     /// The threshold (in number of blocks) for switching from incremental trie building of changes
     /// to whole rebuild.
     pub clean_threshold: u64,
+    /// Whether to compute the storage roots of changed accounts in parallel when updating the
+    /// trie incrementally.
+    pub parallel: bool,
 }
 
 impl Default for MerkleConfig {
     fn default() -> Self {
-        Self { clean_threshold: 50_000 }
+        Self { clean_threshold: 50_000, parallel: false }
     }
 }
 
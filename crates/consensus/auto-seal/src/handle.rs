@@ -0,0 +1,243 @@
+//! A handle to control the [MiningTask](crate::MiningTask), e.g. from the dev RPC namespace.
+
+use reth_interfaces::{executor::BlockExecutionError, provider::ProviderError, RethError};
+use reth_primitives::{Address, Bytes, SealedHeader, TransactionSignedEcRecovered, B256, U256};
+use tokio::sync::{mpsc::UnboundedSender, oneshot};
+
+/// Errors returned by the [AutoSealHandle].
+#[derive(Debug, thiserror::Error)]
+pub enum AutoSealError {
+    /// The mining task is no longer running.
+    #[error("mining task is not running")]
+    TaskClosed,
+    /// The block could not be built.
+    #[error(transparent)]
+    Execution(#[from] BlockExecutionError),
+    /// The engine rejected the new block.
+    #[error("block {0} was rejected by the engine")]
+    InvalidBlock(B256),
+    /// The requested timestamp is not greater than the timestamp of the latest block.
+    #[error("timestamp {timestamp} must be greater than the latest block timestamp {latest}")]
+    InvalidTimestamp {
+        /// The requested timestamp.
+        timestamp: u64,
+        /// The timestamp of the latest block.
+        latest: u64,
+    },
+    /// Failed to read or write the state.
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    /// Failed to unwind the chain.
+    #[error(transparent)]
+    Reth(#[from] RethError),
+}
+
+/// A change to an account, applied on top of its current state.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountStateChange {
+    /// The address of the account.
+    pub address: Address,
+    /// The new balance, if changed.
+    pub balance: Option<U256>,
+    /// The new nonce, if changed.
+    pub nonce: Option<u64>,
+    /// The new code, if changed. Empty code removes the code of the account.
+    pub code: Option<Bytes>,
+    /// The storage slots to change.
+    pub storage: Vec<(B256, U256)>,
+}
+
+/// Commands that are sent from the [AutoSealHandle] to the [MiningTask](crate::MiningTask).
+#[derive(Debug)]
+pub(crate) enum MiningCommand {
+    /// Mine a block with the given transactions, followed by the ready transactions of the pool.
+    Mine(Vec<TransactionSignedEcRecovered>, oneshot::Sender<Result<SealedHeader, AutoSealError>>),
+    /// Advance the clock of the chain by the given number of seconds.
+    IncreaseTime(u64, oneshot::Sender<Result<i64, AutoSealError>>),
+    /// Set the timestamp of the next block.
+    SetNextBlockTimestamp(u64, oneshot::Sender<Result<(), AutoSealError>>),
+    /// Take a snapshot of the chain.
+    Snapshot(oneshot::Sender<Result<U256, AutoSealError>>),
+    /// Revert the chain to the given snapshot.
+    Revert(U256, oneshot::Sender<Result<bool, AutoSealError>>),
+    /// Apply the given changes to the latest state.
+    ModifyState(Vec<AccountStateChange>, oneshot::Sender<Result<(), AutoSealError>>),
+}
+
+/// A handle to the [MiningTask](crate::MiningTask) that can be used to control the dev chain.
+///
+/// All commands are executed by the task in the order they are received, in between the blocks
+/// that are mined.
+#[derive(Debug, Clone)]
+pub struct AutoSealHandle {
+    to_task: UnboundedSender<MiningCommand>,
+}
+
+// === impl AutoSealHandle ===
+
+impl AutoSealHandle {
+    pub(crate) fn new(to_task: UnboundedSender<MiningCommand>) -> Self {
+        Self { to_task }
+    }
+
+    /// Sends a command to the task and waits for the response.
+    async fn send<T>(
+        &self,
+        command: impl FnOnce(oneshot::Sender<Result<T, AutoSealError>>) -> MiningCommand,
+    ) -> Result<T, AutoSealError> {
+        let (tx, rx) = oneshot::channel();
+        self.to_task.send(command(tx)).map_err(|_| AutoSealError::TaskClosed)?;
+        rx.await.map_err(|_| AutoSealError::TaskClosed)?
+    }
+
+    /// Mines a new block with the ready transactions of the pool and returns its header.
+    pub async fn mine(&self) -> Result<SealedHeader, AutoSealError> {
+        self.mine_transactions(Vec::new()).await
+    }
+
+    /// Mines a new block with the given transactions, followed by the ready transactions of the
+    /// pool, and returns its header.
+    ///
+    /// The transactions are included with their recovered signers, so they don't need a valid
+    /// signature, e.g. the transactions of impersonated accounts.
+    pub async fn mine_transactions(
+        &self,
+        transactions: Vec<TransactionSignedEcRecovered>,
+    ) -> Result<SealedHeader, AutoSealError> {
+        self.send(|tx| MiningCommand::Mine(transactions, tx)).await
+    }
+
+    /// Mines `count` blocks in a row and returns their headers.
+    ///
+    /// The blocks use the given timestamps in order, the remaining blocks use the clock of the
+    /// chain.
+    pub async fn mine_blocks(
+        &self,
+        count: usize,
+        timestamps: Vec<u64>,
+    ) -> Result<Vec<SealedHeader>, AutoSealError> {
+        let mut timestamps = timestamps.into_iter();
+        let mut headers = Vec::new();
+        for _ in 0..count {
+            if let Some(timestamp) = timestamps.next() {
+                self.set_next_block_timestamp(timestamp).await?;
+            }
+            headers.push(self.mine().await?);
+        }
+        Ok(headers)
+    }
+
+    /// Advances the clock of the chain by the given number of seconds and returns the total
+    /// offset to the system time.
+    pub async fn increase_time(&self, seconds: u64) -> Result<i64, AutoSealError> {
+        self.send(|tx| MiningCommand::IncreaseTime(seconds, tx)).await
+    }
+
+    /// Sets the timestamp of the next block.
+    ///
+    /// The clock of the chain continues from this timestamp for the following blocks.
+    pub async fn set_next_block_timestamp(&self, timestamp: u64) -> Result<(), AutoSealError> {
+        self.send(|tx| MiningCommand::SetNextBlockTimestamp(timestamp, tx)).await
+    }
+
+    /// Takes a snapshot of the chain and returns its id.
+    pub async fn snapshot(&self) -> Result<U256, AutoSealError> {
+        self.send(MiningCommand::Snapshot).await
+    }
+
+    /// Reverts the chain to the snapshot with the given id.
+    ///
+    /// Blocks that were mined after the snapshot are unwound, and state changes that were
+    /// applied after the snapshot are undone. The snapshot and all snapshots that were taken after
+    /// it are removed. Returns `false` if the snapshot does not exist.
+    pub async fn revert(&self, id: U256) -> Result<bool, AutoSealError> {
+        self.send(|tx| MiningCommand::Revert(id, tx)).await
+    }
+
+    /// Applies the given changes to the latest state.
+    ///
+    /// The changes are visible immediately and become part of the state transition of the next
+    /// block that is mined.
+    pub async fn modify_state(
+        &self,
+        changes: Vec<AccountStateChange>,
+    ) -> Result<(), AutoSealError> {
+        self.send(|tx| MiningCommand::ModifyState(changes, tx)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    #[tokio::test]
+    async fn mine_blocks_sets_timestamps() {
+        let (to_task, mut commands) = unbounded_channel();
+        let handle = AutoSealHandle::new(to_task);
+
+        let task = tokio::spawn(async move {
+            let mut executed = Vec::new();
+            while let Some(command) = commands.recv().await {
+                match command {
+                    MiningCommand::SetNextBlockTimestamp(timestamp, tx) => {
+                        executed.push(Some(timestamp));
+                        let _ = tx.send(Ok(()));
+                    }
+                    MiningCommand::Mine(transactions, tx) => {
+                        assert!(transactions.is_empty());
+                        executed.push(None);
+                        let _ = tx.send(Ok(SealedHeader::default()));
+                    }
+                    command => panic!("unexpected command {command:?}"),
+                }
+            }
+            executed
+        });
+
+        let headers = handle.mine_blocks(3, vec![10, 20]).await.unwrap();
+        assert_eq!(headers.len(), 3);
+
+        drop(handle);
+        assert_eq!(task.await.unwrap(), vec![Some(10), None, Some(20), None, None]);
+    }
+
+    #[tokio::test]
+    async fn returns_command_errors() {
+        let (to_task, mut commands) = unbounded_channel();
+        let handle = AutoSealHandle::new(to_task);
+
+        tokio::spawn(async move {
+            while let Some(command) = commands.recv().await {
+                match command {
+                    MiningCommand::Snapshot(tx) => {
+                        let _ = tx.send(Ok(U256::from(1)));
+                    }
+                    MiningCommand::SetNextBlockTimestamp(timestamp, tx) => {
+                        let _ =
+                            tx.send(Err(AutoSealError::InvalidTimestamp { timestamp, latest: 5 }));
+                    }
+                    // the reply is dropped without a response
+                    _ => {}
+                }
+            }
+        });
+
+        assert_eq!(handle.snapshot().await.unwrap(), U256::from(1));
+        assert!(matches!(
+            handle.set_next_block_timestamp(5).await,
+            Err(AutoSealError::InvalidTimestamp { timestamp: 5, latest: 5 })
+        ));
+        assert!(matches!(handle.revert(U256::from(1)).await, Err(AutoSealError::TaskClosed)));
+    }
+
+    #[tokio::test]
+    async fn task_closed() {
+        let (to_task, commands) = unbounded_channel();
+        drop(commands);
+        assert!(matches!(
+            AutoSealHandle::new(to_task).mine().await,
+            Err(AutoSealError::TaskClosed)
+        ));
+    }
+}
@@ -18,17 +18,20 @@
 
 use reth_beacon_consensus::BeaconEngineMessage;
 use reth_interfaces::{
+    blockchain_tree::BlockchainTreeEngine,
     consensus::{Consensus, ConsensusError},
-    executor::{BlockExecutionError, BlockValidationError},
+    executor::BlockExecutionError,
+    provider::{ProviderError, ProviderResult},
 };
 use reth_primitives::{
     constants::{EMPTY_RECEIPTS, EMPTY_TRANSACTIONS, ETHEREUM_BLOCK_GAS_LIMIT},
-    proofs, Address, Block, BlockBody, BlockHash, BlockHashOrNumber, BlockNumber, Bloom, ChainSpec,
-    Header, ReceiptWithBloom, SealedBlock, SealedHeader, TransactionSigned, B256,
-    EMPTY_OMMER_ROOT_HASH, U256,
+    keccak256, proofs, Address, Block, BlockBody, BlockHash, BlockHashOrNumber, BlockNumber, Bloom,
+    Bytecode, ChainSpec, Header, ReceiptWithBloom, SealedBlock, SealedHeader, StorageEntry,
+    TransactionSigned, B256, EMPTY_OMMER_ROOT_HASH, U256,
 };
 use reth_provider::{
-    BlockExecutor, BlockReaderIdExt, BundleStateWithReceipts, CanonStateNotificationSender,
+    BlockExecutor, BlockReaderIdExt, BundleStateWithReceipts, CanonChainTracker,
+    CanonStateNotificationSender, HeaderProvider, LatestStateChanges, LatestStateWriter,
     StateProviderFactory,
 };
 use reth_revm::{
@@ -37,7 +40,7 @@ use reth_revm::{
 };
 use reth_transaction_pool::TransactionPool;
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     sync::Arc,
     time::{SystemTime, UNIX_EPOCH},
 };
@@ -45,11 +48,13 @@ use tokio::sync::{mpsc::UnboundedSender, RwLock, RwLockReadGuard, RwLockWriteGua
 use tracing::{trace, warn};
 
 mod client;
+mod handle;
 mod mode;
 mod task;
 
 pub use crate::client::AutoSealClient;
-pub use mode::{FixedBlockTimeMiner, MiningMode, ReadyTransactionMiner};
+pub use handle::{AccountStateChange, AutoSealError, AutoSealHandle};
+pub use mode::{FixedBlockTimeMiner, HybridMiner, MiningMode, ReadyTransactionMiner};
 pub use task::MiningTask;
 
 /// A consensus implementation intended for local development and testing purposes.
@@ -179,7 +184,8 @@ impl Storage {
             best_block: header.number,
             ..Default::default()
         };
-        storage.headers.insert(0, header);
+        storage.hash_to_number.insert(best_hash, header.number);
+        storage.headers.insert(header.number, header);
         storage.bodies.insert(best_hash, BlockBody::default());
         Self { inner: Arc::new(RwLock::new(storage)) }
     }
@@ -210,6 +216,59 @@ pub(crate) struct StorageInner {
     pub(crate) best_hash: B256,
     /// The total difficulty of the chain until this block
     pub(crate) total_difficulty: U256,
+    /// The offset of the chain clock to the system time, in seconds.
+    pub(crate) time_offset: i64,
+    /// The timestamp of the next block, if it was set explicitly.
+    pub(crate) next_timestamp: Option<u64>,
+    /// State changes that were applied after the best block was mined.
+    pub(crate) pending_state: Vec<PendingStateChange>,
+    /// The active snapshots, by id.
+    pub(crate) snapshots: BTreeMap<U256, Snapshot>,
+    /// The id of the next snapshot.
+    pub(crate) next_snapshot_id: U256,
+}
+
+/// State changes that were written to the latest state, but are not part of a block yet.
+#[derive(Debug)]
+pub(crate) struct PendingStateChange {
+    /// The values that were written.
+    pub(crate) changes: LatestStateChanges,
+    /// The values that were replaced.
+    pub(crate) replaced: LatestStateChanges,
+}
+
+impl PendingStateChange {
+    /// Returns the values that were replaced by the given state changes, which are the values
+    /// before the first of them was applied.
+    pub(crate) fn reverts(pending_state: &[Self]) -> LatestStateChanges {
+        let mut reverts = LatestStateChanges::default();
+        for PendingStateChange { replaced, .. } in pending_state {
+            for (address, account) in &replaced.accounts {
+                reverts.accounts.entry(*address).or_insert(*account);
+            }
+            for (address, entries) in &replaced.storage {
+                let reverted = reverts.storage.entry(*address).or_default();
+                for entry in entries {
+                    // only the value before the first change is kept
+                    if !reverted.iter().any(|reverted| reverted.key == entry.key) {
+                        reverted.push(*entry);
+                    }
+                }
+            }
+        }
+        reverts
+    }
+}
+
+/// A snapshot of the chain, see [AutoSealHandle::snapshot].
+#[derive(Debug)]
+pub(crate) struct Snapshot {
+    /// The best block at the time of the snapshot.
+    pub(crate) number: BlockNumber,
+    /// The offset of the chain clock at the time of the snapshot.
+    pub(crate) time_offset: i64,
+    /// The state changes that were pending at the time of the snapshot.
+    pub(crate) pending_state: Vec<LatestStateChanges>,
 }
 
 // === impl StorageInner ===
@@ -247,6 +306,205 @@ impl StorageInner {
         self.hash_to_number.insert(self.best_hash, self.best_block);
     }
 
+    /// Advances the chain clock by the given number of seconds and returns the new offset to the
+    /// system time.
+    pub(crate) fn increase_time(&mut self, seconds: u64) -> i64 {
+        self.time_offset = self.time_offset.saturating_add(seconds.try_into().unwrap_or(i64::MAX));
+        self.time_offset
+    }
+
+    /// Sets the timestamp of the next block, which must be greater than the timestamp of the best
+    /// block.
+    pub(crate) fn set_next_block_timestamp(&mut self, timestamp: u64) -> Result<(), AutoSealError> {
+        let latest = self.headers.get(&self.best_block).map(|header| header.timestamp);
+        if let Some(latest) = latest.filter(|latest| timestamp <= *latest) {
+            return Err(AutoSealError::InvalidTimestamp { timestamp, latest })
+        }
+        self.next_timestamp = Some(timestamp);
+        Ok(())
+    }
+
+    /// Takes a snapshot of the chain and returns its id.
+    pub(crate) fn snapshot(&mut self) -> U256 {
+        let id = self.next_snapshot_id;
+        self.next_snapshot_id += U256::from(1);
+        self.snapshots.insert(
+            id,
+            Snapshot {
+                number: self.best_block,
+                time_offset: self.time_offset,
+                pending_state: self.pending_state.iter().map(|p| p.changes.clone()).collect(),
+            },
+        );
+        id
+    }
+
+    /// Applies the given changes on top of the latest state.
+    pub(crate) fn modify_state<C>(
+        &mut self,
+        changes: Vec<AccountStateChange>,
+        client: &C,
+    ) -> Result<(), AutoSealError>
+    where
+        C: StateProviderFactory + LatestStateWriter,
+    {
+        let state = client.latest()?;
+        let mut accounts = BTreeMap::new();
+        let mut storage = BTreeMap::<_, BTreeMap<_, _>>::new();
+        let mut bytecodes = Vec::new();
+
+        for change in changes {
+            let AccountStateChange { address, balance, nonce, code, storage: slots } = change;
+            let account = match accounts.get(&address) {
+                Some(account) => *account,
+                None => state.basic_account(address)?,
+            };
+            let mut account = account.unwrap_or_default();
+            if let Some(balance) = balance {
+                account.balance = balance;
+            }
+            if let Some(nonce) = nonce {
+                account.nonce = nonce;
+            }
+            if let Some(code) = code {
+                account.bytecode_hash = if code.is_empty() {
+                    None
+                } else {
+                    let hash = keccak256(&code);
+                    bytecodes.push((hash, Bytecode::new_raw(code)));
+                    Some(hash)
+                };
+            }
+            accounts.insert(address, Some(account));
+            storage.entry(address).or_default().extend(slots);
+        }
+
+        let storage = storage
+            .into_iter()
+            .map(|(address, slots)| {
+                let entries =
+                    slots.into_iter().map(|(key, value)| StorageEntry { key, value }).collect();
+                (address, entries)
+            })
+            .collect();
+        let changes = LatestStateChanges { accounts, storage, bytecodes };
+
+        trace!(target: "consensus::auto", ?changes, "modifying state");
+        let replaced = client.write_latest_state(changes.clone())?;
+        self.pending_state.push(PendingStateChange { changes, replaced });
+        Ok(())
+    }
+
+    /// Takes the state changes that were applied since the best block was mined, which become
+    /// part of the next block.
+    pub(crate) fn take_pending_state(&mut self) -> Vec<PendingStateChange> {
+        std::mem::take(&mut self.pending_state)
+    }
+
+    /// Removes the best block, which could not be committed, and restores the given state changes
+    /// that were pending before it was mined.
+    ///
+    /// The block is unwound if it was already made canonical.
+    pub(crate) fn discard_best_block<C>(
+        &mut self,
+        pending_state: Vec<PendingStateChange>,
+        client: &C,
+    ) -> Result<(), AutoSealError>
+    where
+        C: BlockchainTreeEngine + HeaderProvider + CanonChainTracker,
+    {
+        let parent = self.best_block.saturating_sub(1);
+        client.unwind(parent)?;
+
+        let header =
+            client.sealed_header(parent)?.ok_or(ProviderError::HeaderNotFound(parent.into()))?;
+        client.set_canonical_head(header.clone());
+        client.set_safe(header.clone());
+        client.set_finalized(header.clone());
+        self.unwind_to(header);
+        self.pending_state = pending_state;
+        Ok(())
+    }
+
+    /// Undoes the pending state changes, starting at the given index.
+    fn undo_pending_state(
+        &mut self,
+        from: usize,
+        client: &impl LatestStateWriter,
+    ) -> ProviderResult<()> {
+        for pending in self.pending_state.drain(from..).rev() {
+            client.write_latest_state(pending.replaced)?;
+        }
+        Ok(())
+    }
+
+    /// Reverts the chain to the snapshot with the given id.
+    ///
+    /// Returns `false` if there is no such snapshot.
+    pub(crate) fn revert<C>(&mut self, id: U256, client: &C) -> Result<bool, AutoSealError>
+    where
+        C: LatestStateWriter + BlockchainTreeEngine + HeaderProvider + CanonChainTracker,
+    {
+        let Some(snapshot) = self.snapshots.remove(&id) else { return Ok(false) };
+        // snapshots that were taken later are no longer valid
+        self.snapshots.retain(|snapshot_id, _| *snapshot_id < id);
+
+        if self.best_block > snapshot.number {
+            // the pending changes are not part of a block, so they need to be undone before the
+            // blocks are unwound
+            self.undo_pending_state(0, client)?;
+            client.unwind(snapshot.number)?;
+
+            let header = client
+                .sealed_header(snapshot.number)?
+                .ok_or(ProviderError::HeaderNotFound(snapshot.number.into()))?;
+            client.set_canonical_head(header.clone());
+            client.set_safe(header.clone());
+            client.set_finalized(header.clone());
+            self.unwind_to(header);
+
+            for changes in snapshot.pending_state {
+                let replaced = client.write_latest_state(changes.clone())?;
+                self.pending_state.push(PendingStateChange { changes, replaced });
+            }
+        } else {
+            self.undo_pending_state(snapshot.pending_state.len(), client)?;
+        }
+
+        self.time_offset = snapshot.time_offset;
+        self.next_timestamp = None;
+        Ok(true)
+    }
+
+    /// Removes all blocks after the given header and makes it the best block.
+    fn unwind_to(&mut self, header: SealedHeader) {
+        let (header, hash) = header.split();
+        let number = header.number;
+
+        let removed = self
+            .hash_to_number
+            .iter()
+            .filter(|(_, num)| **num > number)
+            .map(|(hash, _)| *hash)
+            .collect::<Vec<_>>();
+        for hash in removed {
+            self.hash_to_number.remove(&hash);
+            self.bodies.remove(&hash);
+        }
+        let total_difficulty = &mut self.total_difficulty;
+        self.headers.retain(|num, header| {
+            if *num > number {
+                *total_difficulty -= header.difficulty;
+            }
+            *num <= number
+        });
+
+        self.hash_to_number.insert(hash, number);
+        self.headers.entry(number).or_insert(header);
+        self.best_block = number;
+        self.best_hash = hash;
+    }
+
     /// Fills in pre-execution header fields based on the current best block and given
     /// transactions.
     pub(crate) fn build_header_template(
@@ -254,7 +512,9 @@ impl StorageInner {
         transactions: &[TransactionSigned],
         chain_spec: Arc<ChainSpec>,
     ) -> Header {
-        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let timestamp = self
+            .next_timestamp
+            .unwrap_or_else(|| unix_timestamp().saturating_add_signed(self.time_offset));
 
         // check previous block for base fee
         let base_fee_per_gas = self
@@ -374,16 +634,18 @@ impl StorageInner {
     pub(crate) fn build_and_execute(
         &mut self,
         transactions: Vec<TransactionSigned>,
+        senders: Vec<Address>,
         client: &impl StateProviderFactory,
         chain_spec: Arc<ChainSpec>,
     ) -> Result<(SealedHeader, BundleStateWithReceipts), BlockExecutionError> {
         let header = self.build_header_template(&transactions, chain_spec.clone());
+        if self.next_timestamp.take().is_some() {
+            // the clock continues from the explicitly set timestamp
+            self.time_offset = header.timestamp as i64 - unix_timestamp() as i64;
+        }
 
         let block = Block { header, body: transactions, ommers: vec![], withdrawals: None };
 
-        let senders = TransactionSigned::recover_signers(&block.body, block.body.len())
-            .ok_or(BlockExecutionError::Validation(BlockValidationError::SenderRecoveryError))?;
-
         trace!(target: "consensus::auto", transactions=?&block.body, "executing transactions");
 
         // now execute the block
@@ -421,3 +683,95 @@ impl StorageInner {
         Ok((new_header, bundle_state))
     }
 }
+
+/// Returns the current unix timestamp in seconds.
+fn unix_timestamp() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::Account;
+
+    fn account(nonce: u64) -> Option<Account> {
+        Some(Account { nonce, ..Default::default() })
+    }
+
+    #[test]
+    fn pending_reverts_keep_first_values() {
+        let address = Address::random();
+        let key = B256::random();
+        let change = |nonce: u64, value: u64| PendingStateChange {
+            changes: LatestStateChanges::default(),
+            replaced: LatestStateChanges {
+                accounts: BTreeMap::from([(address, account(nonce))]),
+                storage: BTreeMap::from([(
+                    address,
+                    vec![StorageEntry { key, value: U256::from(value) }],
+                )]),
+                bytecodes: Vec::new(),
+            },
+        };
+
+        let reverts = PendingStateChange::reverts(&[change(1, 10), change(2, 20)]);
+        assert_eq!(reverts.accounts, BTreeMap::from([(address, account(1))]));
+        assert_eq!(
+            reverts.storage,
+            BTreeMap::from([(address, vec![StorageEntry { key, value: U256::from(10) }])])
+        );
+    }
+
+    #[test]
+    fn next_block_timestamp_must_increase() {
+        let mut storage = StorageInner::default();
+        storage.insert_new_block(
+            Header { timestamp: 100, ..Default::default() },
+            BlockBody::default(),
+        );
+
+        assert!(matches!(
+            storage.set_next_block_timestamp(100),
+            Err(AutoSealError::InvalidTimestamp { timestamp: 100, latest: 100 })
+        ));
+        assert!(storage.set_next_block_timestamp(101).is_ok());
+        assert_eq!(storage.next_timestamp, Some(101));
+    }
+
+    #[test]
+    fn snapshots_record_chain() {
+        let mut storage = StorageInner::default();
+        storage.increase_time(10);
+
+        let first = storage.snapshot();
+        storage.insert_new_block(Header::default(), BlockBody::default());
+        let second = storage.snapshot();
+
+        assert_eq!(second, first + U256::from(1));
+        assert_eq!(storage.snapshots[&first].number, 0);
+        assert_eq!(storage.snapshots[&first].time_offset, 10);
+        assert_eq!(storage.snapshots[&second].number, 1);
+    }
+
+    #[test]
+    fn unwind_removes_later_blocks() {
+        let mut storage = StorageInner::default();
+        storage.insert_new_block(
+            Header { difficulty: U256::from(1), ..Default::default() },
+            BlockBody::default(),
+        );
+        let first = storage.headers[&1].clone().seal(storage.best_hash);
+        storage.insert_new_block(
+            Header { difficulty: U256::from(2), ..Default::default() },
+            BlockBody::default(),
+        );
+        assert_eq!(storage.best_block, 2);
+
+        storage.unwind_to(first.clone());
+        assert_eq!(storage.best_block, 1);
+        assert_eq!(storage.best_hash, first.hash);
+        assert_eq!(storage.total_difficulty, U256::from(1));
+        assert_eq!(storage.block_hash(2), None);
+        assert_eq!(storage.bodies.len(), 1);
+    }
+}
@@ -25,6 +25,12 @@ pub enum MiningMode {
     Auto(ReadyTransactionMiner),
     /// A miner that constructs a new block every `interval` tick
     FixedBlockTime(FixedBlockTimeMiner),
+    /// A miner that only constructs blocks when requested via the
+    /// [AutoSealHandle](crate::AutoSealHandle).
+    Manual,
+    /// A miner that constructs a new block every `interval` tick, but also as soon as the ready
+    /// transactions exceed a gas threshold.
+    Hybrid(HybridMiner),
 }
 
 // === impl MiningMode ===
@@ -45,6 +51,22 @@ impl MiningMode {
         MiningMode::FixedBlockTime(FixedBlockTimeMiner::new(duration))
     }
 
+    /// Creates a new manual miner that only builds blocks when requested via the
+    /// [AutoSealHandle](crate::AutoSealHandle).
+    pub fn manual() -> Self {
+        MiningMode::Manual
+    }
+
+    /// Creates a new hybrid miner that builds a block every `duration`, or as soon as the gas
+    /// limits of the ready transactions add up to at least `gas_threshold`.
+    pub fn hybrid(duration: Duration, gas_threshold: u64, listener: Receiver<TxHash>) -> Self {
+        MiningMode::Hybrid(HybridMiner {
+            interval: FixedBlockTimeMiner::new(duration),
+            gas_threshold,
+            rx: ReceiverStream::new(listener).fuse(),
+        })
+    }
+
     /// polls the Pool and returns those transactions that should be put in a block, if any.
     pub(crate) fn poll<Pool>(
         &mut self,
@@ -55,9 +77,10 @@ impl MiningMode {
         Pool: TransactionPool,
     {
         match self {
-            MiningMode::None => Poll::Pending,
+            MiningMode::None | MiningMode::Manual => Poll::Pending,
             MiningMode::Auto(miner) => miner.poll(pool, cx),
             MiningMode::FixedBlockTime(miner) => miner.poll(pool, cx),
+            MiningMode::Hybrid(miner) => miner.poll(pool, cx),
         }
     }
 }
@@ -103,6 +126,58 @@ impl Default for FixedBlockTimeMiner {
     }
 }
 
+/// A miner that creates a new block every `interval`, or as soon as the ready transactions exceed
+/// the gas threshold, whichever comes first.
+///
+/// The interval restarts whenever a block is created because of the gas threshold.
+pub struct HybridMiner {
+    /// The interval between blocks
+    interval: FixedBlockTimeMiner,
+    /// The gas at which a block is created before the interval elapsed
+    gas_threshold: u64,
+    /// Receives hashes of transactions that are ready
+    rx: Fuse<ReceiverStream<TxHash>>,
+}
+
+// === impl HybridMiner ===
+
+impl HybridMiner {
+    fn poll<Pool>(
+        &mut self,
+        pool: &Pool,
+        cx: &mut Context<'_>,
+    ) -> Poll<Vec<Arc<ValidPoolTransaction<<Pool as TransactionPool>::Transaction>>>>
+    where
+        Pool: TransactionPool,
+    {
+        let mut has_new_txs = false;
+        while let Poll::Ready(Some(_hash)) = Pin::new(&mut self.rx).poll_next(cx) {
+            has_new_txs = true;
+        }
+
+        if has_new_txs {
+            let transactions = pool.best_transactions().collect::<Vec<_>>();
+            // gas limits are chosen by the senders, so don't overflow on huge ones
+            let gas = transactions.iter().fold(0u64, |gas, tx| gas.saturating_add(tx.gas_limit()));
+            if gas >= self.gas_threshold {
+                self.interval.interval.reset();
+                return Poll::Ready(transactions)
+            }
+        }
+
+        self.interval.poll(pool, cx)
+    }
+}
+
+impl fmt::Debug for HybridMiner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HybridMiner")
+            .field("interval", &self.interval)
+            .field("gas_threshold", &self.gas_threshold)
+            .finish_non_exhaustive()
+    }
+}
+
 /// A miner that Listens for new ready transactions
 pub struct ReadyTransactionMiner {
     /// how many transactions to mine per block
@@ -153,3 +228,65 @@ impl fmt::Debug for ReadyTransactionMiner {
             .finish_non_exhaustive()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::task::noop_waker_ref;
+    use reth_transaction_pool::test_utils::{testing_pool, MockTransaction, TestPool};
+    use tokio::sync::mpsc::{channel, Sender};
+
+    /// Polls the miner once and returns the number of transactions it wants to mine.
+    fn poll_miner(mode: &mut MiningMode, pool: &TestPool) -> Poll<usize> {
+        let mut cx = Context::from_waker(noop_waker_ref());
+        mode.poll(pool, &mut cx).map(|transactions| transactions.len())
+    }
+
+    /// Adds a transaction with the given gas limit to the pool and notifies the miner about it.
+    async fn add_transaction(pool: &TestPool, listener: &Sender<TxHash>, gas_limit: u64) {
+        let hash = pool
+            .add_external_transaction(MockTransaction::eip1559().with_gas_limit(gas_limit))
+            .await
+            .unwrap();
+        listener.send(hash).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn manual_ignores_transactions() {
+        let pool = testing_pool();
+        let (listener, _rx) = channel(10);
+        add_transaction(&pool, &listener, 21_000).await;
+
+        let mut mode = MiningMode::manual();
+        assert_eq!(poll_miner(&mut mode, &pool), Poll::Pending);
+    }
+
+    #[tokio::test]
+    async fn hybrid_mines_at_gas_threshold() {
+        let pool = testing_pool();
+        let (listener, rx) = channel(10);
+        let mut mode = MiningMode::hybrid(Duration::from_secs(3600), 50_000, rx);
+
+        add_transaction(&pool, &listener, 21_000).await;
+        assert_eq!(poll_miner(&mut mode, &pool), Poll::Pending);
+
+        add_transaction(&pool, &listener, 30_000).await;
+        assert_eq!(poll_miner(&mut mode, &pool), Poll::Ready(2));
+
+        // the transactions are only reconsidered once new ones arrive or the interval elapses
+        assert_eq!(poll_miner(&mut mode, &pool), Poll::Pending);
+    }
+
+    #[tokio::test]
+    async fn hybrid_mines_on_interval() {
+        let pool = testing_pool();
+        let (listener, rx) = channel(10);
+        let mut mode = MiningMode::hybrid(Duration::from_millis(10), u64::MAX, rx);
+
+        add_transaction(&pool, &listener, 21_000).await;
+        assert_eq!(poll_miner(&mut mode, &pool), Poll::Pending);
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(poll_miner(&mut mode, &pool), Poll::Ready(1));
+    }
+}
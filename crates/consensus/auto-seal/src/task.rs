@@ -1,11 +1,24 @@
-use crate::{mode::MiningMode, Storage};
+use crate::{
+    handle::{AutoSealError, AutoSealHandle, MiningCommand},
+    mode::MiningMode,
+    PendingStateChange, Storage, StorageInner,
+};
 use futures_util::{future::BoxFuture, FutureExt};
 use reth_beacon_consensus::{BeaconEngineMessage, ForkchoiceStatus};
-use reth_interfaces::consensus::ForkchoiceState;
-use reth_primitives::{Block, ChainSpec, IntoRecoveredTransaction, SealedBlockWithSenders};
-use reth_provider::{CanonChainTracker, CanonStateNotificationSender, Chain, StateProviderFactory};
+use reth_interfaces::{
+    blockchain_tree::{BlockValidationKind, BlockchainTreeEngine},
+    consensus::ForkchoiceState,
+};
+use reth_primitives::{
+    Block, ChainSpec, IntoRecoveredTransaction, SealedBlockWithSenders, SealedHeader,
+    TransactionSignedEcRecovered,
+};
+use reth_provider::{
+    CanonChainTracker, CanonStateNotificationSender, Chain, HeaderProvider, LatestStateWriter,
+    StateProviderFactory,
+};
 use reth_stages::PipelineEvent;
-use reth_transaction_pool::{TransactionPool, ValidPoolTransaction};
+use reth_transaction_pool::TransactionPool;
 use std::{
     collections::VecDeque,
     future::Future,
@@ -13,7 +26,10 @@ use std::{
     sync::Arc,
     task::{Context, Poll},
 };
-use tokio::sync::{mpsc::UnboundedSender, oneshot};
+use tokio::sync::{
+    mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+    oneshot,
+};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tracing::{debug, error, warn};
 
@@ -25,14 +41,18 @@ pub struct MiningTask<Client, Pool: TransactionPool> {
     client: Client,
     /// The active miner
     miner: MiningMode,
-    /// Single active future that inserts a new block into `storage`
+    /// Single active future that inserts a new block into `storage` or executes a command
     insert_task: Option<BoxFuture<'static, Option<UnboundedReceiverStream<PipelineEvent>>>>,
     /// Shared storage to insert new blocks
     storage: Storage,
     /// Pool where transactions are stored
     pool: Pool,
-    /// backlog of sets of transactions ready to be mined
-    queued: VecDeque<Vec<Arc<ValidPoolTransaction<<Pool as TransactionPool>::Transaction>>>>,
+    /// backlog of sets of transactions ready to be mined and commands to execute
+    queued: VecDeque<QueuedJob>,
+    /// Sender half of the command channel, cloned into every [AutoSealHandle]
+    to_task: UnboundedSender<MiningCommand>,
+    /// Commands sent by the [AutoSealHandle]s
+    commands: UnboundedReceiver<MiningCommand>,
     /// TODO: ideally this would just be a sender of hashes
     to_engine: UnboundedSender<BeaconEngineMessage>,
     /// Used to notify consumers of new blocks
@@ -54,6 +74,7 @@ impl<Client, Pool: TransactionPool> MiningTask<Client, Pool> {
         client: Client,
         pool: Pool,
     ) -> Self {
+        let (to_task, commands) = unbounded_channel();
         Self {
             chain_spec,
             client,
@@ -64,10 +85,17 @@ impl<Client, Pool: TransactionPool> MiningTask<Client, Pool> {
             to_engine,
             canon_state_notification,
             queued: Default::default(),
+            to_task,
+            commands,
             pipe_line_events: None,
         }
     }
 
+    /// Returns a new [AutoSealHandle] that can be used to control the task.
+    pub fn handle(&self) -> AutoSealHandle {
+        AutoSealHandle::new(self.to_task.clone())
+    }
+
     /// Sets the pipeline events to listen on.
     pub fn set_pipeline_events(&mut self, events: UnboundedReceiverStream<PipelineEvent>) {
         self.pipe_line_events = Some(events);
@@ -76,7 +104,14 @@ impl<Client, Pool: TransactionPool> MiningTask<Client, Pool> {
 
 impl<Client, Pool> Future for MiningTask<Client, Pool>
 where
-    Client: StateProviderFactory + CanonChainTracker + Clone + Unpin + 'static,
+    Client: StateProviderFactory
+        + CanonChainTracker
+        + LatestStateWriter
+        + BlockchainTreeEngine
+        + HeaderProvider
+        + Clone
+        + Unpin
+        + 'static,
     Pool: TransactionPool + Unpin + 'static,
     <Pool as TransactionPool>::Transaction: IntoRecoveredTransaction,
 {
@@ -87,9 +122,30 @@ where
 
         // this drives block production and
         loop {
+            // queue all commands in the order they were received
+            while let Poll::Ready(Some(command)) = this.commands.poll_recv(cx) {
+                let job = match command {
+                    MiningCommand::Mine(transactions, tx) => QueuedJob::Block {
+                        transactions: transactions
+                            .into_iter()
+                            .chain(
+                                this.pool
+                                    .best_transactions()
+                                    .map(|tx| tx.to_recovered_transaction()),
+                            )
+                            .collect(),
+                        tx: Some(tx),
+                    },
+                    command => QueuedJob::Command(command),
+                };
+                this.queued.push_back(job);
+            }
+
             if let Poll::Ready(transactions) = this.miner.poll(&this.pool, cx) {
                 // miner returned a set of transaction that we feed to the producer
-                this.queued.push_back(transactions);
+                let transactions =
+                    transactions.iter().map(|tx| tx.to_recovered_transaction()).collect();
+                this.queued.push_back(QueuedJob::Block { transactions, tx: None });
             }
 
             if this.insert_task.is_none() {
@@ -100,7 +156,19 @@ where
 
                 // ready to queue in new insert task
                 let storage = this.storage.clone();
-                let transactions = this.queued.pop_front().expect("not empty");
+                let (transactions, reply) = match this.queued.pop_front().expect("not empty") {
+                    QueuedJob::Block { transactions, tx } => (transactions, tx),
+                    QueuedJob::Command(command) => {
+                        let client = this.client.clone();
+                        let events = this.pipe_line_events.take();
+                        this.insert_task = Some(Box::pin(async move {
+                            let mut storage = storage.write().await;
+                            execute_command(&mut storage, command, &client);
+                            events
+                        }));
+                        continue
+                    }
+                };
 
                 let to_engine = this.to_engine.clone();
                 let client = this.client.clone();
@@ -112,36 +180,70 @@ where
                 // Create the mining future that creates a block, notifies the engine that drives
                 // the pipeline
                 this.insert_task = Some(Box::pin(async move {
+                    let shared_storage = storage.clone();
                     let mut storage = storage.write().await;
 
                     let (transactions, senders): (Vec<_>, Vec<_>) = transactions
                         .into_iter()
                         .map(|tx| {
-                            let recovered = tx.to_recovered_transaction();
-                            let signer = recovered.signer();
-                            (recovered.into_signed(), signer)
+                            let signer = tx.signer();
+                            (tx.into_signed(), signer)
                         })
                         .unzip();
 
-                    match storage.build_and_execute(transactions.clone(), &client, chain_spec) {
+                    match storage.build_and_execute(
+                        transactions.clone(),
+                        senders.clone(),
+                        &client,
+                        chain_spec,
+                    ) {
                         Ok((new_header, bundle_state)) => {
                             // clear all transactions from pool
                             pool.remove_transactions(
                                 transactions.iter().map(|tx| tx.hash()).collect(),
                             );
 
+                            // seal the block
+                            let block = Block {
+                                header: new_header.clone().unseal(),
+                                body: transactions,
+                                ommers: vec![],
+                                withdrawals: None,
+                            };
+                            let sealed_block = block.seal_slow();
+
+                            let sealed_block_with_senders =
+                                SealedBlockWithSenders::new(sealed_block, senders)
+                                    .expect("senders are valid");
+
                             let state = ForkchoiceState {
                                 head_block_hash: new_header.hash,
                                 finalized_block_hash: new_header.hash,
                                 safe_block_hash: new_header.hash,
                             };
+                            // state changes that were applied since the last block are part of
+                            // the new block
+                            let pending_state = storage.take_pending_state();
                             drop(storage);
 
+                            // the block is inserted with its senders, because the signers of
+                            // transactions of impersonated accounts can't be recovered
+                            if let Err(err) = client.insert_block(
+                                sealed_block_with_senders.clone(),
+                                BlockValidationKind::Exhaustive,
+                            ) {
+                                error!(target: "consensus::auto", %err, "Failed to insert block");
+                                let err = AutoSealError::InvalidBlock(new_header.hash);
+                                discard_block(&shared_storage, pending_state, &client, reply, err)
+                                    .await;
+                                return events
+                            }
+
                             // TODO: make this a future
                             // await the fcu call rx for SYNCING, then wait for a VALID response
                             loop {
                                 // send the new update to the engine, this will trigger the engine
-                                // to download and execute the block we just inserted
+                                // to make the block we just inserted canonical
                                 let (tx, rx) = oneshot::channel();
                                 let _ = to_engine.send(BeaconEngineMessage::ForkchoiceUpdated {
                                     state,
@@ -156,6 +258,16 @@ where
                                             ForkchoiceStatus::Valid => break,
                                             ForkchoiceStatus::Invalid => {
                                                 error!(target: "consensus::auto", ?fcu_response, "Forkchoice update returned invalid response");
+                                                let err =
+                                                    AutoSealError::InvalidBlock(new_header.hash);
+                                                discard_block(
+                                                    &shared_storage,
+                                                    pending_state,
+                                                    &client,
+                                                    reply,
+                                                    err,
+                                                )
+                                                .await;
                                                 return None
                                             }
                                             ForkchoiceStatus::Syncing => {
@@ -167,23 +279,39 @@ where
                                     }
                                     Err(err) => {
                                         error!(target: "consensus::auto", ?err, "Autoseal fork choice update failed");
+                                        let err = AutoSealError::InvalidBlock(new_header.hash);
+                                        discard_block(
+                                            &shared_storage,
+                                            pending_state,
+                                            &client,
+                                            reply,
+                                            err,
+                                        )
+                                        .await;
                                         return None
                                     }
                                 }
                             }
 
-                            // seal the block
-                            let block = Block {
-                                header: new_header.clone().unseal(),
-                                body: transactions,
-                                ommers: vec![],
-                                withdrawals: None,
-                            };
-                            let sealed_block = block.seal_slow();
-
-                            let sealed_block_with_senders =
-                                SealedBlockWithSenders::new(sealed_block, senders)
-                                    .expect("senders are valid");
+                            let reverts = PendingStateChange::reverts(&pending_state);
+                            if !reverts.is_empty() {
+                                if let Err(err) =
+                                    client.write_state_reverts(new_header.number, reverts)
+                                {
+                                    // without the reverts, unwinding the block would not undo the
+                                    // pending changes, so the block is not committed
+                                    error!(target: "consensus::auto", ?err, "Failed to write state reverts");
+                                    discard_block(
+                                        &shared_storage,
+                                        pending_state,
+                                        &client,
+                                        reply,
+                                        err.into(),
+                                    )
+                                    .await;
+                                    return events
+                                }
+                            }
 
                             // update canon chain for rpc
                             client.set_canonical_head(new_header.clone());
@@ -198,9 +326,16 @@ where
                             // send block notification
                             let _ = canon_state_notification
                                 .send(reth_provider::CanonStateNotification::Commit { new: chain });
+
+                            if let Some(reply) = reply {
+                                let _ = reply.send(Ok(new_header));
+                            }
                         }
                         Err(err) => {
-                            warn!(target: "consensus::auto", ?err, "failed to execute block")
+                            warn!(target: "consensus::auto", ?err, "failed to execute block");
+                            if let Some(reply) = reply {
+                                let _ = reply.send(Err(err.into()));
+                            }
                         }
                     }
 
@@ -225,6 +360,66 @@ where
     }
 }
 
+/// A unit of work of the [MiningTask].
+enum QueuedJob {
+    /// Mine a block with the given transactions and optionally reply with its header.
+    Block {
+        transactions: Vec<TransactionSignedEcRecovered>,
+        tx: Option<oneshot::Sender<Result<SealedHeader, AutoSealError>>>,
+    },
+    /// Execute a command of an [AutoSealHandle].
+    Command(MiningCommand),
+}
+
+/// Removes the best block from the storage after it could not be committed, restores the state
+/// changes that were pending before it and replies with the given error.
+async fn discard_block<Client>(
+    storage: &Storage,
+    pending_state: Vec<PendingStateChange>,
+    client: &Client,
+    reply: Option<oneshot::Sender<Result<SealedHeader, AutoSealError>>>,
+    err: AutoSealError,
+) where
+    Client: BlockchainTreeEngine + HeaderProvider + CanonChainTracker,
+{
+    if let Err(err) = storage.write().await.discard_best_block(pending_state, client) {
+        error!(target: "consensus::auto", ?err, "Failed to discard block");
+    }
+    if let Some(reply) = reply {
+        let _ = reply.send(Err(err));
+    }
+}
+
+/// Executes the command and sends the result to the [AutoSealHandle].
+fn execute_command<Client>(storage: &mut StorageInner, command: MiningCommand, client: &Client)
+where
+    Client: StateProviderFactory
+        + CanonChainTracker
+        + LatestStateWriter
+        + BlockchainTreeEngine
+        + HeaderProvider,
+{
+    debug!(target: "consensus::auto", ?command, "Executing command");
+    match command {
+        MiningCommand::IncreaseTime(seconds, tx) => {
+            let _ = tx.send(Ok(storage.increase_time(seconds)));
+        }
+        MiningCommand::SetNextBlockTimestamp(timestamp, tx) => {
+            let _ = tx.send(storage.set_next_block_timestamp(timestamp));
+        }
+        MiningCommand::Snapshot(tx) => {
+            let _ = tx.send(Ok(storage.snapshot()));
+        }
+        MiningCommand::Revert(id, tx) => {
+            let _ = tx.send(storage.revert(id, client));
+        }
+        MiningCommand::ModifyState(changes, tx) => {
+            let _ = tx.send(storage.modify_state(changes, client));
+        }
+        MiningCommand::Mine(..) => unreachable!("mining commands are queued as blocks"),
+    }
+}
+
 impl<Client, Pool: TransactionPool> std::fmt::Debug for MiningTask<Client, Pool> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("MiningTask").finish_non_exhaustive()
@@ -0,0 +1,171 @@
+//! anvil namespace integration test
+use clap::Parser;
+use jsonrpsee::{core::client::ClientT, http_client::HttpClientBuilder, rpc_params};
+use reth::{
+    cli::{
+        components::RethNodeComponents,
+        ext::{NoArgs, NoArgsCliExt, RethNodeCommandConfig},
+    },
+    node::NodeCommand,
+    runner::CliRunner,
+    tasks::TaskSpawner,
+};
+use reth_primitives::{ChainSpec, Genesis, B256, U256};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// Account that is funded in the genesis.
+const SENDER: &str = "0x6be02d1d3665660d22ff9624b7be0551ee1ac91b";
+const RECIPIENT: &str = "0x0000000000000000000000000000000000001234";
+
+#[derive(Debug)]
+struct AnvilConfig;
+
+impl RethNodeCommandConfig for AnvilConfig {
+    fn on_node_started<Reth: RethNodeComponents>(&mut self, components: &Reth) -> eyre::Result<()> {
+        components.task_executor().spawn_critical_blocking(
+            "rpc requests",
+            Box::pin(async move {
+                let client = HttpClientBuilder::default()
+                    .build("http://127.0.0.1:8546")
+                    .expect("http client should bind to configured rpc port");
+
+                // transactions of impersonated accounts are mined right away
+                let () = client
+                    .request("anvil_impersonateAccount", rpc_params![SENDER])
+                    .await
+                    .expect("account should be impersonated");
+                let request = json!({ "from": SENDER, "to": RECIPIENT, "value": "0x1" });
+                let hash: B256 = client
+                    .request("eth_sendTransaction", rpc_params![request])
+                    .await
+                    .expect("transaction should be mined");
+                let receipt: Value = client
+                    .request("eth_getTransactionReceipt", rpc_params![hash])
+                    .await
+                    .expect("receipt should be available");
+                assert_eq!(receipt["from"], SENDER);
+                assert_eq!(receipt["status"], "0x1");
+                assert_eq!(receipt["blockNumber"], "0x1");
+
+                // state changes and blocks after a snapshot are undone by reverting to it
+                let snapshot: U256 =
+                    client.request("evm_snapshot", rpc_params![]).await.expect("snapshot is taken");
+                let () = client
+                    .request("anvil_setBalance", rpc_params![RECIPIENT, "0x64"])
+                    .await
+                    .expect("balance should be set");
+                let _: String =
+                    client.request("evm_mine", rpc_params![]).await.expect("block is mined");
+                let balance: U256 = client
+                    .request("eth_getBalance", rpc_params![RECIPIENT, "latest"])
+                    .await
+                    .expect("balance is available");
+                assert_eq!(balance, U256::from(100));
+                let block_number: U256 =
+                    client.request("eth_blockNumber", rpc_params![]).await.expect("block number");
+                assert_eq!(block_number, U256::from(2));
+
+                let reverted: bool =
+                    client.request("evm_revert", rpc_params![snapshot]).await.expect("is reverted");
+                assert!(reverted);
+                let block_number: U256 =
+                    client.request("eth_blockNumber", rpc_params![]).await.expect("block number");
+                assert_eq!(block_number, U256::from(1));
+                let balance: U256 = client
+                    .request("eth_getBalance", rpc_params![RECIPIENT, "latest"])
+                    .await
+                    .expect("balance is available");
+                assert_eq!(balance, U256::from(1));
+
+                // a reverted snapshot can't be reverted to again
+                let reverted: bool =
+                    client.request("evm_revert", rpc_params![snapshot]).await.expect("is handled");
+                assert!(!reverted);
+
+                // mine up to the max block, which terminates the node
+                let _: Result<(), _> = client.request("anvil_mine", rpc_params!["0x2"]).await;
+            }),
+        );
+        Ok(())
+    }
+}
+
+/// This test is disabled for the `optimism` feature flag due to an incompatible feature set.
+#[test]
+#[cfg_attr(feature = "optimism", ignore)]
+pub fn test_anvil_namespace() {
+    // create temp path for test
+    let temp_path = tempfile::TempDir::new().expect("tempdir is okay").into_path();
+    let datadir = temp_path.to_str().expect("temp path is okay");
+
+    let no_args = NoArgs::with(AnvilConfig);
+    let mut command = NodeCommand::<NoArgsCliExt<AnvilConfig>>::parse_from([
+        "reth",
+        "--dev",
+        "--datadir",
+        datadir,
+        "--http.port",
+        "8546",
+        "--http.api",
+        "eth,anvil",
+        "--authrpc.port",
+        "8552",
+        "--ipcdisable",
+        "--port",
+        "30304",
+        "--disable-discovery",
+        "--debug.max-block",
+        "3",
+        "--debug.terminate",
+    ])
+    .with_ext::<NoArgsCliExt<AnvilConfig>>(no_args);
+
+    // use custom chain spec
+    command.chain = custom_chain();
+
+    let runner = CliRunner::default();
+    let node_command = runner.run_command_until_exit(|ctx| command.execute(ctx));
+    assert!(node_command.is_ok())
+}
+
+fn custom_chain() -> Arc<ChainSpec> {
+    let custom_genesis = r#"
+{
+    "nonce": "0x42",
+    "timestamp": "0x0",
+    "extraData": "0x5343",
+    "gasLimit": "0x1c9c380",
+    "difficulty": "0x400000000",
+    "mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+    "coinbase": "0x0000000000000000000000000000000000000000",
+    "alloc": {
+        "0x6Be02d1d3665660d22FF9624b7BE0551ee1Ac91b": {
+            "balance": "0x4a47e3c12448f4ad000000"
+        }
+    },
+    "number": "0x0",
+    "gasUsed": "0x0",
+    "parentHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+    "config": {
+        "ethash": {},
+        "chainId": 2600,
+        "homesteadBlock": 0,
+        "eip150Block": 0,
+        "eip155Block": 0,
+        "eip158Block": 0,
+        "byzantiumBlock": 0,
+        "constantinopleBlock": 0,
+        "petersburgBlock": 0,
+        "istanbulBlock": 0,
+        "berlinBlock": 0,
+        "londonBlock": 0,
+        "terminalTotalDifficulty": 0,
+        "terminalTotalDifficultyPassed": true,
+        "shanghaiTime": 0
+    }
+}
+"#;
+    let genesis: Genesis = serde_json::from_str(custom_genesis).unwrap();
+    Arc::new(genesis.into())
+}
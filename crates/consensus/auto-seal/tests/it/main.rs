@@ -1,4 +1,5 @@
 //! auto-mine consensus tests
+mod anvil;
 mod auto_mine;
 
 async fn main() {}
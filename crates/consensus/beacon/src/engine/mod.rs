@@ -33,7 +33,7 @@ use reth_rpc_types::engine::{
     CancunPayloadFields, ExecutionPayload, PayloadAttributes, PayloadError, PayloadStatus,
     PayloadStatusEnum, PayloadValidationError,
 };
-use reth_rpc_types_compat::engine::payload::{try_into_block, validate_block_hash};
+use reth_rpc_types_compat::engine::payload::try_into_block;
 use reth_stages::{ControlFlow, Pipeline, PipelineError};
 use reth_tasks::TaskSpawner;
 use reth_tokio_util::EventListeners;
@@ -554,6 +554,7 @@ where
     ///
     /// Returns `true` if the engine now reached its maximum block number, See
     /// [EngineSyncController::has_reached_max_block].
+    #[instrument(level = "debug", skip(self, state, attrs, tx), fields(head_block_hash = ?state.head_block_hash, safe_block_hash = ?state.safe_block_hash, finalized_block_hash = ?state.finalized_block_hash, has_attributes = attrs.is_some()), target = "consensus::engine")]
     fn on_forkchoice_updated(
         &mut self,
         state: ForkchoiceState,
@@ -1108,7 +1109,7 @@ where
     ///
     /// This returns a [`PayloadStatus`] that represents the outcome of a processed new payload and
     /// returns an error if an internal error occurred.
-    #[instrument(level = "trace", skip(self, payload, cancun_fields), fields(block_hash= ?payload.block_hash(), block_number = %payload.block_number(), is_pipeline_idle = %self.sync.is_pipeline_idle()), target = "consensus::engine")]
+    #[instrument(level = "debug", skip(self, payload, cancun_fields), fields(block_hash= ?payload.block_hash(), block_number = %payload.block_number(), is_pipeline_idle = %self.sync.is_pipeline_idle()), target = "consensus::engine")]
     fn on_new_payload(
         &mut self,
         payload: ExecutionPayload,
@@ -1189,13 +1190,25 @@ where
             cancun_fields.as_ref().map(|fields| fields.parent_beacon_block_root),
         ) {
             Ok(block) => {
-                // make sure there are no blob transactions in the payload if it is pre-cancun
-                if !self.chain_spec().is_cancun_active_at_timestamp(block.timestamp) &&
+                let block = block.seal_slow();
+                let error = if !self.chain_spec().is_cancun_active_at_timestamp(block.timestamp) &&
                     block.has_blob_transactions()
                 {
-                    Err(PayloadError::PreCancunBlockWithBlobTransactions)
+                    // make sure there are no blob transactions in the payload if it is pre-cancun
+                    Some(PayloadError::PreCancunBlockWithBlobTransactions)
+                } else if block.hash != block_hash {
+                    Some(PayloadError::BlockHash { execution: block.hash, consensus: block_hash })
                 } else {
-                    validate_block_hash(block_hash, block)
+                    None
+                };
+
+                match error {
+                    Some(error) => {
+                        // keep the block around for inspection, since it never reaches the tree
+                        self.blockchain.record_invalid_block(block, error.to_string());
+                        Err(error)
+                    }
+                    None => Ok(block),
                 }
             }
             Err(error) => Err(error),
@@ -1226,7 +1239,13 @@ where
             .flatten()
             .collect::<Vec<_>>();
 
-        self.validate_versioned_hashes(parent_hash, block_versioned_hashes, cancun_fields)?;
+        if let Err(status) =
+            self.validate_versioned_hashes(parent_hash, block_versioned_hashes, cancun_fields)
+        {
+            self.blockchain
+                .record_invalid_block(block, PayloadError::InvalidVersionedHashes.to_string());
+            return Err(status)
+        }
 
         Ok(block)
     }
@@ -2611,6 +2630,53 @@ mod tests {
             assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
         }
 
+        #[tokio::test]
+        async fn malformed_payload_recorded_as_invalid_block() {
+            use reth_interfaces::blockchain_tree::BlockchainTreeViewer;
+
+            let mut rng = generators::rng();
+            let chain_spec = Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .paris_activated()
+                    .build(),
+            );
+
+            let (consensus_engine, env) = TestConsensusEngineBuilder::new(chain_spec.clone())
+                .with_pipeline_exec_outputs(VecDeque::from([Ok(ExecOutput {
+                    checkpoint: StageCheckpoint::new(0),
+                    done: true,
+                })]))
+                .build();
+
+            let genesis = random_block(&mut rng, 0, None, None, Some(0));
+            insert_blocks(env.db.as_ref(), chain_spec.clone(), [&genesis].into_iter());
+
+            let mut engine_rx = spawn_consensus_engine(consensus_engine);
+
+            // Send a payload with the wrong block hash
+            let block = random_block(&mut rng, 1, Some(genesis.hash), None, Some(0));
+            let mut payload = try_block_to_payload_v1(block.clone());
+            payload.block_hash = B256::with_last_byte(1);
+            let res = env.send_new_payload(payload, None).await;
+            assert_matches!(res, Ok(result) => assert_matches!(result.status, PayloadStatusEnum::Invalid { .. }));
+
+            let invalid_blocks = env.tree.invalid_blocks();
+            assert_eq!(invalid_blocks.len(), 1);
+            assert_eq!(invalid_blocks[0].block.hash, block.hash);
+            assert_eq!(
+                invalid_blocks[0].error,
+                PayloadError::BlockHash {
+                    execution: block.hash,
+                    consensus: B256::with_last_byte(1)
+                }
+                .to_string()
+            );
+
+            assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
+        }
+
         #[tokio::test]
         async fn payload_pre_merge() {
             let data = BlockChainTestData::default();
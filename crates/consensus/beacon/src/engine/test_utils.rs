@@ -39,21 +39,22 @@ use reth_tasks::TokioTaskExecutor;
 use std::{collections::VecDeque, sync::Arc};
 use tokio::sync::{oneshot, watch};
 
+type TestBlockchainTree = ShareableBlockchainTree<
+    Arc<DatabaseEnv>,
+    EitherExecutorFactory<TestExecutorFactory, EvmProcessorFactory>,
+>;
+
 type TestBeaconConsensusEngine<Client> = BeaconConsensusEngine<
     Arc<DatabaseEnv>,
-    BlockchainProvider<
-        Arc<DatabaseEnv>,
-        ShareableBlockchainTree<
-            Arc<DatabaseEnv>,
-            EitherExecutorFactory<TestExecutorFactory, EvmProcessorFactory>,
-        >,
-    >,
+    BlockchainProvider<Arc<DatabaseEnv>, TestBlockchainTree>,
     Arc<EitherDownloader<Client, NoopFullBlockClient>>,
 >;
 
 #[derive(Debug)]
 pub struct TestEnv<DB> {
     pub db: DB,
+    /// The blockchain tree used by the engine.
+    pub tree: TestBlockchainTree,
     // Keep the tip receiver around, so it's not dropped.
     #[allow(dead_code)]
     tip_rx: watch::Receiver<B256>,
@@ -63,10 +64,11 @@ pub struct TestEnv<DB> {
 impl<DB> TestEnv<DB> {
     fn new(
         db: DB,
+        tree: TestBlockchainTree,
         tip_rx: watch::Receiver<B256>,
         engine_handle: BeaconConsensusEngineHandle,
     ) -> Self {
-        Self { db, tip_rx, engine_handle }
+        Self { db, tree, tip_rx, engine_handle }
     }
 
     pub async fn send_new_payload<T: Into<ExecutionPayload>>(
@@ -525,7 +527,8 @@ where
             BlockchainTree::new(externals, config, None).expect("failed to create tree"),
         );
         let latest = self.base_config.chain_spec.genesis_header().seal_slow();
-        let blockchain_provider = BlockchainProvider::with_latest(provider_factory, tree, latest);
+        let blockchain_provider =
+            BlockchainProvider::with_latest(provider_factory, tree.clone(), latest);
 
         let pruner = Pruner::new(
             db.clone(),
@@ -559,7 +562,7 @@ where
             engine.sync.set_max_block(max_block)
         }
 
-        (engine, TestEnv::new(db, tip_rx, handle))
+        (engine, TestEnv::new(db, tree, tip_rx, handle))
     }
 }
 
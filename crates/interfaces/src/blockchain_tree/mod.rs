@@ -3,6 +3,7 @@ use reth_primitives::{
     BlockHash, BlockNumHash, BlockNumber, Receipt, SealedBlock, SealedBlockWithSenders,
     SealedHeader,
 };
+use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashSet};
 
 pub mod error;
@@ -98,6 +99,13 @@ pub trait BlockchainTreeEngine: BlockchainTreeViewer + Send + Sync {
 
     /// Unwind tables and put it inside state
     fn unwind(&self, unwind_to: BlockNumber) -> RethResult<()>;
+
+    /// Records a block that was rejected before it could be inserted into the tree, e.g. because
+    /// the payload it was built from is malformed.
+    ///
+    /// The block is retained alongside the blocks the tree rejected itself, see
+    /// [BlockchainTreeViewer::invalid_blocks].
+    fn record_invalid_block(&self, block: SealedBlock, error: String);
 }
 
 /// Represents the kind of validation that should be performed when inserting a block.
@@ -212,6 +220,15 @@ pub enum InsertPayloadOk {
     Inserted(BlockStatus),
 }
 
+/// A block that was rejected by the tree, alongside the reason it was rejected.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvalidBlock {
+    /// The rejected block.
+    pub block: SealedBlock,
+    /// The validation or execution error the block was rejected with.
+    pub error: String,
+}
+
 /// Allows read only functionality on the blockchain tree.
 ///
 /// Tree contains all blocks that are not canonical that can potentially be included
@@ -257,6 +274,11 @@ pub trait BlockchainTreeViewer: Send + Sync {
     /// disconnected from the canonical chain.
     fn buffered_header_by_hash(&self, block_hash: BlockHash) -> Option<SealedHeader>;
 
+    /// Returns the most recent blocks that were rejected by the tree because they failed
+    /// validation or execution, or that were recorded via
+    /// [BlockchainTreeEngine::record_invalid_block], ordered from oldest to newest.
+    fn invalid_blocks(&self) -> Vec<InvalidBlock>;
+
     /// Returns true if the tree contains the block with matching hash.
     fn contains(&self, hash: BlockHash) -> bool {
         self.block_by_hash(hash).is_some()
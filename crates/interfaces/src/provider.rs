@@ -119,6 +119,9 @@ pub enum ProviderError {
     /// Snapshot file is not found for requested transaction.
     #[error("not able to find {0} snapshot file for transaction id {1}")]
     MissingSnapshotTx(SnapshotSegment, TxNumber),
+    /// Snapshots directory could not be read.
+    #[error("not able to read snapshots directory {0}: {1}")]
+    SnapshotsDirectory(PathBuf, String),
 }
 
 impl From<reth_nippy_jar::NippyJarError> for ProviderError {
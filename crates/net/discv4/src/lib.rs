@@ -386,6 +386,16 @@ impl Discv4 {
         Ok(rx.await?)
     }
 
+    /// Returns the signed records of all nodes in the table that responded to an ENR request.
+    ///
+    /// See also [Discv4Service::enrs]
+    pub async fn enrs(&self) -> Result<Vec<Enr<SecretKey>>, Discv4Error> {
+        let (tx, rx) = oneshot::channel();
+        let cmd = Discv4Command::Enrs(tx);
+        self.to_service.send(cmd)?;
+        Ok(rx.await?)
+    }
+
     /// Terminates the spawned [Discv4Service].
     pub fn terminate(&self) {
         self.send_to_service(Discv4Command::Terminated);
@@ -805,6 +815,11 @@ impl Discv4Service {
         removed
     }
 
+    /// Returns the signed records of all nodes in the table that responded to an ENR request.
+    pub fn enrs(&self) -> Vec<Enr<SecretKey>> {
+        self.kbuckets.iter_ref().filter_map(|entry| entry.node.value.enr.clone()).collect()
+    }
+
     /// Gets the number of entries that are considered connected.
     pub fn num_connected(&self) -> usize {
         self.kbuckets.buckets_iter().fold(0, |count, bucket| count + bucket.num_connected())
@@ -1209,13 +1224,14 @@ impl Discv4Service {
             if resp.echo_hash == msg.request_hash {
                 let key = kad_key(id);
                 let fork_id = msg.eth_fork_id();
+                let enr = msg.enr.into_inner();
                 let (record, old_fork_id) = match self.kbuckets.entry(&key) {
                     kbucket::Entry::Present(mut entry, _) => {
-                        let id = entry.value_mut().update_with_fork_id(fork_id);
+                        let id = entry.value_mut().update_with_enr_response(fork_id, enr);
                         (entry.value().record, id)
                     }
                     kbucket::Entry::Pending(mut entry, _) => {
-                        let id = entry.value().update_with_fork_id(fork_id);
+                        let id = entry.value().update_with_enr_response(fork_id, enr);
                         (entry.value().record, id)
                     }
                     _ => return,
@@ -1571,6 +1587,9 @@ impl Discv4Service {
                         let rx = self.update_stream();
                         let _ = tx.send(rx);
                     }
+                    Discv4Command::Enrs(tx) => {
+                        let _ = tx.send(self.enrs());
+                    }
                     Discv4Command::BanPeer(node_id) => self.ban_node(node_id),
                     Discv4Command::Remove(node_id) => {
                         self.remove_node(node_id);
@@ -1769,6 +1788,7 @@ enum Discv4Command {
     Lookup { node_id: Option<PeerId>, tx: Option<NodeRecordSender> },
     SetLookupInterval(Duration),
     Updates(OneshotSender<ReceiverStream<DiscoveryUpdate>>),
+    Enrs(OneshotSender<Vec<Enr<SecretKey>>>),
     Terminated,
 }
 
@@ -2012,6 +2032,8 @@ struct NodeEntry {
     last_enr_seq: Option<u64>,
     /// ForkId if retrieved via ENR requests.
     fork_id: Option<ForkId>,
+    /// The signed record of the node if retrieved via ENR requests.
+    enr: Option<Enr<SecretKey>>,
     /// Counter for failed findNode requests
     find_node_failures: usize,
     /// whether the endpoint of the peer is proven
@@ -2028,6 +2050,7 @@ impl NodeEntry {
             last_seen: Instant::now(),
             last_enr_seq: None,
             fork_id: None,
+            enr: None,
             find_node_failures: 0,
             has_endpoint_proof: false,
         }
@@ -2050,9 +2073,16 @@ impl NodeEntry {
         self.find_node_failures += 1;
     }
 
-    /// Updates the last timestamp and sets the enr seq
-    fn update_with_fork_id(&mut self, fork_id: Option<ForkId>) -> Option<ForkId> {
-        self.update_now(|s| std::mem::replace(&mut s.fork_id, fork_id))
+    /// Updates the last timestamp and sets the fork id and the signed record of the node
+    fn update_with_enr_response(
+        &mut self,
+        fork_id: Option<ForkId>,
+        enr: Enr<SecretKey>,
+    ) -> Option<ForkId> {
+        self.update_now(|s| {
+            s.enr = Some(enr);
+            std::mem::replace(&mut s.fork_id, fork_id)
+        })
     }
 
     /// Updates the last_seen timestamp and calls the closure
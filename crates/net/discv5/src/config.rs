@@ -0,0 +1,134 @@
+//! Configuration of the [Discv5](crate::Discv5) service.
+
+use discv5::{Enr, ListenConfig};
+use reth_primitives::ForkId;
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+
+/// The default port for discv5 discovery.
+///
+/// This is the port that consensus layer clients use by default, so that execution and consensus
+/// layer clients can share the same discv5 network.
+pub const DEFAULT_DISCOVERY_V5_PORT: u16 = 9000;
+
+/// The default address for discv5 discovery: `0.0.0.0:9000`
+pub const DEFAULT_DISCOVERY_V5_ADDR: SocketAddr =
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), DEFAULT_DISCOVERY_V5_PORT);
+
+/// The default interval between two random lookups.
+pub const DEFAULT_LOOKUP_INTERVAL: Duration = Duration::from_secs(20);
+
+/// Configuration of the [Discv5](crate::Discv5) service.
+#[derive(Debug, Clone)]
+pub struct Discv5Config {
+    /// The UDP address the discv5 service listens on.
+    pub listen_addr: SocketAddr,
+    /// The TCP port of the RLPx listener that is advertised in the local ENR.
+    pub tcp_port: u16,
+    /// The fork id that is advertised in the `eth` entry of the local ENR.
+    pub fork_id: Option<ForkId>,
+    /// Signed records of the nodes to bootstrap the routing table with.
+    pub bootstrap_nodes: Vec<Enr>,
+    /// The rate at which lookups for random node ids are triggered.
+    pub lookup_interval: Duration,
+}
+
+// === impl Discv5Config ===
+
+impl Discv5Config {
+    /// Returns a new [Discv5ConfigBuilder].
+    pub fn builder() -> Discv5ConfigBuilder {
+        Discv5ConfigBuilder::default()
+    }
+
+    /// Returns the config of the underlying [discv5::Discv5] service.
+    pub(crate) fn discv5_config(&self) -> discv5::Config {
+        let listen_config = ListenConfig::from_ip(self.listen_addr.ip(), self.listen_addr.port());
+        discv5::ConfigBuilder::new(listen_config).build()
+    }
+}
+
+impl Default for Discv5Config {
+    fn default() -> Self {
+        Self {
+            listen_addr: DEFAULT_DISCOVERY_V5_ADDR,
+            tcp_port: reth_discv4::DEFAULT_DISCOVERY_PORT,
+            fork_id: None,
+            bootstrap_nodes: Vec::new(),
+            lookup_interval: DEFAULT_LOOKUP_INTERVAL,
+        }
+    }
+}
+
+/// Builder type for [Discv5Config].
+#[derive(Debug, Clone, Default)]
+pub struct Discv5ConfigBuilder {
+    config: Discv5Config,
+}
+
+// === impl Discv5ConfigBuilder ===
+
+impl Discv5ConfigBuilder {
+    /// Sets the UDP address the discv5 service listens on.
+    pub fn listen_addr(&mut self, listen_addr: SocketAddr) -> &mut Self {
+        self.config.listen_addr = listen_addr;
+        self
+    }
+
+    /// Sets the TCP port of the RLPx listener that is advertised in the local ENR.
+    pub fn tcp_port(&mut self, tcp_port: u16) -> &mut Self {
+        self.config.tcp_port = tcp_port;
+        self
+    }
+
+    /// Sets the fork id that is advertised in the `eth` entry of the local ENR.
+    pub fn fork_id(&mut self, fork_id: ForkId) -> &mut Self {
+        self.config.fork_id = Some(fork_id);
+        self
+    }
+
+    /// Adds the signed record of a node to bootstrap the routing table with.
+    pub fn add_boot_node(&mut self, node: Enr) -> &mut Self {
+        self.config.bootstrap_nodes.push(node);
+        self
+    }
+
+    /// Adds signed records of nodes to bootstrap the routing table with.
+    pub fn add_boot_nodes(&mut self, nodes: impl IntoIterator<Item = Enr>) -> &mut Self {
+        self.config.bootstrap_nodes.extend(nodes);
+        self
+    }
+
+    /// Sets the rate at which lookups for random node ids are triggered, which must not be zero.
+    pub fn lookup_interval(&mut self, lookup_interval: Duration) -> &mut Self {
+        self.config.lookup_interval = lookup_interval;
+        self
+    }
+
+    /// Returns the configured [Discv5Config].
+    pub fn build(&self) -> Discv5Config {
+        self.config.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_builder() {
+        let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 9001);
+        let config = Discv5Config::builder()
+            .listen_addr(addr)
+            .tcp_port(30304)
+            .add_boot_nodes(Vec::new())
+            .lookup_interval(Duration::from_secs(3))
+            .build();
+        assert_eq!(config.listen_addr, addr);
+        assert_eq!(config.tcp_port, 30304);
+        assert_eq!(config.lookup_interval, Duration::from_secs(3));
+        assert!(config.fork_id.is_none());
+    }
+}
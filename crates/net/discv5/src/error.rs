@@ -0,0 +1,15 @@
+//! Error types that can occur in this crate.
+
+/// Errors that can occur when starting or interacting with the discv5 service
+#[derive(Debug, thiserror::Error)]
+pub enum Discv5Error {
+    /// The secret key can not be used as a discv5 key
+    #[error("invalid secret key: {0}")]
+    InvalidKey(String),
+    /// Failed to build or modify an ENR
+    #[error("invalid ENR: {0}")]
+    InvalidEnr(String),
+    /// Failed to start the discv5 service
+    #[error("failed to start discv5 service: {0}")]
+    Init(String),
+}
@@ -0,0 +1,424 @@
+//! Discovery v5 support: <https://github.com/ethereum/devp2p/blob/master/discv5/discv5.md>
+//!
+//! This wraps the [discv5] implementation of sigp and turns the nodes it discovers into
+//! [NodeRecord]s that can be dialed via RLPx.
+//!
+//! Discv5 is shared by execution and consensus layer clients, so only nodes that advertise an
+//! `eth` entry in their ENR are considered. The local ENR advertises the same `eth` fork id entry
+//! as discv4, see [EnrForkIdEntry].
+//!
+//! Like discv4, this consists of a [`Discv5`] and [`Discv5Service`] pair. The [`Discv5Service`]
+//! processes the events of the underlying service and periodically looks up random node ids, the
+//! [`Discv5`] serves as the frontend to interact with the underlying service.
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![warn(missing_debug_implementations, missing_docs, rustdoc::all)]
+#![deny(unused_must_use, rust_2018_idioms, unreachable_pub, unused_crate_dependencies)]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+use alloy_rlp::Decodable;
+use discv5::{
+    enr::{CombinedKey, CombinedPublicKey, EnrBuilder, NodeId},
+    Enr, Event,
+};
+use reth_discv4::EnrForkIdEntry;
+use reth_primitives::{bytes::Bytes, keccak256, ForkId, NodeRecord, PeerId};
+use secp256k1::{PublicKey, SecretKey};
+use std::{
+    fmt,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+use tokio::{sync::mpsc, task::JoinHandle};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, trace};
+
+mod config;
+mod error;
+
+pub use config::{
+    Discv5Config, Discv5ConfigBuilder, DEFAULT_DISCOVERY_V5_ADDR, DEFAULT_DISCOVERY_V5_PORT,
+    DEFAULT_LOOKUP_INTERVAL,
+};
+pub use discv5;
+pub use error::Discv5Error;
+
+/// The key of the ENR entry that advertises the fork id of an execution layer node.
+const ETH_ENR_KEY: &str = "eth";
+
+/// The capacity of the channel of [DiscoveredPeer]s.
+const UPDATE_CHANNEL_CAPACITY: usize = 1024;
+
+/// Frontend to interact with the discv5 service.
+#[derive(Clone)]
+pub struct Discv5 {
+    /// The underlying discv5 service.
+    inner: Arc<discv5::Discv5>,
+    /// The local node record.
+    local_node_record: NodeRecord,
+}
+
+// === impl Discv5 ===
+
+impl Discv5 {
+    /// Starts the discv5 service with the given key and returns the frontend and the
+    /// [Discv5Service] that must be spawned.
+    ///
+    /// The local ENR advertises the UDP address of the config and the TCP port of the RLPx
+    /// listener, as well as the fork id if configured.
+    pub async fn start(
+        secret_key: &SecretKey,
+        config: Discv5Config,
+    ) -> Result<(Self, Discv5Service), Discv5Error> {
+        let mut key_bytes = secret_key.secret_bytes();
+        let enr_key = CombinedKey::secp256k1_from_bytes(&mut key_bytes)
+            .map_err(|err| Discv5Error::InvalidKey(err.to_string()))?;
+
+        let local_enr = local_enr(&enr_key, &config)?;
+        let mut local_node_record = NodeRecord::from_secret_key(config.listen_addr, secret_key);
+        local_node_record.tcp_port = config.tcp_port;
+
+        let mut discv5 = discv5::Discv5::new(local_enr, enr_key, config.discv5_config())
+            .map_err(|err| Discv5Error::Init(err.to_string()))?;
+        discv5.start().await.map_err(|err| Discv5Error::Init(err.to_string()))?;
+
+        for node in config.bootstrap_nodes.iter().cloned() {
+            if let Err(err) = discv5.add_enr(node) {
+                debug!(target: "net::discv5", %err, "failed to add boot node");
+            }
+        }
+
+        let events =
+            discv5.event_stream().await.map_err(|err| Discv5Error::Init(err.to_string()))?;
+
+        let this = Self { inner: Arc::new(discv5), local_node_record };
+        let service = Discv5Service {
+            discv5: this.clone(),
+            events,
+            lookup_interval: config.lookup_interval,
+            lookup: None,
+            update_listeners: Vec::new(),
+        };
+        Ok((this, service))
+    }
+
+    /// Returns the local node record.
+    pub fn local_node_record(&self) -> NodeRecord {
+        self.local_node_record
+    }
+
+    /// Returns the local ENR.
+    pub fn local_enr(&self) -> Enr {
+        self.inner.local_enr()
+    }
+
+    /// Returns the number of nodes in the routing table that are connected.
+    pub fn connected_peers(&self) -> usize {
+        self.inner.connected_peers()
+    }
+
+    /// Adds the signed record of a node to the routing table.
+    pub fn add_enr(&self, enr: Enr) -> Result<(), Discv5Error> {
+        self.inner.add_enr(enr).map_err(|err| Discv5Error::InvalidEnr(err.to_string()))
+    }
+
+    /// Updates the `eth` fork id entry of the local ENR, e.g. after a hardfork.
+    pub fn set_fork_id(&self, fork_id: ForkId) -> Result<(), Discv5Error> {
+        // use forward-compatible forkid entry, like discv4
+        self.inner
+            .enr_insert(ETH_ENR_KEY, &EnrForkIdEntry::from(fork_id))
+            .map(|_| ())
+            .map_err(|err| Discv5Error::InvalidEnr(err.to_string()))
+    }
+
+    /// Bans the node permanently.
+    pub fn ban_peer(&self, peer_id: PeerId) {
+        self.inner.ban_node(&node_id(peer_id), None)
+    }
+
+    /// Bans the IP permanently.
+    pub fn ban_ip(&self, ip: IpAddr) {
+        self.inner.ban_ip(ip, None)
+    }
+
+    /// Bans the node and the IP permanently.
+    pub fn ban(&self, peer_id: PeerId, ip: IpAddr) {
+        self.ban_peer(peer_id);
+        self.ban_ip(ip);
+    }
+
+    /// Looks up a random node id, this populates the routing table.
+    async fn lookup_random(&self) {
+        let target = NodeId::random();
+        match self.inner.find_node(target).await {
+            Ok(nodes) => trace!(target: "net::discv5", num = nodes.len(), "random lookup finished"),
+            Err(err) => debug!(target: "net::discv5", %err, "random lookup failed"),
+        }
+    }
+}
+
+impl fmt::Debug for Discv5 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Discv5").field("local_node_record", &self.local_node_record).finish()
+    }
+}
+
+/// Processes the events of the discv5 service and triggers random lookups.
+///
+/// Every node that is discovered and advertises an `eth` entry is sent to the listeners as a
+/// [DiscoveredPeer].
+#[must_use = "Service does nothing unless spawned"]
+pub struct Discv5Service {
+    /// The frontend to the discv5 service.
+    discv5: Discv5,
+    /// Events of the discv5 service.
+    events: mpsc::Receiver<Event>,
+    /// The rate at which random lookups are triggered.
+    lookup_interval: std::time::Duration,
+    /// The currently running random lookup, aborted when the service is dropped.
+    lookup: Option<JoinHandle<()>>,
+    /// Listeners for discovered peers.
+    update_listeners: Vec<mpsc::Sender<DiscoveredPeer>>,
+}
+
+// === impl Discv5Service ===
+
+impl Discv5Service {
+    /// Creates a new stream of the discovered peers.
+    pub fn update_stream(&mut self) -> ReceiverStream<DiscoveredPeer> {
+        let (tx, rx) = mpsc::channel(UPDATE_CHANNEL_CAPACITY);
+        self.update_listeners.push(tx);
+        ReceiverStream::new(rx)
+    }
+
+    /// Spawns the service onto a new task.
+    ///
+    /// Note: requires a running runtime
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::task::spawn(self.run())
+    }
+
+    /// Processes events and triggers random lookups until the discv5 service shuts down.
+    async fn run(mut self) {
+        let mut lookup_interval = tokio::time::interval(self.lookup_interval);
+        loop {
+            tokio::select! {
+                event = self.events.recv() => {
+                    let Some(event) = event else {
+                        debug!(target: "net::discv5", "discv5 service shut down");
+                        break
+                    };
+                    self.on_event(event);
+                }
+                _ = lookup_interval.tick() => {
+                    // only one lookup at a time
+                    if self.lookup.as_ref().map_or(true, |lookup| lookup.is_finished()) {
+                        let discv5 = self.discv5.clone();
+                        self.lookup = Some(tokio::task::spawn(async move {
+                            discv5.lookup_random().await
+                        }));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Notifies the listeners about discovered peers.
+    fn on_event(&mut self, event: Event) {
+        let enr = match event {
+            Event::Discovered(enr) | Event::SessionEstablished(enr, _) => enr,
+            Event::EnrAdded { enr, .. } => enr,
+            event => {
+                trace!(target: "net::discv5", ?event, "ignoring event");
+                return
+            }
+        };
+
+        let Some(peer) = DiscoveredPeer::try_from_enr(&enr) else {
+            trace!(target: "net::discv5", node_id = %enr.node_id(), "ignoring non-eth node");
+            return
+        };
+        self.update_listeners.retain(|listener| match listener.try_send(peer) {
+            Ok(()) => true,
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                debug!(target: "net::discv5", "update channel is full, dropping peer");
+                true
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => false,
+        });
+    }
+}
+
+impl Drop for Discv5Service {
+    fn drop(&mut self) {
+        if let Some(lookup) = self.lookup.take() {
+            lookup.abort();
+        }
+    }
+}
+
+impl fmt::Debug for Discv5Service {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Discv5Service")
+            .field("discv5", &self.discv5)
+            .field("lookup_interval", &self.lookup_interval)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An execution layer node that was discovered via discv5.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiscoveredPeer {
+    /// The record to dial the node via RLPx.
+    pub node_record: NodeRecord,
+    /// The fork id advertised in the `eth` entry of the ENR, if it could be decoded.
+    pub fork_id: Option<ForkId>,
+}
+
+// === impl DiscoveredPeer ===
+
+impl DiscoveredPeer {
+    /// Converts the ENR into a [DiscoveredPeer].
+    ///
+    /// Returns `None` if the ENR has no `eth` entry, no TCP port, or no secp256k1 key.
+    pub fn try_from_enr(enr: &Enr) -> Option<Self> {
+        let eth = enr.get_raw_rlp(ETH_ENR_KEY)?;
+        let fork_id = EnrForkIdEntry::decode(&mut &eth[..]).ok().map(|entry| entry.fork_id);
+
+        let (address, tcp_port, udp_port) = match (enr.ip4(), enr.tcp4()) {
+            (Some(ip), Some(tcp)) => (IpAddr::from(ip), tcp, enr.udp4()),
+            _ => (IpAddr::from(enr.ip6()?), enr.tcp6()?, enr.udp6()),
+        };
+        let node_record = NodeRecord {
+            address,
+            tcp_port,
+            udp_port: udp_port.unwrap_or(tcp_port),
+            id: peer_id(&enr.public_key())?,
+        }
+        .into_ipv4_mapped();
+
+        Some(Self { node_record, fork_id })
+    }
+}
+
+/// Builds the local ENR with the UDP address of the config, the TCP port of the RLPx listener and
+/// the `eth` fork id entry.
+fn local_enr(enr_key: &CombinedKey, config: &Discv5Config) -> Result<Enr, Discv5Error> {
+    let mut builder = EnrBuilder::new("v4");
+    match config.listen_addr.ip() {
+        IpAddr::V4(ip) => {
+            // an unspecified address is updated by the service once the external address is known
+            if !ip.is_unspecified() {
+                builder.ip4(ip);
+            }
+            builder.udp4(config.listen_addr.port()).tcp4(config.tcp_port);
+        }
+        IpAddr::V6(ip) => {
+            if !ip.is_unspecified() {
+                builder.ip6(ip);
+            }
+            builder.udp6(config.listen_addr.port()).tcp6(config.tcp_port);
+        }
+    }
+    let mut enr = builder.build(enr_key).map_err(|err| Discv5Error::InvalidEnr(err.to_string()))?;
+
+    if let Some(fork_id) = config.fork_id {
+        // use forward-compatible forkid entry, like discv4
+        let entry = Bytes::from(alloy_rlp::encode(EnrForkIdEntry::from(fork_id)));
+        enr.insert_raw_rlp(ETH_ENR_KEY, entry, enr_key)
+            .map_err(|err| Discv5Error::InvalidEnr(err.to_string()))?;
+    }
+
+    Ok(enr)
+}
+
+/// Converts the public key of an ENR into a [PeerId].
+///
+/// Returns `None` for non-secp256k1 keys.
+fn peer_id(key: &CombinedPublicKey) -> Option<PeerId> {
+    match key {
+        CombinedPublicKey::Secp256k1(_) => {
+            let key = PublicKey::from_slice(&key.encode()).ok()?;
+            Some(PeerId::from_slice(&key.serialize_uncompressed()[1..]))
+        }
+        _ => None,
+    }
+}
+
+/// Converts a [PeerId] into the discv5 [NodeId], which is the keccak256 hash of the public key.
+fn node_id(peer_id: PeerId) -> NodeId {
+    NodeId::new(&keccak256(peer_id).0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{ForkHash, Hardfork, MAINNET};
+    use secp256k1::{rand::thread_rng, SECP256K1};
+    use std::net::Ipv4Addr;
+
+    fn rng_enr(eth: Option<Bytes>) -> (SecretKey, Enr) {
+        let (secret_key, _) = SECP256K1.generate_keypair(&mut thread_rng());
+        let enr_key = CombinedKey::secp256k1_from_bytes(&mut secret_key.secret_bytes()).unwrap();
+        let mut enr = EnrBuilder::new("v4")
+            .ip4(Ipv4Addr::new(10, 0, 0, 1))
+            .udp4(9000)
+            .tcp4(30303)
+            .build(&enr_key)
+            .unwrap();
+        if let Some(eth) = eth {
+            enr.insert_raw_rlp(ETH_ENR_KEY, eth, &enr_key).unwrap();
+        }
+        (secret_key, enr)
+    }
+
+    #[test]
+    fn test_discovered_peer_from_enr() {
+        let fork_id = MAINNET.hardfork_fork_id(Hardfork::Frontier).unwrap();
+        let (secret_key, enr) =
+            rng_enr(Some(Bytes::from(alloy_rlp::encode(EnrForkIdEntry::from(fork_id)))));
+
+        let peer = DiscoveredPeer::try_from_enr(&enr).unwrap();
+        assert_eq!(peer.fork_id, Some(fork_id));
+        let mut record = NodeRecord::from_secret_key("10.0.0.1:9000".parse().unwrap(), &secret_key);
+        record.tcp_port = 30303;
+        assert_eq!(peer.node_record, record);
+        assert_eq!(node_id(peer.node_record.id), enr.node_id());
+    }
+
+    #[test]
+    fn test_ignore_non_eth_enr() {
+        let (_, enr) = rng_enr(None);
+        assert!(DiscoveredPeer::try_from_enr(&enr).is_none());
+
+        // an undecodable entry is still an eth node
+        let (_, enr) = rng_enr(Some(Bytes::from_static(&[0xc0])));
+        assert_eq!(DiscoveredPeer::try_from_enr(&enr).unwrap().fork_id, None);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_start_discv5() {
+        reth_tracing::init_test_tracing();
+
+        let (secret_key, _) = SECP256K1.generate_keypair(&mut thread_rng());
+        let fork_id = ForkId { hash: ForkHash([220, 233, 108, 45]), next: 0 };
+        let config = Discv5Config::builder()
+            .listen_addr(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0))
+            .tcp_port(30303)
+            .fork_id(fork_id)
+            .build();
+
+        let (discv5, mut service) = Discv5::start(&secret_key, config).await.unwrap();
+        let _updates = service.update_stream();
+        let _handle = service.spawn();
+
+        let local_enr = discv5.local_enr();
+        let peer = DiscoveredPeer::try_from_enr(&local_enr).unwrap();
+        assert_eq!(peer.fork_id, Some(fork_id));
+        assert_eq!(peer.node_record.id, discv5.local_node_record().id);
+    }
+}
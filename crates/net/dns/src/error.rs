@@ -38,3 +38,17 @@ pub(crate) enum LookupError {
     #[error("entry not found")]
     EntryNotFound,
 }
+
+/// Errors that can happen while publishing a tree, see [DnsTreePublisher](crate::DnsTreePublisher).
+#[derive(thiserror::Error, Debug)]
+pub enum DnsPublishError {
+    /// Failed to sign the root of the tree.
+    #[error("failed to sign tree root: {0}")]
+    Signing(String),
+    /// Failed to write the records.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// Failed to publish the records with a DNS provider.
+    #[error("{0}")]
+    Provider(String),
+}
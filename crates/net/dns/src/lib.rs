@@ -21,8 +21,13 @@ use crate::{
     tree::{DnsEntry, LinkEntry},
 };
 pub use config::DnsDiscoveryConfig;
-use enr::Enr;
+pub use enr::Enr;
+pub use error::DnsPublishError;
 use error::ParseDnsEntryError;
+pub use publish::{
+    DnsPublishConfig, DnsPublisher, DnsTree, DnsTreePublisher, EnrFilter, EnrSource,
+    ZoneFilePublisher,
+};
 use reth_primitives::{ForkId, NodeRecord, PeerId};
 use schnellru::{ByLength, LruMap};
 use secp256k1::SecretKey;
@@ -51,6 +56,7 @@ use tracing::{debug, trace, warn};
 
 mod config;
 mod error;
+pub mod publish;
 mod query;
 pub mod resolver;
 mod sync;
@@ -391,8 +397,6 @@ pub enum DnsDiscoveryEvent {
 
 /// Converts an [Enr] into a [NodeRecord]
 fn convert_enr_node_record(enr: &Enr<SecretKey>) -> Option<DnsNodeRecordUpdate> {
-    use alloy_rlp::Decodable;
-
     let node_record = NodeRecord {
         address: enr.ip4().map(IpAddr::from).or_else(|| enr.ip6().map(IpAddr::from))?,
         tcp_port: enr.tcp4().or_else(|| enr.tcp6())?,
@@ -401,12 +405,28 @@ fn convert_enr_node_record(enr: &Enr<SecretKey>) -> Option<DnsNodeRecordUpdate>
     }
     .into_ipv4_mapped();
 
-    let mut maybe_fork_id = enr.get(b"eth")?;
-    let fork_id = ForkId::decode(&mut maybe_fork_id).ok();
+    // records without an `eth` entry are not eth nodes
+    enr.get(b"eth")?;
+    let fork_id = enr_fork_id(enr);
 
     Some(DnsNodeRecordUpdate { node_record, fork_id })
 }
 
+/// Decodes the [ForkId] of the `eth` entry of the [Enr], if present.
+///
+/// The entry is either the fork id itself or, as advertised by discv4, a list that contains the
+/// fork id.
+fn enr_fork_id(enr: &Enr<SecretKey>) -> Option<ForkId> {
+    use alloy_rlp::{Decodable, Header};
+
+    let entry = enr.get(b"eth")?;
+    ForkId::decode(&mut &entry[..]).ok().or_else(|| {
+        let mut buf = entry;
+        Header::decode(&mut buf).ok().filter(|header| header.list)?;
+        ForkId::decode(&mut buf).ok()
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
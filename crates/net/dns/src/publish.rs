@@ -0,0 +1,504 @@
+//! Support for creating and publishing
+//! [EIP-1459](https://eips.ethereum.org/EIPS/eip-1459#dns-record-structure) trees.
+//!
+//! A [DnsTree] is built from a list of [Enr]s and links to other trees, signed with the key of
+//! the tree operator and converted into the TXT records that are served under the tree's domain.
+//! The [DnsTreePublisher] rebuilds the tree from an [EnrSource] on an interval and hands it to a
+//! [DnsPublisher], for example the [ZoneFilePublisher].
+
+use crate::{
+    enr_fork_id,
+    error::DnsPublishError,
+    tree::{BranchEntry, LinkEntry, TreeRootEntry},
+};
+use async_trait::async_trait;
+use data_encoding::BASE32_NOPAD;
+use enr::{Enr, EnrKey};
+use reth_primitives::{keccak256, ForkHash};
+use secp256k1::{PublicKey, SecretKey};
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    future::Future,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// The maximum number of children of a branch entry.
+///
+/// This matches geth and keeps the size of a branch entry below 370 bytes, so that it fits into
+/// a single DNS response.
+const MAX_CHILDREN: usize = 13;
+
+/// The number of bytes of the keccak256 hash of an entry that make up its subdomain.
+const HASH_ABBREV_SIZE: usize = 16;
+
+/// The maximum length of a single string of a TXT record.
+const MAX_TXT_STRING_LENGTH: usize = 255;
+
+/// A signed EIP-1459 tree.
+#[derive(Debug, Clone)]
+pub struct DnsTree {
+    /// The signed root of the tree.
+    root: TreeRootEntry,
+    /// All entries of the tree except the root, keyed by their subdomain.
+    entries: BTreeMap<String, String>,
+    /// The public key of the key that signed the root.
+    public_key: PublicKey,
+}
+
+// === impl DnsTree ===
+
+impl DnsTree {
+    /// Builds a new tree from the given nodes and links to other trees and signs it with the key.
+    ///
+    /// Nodes are sorted by their id and links by their text, so the same input always results in
+    /// the same tree.
+    pub fn new(
+        key: &SecretKey,
+        sequence_number: u64,
+        nodes: impl IntoIterator<Item = Enr<SecretKey>>,
+        links: impl IntoIterator<Item = LinkEntry>,
+    ) -> Result<Self, DnsPublishError> {
+        let mut nodes = nodes.into_iter().collect::<Vec<_>>();
+        nodes.sort_by_key(|enr| enr.node_id().raw());
+        nodes.dedup_by_key(|enr| enr.node_id().raw());
+        let mut links = links.into_iter().map(|link| link.to_string()).collect::<Vec<_>>();
+        links.sort();
+        links.dedup();
+
+        let mut entries = BTreeMap::new();
+        let enr_root =
+            build_subtree(nodes.into_iter().map(|enr| enr.to_base64()).collect(), &mut entries);
+        let link_root = build_subtree(links, &mut entries);
+
+        let mut root =
+            TreeRootEntry { enr_root, link_root, sequence_number, signature: Default::default() };
+        root.sign(key).map_err(|err| DnsPublishError::Signing(err.to_string()))?;
+
+        Ok(Self { root, entries, public_key: key.public() })
+    }
+
+    /// Returns the signed root of the tree.
+    pub fn root(&self) -> &TreeRootEntry {
+        &self.root
+    }
+
+    /// Returns the sequence number of the tree.
+    pub fn sequence_number(&self) -> u64 {
+        self.root.sequence_number
+    }
+
+    /// Returns all entries of the tree except the root, keyed by their subdomain.
+    pub fn entries(&self) -> &BTreeMap<String, String> {
+        &self.entries
+    }
+
+    /// Returns the link to the tree if it is published under the given domain.
+    pub fn link(&self, domain: impl Into<String>) -> LinkEntry {
+        LinkEntry { domain: domain.into(), pubkey: self.public_key }
+    }
+
+    /// Returns all TXT records of the tree if it is published under the given domain, keyed by
+    /// their fully qualified name.
+    pub fn records(&self, domain: &str) -> BTreeMap<String, String> {
+        let mut records = self
+            .entries
+            .iter()
+            .map(|(hash, entry)| (format!("{hash}.{domain}"), entry.clone()))
+            .collect::<BTreeMap<_, _>>();
+        records.insert(domain.to_string(), self.root.to_string());
+        records
+    }
+
+    /// Returns the tree as a zone file in the format of RFC 1035 if it is published under the
+    /// given domain.
+    ///
+    /// Records that are longer than 255 bytes are split into multiple strings.
+    pub fn to_zone_file(&self, domain: &str, ttl: u32) -> String {
+        let domain = domain.trim_end_matches('.');
+        let mut zone = format!(
+            "; EIP-1459 tree {}, sequence number {}\n",
+            self.link(domain),
+            self.sequence_number()
+        );
+        for (name, value) in self.records(domain) {
+            let strings = value
+                .as_bytes()
+                .chunks(MAX_TXT_STRING_LENGTH)
+                .map(|chunk| format!("\"{}\"", String::from_utf8_lossy(chunk)))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let _ = writeln!(zone, "{name}. {ttl} IN TXT {strings}");
+        }
+        zone
+    }
+}
+
+/// Adds the leaves to the entries and builds the branches above them.
+///
+/// Returns the subdomain of the root of the subtree. An empty subtree is an empty branch.
+fn build_subtree(leaves: Vec<String>, entries: &mut BTreeMap<String, String>) -> String {
+    let mut hashes = leaves.into_iter().map(|leaf| insert_entry(leaf, entries)).collect::<Vec<_>>();
+    if hashes.len() == 1 {
+        return hashes.remove(0)
+    }
+
+    while hashes.len() > MAX_CHILDREN {
+        hashes = hashes
+            .chunks(MAX_CHILDREN)
+            .map(|children| match children {
+                [hash] => hash.clone(),
+                children => {
+                    let branch = BranchEntry { children: children.to_vec() };
+                    insert_entry(branch.to_string(), entries)
+                }
+            })
+            .collect();
+    }
+
+    insert_entry(BranchEntry { children: hashes }.to_string(), entries)
+}
+
+/// Adds the entry and returns its subdomain, which is the base32 encoded, abbreviated keccak256
+/// hash of the entry.
+fn insert_entry(entry: String, entries: &mut BTreeMap<String, String>) -> String {
+    let hash = BASE32_NOPAD.encode(&keccak256(entry.as_bytes())[..HASH_ABBREV_SIZE]);
+    entries.insert(hash.clone(), entry);
+    hash
+}
+
+/// Filters the nodes that are included in a tree.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnrFilter {
+    /// Only include nodes that advertise an `eth` fork id with this hash.
+    pub fork_hash: Option<ForkHash>,
+    /// Only include nodes that have all of these keys, e.g. `snap` for nodes that serve the snap
+    /// protocol.
+    pub required_keys: Vec<String>,
+}
+
+// === impl EnrFilter ===
+
+impl EnrFilter {
+    /// Returns `true` if the node should be included.
+    pub fn matches(&self, enr: &Enr<SecretKey>) -> bool {
+        if let Some(fork_hash) = self.fork_hash {
+            if enr_fork_id(enr).map(|fork_id| fork_id.hash) != Some(fork_hash) {
+                return false
+            }
+        }
+        self.required_keys.iter().all(|key| enr.get(key.as_bytes()).is_some())
+    }
+}
+
+/// A type that provides the nodes that are published.
+#[async_trait]
+pub trait EnrSource: Send + Sync + 'static {
+    /// Returns the currently known nodes.
+    async fn enrs(&self) -> Vec<Enr<SecretKey>>;
+}
+
+#[async_trait]
+impl<F, Fut> EnrSource for F
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Vec<Enr<SecretKey>>> + Send,
+{
+    async fn enrs(&self) -> Vec<Enr<SecretKey>> {
+        self().await
+    }
+}
+
+/// A type that publishes the records of a tree, e.g. to a DNS provider.
+#[async_trait]
+pub trait DnsPublisher: Send + Sync + 'static {
+    /// Publishes the tree under the given domain.
+    ///
+    /// Records of previous versions of the tree that are no longer part of it may be removed.
+    async fn publish(&self, domain: &str, tree: &DnsTree) -> Result<(), DnsPublishError>;
+}
+
+/// A [DnsPublisher] that writes the tree to a zone file, see [DnsTree::to_zone_file].
+#[derive(Debug, Clone)]
+pub struct ZoneFilePublisher {
+    /// The path of the zone file.
+    path: PathBuf,
+    /// The TTL of the records.
+    ttl: u32,
+}
+
+// === impl ZoneFilePublisher ===
+
+impl ZoneFilePublisher {
+    /// The default TTL of the records.
+    pub const DEFAULT_TTL: u32 = 60 * 30;
+
+    /// Creates a new publisher that writes to the given path.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), ttl: Self::DEFAULT_TTL }
+    }
+
+    /// Sets the TTL of the records.
+    pub fn with_ttl(mut self, ttl: u32) -> Self {
+        self.ttl = ttl;
+        self
+    }
+}
+
+#[async_trait]
+impl DnsPublisher for ZoneFilePublisher {
+    async fn publish(&self, domain: &str, tree: &DnsTree) -> Result<(), DnsPublishError> {
+        // write to a temporary file first, so the zone file is never incomplete
+        let tmp = self.path.with_extension("tmp");
+        tokio::fs::write(&tmp, tree.to_zone_file(domain, self.ttl)).await?;
+        tokio::fs::rename(&tmp, &self.path).await?;
+        Ok(())
+    }
+}
+
+/// Settings for the [DnsTreePublisher].
+#[derive(Debug, Clone)]
+pub struct DnsPublishConfig {
+    /// The domain the tree is published under.
+    pub domain: String,
+    /// The key that signs the tree.
+    pub key: SecretKey,
+    /// The interval at which the tree is rebuilt, which must not be zero.
+    ///
+    /// Default: 1h
+    pub interval: Duration,
+    /// Filters the published nodes.
+    pub filter: EnrFilter,
+    /// Links to other trees that are included in the tree.
+    pub links: Vec<LinkEntry>,
+}
+
+impl DnsPublishConfig {
+    /// Creates a new config for the given domain and key, with the default interval and no
+    /// filter or links.
+    pub fn new(domain: impl Into<String>, key: SecretKey) -> Self {
+        Self {
+            domain: domain.into(),
+            key,
+            interval: Duration::from_secs(60 * 60),
+            filter: Default::default(),
+            links: Default::default(),
+        }
+    }
+}
+
+/// Periodically builds a tree from the nodes of an [EnrSource] and publishes it with a
+/// [DnsPublisher].
+///
+/// The tree is only published again if its content changed, in which case the sequence number is
+/// increased. Sequence numbers are derived from the current time, so they keep increasing across
+/// restarts.
+#[must_use = "Publisher does nothing unless run"]
+pub struct DnsTreePublisher<S, P> {
+    config: DnsPublishConfig,
+    source: S,
+    publisher: P,
+    /// The last published tree
+    published: Option<DnsTree>,
+}
+
+// === impl DnsTreePublisher ===
+
+impl<S, P> DnsTreePublisher<S, P>
+where
+    S: EnrSource,
+    P: DnsPublisher,
+{
+    /// Creates a new publisher.
+    pub fn new(config: DnsPublishConfig, source: S, publisher: P) -> Self {
+        Self { config, source, publisher, published: None }
+    }
+
+    /// Returns the link to the published tree.
+    pub fn link(&self) -> LinkEntry {
+        LinkEntry { domain: self.config.domain.clone(), pubkey: self.config.key.public() }
+    }
+
+    /// Builds the tree from the current nodes and publishes it if it changed.
+    ///
+    /// Returns the tree if it was published.
+    pub async fn publish_once(&mut self) -> Result<Option<&DnsTree>, DnsPublishError> {
+        let DnsPublishConfig { domain, key, filter, links, .. } = &self.config;
+        let nodes = self.source.enrs().await.into_iter().filter(|enr| filter.matches(enr));
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let sequence_number = match &self.published {
+            Some(published) => now.max(published.sequence_number() + 1),
+            None => now,
+        };
+        let tree = DnsTree::new(key, sequence_number, nodes, links.iter().cloned())?;
+
+        if let Some(published) = &self.published {
+            if published.root.enr_root == tree.root.enr_root &&
+                published.root.link_root == tree.root.link_root
+            {
+                debug!(target: "disc::dns", %domain, "tree is unchanged");
+                return Ok(None)
+            }
+        }
+
+        self.publisher.publish(domain, &tree).await?;
+        info!(target: "disc::dns", %domain, seq=tree.sequence_number(), entries=tree.entries.len(), "published tree");
+        Ok(Some(&*self.published.insert(tree)))
+    }
+
+    /// Publishes the tree on every interval tick until the future is dropped.
+    ///
+    /// The first tree is published after the first interval, so that the source can discover
+    /// nodes first. Use [DnsTreePublisher::publish_once] to publish immediately.
+    pub async fn run(mut self) {
+        let period = self.config.interval;
+        let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.publish_once().await {
+                warn!(target: "disc::dns", %err, domain=%self.config.domain, "failed to publish tree");
+            }
+        }
+    }
+
+    /// Spawns the publisher onto a new task.
+    ///
+    /// Note: requires a running runtime
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::task::spawn(self.run())
+    }
+}
+
+impl<S, P> std::fmt::Debug for DnsTreePublisher<S, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DnsTreePublisher")
+            .field("config", &self.config)
+            .field("published", &self.published)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DnsDiscoveryEvent, DnsDiscoveryService, MapResolver};
+    use alloy_rlp::Encodable;
+    use enr::EnrBuilder;
+    use reth_primitives::{Hardfork, MAINNET};
+    use secp256k1::rand::thread_rng;
+    use std::{collections::HashSet, net::Ipv4Addr, sync::Arc};
+    use tokio_stream::StreamExt;
+
+    fn enr(key: &SecretKey, fork_hash: Option<ForkHash>) -> Enr<SecretKey> {
+        let mut builder = EnrBuilder::new("v4");
+        builder.ip4(Ipv4Addr::LOCALHOST).udp4(30303).tcp4(30303);
+        if let Some(hash) = fork_hash {
+            let mut buf = Vec::new();
+            let mut fork_id = MAINNET.hardfork_fork_id(Hardfork::Frontier).unwrap();
+            fork_id.hash = hash;
+            fork_id.encode(&mut buf);
+            builder.add_value(b"eth", &buf);
+        }
+        builder.build(key).unwrap()
+    }
+
+    #[test]
+    fn build_tree() {
+        let key = SecretKey::new(&mut thread_rng());
+        let nodes =
+            (0..30).map(|_| enr(&SecretKey::new(&mut thread_rng()), None)).collect::<Vec<_>>();
+        let tree = DnsTree::new(&key, 1, nodes.clone(), []).unwrap();
+
+        assert!(tree.root().verify::<SecretKey>(&key.public()));
+        // 30 nodes, 3 branches of at most 13 nodes and the root branch
+        assert_eq!(tree.entries().len(), 30 + 3 + 1 + 1);
+        for entry in tree.entries().values() {
+            assert!(entry.len() <= 370 || entry.starts_with("enr:"));
+        }
+
+        // the order of the nodes does not matter
+        let reversed = DnsTree::new(&key, 1, nodes.into_iter().rev(), []).unwrap();
+        assert_eq!(reversed.root().enr_root, tree.root().enr_root);
+
+        let zone = tree.to_zone_file("nodes.example.org", 300);
+        assert!(zone.contains(&format!("nodes.example.org. 300 IN TXT \"{}\"", tree.root())));
+        assert_eq!(zone.lines().filter(|line| line.contains(" IN TXT ")).count(), 36);
+    }
+
+    #[test]
+    fn filter_nodes() {
+        let fork_hash = ForkHash([1, 2, 3, 4]);
+        let key = SecretKey::new(&mut thread_rng());
+        let filter = EnrFilter { fork_hash: Some(fork_hash), required_keys: vec![] };
+        assert!(filter.matches(&enr(&key, Some(fork_hash))));
+        assert!(!filter.matches(&enr(&key, Some(ForkHash([4, 3, 2, 1])))));
+        assert!(!filter.matches(&enr(&key, None)));
+
+        // discv4 advertises the fork id wrapped in a list
+        let mut fork_id = MAINNET.hardfork_fork_id(Hardfork::Frontier).unwrap();
+        fork_id.hash = fork_hash;
+        let mut buf = Vec::new();
+        alloy_rlp::Header { list: true, payload_length: fork_id.length() }.encode(&mut buf);
+        fork_id.encode(&mut buf);
+        let enr = EnrBuilder::new("v4").add_value(b"eth", &buf).build(&key).unwrap();
+        assert!(filter.matches(&enr));
+
+        let filter = EnrFilter { fork_hash: None, required_keys: vec!["eth".to_string()] };
+        assert!(filter.matches(&enr(&key, Some(fork_hash))));
+        assert!(!filter.matches(&enr(&key, None)));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn sync_published_tree() {
+        reth_tracing::init_test_tracing();
+
+        let key = SecretKey::new(&mut thread_rng());
+        let nodes =
+            (0..20).map(|_| enr(&SecretKey::new(&mut thread_rng()), None)).collect::<Vec<_>>();
+        let tree = DnsTree::new(&key, 1, nodes.clone(), []).unwrap();
+        let link = tree.link("nodes.example.org");
+
+        let resolver = MapResolver::default();
+        for (name, value) in tree.records(&link.domain) {
+            resolver.insert(name, value);
+        }
+
+        let mut service = DnsDiscoveryService::new(Arc::new(resolver), Default::default());
+        service.sync_tree_with_link(link);
+
+        let mut discovered = HashSet::new();
+        while discovered.len() < nodes.len() {
+            match service.next().await.unwrap() {
+                DnsDiscoveryEvent::Enr(enr) => {
+                    discovered.insert(enr.to_base64());
+                }
+            }
+        }
+        assert_eq!(discovered, nodes.iter().map(|enr| enr.to_base64()).collect());
+    }
+
+    #[tokio::test]
+    async fn publish_only_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nodes.zone");
+        let node = enr(&SecretKey::new(&mut thread_rng()), None);
+
+        let config = DnsPublishConfig::new("nodes.example.org", SecretKey::new(&mut thread_rng()));
+        let source = move || {
+            let node = node.clone();
+            async move { vec![node] }
+        };
+        let mut publisher =
+            DnsTreePublisher::new(config, source, ZoneFilePublisher::new(path.clone()));
+
+        let seq = publisher.publish_once().await.unwrap().unwrap().sequence_number();
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains(&format!("sequence number {seq}")));
+        assert!(publisher.publish_once().await.unwrap().is_none());
+    }
+}
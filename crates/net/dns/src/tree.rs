@@ -201,8 +201,14 @@ impl BranchEntry {
             Ok(hash.to_string())
         }
 
+        let input = input.trim();
+        // an empty branch is valid, e.g. the link subtree of a tree without links
+        if input.is_empty() {
+            return Ok(Self { children: Vec::new() })
+        }
+
         let children =
-            input.trim().split(',').map(ensure_valid_hash).collect::<ParseEntryResult<Vec<_>>>()?;
+            input.split(',').map(ensure_valid_hash).collect::<ParseEntryResult<Vec<_>>>()?;
         Ok(Self { children })
     }
 }
@@ -405,6 +411,14 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_empty_branch_entry() {
+        let s = "enrtree-branch:";
+        let entry: BranchEntry = s.parse().unwrap();
+        assert!(entry.children.is_empty());
+        assert_eq!(entry.to_string(), s);
+    }
+
     #[test]
     fn parse_invalid_branch_entry() {
         let s = "enrtree-branch:1,2";
@@ -1,6 +1,7 @@
 use super::file_codec::BlockFileCodec;
 use alloy_rlp::{Decodable, Header as RlpHeader};
 use itertools::Either;
+use reth_era::{BlockTuple, Era1Reader, EraError};
 use reth_interfaces::{
     p2p::{
         bodies::client::{BodiesClient, BodiesFut},
@@ -66,6 +67,10 @@ pub enum FileClientError {
     /// An error occurred when decoding blocks, headers, or rlp headers from the file.
     #[error(transparent)]
     Rlp(#[from] alloy_rlp::Error),
+
+    /// An error occurred when reading an Era1 file.
+    #[error(transparent)]
+    Era(#[from] EraError),
 }
 
 impl FileClient {
@@ -114,9 +119,40 @@ impl FileClient {
         Ok(Self { headers, hash_to_number, bodies })
     }
 
-    /// Get the tip hash of the chain.
+    /// Create a new file client from an Era1 file.
+    ///
+    /// The genesis block is skipped, since it is never imported.
+    pub async fn from_era1<P: AsRef<Path>>(path: P) -> Result<Self, FileClientError> {
+        let mut headers = HashMap::new();
+        let mut hash_to_number = HashMap::new();
+        let mut bodies = HashMap::new();
+
+        let data = tokio::fs::read(path).await?;
+        for tuple in Era1Reader::new(data.as_slice())? {
+            let BlockTuple { header, body, .. } = tuple?;
+            if header.number == 0 {
+                continue
+            }
+            let block_hash = header.hash_slow();
+
+            hash_to_number.insert(block_hash, header.number);
+            headers.insert(header.number, header);
+            bodies.insert(block_hash, body);
+        }
+
+        trace!(blocks = headers.len(), "Initialized file client from era1 file");
+
+        Ok(Self { headers, hash_to_number, bodies })
+    }
+
+    /// Get the tip hash of the chain, which is the hash of the highest block.
     pub fn tip(&self) -> Option<B256> {
-        self.headers.get(&(self.headers.len() as u64)).map(|h| h.hash_slow())
+        self.max_block().and_then(|number| self.headers.get(&number)).map(|h| h.hash_slow())
+    }
+
+    /// Returns the lowest block number of this client has or `None` if empty
+    pub fn min_block(&self) -> Option<u64> {
+        self.headers.keys().min().copied()
     }
 
     /// Returns the highest block number of this client has or `None` if empty
@@ -260,6 +296,7 @@ mod tests {
     use futures::SinkExt;
     use futures_util::stream::StreamExt;
     use reth_db::test_utils::create_test_rw_db;
+    use reth_era::Era1Writer;
     use reth_interfaces::{
         p2p::{
             bodies::downloader::BodyDownloader,
@@ -267,7 +304,7 @@ mod tests {
         },
         test_utils::TestConsensus,
     };
-    use reth_primitives::{SealedHeader, MAINNET};
+    use reth_primitives::{SealedBlock, SealedHeader, MAINNET, U256};
     use reth_provider::ProviderFactory;
     use std::{
         io::{Read, Seek, SeekFrom, Write},
@@ -383,4 +420,61 @@ mod tests {
             Some(Ok(res)) => assert_eq!(res, zip_blocks(headers.iter(), &mut bodies))
         );
     }
+
+    #[tokio::test]
+    async fn test_download_bodies_from_era1() {
+        // Generate some random blocks
+        let db = create_test_rw_db();
+        let (headers, mut bodies) = generate_bodies(0..=19);
+
+        // write them to an era1 file
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let mut writer = Era1Writer::new(file.as_file()).unwrap();
+        for header in &headers {
+            let body = bodies[&header.hash()].clone();
+            let block = SealedBlock::new(header.clone(), body);
+            writer.append_block(block, &[], U256::ZERO).unwrap();
+        }
+        writer.finish().unwrap();
+
+        // now try to read them back, without the genesis block
+        let client = Arc::new(FileClient::from_era1(file.path()).await.unwrap());
+        assert_eq!(client.min_block(), Some(1));
+        assert_eq!(client.max_block(), Some(19));
+        assert_eq!(client.tip(), Some(headers.last().unwrap().hash()));
+
+        // insert headers in db for the bodies downloader
+        insert_headers(db.db(), &headers);
+
+        let mut downloader = BodiesDownloaderBuilder::default().build(
+            client.clone(),
+            Arc::new(TestConsensus::default()),
+            ProviderFactory::new(db, MAINNET.clone()),
+        );
+        downloader.set_download_range(1..=19).expect("failed to set download range");
+
+        assert_matches!(
+            downloader.next().await,
+            Some(Ok(res)) => assert_eq!(res, zip_blocks(headers[1..].iter(), &mut bodies))
+        );
+    }
+
+    #[test]
+    fn tip_of_blocks_after_genesis_epoch() {
+        // the blocks of a later era1 file don't start at block 1
+        let (headers, _) = generate_bodies(8192..=8201);
+        let client = FileClient {
+            headers: HashMap::new(),
+            hash_to_number: HashMap::new(),
+            bodies: HashMap::new(),
+        }
+        .with_headers(
+            headers.iter().map(|header| (header.number, header.header.clone())).collect(),
+        );
+
+        assert!(client.has_canonical_blocks());
+        assert_eq!(client.min_block(), Some(8192));
+        assert_eq!(client.max_block(), Some(8201));
+        assert_eq!(client.tip(), Some(headers.last().unwrap().hash()));
+    }
 }
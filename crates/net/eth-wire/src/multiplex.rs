@@ -43,13 +43,28 @@ impl<St> RlpxProtocolMultiplexer<St> {
         Self { conn, protocols: Default::default() }
     }
 
-    /// Installs a new protocol on top of the raw p2p stream
-    pub fn install_protocol<S>(
+    /// Installs a new protocol on top of the raw p2p stream.
+    ///
+    /// This accepts a closure that receives a [ProtocolConnection] that will yield messages for the
+    /// given capability and returns the stream of messages the protocol sends on the wire.
+    ///
+    /// Returns an error if the capability is not shared with the remote.
+    pub fn install_protocol<F, Proto>(
         &mut self,
-        _cap: Capability,
-        _st: S,
-    ) -> Result<(), UnsupportedCapabilityError> {
-        todo!()
+        cap: &Capability,
+        f: F,
+    ) -> Result<(), UnsupportedCapabilityError>
+    where
+        F: FnOnce(ProtocolConnection) -> Proto,
+        Proto: Stream<Item = BytesMut> + Send + 'static,
+    {
+        let shared_cap = self.shared_capabilities().ensure_matching_capability(cap).cloned()?;
+        let (to_satellite, rx) = mpsc::unbounded_channel();
+        let proto_conn = ProtocolConnection { from_wire: UnboundedReceiverStream::new(rx) };
+        let st = f(proto_conn);
+        let st = ProtocolStream { cap: shared_cap, to_satellite, satellite_st: Box::pin(st) };
+        self.protocols.push(st);
+        Ok(())
     }
 
     /// Returns the [SharedCapabilities] of the underlying raw p2p stream
@@ -250,7 +265,37 @@ pub struct RlpxSatelliteStream<St, Primary> {
     out_buffer: VecDeque<Bytes>,
 }
 
-impl<St, Primary> RlpxSatelliteStream<St, Primary> {}
+impl<St, Primary> RlpxSatelliteStream<St, Primary> {
+    /// Returns the primary protocol.
+    #[inline]
+    pub fn primary(&self) -> &Primary {
+        &self.primary
+    }
+
+    /// Returns mutable access to the primary protocol.
+    #[inline]
+    pub fn primary_mut(&mut self) -> &mut Primary {
+        &mut self.primary
+    }
+
+    /// Returns the underlying [P2PStream].
+    #[inline]
+    pub fn inner(&self) -> &P2PStream<St> {
+        &self.conn
+    }
+
+    /// Returns mutable access to the underlying [P2PStream].
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut P2PStream<St> {
+        &mut self.conn
+    }
+
+    /// Consumes this type and returns the wrapped [P2PStream].
+    #[inline]
+    pub fn into_inner(self) -> P2PStream<St> {
+        self.conn
+    }
+}
 
 impl<St, Primary, PrimaryErr> Stream for RlpxSatelliteStream<St, Primary>
 where
@@ -269,25 +314,6 @@ where
                 return Poll::Ready(Some(msg))
             }
 
-            let mut out_ready = true;
-            loop {
-                match this.conn.poll_ready_unpin(cx) {
-                    Poll::Ready(_) => {
-                        if let Some(msg) = this.out_buffer.pop_front() {
-                            if let Err(err) = this.conn.start_send_unpin(msg) {
-                                return Poll::Ready(Some(Err(err.into())))
-                            }
-                        } else {
-                            break
-                        }
-                    }
-                    Poll::Pending => {
-                        out_ready = false;
-                        break
-                    }
-                }
-            }
-
             // advance primary out
             loop {
                 match this.from_primary.poll_next_unpin(cx) {
@@ -302,7 +328,7 @@ where
                 }
             }
 
-            // advance all satellites
+            // advance all satellites, a satellite that finished is removed
             for idx in (0..this.satellites.len()).rev() {
                 let mut proto = this.satellites.swap_remove(idx);
                 loop {
@@ -310,7 +336,7 @@ where
                         Poll::Ready(Some(msg)) => {
                             this.out_buffer.push_back(msg);
                         }
-                        Poll::Ready(None) => return Poll::Ready(None),
+                        Poll::Ready(None) => break,
                         Poll::Pending => {
                             this.satellites.push(proto);
                             break
@@ -319,6 +345,23 @@ where
                 }
             }
 
+            // send all buffered messages to the wire
+            while !this.out_buffer.is_empty() {
+                match this.conn.poll_ready_unpin(cx) {
+                    Poll::Ready(Ok(())) => {
+                        let msg = this.out_buffer.pop_front().expect("not empty");
+                        if let Err(err) = this.conn.start_send_unpin(msg) {
+                            return Poll::Ready(Some(Err(err.into())))
+                        }
+                    }
+                    Poll::Ready(Err(err)) => return Poll::Ready(Some(Err(err.into()))),
+                    Poll::Pending => break,
+                }
+            }
+            if let Poll::Ready(Err(err)) = this.conn.poll_flush_unpin(cx) {
+                return Poll::Ready(Some(Err(err.into())))
+            }
+
             let mut delegated = false;
             loop {
                 // pull messages from connection
@@ -358,7 +401,8 @@ where
                 }
             }
 
-            if !delegated || !out_ready || this.out_buffer.is_empty() {
+            // if messages were delegated, the primary or the satellites may have new messages
+            if !delegated {
                 return Poll::Pending
             }
         }
@@ -368,7 +412,7 @@ where
 impl<St, Primary, T> Sink<T> for RlpxSatelliteStream<St, Primary>
 where
     St: Stream<Item = io::Result<BytesMut>> + Sink<Bytes, Error = io::Error> + Unpin,
-    Primary: Sink<T, Error = io::Error> + Unpin,
+    Primary: Sink<T> + Unpin,
     P2PStreamError: Into<<Primary as Sink<T>>::Error>,
 {
     type Error = <Primary as Sink<T>>::Error;
@@ -389,7 +433,19 @@ where
     }
 
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        self.get_mut().conn.poll_flush_unpin(cx).map_err(Into::into)
+        let this = self.get_mut();
+        // move all messages the primary sent into the buffer and write them to the wire
+        while let Poll::Ready(Some(msg)) = this.from_primary.poll_next_unpin(cx) {
+            this.out_buffer.push_back(msg);
+        }
+        while !this.out_buffer.is_empty() {
+            if let Err(err) = ready!(this.conn.poll_ready_unpin(cx)) {
+                return Poll::Ready(Err(err.into()))
+            }
+            let msg = this.out_buffer.pop_front().expect("not empty");
+            this.conn.start_send_unpin(msg).map_err(Into::into)?;
+        }
+        this.conn.poll_flush_unpin(cx).map_err(Into::into)
     }
 
     fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
@@ -402,7 +458,7 @@ struct ProtocolStream {
     cap: SharedCapability,
     /// the channel shared with the satellite stream
     to_satellite: UnboundedSender<BytesMut>,
-    satellite_st: Pin<Box<dyn Stream<Item = BytesMut>>>,
+    satellite_st: Pin<Box<dyn Stream<Item = BytesMut> + Send>>,
 }
 
 impl ProtocolStream {
@@ -486,7 +542,11 @@ mod tests {
         let conn = connect_passthrough(local_addr, eth_hello().0).await;
         let eth = conn.shared_capabilities().eth().unwrap().clone();
 
-        let multiplexer = RlpxProtocolMultiplexer::new(conn);
+        let mut multiplexer = RlpxProtocolMultiplexer::new(conn);
+
+        // the remote does not support snap
+        let err = multiplexer.install_protocol(&Capability::snap_1(), |conn| conn);
+        assert!(err.is_err());
 
         let _satellite = multiplexer
             .into_satellite_stream_with_handshake(
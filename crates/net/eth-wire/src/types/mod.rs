@@ -23,3 +23,5 @@ pub use state::*;
 
 pub mod receipts;
 pub use receipts::*;
+
+pub mod snap;
@@ -0,0 +1,481 @@
+//! Implements the `snap/1` protocol messages.
+//!
+//! The `snap` protocol is a satellite protocol of `eth` that is used to sync the state by
+//! downloading ranges of the flat state together with the proofs of their boundaries.
+//!
+//! See also <https://github.com/ethereum/devp2p/blob/master/caps/snap.md>
+use alloy_rlp::{Decodable, Encodable, Header, RlpDecodable, RlpEncodable};
+use reth_primitives::{
+    bytes::{Buf, BufMut},
+    constants::EMPTY_ROOT_HASH,
+    trie::TrieAccount,
+    Bytes, B256, KECCAK_EMPTY, U256,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Represents message IDs for `snap` protocol messages.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SnapMessageID {
+    /// Requests an unknown number of accounts from a given account trie.
+    GetAccountRange = 0x00,
+    /// Response to [SnapMessageID::GetAccountRange].
+    AccountRange = 0x01,
+    /// Requests the storage slots of multiple accounts' storage tries.
+    GetStorageRanges = 0x02,
+    /// Response to [SnapMessageID::GetStorageRanges].
+    StorageRanges = 0x03,
+    /// Requests a number of contract byte-codes by hash.
+    GetByteCodes = 0x04,
+    /// Response to [SnapMessageID::GetByteCodes].
+    ByteCodes = 0x05,
+    /// Requests a number of state (either account or storage) trie nodes by path.
+    GetTrieNodes = 0x06,
+    /// Response to [SnapMessageID::GetTrieNodes].
+    TrieNodes = 0x07,
+}
+
+impl Encodable for SnapMessageID {
+    fn encode(&self, out: &mut dyn BufMut) {
+        out.put_u8(*self as u8);
+    }
+
+    fn length(&self) -> usize {
+        1
+    }
+}
+
+impl Decodable for SnapMessageID {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let id = buf.first().ok_or(alloy_rlp::Error::InputTooShort)?;
+        let id = match id {
+            0x00 => SnapMessageID::GetAccountRange,
+            0x01 => SnapMessageID::AccountRange,
+            0x02 => SnapMessageID::GetStorageRanges,
+            0x03 => SnapMessageID::StorageRanges,
+            0x04 => SnapMessageID::GetByteCodes,
+            0x05 => SnapMessageID::ByteCodes,
+            0x06 => SnapMessageID::GetTrieNodes,
+            0x07 => SnapMessageID::TrieNodes,
+            _ => return Err(alloy_rlp::Error::Custom("Invalid message ID")),
+        };
+        buf.advance(1);
+        Ok(id)
+    }
+}
+
+/// Requests an unknown number of accounts from the account trie with the given root, starting at
+/// the given account hash.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GetAccountRange {
+    /// The request id.
+    pub request_id: u64,
+    /// The root of the account trie to serve.
+    pub root_hash: B256,
+    /// The account hash of the first account to retrieve.
+    pub starting_hash: B256,
+    /// The account hash after which to stop serving data.
+    pub limit_hash: B256,
+    /// The soft limit at which to stop returning data.
+    pub response_bytes: u64,
+}
+
+/// The response to [GetAccountRange], containing consecutive accounts and the merkle proofs of the
+/// first requested and the last returned account hash.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AccountRange {
+    /// The request id.
+    pub request_id: u64,
+    /// The consecutive accounts of the range.
+    pub accounts: Vec<AccountData>,
+    /// The trie nodes that prove the boundaries of the range.
+    pub proof: Vec<Bytes>,
+}
+
+/// An account of an [AccountRange].
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AccountData {
+    /// The hash of the account address.
+    pub hash: B256,
+    /// The account in its slim format.
+    pub body: SlimAccount,
+}
+
+/// An account in the slim format of the `snap` protocol.
+///
+/// This is the RLP encoding of the account as it is stored in the trie, except that the empty
+/// storage root and the hash of the empty code are encoded as empty strings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SlimAccount {
+    /// Account nonce.
+    pub nonce: u64,
+    /// Account balance.
+    pub balance: U256,
+    /// Account's storage root.
+    pub storage_root: B256,
+    /// Hash of the account's bytecode.
+    pub code_hash: B256,
+}
+
+impl SlimAccount {
+    /// Returns the storage root as encoded in the slim format.
+    fn slim_storage_root(&self) -> &[u8] {
+        if self.storage_root == EMPTY_ROOT_HASH {
+            &[]
+        } else {
+            self.storage_root.as_slice()
+        }
+    }
+
+    /// Returns the code hash as encoded in the slim format.
+    fn slim_code_hash(&self) -> &[u8] {
+        if self.code_hash == KECCAK_EMPTY {
+            &[]
+        } else {
+            self.code_hash.as_slice()
+        }
+    }
+
+    fn fields_len(&self) -> usize {
+        self.nonce.length() +
+            self.balance.length() +
+            self.slim_storage_root().length() +
+            self.slim_code_hash().length()
+    }
+}
+
+impl From<TrieAccount> for SlimAccount {
+    fn from(account: TrieAccount) -> Self {
+        Self {
+            nonce: account.nonce(),
+            balance: account.balance(),
+            storage_root: account.storage_root(),
+            code_hash: account.code_hash(),
+        }
+    }
+}
+
+impl Encodable for SlimAccount {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.fields_len() }.encode(out);
+        self.nonce.encode(out);
+        self.balance.encode(out);
+        self.slim_storage_root().encode(out);
+        self.slim_code_hash().encode(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.fields_len();
+        payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+impl Decodable for SlimAccount {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString)
+        }
+        let started_len = buf.len();
+
+        let nonce = u64::decode(buf)?;
+        let balance = U256::decode(buf)?;
+        let storage_root = decode_slim_hash(buf, EMPTY_ROOT_HASH)?;
+        let code_hash = decode_slim_hash(buf, KECCAK_EMPTY)?;
+
+        let consumed = started_len - buf.len();
+        if consumed != header.payload_length {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: header.payload_length,
+                got: consumed,
+            })
+        }
+
+        Ok(Self { nonce, balance, storage_root, code_hash })
+    }
+}
+
+/// Decodes a hash that is encoded as an empty string if it is equal to `empty`.
+fn decode_slim_hash(buf: &mut &[u8], empty: B256) -> alloy_rlp::Result<B256> {
+    let bytes = Bytes::decode(buf)?;
+    if bytes.is_empty() {
+        return Ok(empty)
+    }
+    B256::try_from(bytes.as_ref()).map_err(|_| alloy_rlp::Error::UnexpectedLength)
+}
+
+/// Requests the storage slots of multiple accounts' storage tries.
+///
+/// The starting and limit hashes only apply to the first account. They are empty if the storage
+/// of the first account is requested in full.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GetStorageRanges {
+    /// The request id.
+    pub request_id: u64,
+    /// The root of the account trie to serve.
+    pub root_hash: B256,
+    /// The account hashes of the storage tries to serve.
+    pub account_hashes: Vec<B256>,
+    /// The storage slot hash of the first slot to retrieve.
+    pub starting_hash: Bytes,
+    /// The storage slot hash after which to stop serving data.
+    pub limit_hash: Bytes,
+    /// The soft limit at which to stop returning data.
+    pub response_bytes: u64,
+}
+
+impl GetStorageRanges {
+    /// Returns the storage slot hash of the first slot to retrieve, which is zero if the starting
+    /// hash is empty.
+    pub fn origin(&self) -> B256 {
+        B256::try_from(self.starting_hash.as_ref()).unwrap_or_default()
+    }
+
+    /// Returns the storage slot hash after which to stop serving data, which is the maximum hash
+    /// if the limit hash is empty.
+    pub fn limit(&self) -> B256 {
+        B256::try_from(self.limit_hash.as_ref()).unwrap_or(B256::repeat_byte(0xff))
+    }
+}
+
+/// The response to [GetStorageRanges], containing the consecutive storage slots of the requested
+/// accounts and the merkle proofs of the boundaries of the last range if it is incomplete.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StorageRanges {
+    /// The request id.
+    pub request_id: u64,
+    /// The consecutive storage slots of each requested account.
+    pub slots: Vec<Vec<StorageData>>,
+    /// The trie nodes that prove the boundaries of the last range.
+    pub proof: Vec<Bytes>,
+}
+
+/// A storage slot of [StorageRanges].
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StorageData {
+    /// The hash of the storage slot.
+    pub hash: B256,
+    /// The RLP encoded value of the storage slot.
+    pub data: Bytes,
+}
+
+/// Requests a number of contract byte-codes by hash.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GetByteCodes {
+    /// The request id.
+    pub request_id: u64,
+    /// The code hashes to retrieve the code for.
+    pub hashes: Vec<B256>,
+    /// The soft limit at which to stop returning data.
+    pub response_bytes: u64,
+}
+
+/// The response to [GetByteCodes], containing the requested codes in request order.
+///
+/// Unavailable codes are skipped.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ByteCodes {
+    /// The request id.
+    pub request_id: u64,
+    /// The requested codes.
+    pub codes: Vec<Bytes>,
+}
+
+/// Requests a number of state trie nodes by path.
+///
+/// Each path set starts with the compact encoded path of a node in the account trie. If it
+/// contains more paths, the first path is the full account hash and the other paths are compact
+/// encoded paths of nodes in the storage trie of that account.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GetTrieNodes {
+    /// The request id.
+    pub request_id: u64,
+    /// The root of the account trie to serve.
+    pub root_hash: B256,
+    /// The trie node path sets to retrieve.
+    pub paths: Vec<Vec<Bytes>>,
+    /// The soft limit at which to stop returning data.
+    pub response_bytes: u64,
+}
+
+/// The response to [GetTrieNodes], containing the requested nodes in request order.
+///
+/// The response ends at the first unavailable node.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TrieNodes {
+    /// The request id.
+    pub request_id: u64,
+    /// The requested trie nodes.
+    pub nodes: Vec<Bytes>,
+}
+
+/// Represents a message of the `snap` protocol.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SnapMessage {
+    /// Represents a [GetAccountRange] request.
+    GetAccountRange(GetAccountRange),
+    /// Represents an [AccountRange] response.
+    AccountRange(AccountRange),
+    /// Represents a [GetStorageRanges] request.
+    GetStorageRanges(GetStorageRanges),
+    /// Represents a [StorageRanges] response.
+    StorageRanges(StorageRanges),
+    /// Represents a [GetByteCodes] request.
+    GetByteCodes(GetByteCodes),
+    /// Represents a [ByteCodes] response.
+    ByteCodes(ByteCodes),
+    /// Represents a [GetTrieNodes] request.
+    GetTrieNodes(GetTrieNodes),
+    /// Represents a [TrieNodes] response.
+    TrieNodes(TrieNodes),
+}
+
+impl SnapMessage {
+    /// Returns the message's ID.
+    pub fn message_id(&self) -> SnapMessageID {
+        match self {
+            SnapMessage::GetAccountRange(_) => SnapMessageID::GetAccountRange,
+            SnapMessage::AccountRange(_) => SnapMessageID::AccountRange,
+            SnapMessage::GetStorageRanges(_) => SnapMessageID::GetStorageRanges,
+            SnapMessage::StorageRanges(_) => SnapMessageID::StorageRanges,
+            SnapMessage::GetByteCodes(_) => SnapMessageID::GetByteCodes,
+            SnapMessage::ByteCodes(_) => SnapMessageID::ByteCodes,
+            SnapMessage::GetTrieNodes(_) => SnapMessageID::GetTrieNodes,
+            SnapMessage::TrieNodes(_) => SnapMessageID::TrieNodes,
+        }
+    }
+
+    /// Decodes a message that is prefixed with its message ID.
+    pub fn decode_message(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let message = match SnapMessageID::decode(buf)? {
+            SnapMessageID::GetAccountRange => {
+                SnapMessage::GetAccountRange(GetAccountRange::decode(buf)?)
+            }
+            SnapMessageID::AccountRange => SnapMessage::AccountRange(AccountRange::decode(buf)?),
+            SnapMessageID::GetStorageRanges => {
+                SnapMessage::GetStorageRanges(GetStorageRanges::decode(buf)?)
+            }
+            SnapMessageID::StorageRanges => SnapMessage::StorageRanges(StorageRanges::decode(buf)?),
+            SnapMessageID::GetByteCodes => SnapMessage::GetByteCodes(GetByteCodes::decode(buf)?),
+            SnapMessageID::ByteCodes => SnapMessage::ByteCodes(ByteCodes::decode(buf)?),
+            SnapMessageID::GetTrieNodes => SnapMessage::GetTrieNodes(GetTrieNodes::decode(buf)?),
+            SnapMessageID::TrieNodes => SnapMessage::TrieNodes(TrieNodes::decode(buf)?),
+        };
+        Ok(message)
+    }
+}
+
+/// Encodes the message prefixed with its message ID.
+impl Encodable for SnapMessage {
+    fn encode(&self, out: &mut dyn BufMut) {
+        self.message_id().encode(out);
+        match self {
+            SnapMessage::GetAccountRange(msg) => msg.encode(out),
+            SnapMessage::AccountRange(msg) => msg.encode(out),
+            SnapMessage::GetStorageRanges(msg) => msg.encode(out),
+            SnapMessage::StorageRanges(msg) => msg.encode(out),
+            SnapMessage::GetByteCodes(msg) => msg.encode(out),
+            SnapMessage::ByteCodes(msg) => msg.encode(out),
+            SnapMessage::GetTrieNodes(msg) => msg.encode(out),
+            SnapMessage::TrieNodes(msg) => msg.encode(out),
+        }
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = match self {
+            SnapMessage::GetAccountRange(msg) => msg.length(),
+            SnapMessage::AccountRange(msg) => msg.length(),
+            SnapMessage::GetStorageRanges(msg) => msg.length(),
+            SnapMessage::StorageRanges(msg) => msg.length(),
+            SnapMessage::GetByteCodes(msg) => msg.length(),
+            SnapMessage::ByteCodes(msg) => msg.length(),
+            SnapMessage::GetTrieNodes(msg) => msg.length(),
+            SnapMessage::TrieNodes(msg) => msg.length(),
+        };
+        self.message_id().length() + payload_length
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::hex;
+
+    #[test]
+    fn slim_account_roundtrip() {
+        let empty = SlimAccount {
+            nonce: 1,
+            balance: U256::from(2),
+            storage_root: EMPTY_ROOT_HASH,
+            code_hash: KECCAK_EMPTY,
+        };
+        let encoded = alloy_rlp::encode(empty);
+        assert_eq!(encoded, hex!("c401028080"));
+        assert_eq!(SlimAccount::decode(&mut &encoded[..]).unwrap(), empty);
+
+        let contract =
+            SlimAccount { storage_root: B256::repeat_byte(1), code_hash: B256::ZERO, ..empty };
+        let encoded = alloy_rlp::encode(contract);
+        assert_eq!(encoded.len(), contract.length());
+        assert_eq!(SlimAccount::decode(&mut &encoded[..]).unwrap(), contract);
+    }
+
+    #[test]
+    fn snap_message_roundtrip() {
+        let messages = [
+            SnapMessage::GetAccountRange(GetAccountRange {
+                request_id: 1,
+                root_hash: B256::repeat_byte(1),
+                starting_hash: B256::ZERO,
+                limit_hash: B256::repeat_byte(0xff),
+                response_bytes: 512 * 1024,
+            }),
+            SnapMessage::GetStorageRanges(GetStorageRanges {
+                request_id: 2,
+                root_hash: B256::repeat_byte(1),
+                account_hashes: vec![B256::repeat_byte(2)],
+                starting_hash: Bytes::new(),
+                limit_hash: Bytes::new(),
+                response_bytes: 512 * 1024,
+            }),
+            SnapMessage::TrieNodes(TrieNodes {
+                request_id: 3,
+                nodes: vec![Bytes::from_static(&[0xc0])],
+            }),
+        ];
+        for message in messages {
+            let encoded = alloy_rlp::encode(&message);
+            assert_eq!(encoded.len(), message.length());
+            assert_eq!(SnapMessage::decode_message(&mut &encoded[..]).unwrap(), message);
+        }
+    }
+
+    #[test]
+    fn storage_ranges_bounds() {
+        let request = GetStorageRanges {
+            request_id: 2,
+            root_hash: B256::ZERO,
+            account_hashes: vec![],
+            starting_hash: Bytes::new(),
+            limit_hash: Bytes::new(),
+            response_bytes: 0,
+        };
+        assert_eq!(request.origin(), B256::ZERO);
+        assert_eq!(request.limit(), B256::repeat_byte(0xff));
+    }
+}
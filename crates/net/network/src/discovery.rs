@@ -6,6 +6,7 @@ use crate::{
 };
 use futures::StreamExt;
 use reth_discv4::{DiscoveryUpdate, Discv4, Discv4Config, EnrForkIdEntry};
+use reth_discv5::{DiscoveredPeer, Discv5, Discv5Config};
 use reth_dns_discovery::{
     DnsDiscoveryConfig, DnsDiscoveryHandle, DnsDiscoveryService, DnsNodeRecordUpdate, DnsResolver,
 };
@@ -20,6 +21,7 @@ use std::{
 };
 use tokio::{sync::mpsc, task::JoinHandle};
 use tokio_stream::{wrappers::ReceiverStream, Stream};
+use tracing::debug;
 
 /// An abstraction over the configured discovery protocol.
 ///
@@ -39,6 +41,12 @@ pub struct Discovery {
     discv4_updates: Option<ReceiverStream<DiscoveryUpdate>>,
     /// The handle to the spawned discv4 service
     _discv4_service: Option<JoinHandle<()>>,
+    /// Handler to interact with the Discovery v5 service
+    discv5: Option<Discv5>,
+    /// All peers discovered by the discv5 service.
+    discv5_updates: Option<ReceiverStream<DiscoveredPeer>>,
+    /// The handle to the spawned discv5 service, aborted when discovery is dropped.
+    discv5_service: Option<JoinHandle<()>>,
     /// Handler to interact with the DNS discovery service
     _dns_discovery: Option<DnsDiscoveryHandle>,
     /// Updates from the DNS discovery service.
@@ -55,11 +63,13 @@ impl Discovery {
     /// Spawns the discovery service.
     ///
     /// This will spawn the [`reth_discv4::Discv4Service`] onto a new task and establish a listener
-    /// channel to receive all discovered nodes.
+    /// channel to receive all discovered nodes. If configured, the
+    /// [`reth_discv5::Discv5Service`] is spawned alongside it.
     pub async fn new(
         discovery_addr: SocketAddr,
         sk: SecretKey,
         discv4_config: Option<Discv4Config>,
+        discv5_config: Option<Discv5Config>,
         dns_discovery_config: Option<DnsDiscoveryConfig>,
     ) -> Result<Self, NetworkError> {
         // setup discv4
@@ -77,6 +87,20 @@ impl Discovery {
             (None, None, None)
         };
 
+        // setup discv5
+        let (discv5, discv5_updates, discv5_service) = if let Some(disc_config) = discv5_config {
+            let listen_addr = disc_config.listen_addr;
+            let (discv5, mut discv5_service) =
+                Discv5::start(&sk, disc_config).await.map_err(|err| {
+                    NetworkError::from_discv5_error(err, ServiceKind::Discovery(listen_addr))
+                })?;
+            let discv5_updates = discv5_service.update_stream();
+            let discv5_service = discv5_service.spawn();
+            (Some(discv5), Some(discv5_updates), Some(discv5_service))
+        } else {
+            (None, None, None)
+        };
+
         // setup DNS discovery
         let (_dns_discovery, dns_discovery_updates, _dns_disc_service) =
             if let Some(dns_config) = dns_discovery_config {
@@ -97,6 +121,9 @@ impl Discovery {
             discv4,
             discv4_updates,
             _discv4_service,
+            discv5,
+            discv5_updates,
+            discv5_service,
             discovered_nodes: Default::default(),
             queued_events: Default::default(),
             _dns_disc_service,
@@ -116,13 +143,23 @@ impl Discovery {
         self.discovery_listeners.retain_mut(|listener| listener.send(event.clone()).is_ok());
     }
 
-    /// Updates the `eth:ForkId` field in discv4.
+    /// Returns a handle to the discv4 service, if discv4 is enabled.
+    pub(crate) fn discv4(&self) -> Option<Discv4> {
+        self.discv4.clone()
+    }
+
+    /// Updates the `eth:ForkId` field in discv4 and discv5.
     #[allow(unused)]
     pub(crate) fn update_fork_id(&self, fork_id: ForkId) {
         if let Some(discv4) = &self.discv4 {
             // use forward-compatible forkid entry
             discv4.set_eip868_rlp("eth".as_bytes().to_vec(), EnrForkIdEntry::from(fork_id))
         }
+        if let Some(discv5) = &self.discv5 {
+            if let Err(err) = discv5.set_fork_id(fork_id) {
+                debug!(target: "net::discovery", %err, "failed to update discv5 fork id");
+            }
+        }
     }
 
     /// Bans the [`IpAddr`] in the discovery service.
@@ -130,6 +167,9 @@ impl Discovery {
         if let Some(discv4) = &self.discv4 {
             discv4.ban_ip(ip)
         }
+        if let Some(discv5) = &self.discv5 {
+            discv5.ban_ip(ip)
+        }
     }
 
     /// Bans the [`PeerId`] and [`IpAddr`] in the discovery service.
@@ -137,6 +177,9 @@ impl Discovery {
         if let Some(discv4) = &self.discv4 {
             discv4.ban(peer_id, ip)
         }
+        if let Some(discv5) = &self.discv5 {
+            discv5.ban(peer_id, ip)
+        }
     }
 
     /// Returns the id with which the local identifies itself in the network
@@ -203,6 +246,12 @@ impl Discovery {
                 self.on_discv4_update(update)
             }
 
+            while let Some(Poll::Ready(Some(peer))) =
+                self.discv5_updates.as_mut().map(|updates| updates.poll_next_unpin(cx))
+            {
+                self.on_node_record_update(peer.node_record, peer.fork_id);
+            }
+
             while let Some(Poll::Ready(Some(update))) =
                 self.dns_discovery_updates.as_mut().map(|updates| updates.poll_next_unpin(cx))
             {
@@ -225,6 +274,15 @@ impl Stream for Discovery {
     }
 }
 
+impl Drop for Discovery {
+    fn drop(&mut self) {
+        // the service holds a handle to discv5 itself, so it would otherwise keep on running
+        if let Some(service) = self.discv5_service.take() {
+            service.abort();
+        }
+    }
+}
+
 #[cfg(test)]
 impl Discovery {
     /// Returns a Discovery instance that does nothing and is intended for testing purposes.
@@ -246,6 +304,9 @@ impl Discovery {
             discv4_updates: Default::default(),
             queued_events: Default::default(),
             _discv4_service: Default::default(),
+            discv5: None,
+            discv5_updates: None,
+            discv5_service: None,
             _dns_discovery: None,
             dns_discovery_updates: None,
             _dns_disc_service: None,
@@ -275,9 +336,14 @@ mod tests {
         let mut rng = thread_rng();
         let (secret_key, _) = SECP256K1.generate_keypair(&mut rng);
         let discovery_addr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0));
-        let _discovery =
-            Discovery::new(discovery_addr, secret_key, Default::default(), Default::default())
-                .await
-                .unwrap();
+        let _discovery = Discovery::new(
+            discovery_addr,
+            secret_key,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
     }
 }
@@ -1,6 +1,7 @@
 //! Possible errors when interacting with the network.
 
 use crate::session::PendingSessionHandshakeError;
+use reth_discv5::Discv5Error;
 use reth_dns_discovery::resolver::ResolveError;
 use reth_eth_wire::{
     errors::{EthHandshakeError, EthStreamError, P2PHandshakeError, P2PStreamError},
@@ -43,6 +44,9 @@ pub enum NetworkError {
     /// IO error when creating the discovery service
     #[error("failed to launch discovery service: {0}")]
     Discovery(io::Error),
+    /// Error when creating the discv5 service
+    #[error("failed to launch discv5 service: {0}")]
+    Discv5(#[from] Discv5Error),
     /// Error when setting up the DNS resolver failed
     ///
     /// See also [DnsResolver](reth_dns_discovery::DnsResolver::from_system_conf)
@@ -63,6 +67,20 @@ impl NetworkError {
             }
         }
     }
+
+    /// Converts a [`Discv5Error`] to a more descriptive `NetworkError`.
+    ///
+    /// Discv5 only reports errors of the socket as strings, so an address that is already in use
+    /// is detected by its message.
+    pub fn from_discv5_error(err: Discv5Error, kind: ServiceKind) -> Self {
+        match err {
+            Discv5Error::Init(msg) if msg.contains("in use") => NetworkError::AddressAlreadyInUse {
+                kind,
+                error: io::Error::new(ErrorKind::AddrInUse, msg),
+            },
+            err => NetworkError::Discv5(err),
+        }
+    }
 }
 
 /// Abstraction over errors that can lead to a failed session
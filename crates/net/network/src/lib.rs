@@ -25,6 +25,12 @@
 //!
 //!        * Responds to incoming ETH related requests: `Headers`, `Bodies`
 //!
+//!    - `SNAP request Task`: is an optional spawned
+//!      [`SnapRequestHandler`](crate::snap_requests::SnapRequestHandler) future that:
+//!
+//!        * Responds to incoming `snap` requests: `AccountRange`, `StorageRanges`, `ByteCodes`,
+//!          `TrieNodes`
+//!
 //!    - `Discovery Task`: is a spawned [`Discv4`](reth_discv4::Discv4) future that handles peer
 //!      discovery and emits new peers to the `Network`
 //!
@@ -131,6 +137,7 @@ mod network;
 pub mod peers;
 pub mod protocol;
 mod session;
+pub mod snap_requests;
 mod state;
 mod swarm;
 pub mod transactions;
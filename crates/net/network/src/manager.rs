@@ -35,6 +35,7 @@ use crate::{
 };
 use futures::{Future, StreamExt};
 use parking_lot::Mutex;
+use reth_discv4::Discv4;
 use reth_eth_wire::{
     capability::{Capabilities, CapabilityMessage},
     DisconnectReason, EthVersion, Status,
@@ -175,6 +176,7 @@ where
             client,
             secret_key,
             mut discovery_v4_config,
+            mut discovery_v5_config,
             discovery_addr,
             listener_addr,
             peers_config,
@@ -209,9 +211,21 @@ where
             disc_config
         });
 
-        let discovery =
-            Discovery::new(discovery_addr, secret_key, discovery_v4_config, dns_discovery_config)
-                .await?;
+        discovery_v5_config = discovery_v5_config.map(|mut disc_config| {
+            // advertise the actual listener port, since the configured port could be `0`
+            disc_config.tcp_port = incoming.local_address().port();
+            disc_config.fork_id = Some(status.forkid);
+            disc_config
+        });
+
+        let discovery = Discovery::new(
+            discovery_addr,
+            secret_key,
+            discovery_v4_config,
+            discovery_v5_config,
+            dns_discovery_config,
+        )
+        .await?;
         // need to retrieve the addr here since provided port could be `0`
         let local_peer_id = discovery.local_id();
 
@@ -340,6 +354,14 @@ where
         self.swarm.state().peers().handle()
     }
 
+    /// Returns a handle to the discv4 service, if discv4 is enabled.
+    ///
+    /// This can be used to read the signed records of the discovered nodes, e.g. to publish them
+    /// as a DNS tree.
+    pub fn discv4(&self) -> Option<Discv4> {
+        self.swarm.state().discovery().discv4()
+    }
+
     /// Returns a new [`FetchClient`] that can be cloned and shared.
     ///
     /// The [`FetchClient`] is the entrypoint for sending requests to the network.
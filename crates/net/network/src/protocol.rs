@@ -113,6 +113,59 @@ impl RlpxSubProtocols {
     pub fn push(&mut self, protocol: impl IntoRlpxSubProtocol) {
         self.protocols.push(protocol.into_rlpx_sub_protocol());
     }
+
+    /// Returns all additional protocol handlers that should be announced to the remote during the
+    /// Rlpx handshake on an incoming connection.
+    pub(crate) fn on_incoming(&self, socket_addr: SocketAddr) -> RlpxSubProtocolHandlers {
+        RlpxSubProtocolHandlers(
+            self.protocols
+                .iter()
+                .filter_map(|protocol| protocol.0.on_incoming(socket_addr))
+                .collect(),
+        )
+    }
+
+    /// Returns all additional protocol handlers that should be announced to the remote during the
+    /// Rlpx handshake on an outgoing connection.
+    pub(crate) fn on_outgoing(
+        &self,
+        socket_addr: SocketAddr,
+        peer_id: PeerId,
+    ) -> RlpxSubProtocolHandlers {
+        RlpxSubProtocolHandlers(
+            self.protocols
+                .iter()
+                .filter_map(|protocol| protocol.0.on_outgoing(socket_addr, peer_id))
+                .collect(),
+        )
+    }
+}
+
+/// A set of additional RLPx-based sub-protocol connection handlers.
+#[derive(Default)]
+pub(crate) struct RlpxSubProtocolHandlers(Vec<Box<dyn DynConnectionHandler>>);
+
+impl RlpxSubProtocolHandlers {
+    /// Returns true if there are no additional protocol handlers.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the protocols of all handlers.
+    pub(crate) fn protocols(&self) -> impl Iterator<Item = Protocol> + '_ {
+        self.0.iter().map(|handler| handler.protocol())
+    }
+
+    /// Consumes the type and returns all handlers.
+    pub(crate) fn into_inner(self) -> Vec<Box<dyn DynConnectionHandler>> {
+        self.0
+    }
+}
+
+impl fmt::Debug for RlpxSubProtocolHandlers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.protocols()).finish()
+    }
 }
 
 pub(crate) trait DynProtocolHandler: fmt::Debug + Send + Sync + 'static {
@@ -149,14 +202,14 @@ pub(crate) trait DynConnectionHandler: Send + Sync + 'static {
     fn protocol(&self) -> Protocol;
 
     fn on_unsupported_by_peer(
-        self,
+        self: Box<Self>,
         supported: &SharedCapabilities,
         direction: Direction,
         peer_id: PeerId,
     ) -> OnNotSupported;
 
     fn into_connection(
-        self,
+        self: Box<Self>,
         direction: Direction,
         peer_id: PeerId,
         conn: ProtocolConnection,
@@ -172,20 +225,20 @@ where
     }
 
     fn on_unsupported_by_peer(
-        self,
+        self: Box<Self>,
         supported: &SharedCapabilities,
         direction: Direction,
         peer_id: PeerId,
     ) -> OnNotSupported {
-        T::on_unsupported_by_peer(self, supported, direction, peer_id)
+        T::on_unsupported_by_peer(*self, supported, direction, peer_id)
     }
 
     fn into_connection(
-        self,
+        self: Box<Self>,
         direction: Direction,
         peer_id: PeerId,
         conn: ProtocolConnection,
     ) -> Pin<Box<dyn Stream<Item = BytesMut> + Send + 'static>> {
-        Box::pin(T::into_connection(self, direction, peer_id, conn))
+        Box::pin(T::into_connection(*self, direction, peer_id, conn))
     }
 }
@@ -0,0 +1,146 @@
+//! Connection types for a session
+
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use reth_ecies::stream::ECIESStream;
+use reth_eth_wire::{
+    errors::EthStreamError,
+    message::EthBroadcastMessage,
+    multiplex::{ProtocolProxy, RlpxSatelliteStream},
+    EthMessage, EthStream, EthVersion, P2PStream,
+};
+use reth_net_common::bandwidth_meter::MeteredStream;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::net::TcpStream;
+
+/// The type of the underlying peer network connection.
+pub type EthPeerConnection = EthStream<P2PStream<ECIESStream<MeteredStream<TcpStream>>>>;
+
+/// The type of the underlying peer network connection if additional RLPx sub-protocols are
+/// multiplexed next to the `eth` protocol.
+pub type EthSatelliteConnection =
+    RlpxSatelliteStream<ECIESStream<MeteredStream<TcpStream>>, EthStream<ProtocolProxy>>;
+
+/// Connection types that support the ETH protocol.
+///
+/// This can be either:
+/// - A connection that only supports the ETH protocol
+/// - A connection that supports the ETH protocol and at least one other RLPx protocol
+// The variants are boxed because the underlying stream is ~6KB,
+// mostly coming from `P2PStream`'s `snap::Encoder` (2072), and `ECIESStream` (3600).
+#[derive(Debug)]
+pub enum EthRlpxConnection {
+    /// A connection that only supports the ETH protocol.
+    EthOnly(Box<EthPeerConnection>),
+    /// A connection that supports the ETH protocol and __at least one other__ RLPx protocol.
+    Satellite(Box<EthSatelliteConnection>),
+}
+
+impl EthRlpxConnection {
+    /// Returns the negotiated ETH version.
+    #[inline]
+    pub(crate) fn version(&self) -> EthVersion {
+        match self {
+            Self::EthOnly(conn) => conn.version(),
+            Self::Satellite(conn) => conn.primary().version(),
+        }
+    }
+
+    /// Consumes this type and returns the wrapped [P2PStream].
+    #[inline]
+    pub(crate) fn into_inner(self) -> P2PStream<ECIESStream<MeteredStream<TcpStream>>> {
+        match self {
+            Self::EthOnly(conn) => conn.into_inner(),
+            Self::Satellite(conn) => conn.into_inner(),
+        }
+    }
+
+    /// Returns a reference to the inner [P2PStream].
+    #[inline]
+    pub(crate) fn inner(&self) -> &P2PStream<ECIESStream<MeteredStream<TcpStream>>> {
+        match self {
+            Self::EthOnly(conn) => conn.inner(),
+            Self::Satellite(conn) => conn.inner(),
+        }
+    }
+
+    /// Returns mutable access to the inner [P2PStream].
+    #[inline]
+    pub(crate) fn inner_mut(&mut self) -> &mut P2PStream<ECIESStream<MeteredStream<TcpStream>>> {
+        match self {
+            Self::EthOnly(conn) => conn.inner_mut(),
+            Self::Satellite(conn) => conn.inner_mut(),
+        }
+    }
+
+    /// Same as [`Sink::start_send`] but accepts a [`EthBroadcastMessage`] instead.
+    #[inline]
+    pub(crate) fn start_send_broadcast(
+        &mut self,
+        item: EthBroadcastMessage,
+    ) -> Result<(), EthStreamError> {
+        match self {
+            Self::EthOnly(conn) => conn.start_send_broadcast(item),
+            Self::Satellite(conn) => conn.primary_mut().start_send_broadcast(item),
+        }
+    }
+}
+
+impl From<EthPeerConnection> for EthRlpxConnection {
+    #[inline]
+    fn from(conn: EthPeerConnection) -> Self {
+        Self::EthOnly(Box::new(conn))
+    }
+}
+
+impl From<EthSatelliteConnection> for EthRlpxConnection {
+    #[inline]
+    fn from(conn: EthSatelliteConnection) -> Self {
+        Self::Satellite(Box::new(conn))
+    }
+}
+
+impl Stream for EthRlpxConnection {
+    type Item = Result<EthMessage, EthStreamError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match self.get_mut() {
+            Self::EthOnly(conn) => conn.poll_next_unpin(cx),
+            Self::Satellite(conn) => conn.poll_next_unpin(cx),
+        }
+    }
+}
+
+impl Sink<EthMessage> for EthRlpxConnection {
+    type Error = EthStreamError;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match self.get_mut() {
+            Self::EthOnly(conn) => conn.poll_ready_unpin(cx),
+            Self::Satellite(conn) => conn.poll_ready_unpin(cx),
+        }
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: EthMessage) -> Result<(), Self::Error> {
+        match self.get_mut() {
+            Self::EthOnly(conn) => conn.start_send_unpin(item),
+            Self::Satellite(conn) => conn.start_send_unpin(item),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match self.get_mut() {
+            Self::EthOnly(conn) => conn.poll_flush_unpin(cx),
+            Self::Satellite(conn) => conn.poll_flush_unpin(cx),
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match self.get_mut() {
+            Self::EthOnly(conn) => conn.poll_close_unpin(cx),
+            Self::Satellite(conn) => conn.poll_close_unpin(cx),
+        }
+    }
+}
@@ -9,7 +9,8 @@ use futures::{future::Either, io, FutureExt, StreamExt};
 use reth_ecies::{stream::ECIESStream, ECIESError};
 use reth_eth_wire::{
     capability::{Capabilities, CapabilityMessage},
-    errors::EthStreamError,
+    errors::{EthStreamError, P2PStreamError},
+    multiplex::RlpxProtocolMultiplexer,
     DisconnectReason, EthVersion, HelloMessageWithProtocols, Status, UnauthedEthStream,
     UnauthedP2PStream,
 };
@@ -40,15 +41,19 @@ use tracing::{instrument, trace};
 
 mod active;
 mod config;
+mod conn;
 mod handle;
 pub use crate::message::PeerRequestSender;
 pub use config::{SessionLimits, SessionsConfig};
+pub use conn::EthRlpxConnection;
 pub use handle::{
     ActiveSessionHandle, ActiveSessionMessage, PendingSessionEvent, PendingSessionHandle,
     SessionCommand,
 };
 
-use crate::protocol::{IntoRlpxSubProtocol, RlpxSubProtocols};
+use crate::protocol::{
+    IntoRlpxSubProtocol, OnNotSupported, RlpxSubProtocolHandlers, RlpxSubProtocols,
+};
 pub use reth_network_api::{Direction, PeerInfo};
 
 /// Internal identifier for active sessions.
@@ -228,6 +233,7 @@ impl SessionManager {
         let hello_message = self.hello_message.clone();
         let status = self.status;
         let fork_filter = self.fork_filter.clone();
+        let extra_handlers = self.extra_protocols.on_incoming(remote_addr);
         self.spawn(start_pending_incoming_session(
             disconnect_rx,
             session_id,
@@ -238,6 +244,7 @@ impl SessionManager {
             hello_message,
             status,
             fork_filter,
+            extra_handlers,
         ));
 
         let handle = PendingSessionHandle {
@@ -261,6 +268,7 @@ impl SessionManager {
             let fork_filter = self.fork_filter.clone();
             let status = self.status;
             let band_with_meter = self.bandwidth_meter.clone();
+            let extra_handlers = self.extra_protocols.on_outgoing(remote_addr, remote_peer_id);
             self.spawn(start_pending_outbound_session(
                 disconnect_rx,
                 pending_events,
@@ -272,6 +280,7 @@ impl SessionManager {
                 status,
                 fork_filter,
                 band_with_meter,
+                extra_handlers,
             ));
 
             let handle = PendingSessionHandle {
@@ -757,6 +766,7 @@ pub(crate) async fn start_pending_incoming_session(
     hello: HelloMessageWithProtocols,
     status: Status,
     fork_filter: ForkFilter,
+    extra_handlers: RlpxSubProtocolHandlers,
 ) {
     authenticate(
         disconnect_rx,
@@ -769,6 +779,7 @@ pub(crate) async fn start_pending_incoming_session(
         hello,
         status,
         fork_filter,
+        extra_handlers,
     )
     .await
 }
@@ -787,6 +798,7 @@ async fn start_pending_outbound_session(
     status: Status,
     fork_filter: ForkFilter,
     bandwidth_meter: BandwidthMeter,
+    extra_handlers: RlpxSubProtocolHandlers,
 ) {
     let stream = match TcpStream::connect(remote_addr).await {
         Ok(stream) => {
@@ -818,6 +830,7 @@ async fn start_pending_outbound_session(
         hello,
         status,
         fork_filter,
+        extra_handlers,
     )
     .await
 }
@@ -835,6 +848,7 @@ async fn authenticate(
     hello: HelloMessageWithProtocols,
     status: Status,
     fork_filter: ForkFilter,
+    extra_handlers: RlpxSubProtocolHandlers,
 ) {
     let local_addr = stream.inner().local_addr().ok();
     let stream = match get_eciess_stream(stream, secret_key, direction).await {
@@ -863,6 +877,7 @@ async fn authenticate(
         hello,
         status,
         fork_filter,
+        extra_handlers,
     )
     .boxed();
 
@@ -900,7 +915,10 @@ async fn get_eciess_stream<Io: AsyncRead + AsyncWrite + Unpin + HasRemoteAddr>(
 
 /// Authenticate the stream via handshake
 ///
-/// On Success return the authenticated stream as [`PendingSessionEvent`]
+/// On Success return the authenticated stream as [`PendingSessionEvent`].
+///
+/// If additional [RlpxSubProtocolHandlers] are provided, the hello message is updated to also
+/// negotiate the additional protocols.
 #[allow(clippy::too_many_arguments)]
 async fn authenticate_stream(
     stream: UnauthedP2PStream<ECIESStream<MeteredStream<TcpStream>>>,
@@ -908,10 +926,14 @@ async fn authenticate_stream(
     remote_addr: SocketAddr,
     local_addr: Option<SocketAddr>,
     direction: Direction,
-    hello: HelloMessageWithProtocols,
+    mut hello: HelloMessageWithProtocols,
     status: Status,
     fork_filter: ForkFilter,
+    extra_handlers: RlpxSubProtocolHandlers,
 ) -> PendingSessionEvent {
+    // Add extra protocols to the hello message
+    hello.protocols.extend(extra_handlers.protocols());
+
     // conduct the p2p handshake and return the authenticated stream
     let (p2p_stream, their_hello) = match stream.handshake(hello).await {
         Ok(stream_res) => stream_res,
@@ -926,7 +948,7 @@ async fn authenticate_stream(
     };
 
     // Ensure we negotiated eth protocol
-    let version = match p2p_stream.shared_capabilities().eth_version() {
+    let eth_version = match p2p_stream.shared_capabilities().eth_version() {
         Ok(version) => version,
         Err(err) => {
             return PendingSessionEvent::Disconnected {
@@ -938,22 +960,86 @@ async fn authenticate_stream(
         }
     };
 
-    // if the hello handshake was successful we can try status handshake
-    //
-    // Before trying status handshake, set up the version to shared_capability
-    let status = Status { version, ..status };
-    let eth_unauthed = UnauthedEthStream::new(p2p_stream);
-    let (eth_stream, their_status) = match eth_unauthed.handshake(status, fork_filter).await {
-        Ok(stream_res) => stream_res,
-        Err(err) => {
-            return PendingSessionEvent::Disconnected {
-                remote_addr,
-                session_id,
+    let (conn, their_status): (EthRlpxConnection, _) = if extra_handlers.is_empty() {
+        // if the hello handshake was successful we can try status handshake
+        //
+        // Before trying status handshake, set up the version to negotiated shared version
+        let status = Status { version: eth_version, ..status };
+        let eth_unauthed = UnauthedEthStream::new(p2p_stream);
+        let (eth_stream, their_status) = match eth_unauthed.handshake(status, fork_filter).await {
+            Ok(stream_res) => stream_res,
+            Err(err) => {
+                return PendingSessionEvent::Disconnected {
+                    remote_addr,
+                    session_id,
+                    direction,
+                    error: Some(err),
+                }
+            }
+        };
+        (eth_stream.into(), their_status)
+    } else {
+        // Multiplex the stream with the extra protocols
+        let mut multiplex_stream = RlpxProtocolMultiplexer::new(p2p_stream);
+
+        // install additional handlers
+        for handler in extra_handlers.into_inner() {
+            let cap = handler.protocol().cap;
+            let remote_peer_id = their_hello.id;
+
+            if multiplex_stream.shared_capabilities().contains(&cap) {
+                let _ = multiplex_stream.install_protocol(&cap, move |conn| {
+                    handler.into_connection(direction, remote_peer_id, conn)
+                });
+            } else if handler.on_unsupported_by_peer(
+                multiplex_stream.shared_capabilities(),
                 direction,
-                error: Some(err),
+                remote_peer_id,
+            ) == OnNotSupported::Disconnect
+            {
+                return PendingSessionEvent::Disconnected {
+                    remote_addr,
+                    session_id,
+                    direction,
+                    error: Some(P2PStreamError::CapabilityNotShared.into()),
+                }
             }
         }
+
+        // Before trying status handshake, set up the version to negotiated shared version
+        let status = Status { version: eth_version, ..status };
+        let eth_cap = multiplex_stream
+            .shared_capabilities()
+            .eth()
+            .expect("eth is negotiated")
+            .capability()
+            .into_owned();
+
+        let mut their_status = None;
+        let their_status_ref = &mut their_status;
+        let satellite = match multiplex_stream
+            .into_satellite_stream_with_handshake(&eth_cap, move |proxy| async move {
+                let (eth_stream, their_status) =
+                    UnauthedEthStream::new(proxy).handshake(status, fork_filter).await?;
+                *their_status_ref = Some(their_status);
+                Ok::<_, EthStreamError>(eth_stream)
+            })
+            .await
+        {
+            Ok(satellite) => satellite,
+            Err(err) => {
+                return PendingSessionEvent::Disconnected {
+                    remote_addr,
+                    session_id,
+                    direction,
+                    error: Some(err),
+                }
+            }
+        };
+
+        (satellite.into(), their_status.expect("status is set on successful handshake"))
     };
+
     PendingSessionEvent::Established {
         session_id,
         remote_addr,
@@ -961,7 +1047,7 @@ async fn authenticate_stream(
         peer_id: their_hello.id,
         capabilities: Arc::new(Capabilities::from(their_hello.capabilities)),
         status: Arc::new(their_status),
-        conn: Box::new(eth_stream),
+        conn,
         direction,
         client_id: their_hello.client_version,
     }
@@ -0,0 +1,831 @@
+//! Serves the `snap` protocol on top of the p2p network.
+//!
+//! The `snap` protocol is installed as an additional RLPx sub-protocol next to `eth`, see
+//! [SnapProtocolHandler]. Requests are delegated to the [SnapRequestHandler] that serves them from
+//! the persisted hashed state and state trie, for the state of any of the latest canonical blocks.
+//!
+//! See also <https://github.com/ethereum/devp2p/blob/master/caps/snap.md>
+
+use crate::protocol::{ConnectionHandler, OnNotSupported, ProtocolHandler};
+use alloy_rlp::Encodable;
+use futures::{stream::FuturesUnordered, Future, FutureExt, Stream, StreamExt};
+use reth_eth_wire::{
+    capability::SharedCapabilities,
+    multiplex::ProtocolConnection,
+    protocol::Protocol,
+    snap::{
+        AccountData, AccountRange, ByteCodes, GetAccountRange, GetByteCodes, GetStorageRanges,
+        GetTrieNodes, SnapMessage, StorageData, StorageRanges, TrieNodes,
+    },
+};
+use reth_network_api::Direction;
+use reth_primitives::{trie::Nibbles, Bytes, BytesMut, PeerId, B256, KECCAK_EMPTY, U256};
+use reth_provider::{
+    RevertStateCache, StateProvider, StateProviderFactory, StateRangeProvider,
+    StateRangeProviderBox, StateRangeReader,
+};
+use std::{
+    collections::BTreeMap,
+    net::SocketAddr,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::{
+    sync::{
+        mpsc::{self, Receiver},
+        oneshot,
+    },
+    task::JoinHandle,
+};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::trace;
+
+// Limits: <https://github.com/ethereum/go-ethereum/blob/e6b6a8b738069ad0579f6798ee59fde93ed13b43/eth/protocols/snap/handler.go#L34-L54>
+
+/// Maximum size of replies to data retrievals.
+const SOFT_RESPONSE_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Maximum number of byte codes to serve.
+///
+/// Used to limit lookups.
+const MAX_CODE_LOOKUPS: usize = 1024;
+
+/// Maximum number of trie nodes to serve.
+///
+/// Used to limit lookups.
+const MAX_TRIE_NODE_LOOKUPS: usize = 1024;
+
+/// Number of accounts or storage slots that are read from the database at once.
+const RANGE_BATCH_SIZE: usize = 256;
+
+/// Number of the latest canonical blocks whose state is served.
+///
+/// Peers request the state at a pivot block that moves forward while they sync, so the state of
+/// recent blocks is served as well, like geth serves its diff layers.
+const SERVED_STATE_BLOCKS: u64 = 128;
+
+/// Maximum number of requests that are served concurrently.
+const MAX_CONCURRENT_REQUESTS: usize = 8;
+
+/// The capacity of the channel that delegates requests to the [SnapRequestHandler].
+pub const SNAP_REQUEST_CHANNEL_CAPACITY: usize = 256;
+
+/// Manages `snap` requests on top of the p2p network.
+///
+/// Requests are served on blocking tasks, because they are dominated by database reads and trie
+/// hashing. The state of earlier blocks than the latest one is served by reverting the latest
+/// state, which is cached and shared by all requests, so peers can't trigger it over and over.
+///
+/// This can be spawned to another task and is supposed to be run as background service.
+#[derive(Debug)]
+#[must_use = "Manager does nothing unless polled."]
+pub struct SnapRequestHandler<C> {
+    /// The client type that can interact with the state.
+    client: C,
+    /// Incoming requests from the [SnapConnection]s.
+    incoming_requests: ReceiverStream<IncomingSnapRequest>,
+    /// Requests that are being served.
+    inflight_requests: FuturesUnordered<JoinHandle<()>>,
+    /// The states that revert the latest state to the served states of earlier blocks.
+    revert_states: Arc<RevertStateCache>,
+}
+
+// === impl SnapRequestHandler ===
+
+impl<C> SnapRequestHandler<C> {
+    /// Create a new instance
+    pub fn new(client: C, incoming: Receiver<IncomingSnapRequest>) -> Self {
+        Self {
+            client,
+            incoming_requests: ReceiverStream::new(incoming),
+            inflight_requests: Default::default(),
+            revert_states: Default::default(),
+        }
+    }
+}
+
+impl<C> SnapRequestHandler<C>
+where
+    C: StateRangeReader + StateProviderFactory + Clone + 'static,
+{
+    /// Serves the request on a blocking task.
+    fn on_request(&mut self, incoming: IncomingSnapRequest) {
+        let client = self.client.clone();
+        let revert_states = self.revert_states.clone();
+        let task = tokio::task::spawn_blocking(move || match incoming {
+            IncomingSnapRequest::GetAccountRange { peer_id, request, response } => {
+                trace!(target: "net::snap", ?peer_id, ?request, "Received account range request");
+                let _ = response.send(get_account_range_response(&client, &revert_states, request));
+            }
+            IncomingSnapRequest::GetStorageRanges { peer_id, request, response } => {
+                trace!(target: "net::snap", ?peer_id, ?request, "Received storage ranges request");
+                let _ =
+                    response.send(get_storage_ranges_response(&client, &revert_states, request));
+            }
+            IncomingSnapRequest::GetByteCodes { peer_id, request, response } => {
+                trace!(
+                    target: "net::snap",
+                    ?peer_id, num = request.hashes.len(),
+                    "Received byte codes request"
+                );
+                let _ = response.send(get_byte_codes_response(&client, request));
+            }
+            IncomingSnapRequest::GetTrieNodes { peer_id, request, response } => {
+                trace!(
+                    target: "net::snap",
+                    ?peer_id, num = request.paths.len(),
+                    "Received trie nodes request"
+                );
+                let _ = response.send(get_trie_nodes_response(&client, &revert_states, request));
+            }
+        });
+        self.inflight_requests.push(task);
+    }
+}
+
+/// Returns the state with the given root, if it is served.
+fn served_state<C: StateRangeReader>(
+    client: &C,
+    revert_states: &RevertStateCache,
+    root: B256,
+) -> Option<StateRangeProviderBox> {
+    client.state_range_by_root(root, SERVED_STATE_BLOCKS, revert_states).ok().flatten()
+}
+
+/// Returns the consecutive accounts of the requested range and the proofs of its boundaries.
+fn get_account_range_response<C: StateRangeReader>(
+    client: &C,
+    revert_states: &RevertStateCache,
+    request: GetAccountRange,
+) -> AccountRange {
+    let GetAccountRange { request_id, root_hash, starting_hash, limit_hash, response_bytes } =
+        request;
+    let mut response = AccountRange { request_id, accounts: Vec::new(), proof: Vec::new() };
+
+    let Some(state) = served_state(client, revert_states, root_hash) else { return response };
+
+    let soft_limit = (response_bytes as usize).min(SOFT_RESPONSE_LIMIT);
+    let mut total_bytes = 0;
+    let mut start = Some(starting_hash);
+
+    'accounts: while let Some(batch_start) = start {
+        let Ok(batch) = state.hashed_accounts_range(batch_start, RANGE_BATCH_SIZE) else {
+            return response
+        };
+        start = (batch.len() == RANGE_BATCH_SIZE)
+            .then(|| batch.last().and_then(|(hash, _)| next_hash(*hash)))
+            .flatten();
+
+        for (hash, account) in batch {
+            let account = AccountData { hash, body: account.into() };
+            total_bytes += account.length();
+            response.accounts.push(account);
+
+            // the first account beyond the limit is included to prove the range
+            if hash >= limit_hash || total_bytes >= soft_limit {
+                break 'accounts
+            }
+        }
+    }
+
+    let mut targets = vec![Nibbles::unpack(starting_hash)];
+    if let Some(last) = response.accounts.last() {
+        targets.push(Nibbles::unpack(last.hash));
+    }
+    let Ok(proof) = state.account_trie_nodes(targets) else {
+        response.accounts.clear();
+        return response
+    };
+    response.proof = proof.into_values().collect();
+
+    response
+}
+
+/// Returns the consecutive storage slots of the requested accounts and the proofs of the
+/// boundaries of the last range if it is incomplete.
+fn get_storage_ranges_response<C: StateRangeReader>(
+    client: &C,
+    revert_states: &RevertStateCache,
+    request: GetStorageRanges,
+) -> StorageRanges {
+    let (origin, limit) = (request.origin(), request.limit());
+    let GetStorageRanges { request_id, root_hash, account_hashes, response_bytes, .. } = request;
+    let mut response = StorageRanges { request_id, slots: Vec::new(), proof: Vec::new() };
+
+    let Some(state) = served_state(client, revert_states, root_hash) else { return response };
+
+    let soft_limit = (response_bytes as usize).min(SOFT_RESPONSE_LIMIT);
+    let mut total_bytes = 0;
+
+    for (idx, hashed_address) in account_hashes.into_iter().enumerate() {
+        if total_bytes >= soft_limit {
+            break
+        }
+
+        // origin and limit only apply to the first account
+        let (origin, limit) =
+            if idx == 0 { (origin, limit) } else { (B256::ZERO, B256::repeat_byte(0xff)) };
+
+        let mut slots = Vec::new();
+        let mut aborted = false;
+        let mut start = Some(origin);
+
+        'slots: while let Some(batch_start) = start {
+            let Ok(batch) =
+                state.hashed_storage_range(hashed_address, batch_start, RANGE_BATCH_SIZE)
+            else {
+                return StorageRanges { request_id, slots: Vec::new(), proof: Vec::new() }
+            };
+            start = (batch.len() == RANGE_BATCH_SIZE)
+                .then(|| batch.last().and_then(|entry| next_hash(entry.key)))
+                .flatten();
+
+            for entry in batch {
+                let slot =
+                    StorageData { hash: entry.key, data: alloy_rlp::encode(entry.value).into() };
+                total_bytes += slot.length();
+                slots.push(slot);
+
+                if entry.key >= limit {
+                    break 'slots
+                }
+                if total_bytes >= soft_limit {
+                    aborted = true;
+                    break 'slots
+                }
+            }
+        }
+
+        // a partial range must be proven, which also ends the response
+        if origin != B256::ZERO || aborted {
+            let mut targets = vec![Nibbles::unpack(origin)];
+            if let Some(last) = slots.last() {
+                targets.push(Nibbles::unpack(last.hash));
+            }
+            let Ok(proof) = state.storage_trie_nodes(hashed_address, targets) else {
+                return StorageRanges { request_id, slots: Vec::new(), proof: Vec::new() }
+            };
+            response.proof = proof.into_values().collect();
+            response.slots.push(slots);
+            break
+        }
+
+        response.slots.push(slots);
+    }
+
+    response
+}
+
+/// Returns the requested byte codes, skipping unknown codes.
+fn get_byte_codes_response<C: StateProviderFactory>(
+    client: &C,
+    request: GetByteCodes,
+) -> ByteCodes {
+    let GetByteCodes { request_id, hashes, response_bytes } = request;
+    let mut response = ByteCodes { request_id, codes: Vec::new() };
+
+    let Ok(state) = client.latest() else { return response };
+
+    let soft_limit = (response_bytes as usize).min(SOFT_RESPONSE_LIMIT);
+    let mut total_bytes = 0;
+
+    for hash in hashes.into_iter().take(MAX_CODE_LOOKUPS) {
+        let code = if hash == KECCAK_EMPTY {
+            Bytes::new()
+        } else if let Some(code) = state.bytecode_by_hash(hash).unwrap_or_default() {
+            code.original_bytes()
+        } else {
+            continue
+        };
+
+        total_bytes += code.len();
+        response.codes.push(code);
+
+        if total_bytes >= soft_limit {
+            break
+        }
+    }
+
+    response
+}
+
+/// Returns the requested trie nodes, up to the first unknown node.
+fn get_trie_nodes_response<C: StateRangeReader>(
+    client: &C,
+    revert_states: &RevertStateCache,
+    request: GetTrieNodes,
+) -> TrieNodes {
+    let GetTrieNodes { request_id, root_hash, paths, response_bytes } = request;
+    let mut response = TrieNodes { request_id, nodes: Vec::new() };
+
+    let Some(state) = served_state(client, revert_states, root_hash) else { return response };
+
+    let soft_limit = (response_bytes as usize).min(SOFT_RESPONSE_LIMIT);
+    let mut total_bytes = 0;
+    let mut lookups = 0;
+
+    'paths: for path_set in paths {
+        let nodes = match path_set.as_slice() {
+            [] => break,
+            [path] => {
+                let Some(path) = decode_compact_path(path) else { break };
+                state
+                    .account_trie_nodes(vec![path.clone()])
+                    .map(|nodes| select_nodes(nodes, vec![path]))
+            }
+            [account, storage_paths @ ..] => {
+                let Ok(hashed_address) = B256::try_from(account.as_ref()) else { break };
+                let Some(targets) = storage_paths
+                    .iter()
+                    .map(|path| decode_compact_path(path))
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    break
+                };
+                state
+                    .storage_trie_nodes(hashed_address, targets.clone())
+                    .map(|nodes| select_nodes(nodes, targets))
+            }
+        };
+
+        let Ok(nodes) = nodes else { break };
+        for node in nodes {
+            let Some(node) = node else { break 'paths };
+            total_bytes += node.len();
+            response.nodes.push(node);
+            lookups += 1;
+
+            if total_bytes >= soft_limit || lookups >= MAX_TRIE_NODE_LOOKUPS {
+                break 'paths
+            }
+        }
+    }
+
+    response
+}
+
+/// An endless future.
+///
+/// This should be spawned or used as part of `tokio::select!`.
+impl<C> Future for SnapRequestHandler<C>
+where
+    C: StateRangeReader + StateProviderFactory + Clone + Unpin + 'static,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        loop {
+            // drain finished requests
+            while let Poll::Ready(Some(_)) = this.inflight_requests.poll_next_unpin(cx) {}
+
+            // backpressure: requests queue up in the channel while too many are being served
+            if this.inflight_requests.len() >= MAX_CONCURRENT_REQUESTS {
+                return Poll::Pending
+            }
+
+            match this.incoming_requests.poll_next_unpin(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(None) => return Poll::Ready(()),
+                Poll::Ready(Some(incoming)) => this.on_request(incoming),
+            }
+        }
+    }
+}
+
+/// All `snap` requests delegated by the [SnapConnection]s.
+#[derive(Debug)]
+#[allow(missing_docs)]
+pub enum IncomingSnapRequest {
+    /// Request a range of accounts from the peer.
+    ///
+    /// The response should be sent through the channel.
+    GetAccountRange {
+        peer_id: PeerId,
+        request: GetAccountRange,
+        response: oneshot::Sender<AccountRange>,
+    },
+    /// Request ranges of storage slots from the peer.
+    ///
+    /// The response should be sent through the channel.
+    GetStorageRanges {
+        peer_id: PeerId,
+        request: GetStorageRanges,
+        response: oneshot::Sender<StorageRanges>,
+    },
+    /// Request byte codes from the peer.
+    ///
+    /// The response should be sent through the channel.
+    GetByteCodes { peer_id: PeerId, request: GetByteCodes, response: oneshot::Sender<ByteCodes> },
+    /// Request trie nodes from the peer.
+    ///
+    /// The response should be sent through the channel.
+    GetTrieNodes { peer_id: PeerId, request: GetTrieNodes, response: oneshot::Sender<TrieNodes> },
+}
+
+/// The [ProtocolHandler] that announces `snap/1` on all connections and delegates the requests
+/// of the remote to the [SnapRequestHandler].
+#[derive(Debug, Clone)]
+pub struct SnapProtocolHandler {
+    /// Sender half of the channel to the [SnapRequestHandler].
+    to_request_handler: mpsc::Sender<IncomingSnapRequest>,
+}
+
+impl SnapProtocolHandler {
+    /// Creates a new handler that delegates all requests to the given channel.
+    pub fn new(to_request_handler: mpsc::Sender<IncomingSnapRequest>) -> Self {
+        Self { to_request_handler }
+    }
+}
+
+impl ProtocolHandler for SnapProtocolHandler {
+    type ConnectionHandler = SnapConnectionHandler;
+
+    fn on_incoming(&self, _socket_addr: SocketAddr) -> Option<Self::ConnectionHandler> {
+        Some(SnapConnectionHandler { to_request_handler: self.to_request_handler.clone() })
+    }
+
+    fn on_outgoing(
+        &self,
+        _socket_addr: SocketAddr,
+        _peer_id: PeerId,
+    ) -> Option<Self::ConnectionHandler> {
+        Some(SnapConnectionHandler { to_request_handler: self.to_request_handler.clone() })
+    }
+}
+
+/// The [ConnectionHandler] of the `snap/1` protocol.
+#[derive(Debug)]
+pub struct SnapConnectionHandler {
+    to_request_handler: mpsc::Sender<IncomingSnapRequest>,
+}
+
+impl ConnectionHandler for SnapConnectionHandler {
+    type Connection = SnapConnection;
+
+    fn protocol(&self) -> Protocol {
+        Protocol::snap_1()
+    }
+
+    fn on_unsupported_by_peer(
+        self,
+        _supported: &SharedCapabilities,
+        _direction: Direction,
+        _peer_id: PeerId,
+    ) -> OnNotSupported {
+        OnNotSupported::KeepAlive
+    }
+
+    fn into_connection(
+        self,
+        _direction: Direction,
+        peer_id: PeerId,
+        conn: ProtocolConnection,
+    ) -> Self::Connection {
+        SnapConnection {
+            peer_id,
+            conn,
+            to_request_handler: self.to_request_handler,
+            pending_responses: Default::default(),
+        }
+    }
+}
+
+/// A pending response to a `snap` request of the remote.
+type PendingResponse = Pin<Box<dyn Future<Output = Option<SnapMessage>> + Send>>;
+
+/// The `snap/1` connection to a peer.
+///
+/// Decodes the requests of the remote, delegates them to the [SnapRequestHandler] and yields the
+/// encoded responses.
+#[must_use = "Connection does nothing unless polled."]
+pub struct SnapConnection {
+    /// The remote peer.
+    peer_id: PeerId,
+    /// The messages of the remote.
+    conn: ProtocolConnection,
+    /// Sender half of the channel to the [SnapRequestHandler].
+    to_request_handler: mpsc::Sender<IncomingSnapRequest>,
+    /// Responses that are being served by the [SnapRequestHandler].
+    pending_responses: FuturesUnordered<PendingResponse>,
+}
+
+impl SnapConnection {
+    /// Delegates the request to the [SnapRequestHandler].
+    ///
+    /// Requests are dropped if the handler is busy.
+    fn on_request(&mut self, msg: SnapMessage) {
+        let peer_id = self.peer_id;
+        let (request, pending) = match msg {
+            SnapMessage::GetAccountRange(request) => {
+                let (response, rx) = oneshot::channel();
+                let pending = response_of(rx, SnapMessage::AccountRange);
+                (IncomingSnapRequest::GetAccountRange { peer_id, request, response }, pending)
+            }
+            SnapMessage::GetStorageRanges(request) => {
+                let (response, rx) = oneshot::channel();
+                let pending = response_of(rx, SnapMessage::StorageRanges);
+                (IncomingSnapRequest::GetStorageRanges { peer_id, request, response }, pending)
+            }
+            SnapMessage::GetByteCodes(request) => {
+                let (response, rx) = oneshot::channel();
+                let pending = response_of(rx, SnapMessage::ByteCodes);
+                (IncomingSnapRequest::GetByteCodes { peer_id, request, response }, pending)
+            }
+            SnapMessage::GetTrieNodes(request) => {
+                let (response, rx) = oneshot::channel();
+                let pending = response_of(rx, SnapMessage::TrieNodes);
+                (IncomingSnapRequest::GetTrieNodes { peer_id, request, response }, pending)
+            }
+            // responses are ignored because no requests are sent
+            _ => return,
+        };
+
+        if self.to_request_handler.try_send(request).is_ok() {
+            self.pending_responses.push(pending);
+        } else {
+            trace!(target: "net::snap", ?peer_id, "Dropped snap request, handler is busy");
+        }
+    }
+}
+
+impl Stream for SnapConnection {
+    type Item = BytesMut;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Poll::Ready(Some(Some(msg))) = this.pending_responses.poll_next_unpin(cx) {
+                let mut buf = BytesMut::with_capacity(msg.length());
+                msg.encode(&mut buf);
+                return Poll::Ready(Some(buf))
+            }
+
+            match this.conn.poll_next_unpin(cx) {
+                Poll::Ready(Some(msg)) => match SnapMessage::decode_message(&mut &msg[..]) {
+                    Ok(msg) => this.on_request(msg),
+                    Err(err) => {
+                        trace!(
+                            target: "net::snap",
+                            peer_id=?this.peer_id, %err,
+                            "Failed to decode snap message"
+                        );
+                        return Poll::Ready(None)
+                    }
+                },
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for SnapConnection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SnapConnection")
+            .field("peer_id", &self.peer_id)
+            .field("pending_responses", &self.pending_responses.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Wraps the receiver of a response into a [PendingResponse].
+fn response_of<T: Send + 'static>(
+    rx: oneshot::Receiver<T>,
+    f: fn(T) -> SnapMessage,
+) -> PendingResponse {
+    Box::pin(rx.map(move |res| res.ok().map(f)))
+}
+
+/// Returns the nodes at the given paths in order, `None` for unknown nodes.
+fn select_nodes(mut nodes: BTreeMap<Nibbles, Bytes>, paths: Vec<Nibbles>) -> Vec<Option<Bytes>> {
+    paths.into_iter().map(|path| nodes.remove(&path)).collect()
+}
+
+/// Returns the hash that follows the given hash, if any.
+fn next_hash(hash: B256) -> Option<B256> {
+    U256::from_be_bytes(hash.0)
+        .checked_add(U256::from(1))
+        .map(|next| B256::from(next.to_be_bytes()))
+}
+
+/// Decodes a hex-prefix encoded path, see also
+/// <https://ethereum.org/en/developers/docs/data-structures-and-encoding/patricia-merkle-trie/#specification>
+fn decode_compact_path(path: &[u8]) -> Option<Nibbles> {
+    let flag = *path.first()? >> 4;
+    if flag > 3 {
+        return None
+    }
+    let nibbles = Nibbles::unpack(path);
+    // odd paths are prefixed by a single flag nibble, even paths by the flag and a zero nibble
+    let prefix = if flag & 1 == 1 { 1 } else { 2 };
+    Some(nibbles.slice(prefix..))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{
+        models::AccountBeforeTx, tables, test_utils::TempDatabase, transaction::DbTxMut,
+        DatabaseEnv,
+    };
+    use reth_primitives::{
+        keccak256,
+        stage::{StageCheckpoint, StageId},
+        Account, Address, Header, StorageEntry,
+    };
+    use reth_provider::{
+        test_utils::create_test_provider_factory, BundleStateWithReceipts, ProviderFactory,
+        StageCheckpointWriter, StateRootProvider,
+    };
+    use std::sync::Arc;
+
+    type TestFactory = ProviderFactory<Arc<TempDatabase<DatabaseEnv>>>;
+
+    const ADDRESS_A: Address = Address::with_last_byte(1);
+    const ADDRESS_B: Address = Address::with_last_byte(2);
+    const SLOT_1: B256 = B256::with_last_byte(1);
+    const SLOT_2: B256 = B256::with_last_byte(2);
+
+    fn account(balance: u64) -> Account {
+        Account { nonce: 1, balance: U256::from(balance), bytecode_hash: None }
+    }
+
+    /// Commits the state root of the persisted hashed state as the state of the given block.
+    fn commit_block(factory: &TestFactory, number: u64) -> B256 {
+        let state_root =
+            factory.latest().unwrap().state_root(&BundleStateWithReceipts::default()).unwrap();
+        let header = Header { number, state_root, ..Default::default() };
+
+        let provider = factory.provider_rw().unwrap();
+        provider.tx_ref().put::<tables::CanonicalHeaders>(number, header.hash_slow()).unwrap();
+        provider.tx_ref().put::<tables::Headers>(number, header).unwrap();
+        provider.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(number)).unwrap();
+        provider.commit().unwrap();
+        state_root
+    }
+
+    /// Creates the state of two blocks and returns their state roots.
+    ///
+    /// Block 1 changes the balance of account B and adds a storage slot to account A.
+    fn setup() -> (TestFactory, B256, B256) {
+        let factory = create_test_provider_factory();
+
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        tx.put::<tables::HashedAccount>(keccak256(ADDRESS_A), account(10)).unwrap();
+        tx.put::<tables::HashedAccount>(keccak256(ADDRESS_B), account(20)).unwrap();
+        tx.put::<tables::HashedStorage>(
+            keccak256(ADDRESS_A),
+            StorageEntry { key: keccak256(SLOT_1), value: U256::from(1) },
+        )
+        .unwrap();
+        provider.commit().unwrap();
+        let root_0 = commit_block(&factory, 0);
+
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        tx.put::<tables::AccountChangeSet>(
+            1,
+            AccountBeforeTx { address: ADDRESS_B, info: Some(account(20)) },
+        )
+        .unwrap();
+        tx.put::<tables::StorageChangeSet>(
+            (1, ADDRESS_A).into(),
+            StorageEntry { key: SLOT_2, value: U256::ZERO },
+        )
+        .unwrap();
+        tx.put::<tables::HashedAccount>(keccak256(ADDRESS_B), account(30)).unwrap();
+        tx.put::<tables::HashedStorage>(
+            keccak256(ADDRESS_A),
+            StorageEntry { key: keccak256(SLOT_2), value: U256::from(2) },
+        )
+        .unwrap();
+        provider.commit().unwrap();
+        let root_1 = commit_block(&factory, 1);
+
+        (factory, root_0, root_1)
+    }
+
+    fn account_range(
+        factory: &TestFactory,
+        revert_states: &RevertStateCache,
+        root_hash: B256,
+    ) -> AccountRange {
+        get_account_range_response(
+            factory,
+            revert_states,
+            GetAccountRange {
+                request_id: 1,
+                root_hash,
+                starting_hash: B256::ZERO,
+                limit_hash: B256::repeat_byte(0xff),
+                response_bytes: SOFT_RESPONSE_LIMIT as u64,
+            },
+        )
+    }
+
+    #[test]
+    fn serves_account_range_of_recent_blocks() {
+        let (factory, root_0, root_1) = setup();
+        assert_ne!(root_0, root_1);
+        let revert_states = RevertStateCache::default();
+
+        for (root, balance_b) in [(root_0, 20), (root_1, 30), (root_0, 20)] {
+            let response = account_range(&factory, &revert_states, root);
+
+            let mut expected = vec![
+                (keccak256(ADDRESS_A), U256::from(10)),
+                (keccak256(ADDRESS_B), U256::from(balance_b)),
+            ];
+            expected.sort();
+            let accounts = response
+                .accounts
+                .iter()
+                .map(|account| (account.hash, account.body.balance))
+                .collect::<Vec<_>>();
+            assert_eq!(accounts, expected);
+
+            // the boundary proofs start at the root node of the requested state
+            assert_eq!(keccak256(&response.proof[0]), root);
+        }
+        // only the reverted state of the earlier block is cached, and only once
+        assert_eq!(revert_states.len(), 1);
+
+        let response = account_range(&factory, &revert_states, B256::repeat_byte(0x11));
+        assert!(response.accounts.is_empty());
+        assert!(response.proof.is_empty());
+    }
+
+    #[test]
+    fn serves_storage_ranges_of_recent_blocks() {
+        let (factory, root_0, root_1) = setup();
+        let hashed_address = keccak256(ADDRESS_A);
+        let revert_states = RevertStateCache::default();
+        let storage_ranges = |root_hash, starting_hash| {
+            get_storage_ranges_response(
+                &factory,
+                &revert_states,
+                GetStorageRanges {
+                    request_id: 1,
+                    root_hash,
+                    account_hashes: vec![hashed_address],
+                    starting_hash,
+                    limit_hash: Bytes::new(),
+                    response_bytes: SOFT_RESPONSE_LIMIT as u64,
+                },
+            )
+        };
+
+        let response = storage_ranges(root_0, Bytes::new());
+        let slots = response.slots.iter().flatten().map(|slot| slot.hash).collect::<Vec<_>>();
+        assert_eq!(slots, vec![keccak256(SLOT_1)]);
+        assert!(response.proof.is_empty());
+
+        let response = storage_ranges(root_1, Bytes::new());
+        let mut expected = vec![keccak256(SLOT_1), keccak256(SLOT_2)];
+        expected.sort();
+        let slots = response.slots.iter().flatten().map(|slot| slot.hash).collect::<Vec<_>>();
+        assert_eq!(slots, expected);
+        assert!(response.proof.is_empty());
+
+        // a range that doesn't start at the first slot is proven against the storage root
+        let storage_root = account_range(&factory, &revert_states, root_1)
+            .accounts
+            .into_iter()
+            .find(|account| account.hash == hashed_address)
+            .unwrap()
+            .body
+            .storage_root;
+        let origin = next_hash(expected[0]).unwrap();
+        let response = storage_ranges(root_1, Bytes::copy_from_slice(origin.as_slice()));
+        let slots = response.slots.iter().flatten().map(|slot| slot.hash).collect::<Vec<_>>();
+        assert_eq!(slots, vec![expected[1]]);
+        assert_eq!(keccak256(&response.proof[0]), storage_root);
+    }
+
+    #[test]
+    fn compact_path_decoding() {
+        assert_eq!(decode_compact_path(&[0x00]), Some(Nibbles::default()));
+        assert_eq!(decode_compact_path(&[0x1a]), Some(Nibbles::from_nibbles_unchecked([0xa])));
+        assert_eq!(
+            decode_compact_path(&[0x00, 0xab]),
+            Some(Nibbles::from_nibbles_unchecked([0xa, 0xb]))
+        );
+        assert_eq!(
+            decode_compact_path(&[0x3a, 0xbc]),
+            Some(Nibbles::from_nibbles_unchecked([0xa, 0xb, 0xc]))
+        );
+        assert_eq!(decode_compact_path(&[0x40]), None);
+        assert_eq!(decode_compact_path(&[]), None);
+    }
+
+    #[test]
+    fn next_hash_overflow() {
+        assert_eq!(next_hash(B256::ZERO), Some(B256::with_last_byte(1)));
+        assert_eq!(next_hash(B256::repeat_byte(0xff)), None);
+    }
+}
@@ -97,6 +97,11 @@ where
         &mut self.peers_manager
     }
 
+    /// Returns access to the [`Discovery`]
+    pub(crate) fn discovery(&self) -> &Discovery {
+        &self.discovery
+    }
+
     /// Returns mutable access to the [`Discovery`]
     pub(crate) fn discovery_mut(&mut self) -> &mut Discovery {
         &mut self.discovery
@@ -55,7 +55,7 @@ use tokio::{
     sync::{oneshot, Semaphore},
     time::{Interval, Sleep},
 };
-use tracing::{debug, trace, warn};
+use tracing::{debug, debug_span, trace, warn};
 
 mod metrics;
 
@@ -193,6 +193,7 @@ where
         let config = PayloadConfig::new(
             Arc::new(parent_block),
             self.config.extradata.clone(),
+            self.config.desired_gas_limit,
             attributes,
             Arc::clone(&self.chain_spec),
             #[cfg(feature = "optimism")]
@@ -236,8 +237,8 @@ impl PayloadTaskGuard {
 pub struct BasicPayloadJobGeneratorConfig {
     /// Data to include in the block's extra data field.
     extradata: Bytes,
-    /// Target gas ceiling for built blocks, defaults to [ETHEREUM_BLOCK_GAS_LIMIT] gas.
-    max_gas_limit: u64,
+    /// The gas limit built blocks move toward, defaults to [ETHEREUM_BLOCK_GAS_LIMIT] gas.
+    desired_gas_limit: u64,
     /// The interval at which the job should build a new payload after the last.
     interval: Duration,
     /// The deadline for when the payload builder job should resolve.
@@ -285,11 +286,15 @@ impl BasicPayloadJobGeneratorConfig {
         self
     }
 
-    /// Sets the target gas ceiling for mined blocks.
+    /// Sets the gas limit that built blocks move toward.
+    ///
+    /// Every built block moves the gas limit of its parent toward this target by at most
+    /// `parent_gas_limit / 1024` gas. The gas limit can be lowered as well as raised, so this is a
+    /// target rather than a ceiling.
     ///
     /// Defaults to [ETHEREUM_BLOCK_GAS_LIMIT] gas.
-    pub fn max_gas_limit(mut self, max_gas_limit: u64) -> Self {
-        self.max_gas_limit = max_gas_limit;
+    pub fn desired_gas_limit(mut self, desired_gas_limit: u64) -> Self {
+        self.desired_gas_limit = desired_gas_limit;
         self
     }
 
@@ -309,7 +314,7 @@ impl Default for BasicPayloadJobGeneratorConfig {
         RETH_CLIENT_VERSION.as_bytes().encode(&mut extradata);
         Self {
             extradata: extradata.freeze().into(),
-            max_gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
+            desired_gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
             interval: Duration::from_secs(1),
             // 12s slot time
             deadline: SLOT_DURATION,
@@ -388,6 +393,11 @@ where
                 this.metrics.inc_initiated_payload_builds();
                 let cached_reads = this.cached_reads.take().unwrap_or_default();
                 let builder = this.builder.clone();
+                let span = debug_span!(
+                    target: "payload_builder",
+                    "build_payload",
+                    id = %payload_config.payload_id()
+                );
                 this.executor.spawn_blocking(Box::pin(async move {
                     // acquire the permit for executing the task
                     let _permit = guard.0.acquire().await;
@@ -399,7 +409,7 @@ where
                         cancel,
                         best_payload,
                     };
-                    let result = builder.try_build(args);
+                    let result = span.in_scope(|| builder.try_build(args));
                     let _ = tx.send(result);
                 }));
 
@@ -484,8 +494,13 @@ where
             let (tx, rx) = oneshot::channel();
             let client = self.client.clone();
             let config = self.config.clone();
+            let span = debug_span!(
+                target: "payload_builder",
+                "build_empty_payload",
+                id = %config.payload_id()
+            );
             self.executor.spawn_blocking(Box::pin(async move {
-                let res = build_empty_payload(&client, config);
+                let res = span.in_scope(|| build_empty_payload(&client, config));
                 let _ = tx.send(res);
             }));
 
@@ -671,16 +686,30 @@ impl PayloadConfig {
 
 impl PayloadConfig {
     /// Create new payload config.
+    ///
+    /// The gas limit of the payload moves from the parent's gas limit toward the desired gas limit
+    /// by the largest step allowed by consensus, unless the payload attributes set the gas limit
+    /// (optimism).
     pub fn new(
         parent_block: Arc<SealedBlock>,
         extra_data: Bytes,
+        desired_gas_limit: u64,
         attributes: PayloadBuilderAttributes,
         chain_spec: Arc<ChainSpec>,
         #[cfg(feature = "optimism")] compute_pending_block: bool,
     ) -> Self {
         // configure evm env based on parent block
-        let (initialized_cfg, initialized_block_env) =
+        let (initialized_cfg, mut initialized_block_env) =
             attributes.cfg_and_block_env(&chain_spec, &parent_block);
+        initialized_block_env.gas_limit =
+            U256::from(parent_block.next_block_gas_limit(desired_gas_limit));
+
+        // the sequencer dictates the gas limit of optimism payloads, the header and the evm must
+        // use the same gas limit
+        #[cfg(feature = "optimism")]
+        if let Some(gas_limit) = attributes.optimism_payload_attributes.gas_limit {
+            initialized_block_env.gas_limit = U256::from(gas_limit);
+        }
 
         Self {
             initialized_block_env,
@@ -38,10 +38,8 @@ where
 
     debug!(target: "payload_builder", id=%attributes.id, parent_hash = ?parent_block.hash, parent_number = parent_block.number, "building new payload");
     let mut cumulative_gas_used = 0;
-    let block_gas_limit: u64 = attributes
-        .optimism_payload_attributes
-        .gas_limit
-        .unwrap_or(initialized_block_env.gas_limit.try_into().unwrap_or(u64::MAX));
+    // this is the gas limit of the payload attributes, if set, see [PayloadConfig::new]
+    let block_gas_limit: u64 = initialized_block_env.gas_limit.try_into().unwrap_or(u64::MAX);
     let base_fee = initialized_block_env.basefee.to::<u64>();
 
     let mut executed_txs = Vec::new();
@@ -39,6 +39,13 @@ pub const BEACON_NONCE: u64 = 0u64;
 /// See <https://github.com/paradigmxyz/reth/issues/3233>.
 pub const ETHEREUM_BLOCK_GAS_LIMIT: u64 = 30_000_000;
 
+/// The minimum gas limit of a block.
+pub const MINIMUM_GAS_LIMIT: u64 = 5000;
+
+/// The bound divisor of the gas limit, the gas limit of a block may change by less than
+/// `parent_gas_limit / GAS_LIMIT_BOUND_DIVISOR` compared to its parent.
+pub const GAS_LIMIT_BOUND_DIVISOR: u64 = 1024;
+
 /// The minimum tx fee below which the txpool will reject the transaction.
 ///
 /// Configured to `7` WEI which is the lowest possible value of base fee under mainnet EIP-1559
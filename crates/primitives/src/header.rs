@@ -1,6 +1,8 @@
 use crate::{
     basefee::calculate_next_block_base_fee,
-    constants::{EMPTY_OMMER_ROOT_HASH, EMPTY_ROOT_HASH},
+    constants::{
+        EMPTY_OMMER_ROOT_HASH, EMPTY_ROOT_HASH, GAS_LIMIT_BOUND_DIVISOR, MINIMUM_GAS_LIMIT,
+    },
     eip4844::{calc_blob_gasprice, calculate_excess_blob_gas},
     keccak256, Address, BaseFeeParams, BlockBodyRoots, BlockHash, BlockNumHash, BlockNumber, Bloom,
     Bytes, B256, B64, U256,
@@ -194,6 +196,21 @@ impl Header {
         Some(calculate_excess_blob_gas(self.excess_blob_gas?, self.blob_gas_used?))
     }
 
+    /// Calculate the gas limit for the next block, moving it toward the desired gas limit by the
+    /// largest step allowed by consensus.
+    ///
+    /// The desired gas limit is clamped to [MINIMUM_GAS_LIMIT].
+    pub fn next_block_gas_limit(&self, desired_gas_limit: u64) -> u64 {
+        let desired_gas_limit = desired_gas_limit.max(MINIMUM_GAS_LIMIT);
+        // the difference to the parent gas limit must be strictly smaller than the bound
+        let max_delta = (self.gas_limit / GAS_LIMIT_BOUND_DIVISOR).saturating_sub(1);
+        if self.gas_limit < desired_gas_limit {
+            self.gas_limit.saturating_add(max_delta).min(desired_gas_limit)
+        } else {
+            self.gas_limit.saturating_sub(max_delta).max(desired_gas_limit)
+        }
+    }
+
     /// Seal the header with a known hash.
     ///
     /// WARNING: This method does not perform validation whether the hash is correct.
@@ -700,6 +717,26 @@ mod tests {
     use crate::{address, b256, bloom, bytes, hex, Address, HeadersDirection, U256};
     use std::str::FromStr;
 
+    #[test]
+    fn test_next_block_gas_limit() {
+        let header = Header { gas_limit: 30_000_000, ..Default::default() };
+        let max_delta = 30_000_000 / 1024 - 1;
+
+        assert_eq!(header.next_block_gas_limit(30_000_000), 30_000_000);
+        assert_eq!(header.next_block_gas_limit(36_000_000), 30_000_000 + max_delta);
+        assert_eq!(header.next_block_gas_limit(30_000_100), 30_000_100);
+        assert_eq!(header.next_block_gas_limit(20_000_000), 30_000_000 - max_delta);
+        assert_eq!(header.next_block_gas_limit(29_999_900), 29_999_900);
+
+        // the gas limit can't go below the minimum
+        let header = Header { gas_limit: 5000, ..Default::default() };
+        assert_eq!(header.next_block_gas_limit(0), 5000);
+
+        // the gas limit can't overflow
+        let header = Header { gas_limit: u64::MAX - 1, ..Default::default() };
+        assert_eq!(header.next_block_gas_limit(u64::MAX), u64::MAX);
+    }
+
     // Test vector from: https://eips.ethereum.org/EIPS/eip-2481
     #[test]
     fn test_encode_block_header() {
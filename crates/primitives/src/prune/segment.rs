@@ -23,15 +23,22 @@ pub enum PruneSegment {
     Headers,
     /// Prune segment responsible for the `Transactions` table.
     Transactions,
+    /// Prune segment responsible for the `AccountChangeSet` table, once it has been snapshotted.
+    AccountChangeSets,
+    /// Prune segment responsible for the `StorageChangeSet` table, once it has been snapshotted.
+    StorageChangeSets,
 }
 
 impl PruneSegment {
     /// Returns minimum number of blocks to left in the database for this segment.
     pub fn min_blocks(&self) -> u64 {
         match self {
-            Self::SenderRecovery | Self::TransactionLookup | Self::Headers | Self::Transactions => {
-                0
-            }
+            Self::SenderRecovery |
+            Self::TransactionLookup |
+            Self::Headers |
+            Self::Transactions |
+            Self::AccountChangeSets |
+            Self::StorageChangeSets => 0,
             Self::Receipts | Self::ContractLogs | Self::AccountHistory | Self::StorageHistory => {
                 MINIMUM_PRUNING_DISTANCE
             }
@@ -27,6 +27,12 @@ pub struct HighestSnapshots {
     /// Highest snapshotted block of transactions, inclusive.
     /// If [`None`], no snapshot is available.
     pub transactions: Option<BlockNumber>,
+    /// Highest snapshotted block of account changesets, inclusive.
+    /// If [`None`], no snapshot is available.
+    pub account_changesets: Option<BlockNumber>,
+    /// Highest snapshotted block of storage changesets, inclusive.
+    /// If [`None`], no snapshot is available.
+    pub storage_changesets: Option<BlockNumber>,
 }
 
 impl HighestSnapshots {
@@ -36,6 +42,8 @@ impl HighestSnapshots {
             SnapshotSegment::Headers => self.headers,
             SnapshotSegment::Transactions => self.transactions,
             SnapshotSegment::Receipts => self.receipts,
+            SnapshotSegment::AccountChangeSets => self.account_changesets,
+            SnapshotSegment::StorageChangeSets => self.storage_changesets,
         }
     }
 
@@ -45,6 +53,8 @@ impl HighestSnapshots {
             SnapshotSegment::Headers => &mut self.headers,
             SnapshotSegment::Transactions => &mut self.transactions,
             SnapshotSegment::Receipts => &mut self.receipts,
+            SnapshotSegment::AccountChangeSets => &mut self.account_changesets,
+            SnapshotSegment::StorageChangeSets => &mut self.storage_changesets,
         }
     }
 }
@@ -34,6 +34,12 @@ pub enum SnapshotSegment {
     #[strum(serialize = "receipts")]
     /// Snapshot segment responsible for the `Receipts` table.
     Receipts,
+    #[strum(serialize = "account-changesets")]
+    /// Snapshot segment responsible for the `AccountChangeSet` table.
+    AccountChangeSets,
+    #[strum(serialize = "storage-changesets")]
+    /// Snapshot segment responsible for the `StorageChangeSet` table.
+    StorageChangeSets,
 }
 
 impl SnapshotSegment {
@@ -51,9 +57,24 @@ impl SnapshotSegment {
             SnapshotSegment::Headers => default_config,
             SnapshotSegment::Transactions => default_config,
             SnapshotSegment::Receipts => default_config,
+            // Changesets are only queried by block number, so there's no key to build filters for.
+            SnapshotSegment::AccountChangeSets | SnapshotSegment::StorageChangeSets => {
+                SegmentConfig { filters: Filters::WithoutFilters, ..default_config }
+            }
         }
     }
 
+    /// Returns `true` if the rows of the segment are indexed by block number, and `false` if
+    /// they're indexed by transaction number.
+    pub const fn is_block_based(&self) -> bool {
+        matches!(
+            self,
+            SnapshotSegment::Headers |
+                SnapshotSegment::AccountChangeSets |
+                SnapshotSegment::StorageChangeSets
+        )
+    }
+
     /// Returns the default file name for the provided segment and range.
     pub fn filename(
         &self,
@@ -170,9 +191,10 @@ impl SegmentHeader {
 
     /// Returns the row offset which depends on whether the segment is block or transaction based.
     pub fn start(&self) -> u64 {
-        match self.segment {
-            SnapshotSegment::Headers => self.block_start(),
-            SnapshotSegment::Transactions | SnapshotSegment::Receipts => self.tx_start(),
+        if self.segment.is_block_based() {
+            self.block_start()
+        } else {
+            self.tx_start()
         }
     }
 }
@@ -208,6 +230,13 @@ mod tests {
                 "snapshot_transactions_1123233_11223233_1123233_2123233",
                 None,
             ),
+            (
+                SnapshotSegment::AccountChangeSets,
+                500_000..=999_999,
+                610_000..=1_400_000,
+                "snapshot_account-changesets_500000_999999_610000_1400000",
+                None,
+            ),
             (
                 SnapshotSegment::Headers,
                 2..=30,
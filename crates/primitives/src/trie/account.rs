@@ -64,8 +64,23 @@ impl From<GenesisAccount> for TrieAccount {
 }
 
 impl TrieAccount {
+    /// Get account's nonce.
+    pub fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    /// Get account's balance.
+    pub fn balance(&self) -> U256 {
+        self.balance
+    }
+
     /// Get account's storage root.
     pub fn storage_root(&self) -> B256 {
         self.storage_root
     }
+
+    /// Get the hash of the account's bytecode.
+    pub fn code_hash(&self) -> B256 {
+        self.code_hash
+    }
 }
@@ -6,7 +6,7 @@ use crate::{
     Metrics, PrunerError, PrunerEvent,
 };
 use reth_db::database::Database;
-use reth_primitives::{BlockNumber, ChainSpec, PruneMode, PruneProgress, PruneSegment};
+use reth_primitives::{BlockNumber, ChainSpec, PruneCheckpoint, PruneMode, PruneProgress};
 use reth_provider::{ProviderFactory, PruneCheckpointReader};
 use reth_snapshot::HighestSnapshotsTracker;
 use reth_tokio_util::EventListeners;
@@ -37,7 +37,6 @@ pub struct Pruner<DB> {
     /// Maximum number of blocks to be pruned per run, as an additional restriction to
     /// `previous_tip_block_number`.
     prune_max_blocks_per_run: usize,
-    #[allow(dead_code)]
     highest_snapshots_tracker: HighestSnapshotsTracker,
     metrics: Metrics,
     listeners: EventListeners<PrunerEvent>,
@@ -89,7 +88,6 @@ impl<DB: Database> Pruner<DB> {
         let mut done = true;
         let mut stats = BTreeMap::new();
 
-        // TODO(alexey): prune snapshotted segments of data (headers, transactions)
         let highest_snapshots = *self.highest_snapshots_tracker.borrow();
 
         // Multiply `self.delete_limit` (number of rows to delete per block) by number of blocks
@@ -152,69 +150,70 @@ impl<DB: Database> Pruner<DB> {
             }
         }
 
+        // Snapshotted data can be read from the snapshots, so it's deleted from the database.
         if let Some(snapshots) = highest_snapshots {
-            if let (Some(to_block), true) = (snapshots.headers, delete_limit > 0) {
-                let prune_mode = PruneMode::Before(to_block + 1);
-                trace!(
-                    target: "pruner",
-                    prune_segment = ?PruneSegment::Headers,
-                    %to_block,
-                    ?prune_mode,
-                    "Got target block to prune"
-                );
-
-                let segment_start = Instant::now();
-                let segment = segments::Headers::new(prune_mode);
-                let previous_checkpoint = provider.get_prune_checkpoint(PruneSegment::Headers)?;
-                let output = segment
-                    .prune(&provider, PruneInput { previous_checkpoint, to_block, delete_limit })?;
-                if let Some(checkpoint) = output.checkpoint {
-                    segment
-                        .save_checkpoint(&provider, checkpoint.as_prune_checkpoint(prune_mode))?;
+            let snapshot_segments: [(Option<BlockNumber>, fn(PruneMode) -> Arc<dyn Segment<DB>>);
+                5] = [
+                (snapshots.headers, |mode| Arc::new(segments::Headers::new(mode))),
+                (snapshots.transactions, |mode| Arc::new(segments::Transactions::new(mode))),
+                (snapshots.receipts, |mode| Arc::new(segments::Receipts::new(mode))),
+                (snapshots.account_changesets, |mode| {
+                    Arc::new(segments::AccountChangeSets::new(mode))
+                }),
+                (snapshots.storage_changesets, |mode| {
+                    Arc::new(segments::StorageChangeSets::new(mode))
+                }),
+            ];
+
+            for (highest_snapshot, new_segment) in snapshot_segments {
+                let Some(to_block) = highest_snapshot else { continue };
+                if delete_limit == 0 {
+                    break
                 }
-                self.metrics
-                    .get_prune_segment_metrics(PruneSegment::Headers)
-                    .duration_seconds
-                    .record(segment_start.elapsed());
 
-                done = done && output.done;
-                delete_limit = delete_limit.saturating_sub(output.pruned);
-                stats.insert(
-                    PruneSegment::Headers,
-                    (PruneProgress::from_done(output.done), output.pruned),
-                );
-            }
-
-            if let (Some(to_block), true) = (snapshots.transactions, delete_limit > 0) {
                 let prune_mode = PruneMode::Before(to_block + 1);
+                let segment = new_segment(prune_mode);
                 trace!(
                     target: "pruner",
-                    prune_segment = ?PruneSegment::Transactions,
+                    prune_segment = ?segment.segment(),
                     %to_block,
                     ?prune_mode,
                     "Got target block to prune"
                 );
 
                 let segment_start = Instant::now();
-                let segment = segments::Transactions::new(prune_mode);
-                let previous_checkpoint = provider.get_prune_checkpoint(PruneSegment::Headers)?;
+                let previous_checkpoint = provider.get_prune_checkpoint(segment.segment())?;
                 let output = segment
                     .prune(&provider, PruneInput { previous_checkpoint, to_block, delete_limit })?;
                 if let Some(checkpoint) = output.checkpoint {
-                    segment
-                        .save_checkpoint(&provider, checkpoint.as_prune_checkpoint(prune_mode))?;
+                    // The checkpoint is shared with the configured pruning of the segment, so it
+                    // keeps the configured prune mode and never moves backwards.
+                    let prune_mode = self
+                        .segments
+                        .iter()
+                        .find(|configured| configured.segment() == segment.segment())
+                        .and_then(|configured| configured.mode())
+                        .unwrap_or(prune_mode);
+                    let checkpoint = match previous_checkpoint {
+                        Some(previous) if previous.block_number >= checkpoint.block_number => {
+                            PruneCheckpoint { prune_mode, ..previous }
+                        }
+                        _ => checkpoint.as_prune_checkpoint(prune_mode),
+                    };
+                    segment.save_checkpoint(&provider, checkpoint)?;
                 }
                 self.metrics
-                    .get_prune_segment_metrics(PruneSegment::Transactions)
+                    .get_prune_segment_metrics(segment.segment())
                     .duration_seconds
                     .record(segment_start.elapsed());
 
                 done = done && output.done;
                 delete_limit = delete_limit.saturating_sub(output.pruned);
-                stats.insert(
-                    PruneSegment::Transactions,
-                    (PruneProgress::from_done(output.done), output.pruned),
-                );
+                let (progress, pruned) =
+                    stats.entry(segment.segment()).or_insert((PruneProgress::Finished, 0));
+                *progress =
+                    PruneProgress::from_done(*progress == PruneProgress::Finished && output.done);
+                *pruned += output.pruned;
             }
         }
 
@@ -264,9 +263,20 @@ impl<DB: Database> Pruner<DB> {
 
 #[cfg(test)]
 mod tests {
-    use crate::Pruner;
-    use reth_db::test_utils::create_test_rw_db;
-    use reth_primitives::MAINNET;
+    use crate::{segments, Pruner};
+    use assert_matches::assert_matches;
+    use reth_db::{tables, test_utils::create_test_rw_db};
+    use reth_interfaces::test_utils::{generators, generators::random_block_range};
+    use reth_primitives::{
+        snapshot::HighestSnapshots, PruneMode, PruneProgress, PruneSegment, B256, MAINNET,
+    };
+    use reth_provider::{
+        HeaderProvider, ProviderFactory, PruneCheckpointReader, TransactionsProvider,
+        TransactionsProviderExt,
+    };
+    use reth_snapshot::segments::{Headers, Segment, Transactions};
+    use reth_stages::test_utils::TestStageDB;
+    use std::sync::Arc;
     use tokio::sync::watch;
 
     #[test]
@@ -288,4 +298,95 @@ mod tests {
         let third_block_number = second_block_number;
         assert!(!pruner.is_pruning_needed(third_block_number));
     }
+
+    #[test]
+    fn snapshotted_data_readable_after_pruning() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(&mut rng, 0..=19, B256::ZERO, 1..3);
+        db.insert_blocks(blocks.iter(), None).expect("insert blocks");
+        db.insert_headers_with_td(blocks.iter().map(|block| &block.header))
+            .expect("insert headers");
+
+        let provider = db.factory.provider().unwrap();
+        let headers = provider.headers_range(0..20).unwrap();
+        let snapshotted_txs = provider.transaction_range_by_block_range(0..=9).unwrap();
+        let txs = provider.transaction_range_by_block_range(0..=19).unwrap();
+        let txs = *txs.start()..*txs.end() + 1;
+        let transactions = provider.transactions_by_tx_range(txs.clone()).unwrap();
+
+        let snapshots_dir = db.factory.db_ref().path().join("snapshots");
+        std::fs::create_dir_all(&snapshots_dir).unwrap();
+        Headers::default().snapshot(&provider, &snapshots_dir, 0..=9).unwrap();
+        Transactions::default().snapshot(&provider, &snapshots_dir, 0..=9).unwrap();
+        drop(provider);
+
+        let (_, highest_snapshots) = watch::channel(Some(HighestSnapshots {
+            headers: Some(9),
+            transactions: Some(9),
+            ..Default::default()
+        }));
+        let mut pruner = Pruner::new(
+            db.factory.db_ref().clone(),
+            MAINNET.clone(),
+            vec![],
+            1,
+            1000,
+            5,
+            highest_snapshots.clone(),
+        );
+        assert_matches!(pruner.run(19), Ok(PruneProgress::Finished));
+
+        // The snapshotted data has been deleted from the database.
+        assert!(db.table::<tables::Headers>().unwrap().iter().all(|(number, _)| *number > 9));
+        assert!(db
+            .table::<tables::Transactions>()
+            .unwrap()
+            .iter()
+            .all(|(number, _)| number > snapshotted_txs.end()));
+
+        let factory = ProviderFactory::new(db.factory.db_ref().clone(), MAINNET.clone())
+            .with_snapshots(snapshots_dir, highest_snapshots);
+        let provider = factory.provider().unwrap();
+        assert_eq!(provider.headers_range(0..20).unwrap(), headers);
+        assert_eq!(provider.transactions_by_tx_range(txs).unwrap(), transactions);
+    }
+
+    #[test]
+    fn snapshot_pruning_keeps_configured_checkpoint() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(&mut rng, 0..=19, B256::ZERO, 1..3);
+        db.insert_blocks(blocks.iter(), None).expect("insert blocks");
+
+        let prune_checkpoint = |highest_snapshot, configured_mode| {
+            let (_, highest_snapshots) = watch::channel(Some(HighestSnapshots {
+                transactions: Some(highest_snapshot),
+                ..Default::default()
+            }));
+            let mut pruner = Pruner::new(
+                db.factory.db_ref().clone(),
+                MAINNET.clone(),
+                vec![Arc::new(segments::Transactions::new(configured_mode))],
+                1,
+                1000,
+                5,
+                highest_snapshots,
+            );
+            assert_matches!(pruner.run(19), Ok(PruneProgress::Finished));
+            db.factory.provider().unwrap().get_prune_checkpoint(PruneSegment::Transactions).unwrap()
+        };
+
+        // the snapshots are ahead of the configured pruning
+        let checkpoint = prune_checkpoint(9, PruneMode::Before(5)).unwrap();
+        assert_eq!(checkpoint.block_number, Some(9));
+        assert_eq!(checkpoint.prune_mode, PruneMode::Before(5));
+
+        // the configured pruning is ahead of the snapshots
+        let checkpoint = prune_checkpoint(9, PruneMode::Before(15)).unwrap();
+        assert_eq!(checkpoint.block_number, Some(14));
+        assert_eq!(checkpoint.prune_mode, PruneMode::Before(15));
+    }
 }
@@ -1,3 +1,4 @@
+mod account_changesets;
 mod account_history;
 mod headers;
 mod history;
@@ -5,10 +6,12 @@ mod receipts;
 mod receipts_by_logs;
 mod sender_recovery;
 mod set;
+mod storage_changesets;
 mod storage_history;
 mod transaction_lookup;
 mod transactions;
 
+pub use account_changesets::AccountChangeSets;
 pub use account_history::AccountHistory;
 pub use headers::Headers;
 pub use receipts::Receipts;
@@ -16,6 +19,7 @@ pub use receipts_by_logs::ReceiptsByLogs;
 pub use sender_recovery::SenderRecovery;
 pub use set::SegmentSet;
 use std::fmt::Debug;
+pub use storage_changesets::StorageChangeSets;
 pub use storage_history::StorageHistory;
 pub use transaction_lookup::TransactionLookup;
 pub use transactions::Transactions;
@@ -0,0 +1,172 @@
+use crate::{
+    segments::{PruneInput, PruneOutput, PruneOutputCheckpoint, Segment},
+    PrunerError,
+};
+use reth_db::{database::Database, models::BlockNumberAddress, tables};
+use reth_primitives::{PruneMode, PruneSegment};
+use reth_provider::DatabaseProviderRW;
+use tracing::{instrument, trace};
+
+/// Prunes the storage changesets which have been moved to the snapshots. Unlike
+/// [`StorageHistory`](crate::segments::StorageHistory), it keeps the history indices, since the
+/// changesets can still be read from the snapshots.
+#[derive(Debug)]
+pub struct StorageChangeSets {
+    mode: PruneMode,
+}
+
+impl StorageChangeSets {
+    pub fn new(mode: PruneMode) -> Self {
+        Self { mode }
+    }
+}
+
+impl<DB: Database> Segment<DB> for StorageChangeSets {
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::StorageChangeSets
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        Some(self.mode)
+    }
+
+    #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
+    fn prune(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        input: PruneInput,
+    ) -> Result<PruneOutput, PrunerError> {
+        let range = match input.get_next_block_range() {
+            Some(range) => range,
+            None => {
+                trace!(target: "pruner", "No storage changesets to prune");
+                return Ok(PruneOutput::done())
+            }
+        };
+        let range_end = *range.end();
+
+        let mut last_pruned_block = None;
+        let (pruned, done) = provider.prune_table_with_range::<tables::StorageChangeSet>(
+            BlockNumberAddress::range(range),
+            input.delete_limit,
+            |_| false,
+            |row| last_pruned_block = Some(row.0.block_number()),
+        )?;
+        trace!(target: "pruner", %pruned, %done, "Pruned storage changesets");
+
+        let last_pruned_block = last_pruned_block
+            // If there's more storage changesets to prune, set the checkpoint block number to
+            // previous, so we could finish pruning its storage changesets on the next run.
+            .map(|block_number| if done { block_number } else { block_number.saturating_sub(1) })
+            .unwrap_or(range_end);
+
+        Ok(PruneOutput {
+            done,
+            pruned,
+            checkpoint: Some(PruneOutputCheckpoint {
+                block_number: Some(last_pruned_block),
+                tx_number: None,
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::segments::{PruneInput, PruneOutput, Segment, StorageChangeSets};
+    use assert_matches::assert_matches;
+    use reth_db::tables;
+    use reth_interfaces::test_utils::{
+        generators,
+        generators::{random_block_range, random_changeset_range, random_eoa_account_range},
+    };
+    use reth_primitives::{BlockNumber, PruneCheckpoint, PruneMode, PruneSegment, B256};
+    use reth_provider::PruneCheckpointReader;
+    use reth_stages::test_utils::TestStageDB;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn prune() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(&mut rng, 1..=100, B256::ZERO, 0..1);
+        db.insert_blocks(blocks.iter(), None).expect("insert blocks");
+
+        let accounts =
+            random_eoa_account_range(&mut rng, 0..3).into_iter().collect::<BTreeMap<_, _>>();
+
+        let (changesets, _) = random_changeset_range(
+            &mut rng,
+            blocks.iter(),
+            accounts.into_iter().map(|(addr, acc)| (addr, (acc, Vec::new()))),
+            2..3,
+            1..2,
+        );
+        db.insert_changesets(changesets.clone(), None).expect("insert changesets");
+        db.insert_history(changesets.clone(), None).expect("insert history");
+
+        let original_changesets = db.table::<tables::StorageChangeSet>().unwrap();
+        let original_shards = db.table::<tables::StorageHistory>().unwrap();
+
+        let test_prune = |to_block: BlockNumber, delete_limit: usize, expected_done: bool| {
+            let prune_mode = PruneMode::Before(to_block);
+            let input = PruneInput {
+                previous_checkpoint: db
+                    .factory
+                    .provider()
+                    .unwrap()
+                    .get_prune_checkpoint(PruneSegment::StorageChangeSets)
+                    .unwrap(),
+                to_block,
+                delete_limit,
+            };
+            let segment = StorageChangeSets::new(prune_mode);
+
+            let provider = db.factory.provider_rw().unwrap();
+            let result = segment.prune(&provider, input).unwrap();
+            assert_matches!(
+                result,
+                PruneOutput { done, checkpoint: Some(_), .. } if done == expected_done
+            );
+            let checkpoint = result.checkpoint.unwrap();
+            segment.save_checkpoint(&provider, checkpoint.as_prune_checkpoint(prune_mode)).unwrap();
+            provider.commit().expect("commit");
+
+            let last_pruned_block = checkpoint.block_number.unwrap();
+            if expected_done {
+                assert_eq!(last_pruned_block, to_block);
+            }
+
+            // Changesets up to the checkpoint are removed, the later ones are kept.
+            let actual_changesets = db.table::<tables::StorageChangeSet>().unwrap();
+            assert!(actual_changesets
+                .iter()
+                .all(|(key, _)| key.block_number() > last_pruned_block));
+            assert!(original_changesets
+                .iter()
+                .filter(|(key, _)| key.block_number() > to_block)
+                .all(|entry| actual_changesets.contains(entry)));
+
+            // History indices are left untouched.
+            assert_eq!(db.table::<tables::StorageHistory>().unwrap(), original_shards);
+
+            assert_eq!(
+                db.factory
+                    .provider()
+                    .unwrap()
+                    .get_prune_checkpoint(PruneSegment::StorageChangeSets)
+                    .unwrap(),
+                Some(PruneCheckpoint {
+                    block_number: Some(last_pruned_block),
+                    tx_number: None,
+                    prune_mode
+                })
+            );
+        };
+
+        test_prune(50, 10, false);
+        test_prune(50, 1000, true);
+        test_prune(80, 1000, true);
+    }
+}
@@ -5,9 +5,9 @@ use crate::tracing::{
     utils::load_account_code,
     TracingInspectorConfig,
 };
-use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_primitives::{hex, Address, Bytes, B256, U256};
 use reth_rpc_types::trace::geth::{
-    AccountChangeKind, AccountState, CallConfig, CallFrame, DefaultFrame, DiffMode,
+    AccountChangeKind, AccountState, CallConfig, CallFrame, DefaultFrame, DiffMode, FourByteFrame,
     GethDefaultTracingOptions, PreStateConfig, PreStateFrame, PreStateMode, StructLog,
 };
 use revm::{db::DatabaseRef, primitives::ResultAndState};
@@ -169,6 +169,22 @@ impl GethTraceBuilder {
         }
     }
 
+    /// Generate the 4byte tracer output from the recorded calls.
+    ///
+    /// This counts the function selectors and calldata sizes of all calls with at least 4 bytes
+    /// of input, like the [FourByteInspector](crate::tracing::FourByteInspector) does.
+    pub fn geth_four_byte_traces(&self) -> FourByteFrame {
+        let mut frame = FourByteFrame::default();
+        for node in self.nodes.iter().filter(|node| !node.kind().is_any_create()) {
+            let input = &node.trace.data;
+            if input.len() >= 4 {
+                let key = format!("0x{}-{}", hex::encode(&input[..4]), input.len() - 4);
+                *frame.0.entry(key).or_default() += 1;
+            }
+        }
+        frame
+    }
+
     ///  Returns the accounts necessary for transaction execution.
     ///
     /// The prestate mode returns the accounts necessary to execute a given transaction.
@@ -221,7 +221,7 @@ impl CallTraceNode {
 
     /// Returns true if the call was a selfdestruct
     #[inline]
-    pub(crate) fn is_selfdestruct(&self) -> bool {
+    pub fn is_selfdestruct(&self) -> bool {
         self.status() == InstructionResult::SelfDestruct
     }
 
@@ -0,0 +1,75 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use reth_primitives::{Address, Bytes, B256, U256, U64};
+use reth_rpc_types::TransactionRequest;
+
+/// Anvil compatible rpc interface to control a dev chain.
+///
+/// The methods use the names of the `evm_` and `anvil_` methods of
+/// [anvil](https://book.getfoundry.sh/reference/anvil/), so existing test tooling can be used.
+#[cfg_attr(not(feature = "client"), rpc(server))]
+#[cfg_attr(feature = "client", rpc(server, client))]
+#[async_trait::async_trait]
+pub trait AnvilApi {
+    /// Mines a new block with the ready transactions of the pool.
+    #[method(name = "evm_mine")]
+    async fn evm_mine(&self) -> RpcResult<String>;
+
+    /// Mines the given number of blocks, one by default and at most 10000.
+    ///
+    /// If an interval is given, the timestamps of the blocks are spaced by the interval, starting
+    /// from the latest block.
+    #[method(name = "anvil_mine")]
+    async fn anvil_mine(&self, blocks: Option<U256>, interval: Option<U256>) -> RpcResult<()>;
+
+    /// Advances the clock of the chain by the given number of seconds and returns the total
+    /// offset to the system time.
+    #[method(name = "evm_increaseTime")]
+    async fn evm_increase_time(&self, seconds: U256) -> RpcResult<i64>;
+
+    /// Sets the timestamp of the next block.
+    #[method(name = "evm_setNextBlockTimestamp")]
+    async fn evm_set_next_block_timestamp(&self, timestamp: U64) -> RpcResult<()>;
+
+    /// Takes a snapshot of the chain and returns its id.
+    #[method(name = "evm_snapshot")]
+    async fn evm_snapshot(&self) -> RpcResult<U256>;
+
+    /// Reverts the chain to the snapshot with the given id.
+    ///
+    /// Returns `false` if the snapshot does not exist.
+    #[method(name = "evm_revert")]
+    async fn evm_revert(&self, id: U256) -> RpcResult<bool>;
+
+    /// Sets the balance of the account.
+    #[method(name = "anvil_setBalance")]
+    async fn anvil_set_balance(&self, address: Address, balance: U256) -> RpcResult<()>;
+
+    /// Sets the code of the account.
+    #[method(name = "anvil_setCode")]
+    async fn anvil_set_code(&self, address: Address, code: Bytes) -> RpcResult<()>;
+
+    /// Sets the value of a storage slot of the account.
+    #[method(name = "anvil_setStorageAt")]
+    async fn anvil_set_storage_at(
+        &self,
+        address: Address,
+        slot: U256,
+        value: B256,
+    ) -> RpcResult<bool>;
+
+    /// Allows transactions to be sent from the account without a signature, with
+    /// `eth_sendTransaction` or `eth_sendUnsignedTransaction`.
+    #[method(name = "anvil_impersonateAccount")]
+    async fn anvil_impersonate_account(&self, address: Address) -> RpcResult<()>;
+
+    /// Stops impersonating the account.
+    #[method(name = "anvil_stopImpersonatingAccount")]
+    async fn anvil_stop_impersonating_account(&self, address: Address) -> RpcResult<()>;
+
+    /// Mines a transaction of an impersonated account in a new block and returns its hash.
+    ///
+    /// The receipt of the transaction is available once this returns. Since every block of the
+    /// chain must be valid, impersonated accounts that have code can't send transactions.
+    #[method(name = "eth_sendUnsignedTransaction")]
+    async fn eth_send_unsigned_transaction(&self, request: TransactionRequest) -> RpcResult<B256>;
+}
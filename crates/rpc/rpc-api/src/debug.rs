@@ -5,7 +5,7 @@ use reth_rpc_types::{
         BlockTraceResult, GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace,
         TraceResult,
     },
-    Bundle, CallRequest, RichBlock, StateContext,
+    AccountRangeResult, BadBlock, Bundle, CallRequest, MemStats, StateContext,
 };
 
 /// Debug rpc interface.
@@ -32,18 +32,28 @@ pub trait DebugApi {
     #[method(name = "getRawReceipts")]
     async fn raw_receipts(&self, block_id: BlockId) -> RpcResult<Vec<Bytes>>;
 
-    /// Returns an array of recent bad blocks that the client has seen on the network.
+    /// Returns an array of recent bad blocks that the client has seen on the network, alongside
+    /// the reason they were rejected.
     #[method(name = "getBadBlocks")]
-    async fn bad_blocks(&self) -> RpcResult<Vec<RichBlock>>;
+    async fn bad_blocks(&self) -> RpcResult<Vec<BadBlock>>;
 
-    /// Returns the structured logs created during the execution of EVM between two blocks
-    /// (excluding start) as a JSON object.
-    #[method(name = "traceChain")]
+    /// Creates a subscription that traces all blocks between two blocks (excluding start) with the
+    /// given tracer and streams a [BlockTraceResult] for each block, in order.
+    ///
+    /// The subscription ends once the last block was traced or tracing a block failed. The error of
+    /// a failed block is sent to the subscriber as an error notification before the subscription is
+    /// closed.
+    #[subscription(
+        name = "traceChain" => "traceChain",
+        unsubscribe = "unsubscribeTraceChain",
+        item = BlockTraceResult
+    )]
     async fn debug_trace_chain(
         &self,
         start_exclusive: BlockNumberOrTag,
         end_inclusive: BlockNumberOrTag,
-    ) -> RpcResult<Vec<BlockTraceResult>>;
+        opts: Option<GethDebugTracingOptions>,
+    ) -> jsonrpsee::core::SubscriptionResult;
 
     /// The `debug_traceBlock` method will return a full stack trace of all invoked opcodes of all
     /// transaction that were included in this block.
@@ -137,10 +147,11 @@ pub trait DebugApi {
     async fn debug_backtrace_at(&self, location: &str) -> RpcResult<()>;
 
     /// Enumerates all accounts at a given block with paging capability. `maxResults` are returned
-    /// in the page and the items have keys that come after the `start` key (hashed address).
+    /// in the page and the items have keys that come after the `start` key.
     ///
-    /// If incompletes is false, then accounts for which the key preimage (i.e: the address) doesn't
-    /// exist in db are skipped. NB: geth by default does not store preimages.
+    /// Unlike geth, accounts are ordered by address instead of hashed address, so `start` is
+    /// (a prefix of) the address to start at. Since the plain state is keyed by address, every
+    /// account has a preimage and `incompletes` has no effect.
     #[method(name = "accountRange")]
     async fn debug_account_range(
         &self,
@@ -150,7 +161,7 @@ pub trait DebugApi {
         nocode: bool,
         nostorage: bool,
         incompletes: bool,
-    ) -> RpcResult<()>;
+    ) -> RpcResult<AccountRangeResult>;
 
     /// Turns on block profiling for the given duration and writes profile data to disk. It uses a
     /// profile rate of 1 for most accurate information. If a different rate is desired, set the
@@ -221,17 +232,18 @@ pub trait DebugApi {
     async fn debug_get_modified_accounts_by_hash(
         &self,
         start_hash: B256,
-        end_hash: B256,
-    ) -> RpcResult<()>;
+        end_hash: Option<B256>,
+    ) -> RpcResult<Vec<Address>>;
 
     /// Returns all accounts that have changed between the two blocks specified. A change is defined
-    /// as a difference in nonce, balance, code hash or storage hash.
+    /// as a difference in nonce, balance, code hash or storage hash. With one parameter, returns
+    /// the list of accounts modified in the specified block.
     #[method(name = "getModifiedAccountsByNumber")]
     async fn debug_get_modified_accounts_by_number(
         &self,
         start_number: u64,
-        end_number: u64,
-    ) -> RpcResult<()>;
+        end_number: Option<u64>,
+    ) -> RpcResult<Vec<Address>>;
 
     /// Turns on Go runtime tracing for the given duration and writes trace data to disk.
     #[method(name = "goTrace")]
@@ -244,11 +256,14 @@ pub trait DebugApi {
         &self,
         block_hash: B256,
         opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()>;
+    ) -> RpcResult<Vec<B256>>;
 
     /// Returns detailed runtime memory statistics.
+    ///
+    /// These are the statistics of the memory allocator, which are only available if the node uses
+    /// jemalloc.
     #[method(name = "memStats")]
-    async fn debug_mem_stats(&self) -> RpcResult<()>;
+    async fn debug_mem_stats(&self) -> RpcResult<MemStats>;
 
     /// Turns on mutex profiling for `nsec` seconds and writes profile data to file. It uses a
     /// profile rate of 1 for most accurate information. If a different rate is desired, set the
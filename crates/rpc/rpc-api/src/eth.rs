@@ -4,9 +4,11 @@ use reth_primitives::{
     Address, BlockId, BlockNumberOrTag, Bytes, B256, B64, U256, U64,
 };
 use reth_rpc_types::{
-    state::StateOverride, AccessListWithGasUsed, BlockOverrides, Bundle, CallRequest,
-    EIP1186AccountProofResponse, EthCallResponse, FeeHistory, Index, RichBlock, StateContext,
-    SyncStatus, Transaction, TransactionReceipt, TransactionRequest, Work,
+    simulate::{SimulatePayload, SimulatedBlock},
+    state::StateOverride,
+    AccessListWithGasUsed, BlockOverrides, Bundle, CallRequest, EIP1186AccountProofResponse,
+    EthCallResponse, FeeHistory, Index, RichBlock, StateContext, SyncStatus, Transaction,
+    TransactionReceipt, TransactionRequest, Work,
 };
 
 /// Eth rpc interface: <https://ethereum.github.io/execution-apis/api-documentation/>
@@ -162,6 +164,17 @@ pub trait EthApi {
         state_override: Option<StateOverride>,
     ) -> RpcResult<Vec<EthCallResponse>>;
 
+    /// Simulates a sequence of blocks with calls on top of the given block, with optional block
+    /// and state overrides per block.
+    ///
+    /// Returns the logs, gas usage and optionally the call traces of every call.
+    #[method(name = "simulateV1")]
+    async fn simulate_v1(
+        &self,
+        payload: SimulatePayload,
+        block_number: Option<BlockId>,
+    ) -> RpcResult<Vec<SimulatedBlock>>;
+
     /// Generates an access list for a transaction.
     ///
     /// This method creates an [EIP2930](https://eips.ethereum.org/EIPS/eip-2930) type accessList based on a given Transaction.
@@ -261,8 +274,10 @@ pub trait EthApi {
 
     /// Signs a transaction that can be submitted to the network at a later time using with
     /// `sendRawTransaction.`
+    ///
+    /// Missing nonce, gas limit and fee fields are filled in before signing.
     #[method(name = "signTransaction")]
-    async fn sign_transaction(&self, transaction: CallRequest) -> RpcResult<Bytes>;
+    async fn sign_transaction(&self, transaction: TransactionRequest) -> RpcResult<Bytes>;
 
     /// Signs data via [EIP-712](https://github.com/ethereum/EIPs/blob/master/EIPS/eip-712.md).
     #[method(name = "signTypedData")]
@@ -1,5 +1,5 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use reth_primitives::{Address, BlockId, BlockNumberOrTag, TxHash, B256};
+use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, TxHash, B256};
 use reth_rpc_types::{
     BlockDetails, ContractCreator, InternalOperation, OtsBlockTransactions, TraceEntry,
     Transaction, TransactionsWithReceipts,
@@ -25,12 +25,12 @@ pub trait Otterscan {
 
     /// Given a transaction hash, returns its raw revert reason.
     #[method(name = "getTransactionError")]
-    async fn get_transaction_error(&self, tx_hash: TxHash) -> RpcResult<String>;
+    async fn get_transaction_error(&self, tx_hash: TxHash) -> RpcResult<Bytes>;
 
     /// Extract all variations of calls, contract creation and self-destructs and returns a call
     /// tree.
     #[method(name = "traceTransaction")]
-    async fn trace_transaction(&self, tx_hash: TxHash) -> RpcResult<TraceEntry>;
+    async fn trace_transaction(&self, tx_hash: TxHash) -> RpcResult<Vec<TraceEntry>>;
 
     /// Tailor-made and expanded version of eth_getBlockByNumber for block details page in
     /// Otterscan.
@@ -45,6 +45,9 @@ pub trait Otterscan {
     async fn get_block_details_by_hash(&self, block_hash: B256) -> RpcResult<Option<BlockDetails>>;
 
     /// Get paginated transactions for a certain block. Also remove some verbose fields like logs.
+    ///
+    /// Pages are counted from the end of the block, so page 0 contains the last `page_size`
+    /// transactions of the block.
     #[method(name = "getBlockTransactions")]
     async fn get_block_transactions(
         &self,
@@ -54,6 +57,13 @@ pub trait Otterscan {
     ) -> RpcResult<OtsBlockTransactions>;
 
     /// Gets paginated inbound/outbound transaction calls for a certain address.
+    ///
+    /// Searches blocks before the given block, block 0 means searching from the latest block.
+    ///
+    /// Only blocks in which the state of the address changed are searched, as recorded by the
+    /// history indices. Transactions that touch the address without changing its state, like a
+    /// call to a view function, are missed unless another transaction of the same block changed
+    /// it.
     #[method(name = "searchTransactionsBefore")]
     async fn search_transactions_before(
         &self,
@@ -63,6 +73,9 @@ pub trait Otterscan {
     ) -> RpcResult<TransactionsWithReceipts>;
 
     /// Gets paginated inbound/outbound transaction calls for a certain address.
+    ///
+    /// Searches blocks after the given block, block 0 means searching from genesis.
+    /// The searched blocks are the same as for `searchTransactionsBefore`.
     #[method(name = "searchTransactionsAfter")]
     async fn search_transactions_after(
         &self,
@@ -72,6 +72,7 @@ where
         eth_cache.clone(),
         gas_oracle,
         EthConfig::default().rpc_gas_cap,
+        EthConfig::default().eth_proof_window,
         Box::new(executor.clone()),
         BlockingTaskPool::build().expect("failed to build tracing pool"),
         fee_history_cache,
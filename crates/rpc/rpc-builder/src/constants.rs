@@ -22,6 +22,16 @@ pub const DEFAULT_MAX_LOGS_PER_RESPONSE: usize = 20_000;
 /// The default maximum number of concurrently executed tracing calls
 pub const DEFAULT_MAX_TRACING_REQUESTS: u32 = 25;
 
+/// The default number of blocks behind the tip for which `eth_getProof` is served.
+///
+/// Proofs are only served for the latest block by default.
+pub const DEFAULT_ETH_PROOF_WINDOW: u64 = 0;
+
+/// The maximum number of blocks behind the tip for which `eth_getProof` can be served.
+///
+/// This is roughly 28 days of blocks, assuming 12 second block times.
+pub const MAX_ETH_PROOF_WINDOW: u64 = 28 * 24 * 60 * 60 / 12;
+
 /// The default IPC endpoint
 #[cfg(windows)]
 pub const DEFAULT_IPC_ENDPOINT: &str = r"\\.\pipe\reth.ipc";
@@ -1,5 +1,6 @@
 use crate::constants::{
-    DEFAULT_MAX_BLOCKS_PER_FILTER, DEFAULT_MAX_LOGS_PER_RESPONSE, DEFAULT_MAX_TRACING_REQUESTS,
+    DEFAULT_ETH_PROOF_WINDOW, DEFAULT_MAX_BLOCKS_PER_FILTER, DEFAULT_MAX_LOGS_PER_RESPONSE,
+    DEFAULT_MAX_TRACING_REQUESTS,
 };
 use reth_rpc::{
     eth::{
@@ -43,6 +44,10 @@ pub struct EthConfig {
     ///
     /// Defaults to [RPC_DEFAULT_GAS_CAP]
     pub rpc_gas_cap: u64,
+    /// The maximum number of blocks behind the tip for which `eth_getProof` is served.
+    ///
+    /// Defaults to [DEFAULT_ETH_PROOF_WINDOW]
+    pub eth_proof_window: u64,
     ///
     /// Sets TTL for stale filters
     pub stale_filter_ttl: std::time::Duration,
@@ -72,6 +77,7 @@ impl Default for EthConfig {
             max_blocks_per_filter: DEFAULT_MAX_BLOCKS_PER_FILTER,
             max_logs_per_response: DEFAULT_MAX_LOGS_PER_RESPONSE,
             rpc_gas_cap: RPC_DEFAULT_GAS_CAP.into(),
+            eth_proof_window: DEFAULT_ETH_PROOF_WINDOW,
             stale_filter_ttl: DEFAULT_STALE_FILTER_TTL,
             fee_history_cache: FeeHistoryCacheConfig::default(),
         }
@@ -114,4 +120,10 @@ impl EthConfig {
         self.rpc_gas_cap = rpc_gas_cap;
         self
     }
+
+    /// Configures the maximum number of blocks behind the tip for which `eth_getProof` is served
+    pub fn eth_proof_window(mut self, eth_proof_window: u64) -> Self {
+        self.eth_proof_window = eth_proof_window;
+        self
+    }
 }
@@ -20,7 +20,8 @@
 //! use reth_network_api::{NetworkInfo, Peers};
 //! use reth_provider::{
 //!     AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider,
-//!     ChangeSetReader, EvmEnvProvider, StateProviderFactory,
+//!     ChangeSetReader, EvmEnvProvider, HistoryReader, InvalidBlockReader, PlainStateReader,
+//!     StateProviderFactory,
 //! };
 //! use reth_rpc_builder::{
 //!     RethRpcModule, RpcModuleBuilder, RpcServerConfig, ServerBuilder, TransportRpcModuleConfig,
@@ -37,6 +38,9 @@
 //!         + BlockReaderIdExt
 //!         + ChainSpecProvider
 //!         + ChangeSetReader
+//!         + HistoryReader
+//!         + InvalidBlockReader
+//!         + PlainStateReader
 //!         + StateProviderFactory
 //!         + EvmEnvProvider
 //!         + Clone
@@ -71,7 +75,8 @@
 //! use reth_network_api::{NetworkInfo, Peers};
 //! use reth_provider::{
 //!     AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider,
-//!     ChangeSetReader, EvmEnvProvider, StateProviderFactory,
+//!     ChangeSetReader, EvmEnvProvider, HistoryReader, InvalidBlockReader, PlainStateReader,
+//!     StateProviderFactory,
 //! };
 //! use reth_rpc::JwtSecret;
 //! use reth_rpc_api::EngineApiServer;
@@ -93,6 +98,9 @@
 //!         + BlockReaderIdExt
 //!         + ChainSpecProvider
 //!         + ChangeSetReader
+//!         + HistoryReader
+//!         + InvalidBlockReader
+//!         + PlainStateReader
 //!         + StateProviderFactory
 //!         + EvmEnvProvider
 //!         + Clone
@@ -140,6 +148,7 @@ use std::{
     collections::{HashMap, HashSet},
     fmt,
     net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    path::PathBuf,
     str::FromStr,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
@@ -148,22 +157,25 @@ use hyper::{header::AUTHORIZATION, HeaderMap};
 pub use jsonrpsee::server::ServerBuilder;
 use jsonrpsee::{
     server::{IdProvider, Server, ServerHandle},
+    types::ErrorObjectOwned,
     Methods, RpcModule,
 };
 use serde::{Deserialize, Serialize, Serializer};
 use strum::{AsRefStr, EnumVariantNames, ParseError, VariantNames};
 use tower::layer::util::{Identity, Stack};
 use tower_http::cors::CorsLayer;
-use tracing::{instrument, trace};
+use tracing::{instrument, trace, warn};
 
 use constants::*;
 use error::{RpcError, ServerKind};
+use reth_auto_seal_consensus::AutoSealHandle;
 use reth_ipc::server::IpcServer;
 pub use reth_ipc::server::{Builder as IpcServerBuilder, Endpoint};
 use reth_network_api::{noop::NoopNetwork, NetworkInfo, Peers};
 use reth_provider::{
     AccountReader, BlockReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider,
-    ChangeSetReader, EvmEnvProvider, StateProviderFactory,
+    ChangeSetReader, EvmEnvProvider, HistoryReader, InvalidBlockReader, PlainStateReader,
+    StateProviderFactory,
 };
 use reth_rpc::{
     eth::{
@@ -172,9 +184,9 @@ use reth_rpc::{
         gas_oracle::GasPriceOracle,
         EthBundle, FeeHistoryCache,
     },
-    AdminApi, AuthLayer, BlockingTaskGuard, BlockingTaskPool, Claims, DebugApi, EngineEthApi,
-    EthApi, EthFilter, EthPubSub, EthSubscriptionIdProvider, JwtAuthValidator, JwtSecret, NetApi,
-    OtterscanApi, RPCApi, RethApi, TraceApi, TxPoolApi, Web3Api,
+    AdminApi, AnvilApi, AuthLayer, BlockingTaskGuard, BlockingTaskPool, Claims, DebugApi,
+    EngineEthApi, EthApi, EthFilter, EthPubSub, EthSubscriptionIdProvider, JwtAuthValidator,
+    JwtSecret, MevApi, NetApi, OtterscanApi, RPCApi, RethApi, TraceApi, TxPoolApi, Web3Api,
 };
 use reth_rpc_api::{servers::*, EngineApiServer};
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
@@ -222,6 +234,9 @@ where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + HistoryReader
+        + InvalidBlockReader
+        + PlainStateReader
         + Clone
         + Unpin
         + 'static,
@@ -253,6 +268,8 @@ pub struct RpcModuleBuilder<Provider, Pool, Network, Tasks, Events> {
     executor: Tasks,
     /// Provides access to chain events, such as new blocks, required by pubsub.
     events: Events,
+    /// Handle to the mining task of a dev chain, required by the `anvil` namespace.
+    auto_seal: Option<AutoSealHandle>,
 }
 
 // === impl RpcBuilder ===
@@ -268,7 +285,7 @@ impl<Provider, Pool, Network, Tasks, Events>
         executor: Tasks,
         events: Events,
     ) -> Self {
-        Self { provider, pool, network, executor, events }
+        Self { provider, pool, network, executor, events, auto_seal: None }
     }
 
     /// Configure the provider instance.
@@ -276,8 +293,8 @@ impl<Provider, Pool, Network, Tasks, Events>
     where
         P: BlockReader + StateProviderFactory + EvmEnvProvider + 'static,
     {
-        let Self { pool, network, executor, events, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, events }
+        let Self { pool, network, executor, events, auto_seal, .. } = self;
+        RpcModuleBuilder { provider, network, pool, executor, events, auto_seal }
     }
 
     /// Configure the transaction pool instance.
@@ -285,8 +302,8 @@ impl<Provider, Pool, Network, Tasks, Events>
     where
         P: TransactionPool + 'static,
     {
-        let Self { provider, network, executor, events, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, events }
+        let Self { provider, network, executor, events, auto_seal, .. } = self;
+        RpcModuleBuilder { provider, network, pool, executor, events, auto_seal }
     }
 
     /// Configure a [NoopTransactionPool] instance.
@@ -297,12 +314,13 @@ impl<Provider, Pool, Network, Tasks, Events>
     pub fn with_noop_pool(
         self,
     ) -> RpcModuleBuilder<Provider, NoopTransactionPool, Network, Tasks, Events> {
-        let Self { provider, executor, events, network, .. } = self;
+        let Self { provider, executor, events, network, auto_seal, .. } = self;
         RpcModuleBuilder {
             provider,
             executor,
             events,
             network,
+            auto_seal,
             pool: NoopTransactionPool::default(),
         }
     }
@@ -312,8 +330,8 @@ impl<Provider, Pool, Network, Tasks, Events>
     where
         N: NetworkInfo + Peers + 'static,
     {
-        let Self { provider, pool, executor, events, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, events }
+        let Self { provider, pool, executor, events, auto_seal, .. } = self;
+        RpcModuleBuilder { provider, network, pool, executor, events, auto_seal }
     }
 
     /// Configure a [NoopNetwork] instance.
@@ -322,8 +340,15 @@ impl<Provider, Pool, Network, Tasks, Events>
     /// This is only intended for allow easier setup of namespaces that depend on the [EthApi] which
     /// requires a [NetworkInfo] implementation.
     pub fn with_noop_network(self) -> RpcModuleBuilder<Provider, Pool, NoopNetwork, Tasks, Events> {
-        let Self { provider, pool, executor, events, .. } = self;
-        RpcModuleBuilder { provider, pool, executor, events, network: NoopNetwork::default() }
+        let Self { provider, pool, executor, events, auto_seal, .. } = self;
+        RpcModuleBuilder {
+            provider,
+            pool,
+            executor,
+            events,
+            auto_seal,
+            network: NoopNetwork::default(),
+        }
     }
 
     /// Configure the task executor to use for additional tasks.
@@ -334,8 +359,8 @@ impl<Provider, Pool, Network, Tasks, Events>
     where
         T: TaskSpawner + 'static,
     {
-        let Self { pool, network, provider, events, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, events }
+        let Self { pool, network, provider, events, auto_seal, .. } = self;
+        RpcModuleBuilder { provider, network, pool, executor, events, auto_seal }
     }
 
     /// Configure [TokioTaskExecutor] as the task executor to use for additional tasks.
@@ -345,8 +370,15 @@ impl<Provider, Pool, Network, Tasks, Events>
     pub fn with_tokio_executor(
         self,
     ) -> RpcModuleBuilder<Provider, Pool, Network, TokioTaskExecutor, Events> {
-        let Self { pool, network, provider, events, .. } = self;
-        RpcModuleBuilder { provider, network, pool, events, executor: TokioTaskExecutor::default() }
+        let Self { pool, network, provider, events, auto_seal, .. } = self;
+        RpcModuleBuilder {
+            provider,
+            network,
+            pool,
+            events,
+            auto_seal,
+            executor: TokioTaskExecutor::default(),
+        }
     }
 
     /// Configure the event subscriber instance
@@ -354,8 +386,16 @@ impl<Provider, Pool, Network, Tasks, Events>
     where
         E: CanonStateSubscriptions + 'static,
     {
-        let Self { provider, pool, executor, network, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, events }
+        let Self { provider, pool, executor, network, auto_seal, .. } = self;
+        RpcModuleBuilder { provider, network, pool, executor, events, auto_seal }
+    }
+
+    /// Configure the handle to the mining task of a dev chain.
+    ///
+    /// This is required by the [RethRpcModule::Anvil] namespace.
+    pub fn with_auto_seal_handle(mut self, handle: AutoSealHandle) -> Self {
+        self.auto_seal = Some(handle);
+        self
     }
 }
 
@@ -368,6 +408,9 @@ where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + HistoryReader
+        + InvalidBlockReader
+        + PlainStateReader
         + Clone
         + Unpin
         + 'static,
@@ -395,7 +438,7 @@ where
     {
         let mut modules = TransportRpcModules::default();
 
-        let Self { provider, pool, network, executor, events } = self;
+        let Self { provider, pool, network, executor, events, auto_seal } = self;
 
         let TransportRpcModuleConfig { http, ws, ipc, config } = module_config.clone();
 
@@ -407,6 +450,7 @@ where
             events,
             config.unwrap_or_default(),
         );
+        registry.auto_seal = auto_seal;
 
         modules.config = module_config;
         modules.http = registry.maybe_module(http.as_ref());
@@ -445,8 +489,11 @@ where
         self,
         config: RpcModuleConfig,
     ) -> RethModuleRegistry<Provider, Pool, Network, Tasks, Events> {
-        let Self { provider, pool, network, executor, events } = self;
-        RethModuleRegistry::new(provider, pool, network, executor, events, config)
+        let Self { provider, pool, network, executor, events, auto_seal } = self;
+        let mut registry =
+            RethModuleRegistry::new(provider, pool, network, executor, events, config);
+        registry.auto_seal = auto_seal;
+        registry
     }
 
     /// Configures all [RpcModule]s specific to the given [TransportRpcModuleConfig] which can be
@@ -456,7 +503,7 @@ where
     pub fn build(self, module_config: TransportRpcModuleConfig) -> TransportRpcModules<()> {
         let mut modules = TransportRpcModules::default();
 
-        let Self { provider, pool, network, executor, events } = self;
+        let Self { provider, pool, network, executor, events, auto_seal } = self;
 
         if !module_config.is_empty() {
             let TransportRpcModuleConfig { http, ws, ipc, config } = module_config.clone();
@@ -469,6 +516,7 @@ where
                 events,
                 config.unwrap_or_default(),
             );
+            registry.auto_seal = auto_seal;
 
             modules.config = module_config;
             modules.http = registry.maybe_module(http.as_ref());
@@ -491,6 +539,10 @@ impl Default for RpcModuleBuilder<(), (), (), (), ()> {
 pub struct RpcModuleConfig {
     /// `eth` namespace settings
     eth: EthConfig,
+    /// The directory the profiles requested over the `debug` namespace are written to.
+    ///
+    /// If not set, the `debug` profiling methods that write files are rejected.
+    profiles_dir: Option<PathBuf>,
 }
 
 // === impl RpcModuleConfig ===
@@ -502,7 +554,13 @@ impl RpcModuleConfig {
     }
     /// Returns a new RPC module config given the eth namespace config
     pub fn new(eth: EthConfig) -> Self {
-        Self { eth }
+        Self { eth, profiles_dir: None }
+    }
+
+    /// Sets the directory the profiles requested over the `debug` namespace are written to.
+    pub fn with_profiles_dir(mut self, profiles_dir: PathBuf) -> Self {
+        self.profiles_dir = Some(profiles_dir);
+        self
     }
 }
 
@@ -510,6 +568,7 @@ impl RpcModuleConfig {
 #[derive(Clone, Debug, Default)]
 pub struct RpcModuleConfigBuilder {
     eth: Option<EthConfig>,
+    profiles_dir: Option<PathBuf>,
 }
 
 // === impl RpcModuleConfigBuilder ===
@@ -521,10 +580,16 @@ impl RpcModuleConfigBuilder {
         self
     }
 
+    /// Configures the directory the profiles requested over the `debug` namespace are written to
+    pub fn profiles_dir(mut self, profiles_dir: PathBuf) -> Self {
+        self.profiles_dir = Some(profiles_dir);
+        self
+    }
+
     /// Consumes the type and creates the [RpcModuleConfig]
     pub fn build(self) -> RpcModuleConfig {
-        let RpcModuleConfigBuilder { eth } = self;
-        RpcModuleConfig { eth: eth.unwrap_or_default() }
+        let RpcModuleConfigBuilder { eth, profiles_dir } = self;
+        RpcModuleConfig { eth: eth.unwrap_or_default(), profiles_dir }
     }
 }
 
@@ -654,6 +719,9 @@ impl RpcModuleSelection {
             + EvmEnvProvider
             + ChainSpecProvider
             + ChangeSetReader
+            + HistoryReader
+            + InvalidBlockReader
+            + PlainStateReader
             + Clone
             + Unpin
             + 'static,
@@ -763,11 +831,17 @@ pub enum RethRpcModule {
     Reth,
     /// `ots_` module
     Ots,
+    /// Anvil compatible `evm_` and `anvil_` methods to control a dev chain
+    ///
+    /// This is only available if the node runs in `--dev` mode.
+    Anvil,
     /// For single non-standard `eth_` namespace call `eth_callBundle`
     ///
     /// This is separate from [RethRpcModule::Eth] because it is a non standardized call that
     /// should be opt-in.
     EthCallBundle,
+    /// `mev_` module, which can only simulate MEV-Share bundles
+    Mev,
 }
 
 // === impl RethRpcModule ===
@@ -794,7 +868,9 @@ impl FromStr for RethRpcModule {
             "rpc" => RethRpcModule::Rpc,
             "reth" => RethRpcModule::Reth,
             "ots" => RethRpcModule::Ots,
+            "anvil" => RethRpcModule::Anvil,
             "eth-call-bundle" | "eth_callBundle" => RethRpcModule::EthCallBundle,
+            "mev" => RethRpcModule::Mev,
             _ => return Err(ParseError::VariantNotFound),
         })
     }
@@ -838,6 +914,10 @@ pub struct RethModuleRegistry<Provider, Pool, Network, Tasks, Events> {
     blocking_pool_guard: BlockingTaskGuard,
     /// Contains the [Methods] of a module
     modules: HashMap<RethRpcModule, Methods>,
+    /// Handle to the mining task of a dev chain, required by the `anvil` namespace
+    auto_seal: Option<AutoSealHandle>,
+    /// Holds the `anvil` namespace handler, which is shared with the `eth` namespace
+    anvil: Option<AnvilApi<Provider, Pool, Network>>,
 }
 
 // === impl RethModuleRegistry ===
@@ -864,6 +944,8 @@ impl<Provider, Pool, Network, Tasks, Events>
             blocking_pool_guard: BlockingTaskGuard::new(config.eth.max_tracing_requests),
             config,
             events,
+            auto_seal: None,
+            anvil: None,
         }
     }
 
@@ -941,6 +1023,9 @@ where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + HistoryReader
+        + InvalidBlockReader
+        + PlainStateReader
         + Clone
         + Unpin
         + 'static,
@@ -1077,6 +1162,7 @@ where
             cache: _,
             blocking_task_pool: _,
         } = self.with_eth(|eth| eth.clone());
+        let anvil_api = self.anvil_api();
 
         // Create a copy, so we can list out all the methods for rpc_ api
         let namespaces: Vec<_> = namespaces.collect();
@@ -1090,11 +1176,12 @@ where
                         RethRpcModule::Admin => {
                             AdminApi::new(self.network.clone()).into_rpc().into()
                         }
-                        RethRpcModule::Debug => DebugApi::new(
+                        RethRpcModule::Debug => DebugApi::with_profiles_dir(
                             self.provider.clone(),
                             eth_api.clone(),
                             Box::new(self.executor.clone()),
                             self.blocking_pool_guard.clone(),
+                            self.config.profiles_dir.clone(),
                         )
                         .into_rpc()
                         .into(),
@@ -1104,6 +1191,26 @@ where
                             module.merge(eth_filter.clone().into_rpc()).expect("No conflicts");
                             module.merge(eth_pubsub.clone().into_rpc()).expect("No conflicts");
 
+                            if let Some(anvil_api) = anvil_api.clone() {
+                                // on a dev chain, impersonated accounts send transactions without
+                                // a signature
+                                module.remove_method("eth_sendTransaction");
+                                module
+                                    .register_async_method(
+                                        "eth_sendTransaction",
+                                        move |params, _| {
+                                            let anvil_api = anvil_api.clone();
+                                            async move {
+                                                let request = params.one()?;
+                                                Ok::<_, ErrorObjectOwned>(
+                                                    anvil_api.send_transaction(request).await?,
+                                                )
+                                            }
+                                        },
+                                    )
+                                    .expect("eth_sendTransaction was removed");
+                            }
+
                             module.into()
                         }
                         RethRpcModule::Net => {
@@ -1128,7 +1235,13 @@ where
                         )
                         .into_rpc()
                         .into(),
-                        RethRpcModule::Ots => OtterscanApi::new(eth_api.clone()).into_rpc().into(),
+                        RethRpcModule::Ots => OtterscanApi::new(
+                            self.provider.clone(),
+                            eth_api.clone(),
+                            Box::new(self.executor.clone()),
+                        )
+                        .into_rpc()
+                        .into(),
                         RethRpcModule::Reth => {
                             RethApi::new(self.provider.clone(), Box::new(self.executor.clone()))
                                 .into_rpc()
@@ -1139,6 +1252,18 @@ where
                                 .into_rpc()
                                 .into()
                         }
+                        RethRpcModule::Mev => {
+                            MevApi::new(eth_api.clone(), self.blocking_pool_guard.clone())
+                                .into_rpc()
+                                .into()
+                        }
+                        RethRpcModule::Anvil => match anvil_api.clone() {
+                            Some(anvil_api) => anvil_api.into_rpc().into(),
+                            None => {
+                                warn!(target: "rpc", "anvil namespace is only available in dev mode");
+                                Methods::new()
+                            }
+                        },
                     })
                     .clone()
             })
@@ -1206,6 +1331,7 @@ where
                 cache.clone(),
                 gas_oracle,
                 self.config.eth.rpc_gas_cap,
+                self.config.eth.eth_proof_window,
                 executor.clone(),
                 blocking_task_pool.clone(),
                 fee_history_cache,
@@ -1272,14 +1398,24 @@ where
         EthBundle::new(eth_api, self.blocking_pool_guard.clone())
     }
 
+    /// Instantiates [MevApi]
+    ///
+    /// # Panics
+    ///
+    /// If called outside of the tokio runtime. See also [Self::eth_api]
+    pub fn mev_api(&mut self) -> MevApi<EthApi<Provider, Pool, Network>> {
+        let eth_api = self.eth_api();
+        MevApi::new(eth_api, self.blocking_pool_guard.clone())
+    }
+
     /// Instantiates OtterscanApi
     ///
     /// # Panics
     ///
     /// If called outside of the tokio runtime. See also [Self::eth_api]
-    pub fn otterscan_api(&mut self) -> OtterscanApi<EthApi<Provider, Pool, Network>> {
+    pub fn otterscan_api(&mut self) -> OtterscanApi<Provider, EthApi<Provider, Pool, Network>> {
         let eth_api = self.eth_api();
-        OtterscanApi::new(eth_api)
+        OtterscanApi::new(self.provider.clone(), eth_api, Box::new(self.executor.clone()))
     }
 
     /// Instantiates DebugApi
@@ -1289,11 +1425,12 @@ where
     /// If called outside of the tokio runtime. See also [Self::eth_api]
     pub fn debug_api(&mut self) -> DebugApi<Provider, EthApi<Provider, Pool, Network>> {
         let eth_api = self.eth_api();
-        DebugApi::new(
+        DebugApi::with_profiles_dir(
             self.provider.clone(),
             eth_api,
             Box::new(self.executor.clone()),
             self.blocking_pool_guard.clone(),
+            self.config.profiles_dir.clone(),
         )
     }
 
@@ -1311,6 +1448,22 @@ where
     pub fn reth_api(&mut self) -> RethApi<Provider> {
         RethApi::new(self.provider.clone(), Box::new(self.executor.clone()))
     }
+
+    /// Returns the AnvilApi, if the registry has a handle to the mining task of a dev chain.
+    ///
+    /// The instance is created once and shared with the `eth` namespace, so accounts that are
+    /// impersonated through it can use `eth_sendTransaction`.
+    ///
+    /// # Panics
+    ///
+    /// If called outside of the tokio runtime. See also [Self::eth_api]
+    pub fn anvil_api(&mut self) -> Option<AnvilApi<Provider, Pool, Network>> {
+        if self.anvil.is_none() {
+            let handle = self.auto_seal.clone()?;
+            self.anvil = Some(AnvilApi::new(self.eth_api(), handle));
+        }
+        self.anvil.clone()
+    }
 }
 
 /// A builder type for configuring and launching the servers that will handle RPC requests.
@@ -1722,6 +1875,11 @@ impl TransportRpcModuleConfig {
         self.ipc.as_ref()
     }
 
+    /// Returns the [RpcModuleConfig] for the configured modules
+    pub fn config(&self) -> Option<&RpcModuleConfig> {
+        self.config.as_ref()
+    }
+
     /// Ensures that both http and ws are configured and that they are configured to use the same
     /// port.
     fn ensure_ws_http_identical(&self) -> Result<(), WsHttpSamePortError> {
@@ -2272,6 +2430,8 @@ mod tests {
                 "rpc" => RethRpcModule::Rpc,
                 "ots" => RethRpcModule::Ots,
                 "reth" => RethRpcModule::Reth,
+                "anvil" => RethRpcModule::Anvil,
+                "mev" => RethRpcModule::Mev,
             );
     }
 
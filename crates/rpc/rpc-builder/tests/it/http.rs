@@ -18,8 +18,8 @@ use reth_rpc_api::{
 };
 use reth_rpc_builder::RethRpcModule;
 use reth_rpc_types::{
-    trace::filter::TraceFilter, CallRequest, Filter, Index, PendingTransactionFilterKind,
-    TransactionRequest,
+    simulate::SimulatePayload, trace::filter::TraceFilter, CallRequest, Filter, Index,
+    PendingTransactionFilterKind, TransactionRequest,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -166,8 +166,10 @@ where
     EthApiClient::call(client, call_request.clone(), Some(block_number.into()), None, None)
         .await
         .unwrap();
+    EthApiClient::simulate_v1(client, SimulatePayload::default(), None).await.unwrap_err();
     EthApiClient::syncing(client).await.unwrap();
-    EthApiClient::send_transaction(client, transaction_request).await.unwrap_err();
+    EthApiClient::send_transaction(client, transaction_request.clone()).await.unwrap_err();
+    EthApiClient::sign_transaction(client, transaction_request).await.unwrap_err();
     EthApiClient::hashrate(client).await.unwrap();
     EthApiClient::submit_hashrate(client, U256::default(), B256::default()).await.unwrap();
     EthApiClient::gas_price(client).await.unwrap_err();
@@ -184,9 +186,6 @@ where
             .err()
             .unwrap()
     ));
-    assert!(is_unimplemented(
-        EthApiClient::sign_transaction(client, call_request.clone()).await.err().unwrap()
-    ));
 }
 
 async fn test_basic_debug_calls<C>(client: &C)
@@ -199,7 +198,7 @@ where
     DebugApiClient::raw_block(client, block_id).await.unwrap();
     DebugApiClient::raw_transaction(client, B256::default()).await.unwrap();
     DebugApiClient::raw_receipts(client, block_id).await.unwrap();
-    assert!(is_unimplemented(DebugApiClient::bad_blocks(client).await.err().unwrap()));
+    assert!(DebugApiClient::bad_blocks(client).await.unwrap().is_empty());
 }
 
 async fn test_basic_net_calls<C>(client: &C)
@@ -267,38 +266,23 @@ where
 
     OtterscanClient::get_api_level(client).await.unwrap();
 
-    assert!(is_unimplemented(
-        OtterscanClient::get_internal_operations(client, tx_hash).await.err().unwrap()
-    ));
-    assert!(is_unimplemented(
-        OtterscanClient::get_transaction_error(client, tx_hash).await.err().unwrap()
-    ));
-    assert!(is_unimplemented(
-        OtterscanClient::trace_transaction(client, tx_hash).await.err().unwrap()
-    ));
+    OtterscanClient::get_internal_operations(client, tx_hash).await.unwrap_err();
+    OtterscanClient::get_transaction_error(client, tx_hash).await.unwrap_err();
+    OtterscanClient::trace_transaction(client, tx_hash).await.unwrap_err();
 
     OtterscanClient::get_block_details(client, block_number).await.unwrap();
 
     OtterscanClient::get_block_details_by_hash(client, block_hash).await.unwrap();
 
-    assert!(is_unimplemented(
-        OtterscanClient::get_block_transactions(client, block_number, page_number, page_size,)
-            .await
-            .err()
-            .unwrap()
-    ));
-    assert!(is_unimplemented(
-        OtterscanClient::search_transactions_before(client, address, block_number, page_size,)
-            .await
-            .err()
-            .unwrap()
-    ));
-    assert!(is_unimplemented(
-        OtterscanClient::search_transactions_after(client, address, block_number, page_size,)
-            .await
-            .err()
-            .unwrap()
-    ));
+    OtterscanClient::get_block_transactions(client, block_number, page_number, page_size)
+        .await
+        .unwrap_err();
+    OtterscanClient::search_transactions_before(client, address, block_number, page_size)
+        .await
+        .unwrap();
+    OtterscanClient::search_transactions_after(client, address, block_number, page_size)
+        .await
+        .unwrap();
     assert!(is_unimplemented(
         OtterscanClient::get_transaction_by_sender_and_nonce(client, sender, nonce,)
             .await
@@ -0,0 +1,25 @@
+//! Types for the `debug` API.
+
+use serde::{Deserialize, Serialize};
+
+/// Memory allocator statistics returned by `debug_memStats`.
+///
+/// See the [jemalloc documentation](https://jemalloc.net/jemalloc.3.html) for details on the
+/// individual statistics.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemStats {
+    /// Total number of bytes allocated by the application.
+    pub allocated: u64,
+    /// Total number of bytes in active pages allocated by the application.
+    pub active: u64,
+    /// Total number of bytes dedicated to allocator metadata.
+    pub metadata: u64,
+    /// Maximum number of bytes in physically resident data pages mapped by the allocator.
+    pub resident: u64,
+    /// Total number of bytes in active extents mapped by the allocator.
+    pub mapped: u64,
+    /// Total number of bytes in virtual memory mappings that were retained rather than being
+    /// returned to the operating system.
+    pub retained: u64,
+}
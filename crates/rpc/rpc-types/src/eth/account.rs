@@ -2,6 +2,7 @@
 use crate::serde_helpers::storage::JsonStorageKey;
 use alloy_primitives::{Address, Bytes, B256, B512, U256, U64};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Account information.
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
@@ -63,6 +64,44 @@ pub struct RecoveredAccount {
     pub is_valid_for_current_chain: bool,
 }
 
+/// An account of the state dump returned by `debug_accountRange`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DumpAccount {
+    /// The balance of the account, in decimal.
+    pub balance: String,
+    /// The nonce of the account.
+    pub nonce: u64,
+    /// The storage root of the account.
+    pub root: B256,
+    /// The hash of the account's code.
+    pub code_hash: B256,
+    /// The code of the account, omitted if code was not requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// The storage of the account, omitted if storage was not requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage: Option<BTreeMap<B256, U256>>,
+    /// The address of the account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address>,
+    /// The hashed address of the account.
+    #[serde(rename = "key")]
+    pub address_hash: B256,
+}
+
+/// A page of the state dump returned by `debug_accountRange`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AccountRangeResult {
+    /// The state root of the dumped state.
+    pub root: B256,
+    /// The accounts of the page, keyed by address.
+    pub accounts: BTreeMap<Address, DumpAccount>,
+    /// The key to start the next page at, if there are more accounts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<Bytes>,
+}
+
 #[test]
 fn test_eip_1186_account_without_storage_proof() {
     let response = r#"{
@@ -794,6 +794,20 @@ pub struct BlockOverrides {
     pub block_hash: Option<BTreeMap<u64, B256>>,
 }
 
+/// A block that was rejected by the node, as returned by `debug_getBadBlocks`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BadBlock {
+    /// Hash of the block.
+    pub hash: B256,
+    /// The block with full transaction objects.
+    pub block: RichBlock,
+    /// The RLP encoded block.
+    pub rlp: Bytes,
+    /// The validation or execution error the block was rejected with.
+    pub reason: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -11,6 +11,7 @@ mod index;
 mod log;
 pub mod pubsub;
 pub mod raw_log;
+pub mod simulate;
 pub mod state;
 mod syncing;
 pub mod trace;
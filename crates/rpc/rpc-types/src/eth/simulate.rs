@@ -0,0 +1,139 @@
+//! Types for the `eth_simulateV1` endpoint.
+
+use crate::{state::StateOverride, trace::geth::CallFrame, BlockOverrides, CallRequest, Log};
+use alloy_primitives::{Address, Bytes, B256, U256, U64};
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of blocks that can be simulated in a single `eth_simulateV1` request.
+pub const MAX_SIMULATE_BLOCKS: usize = 256;
+
+/// Error code of a call that reverted.
+pub const SIMULATE_REVERT_ERROR_CODE: i32 = 3;
+
+/// Error code of a call that failed with an EVM error other than a revert, e.g. out of gas.
+pub const SIMULATE_VM_ERROR_CODE: i32 = -32015;
+
+/// The payload of an `eth_simulateV1` request.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SimulatePayload {
+    /// The blocks to simulate, in order.
+    pub block_state_calls: Vec<SimBlock>,
+    /// Whether to return a call trace for every call.
+    pub trace_calls: bool,
+    /// Whether to validate the calls like regular transactions.
+    ///
+    /// If enabled, nonces, balances and the base fee are checked. Otherwise the calls are
+    /// executed with the relaxed rules of `eth_call`.
+    pub validation: bool,
+}
+
+/// A block to simulate, consisting of a set of calls and the overrides that are applied before
+/// the calls are executed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SimBlock {
+    /// Overrides for the header fields of the simulated block.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_overrides: Option<BlockOverrides>,
+    /// State overrides that are applied at the start of the block.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_overrides: Option<StateOverride>,
+    /// The calls to execute in the block.
+    pub calls: Vec<CallRequest>,
+}
+
+/// The result of a simulated block.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedBlock {
+    /// The number of the block.
+    pub number: U256,
+    /// The hash of the block.
+    ///
+    /// This is the hash of the simulated header, which does not commit to the calls of the block.
+    pub hash: B256,
+    /// The hash of the parent block.
+    pub parent_hash: B256,
+    /// The timestamp of the block.
+    pub timestamp: U64,
+    /// The gas limit of the block.
+    pub gas_limit: U64,
+    /// The total gas used by all calls of the block.
+    pub gas_used: U64,
+    /// The fee recipient of the block.
+    pub fee_recipient: Address,
+    /// The base fee of the block.
+    pub base_fee_per_gas: U256,
+    /// The prevrandao of the block.
+    pub prev_randao: B256,
+    /// The results of the calls of the block, in order.
+    pub calls: Vec<SimCallResult>,
+}
+
+/// The result of a single simulated call.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimCallResult {
+    /// The data returned by the call, or the revert data if it reverted.
+    pub return_data: Bytes,
+    /// The logs emitted by the call.
+    pub logs: Vec<Log>,
+    /// The gas used by the call.
+    pub gas_used: U64,
+    /// `1` if the call succeeded, `0` otherwise.
+    pub status: U64,
+    /// The error of the call, if it failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<SimulateError>,
+    /// The call trace, if requested via [SimulatePayload::trace_calls].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<CallFrame>,
+}
+
+/// The error of a simulated call that failed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimulateError {
+    /// The error code, see [SIMULATE_REVERT_ERROR_CODE] and [SIMULATE_VM_ERROR_CODE].
+    pub code: i32,
+    /// The error message.
+    pub message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_simulate_payload() {
+        let s = r#"{
+            "blockStateCalls": [
+                {
+                    "blockOverrides": { "number": "0x10", "baseFee": "0x0" },
+                    "stateOverrides": {
+                        "0xc000000000000000000000000000000000000000": { "balance": "0x3e8" }
+                    },
+                    "calls": [
+                        {
+                            "from": "0xc000000000000000000000000000000000000000",
+                            "to": "0xc100000000000000000000000000000000000000",
+                            "value": "0x3e8"
+                        }
+                    ]
+                },
+                { "calls": [] }
+            ],
+            "traceCalls": true
+        }"#;
+        let payload: SimulatePayload = serde_json::from_str(s).unwrap();
+        assert_eq!(payload.block_state_calls.len(), 2);
+        assert!(payload.trace_calls);
+        assert!(!payload.validation);
+
+        let block = &payload.block_state_calls[0];
+        assert_eq!(block.block_overrides.as_ref().unwrap().number, Some(U256::from(0x10)));
+        assert_eq!(block.state_overrides.as_ref().unwrap().len(), 1);
+        assert_eq!(block.calls[0].value, Some(U256::from(1000)));
+        assert!(payload.block_state_calls[1].block_overrides.is_none());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::serde_helpers::num::from_int_or_hex;
+use crate::{serde_helpers::num::from_int_or_hex, trace::parity::LocalizedTransactionTrace};
 use alloy_primitives::{Address, Bytes, B256, U256};
 use serde::{Deserialize, Serialize};
 
@@ -79,6 +79,27 @@ impl CallConfig {
     }
 }
 
+/// The response object for `debug_traceTransaction` with `"tracer": "flatCallTracer"`.
+///
+/// These are the Parity style traces of the transaction, see
+/// <https://github.com/ethereum/go-ethereum/blob/0a2f33946b95989e8ce36e72a88138adceab6a23/eth/tracers/native/call_flat.go>
+pub type FlatCallFrame = Vec<LocalizedTransactionTrace>;
+
+/// The configuration for the flat call tracer.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlatCallConfig {
+    /// If true, errors are reported like Parity does.
+    ///
+    /// Note: the traces are built by the Parity trace builder, hence errors are always reported
+    /// like Parity does.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub convert_parity_errors: Option<bool>,
+    /// If true, calls to precompiles are included in the traces.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include_precompiles: Option<bool>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -8,8 +8,9 @@ use std::{collections::BTreeMap, time::Duration};
 
 // re-exports
 pub use self::{
-    call::{CallConfig, CallFrame, CallLogFrame},
+    call::{CallConfig, CallFrame, CallLogFrame, FlatCallConfig, FlatCallFrame},
     four_byte::FourByteFrame,
+    mux::{MuxConfig, MuxFrame},
     noop::NoopFrame,
     pre_state::{
         AccountChangeKind, AccountState, DiffMode, DiffStateKind, PreStateConfig, PreStateFrame,
@@ -19,6 +20,7 @@ pub use self::{
 
 mod call;
 mod four_byte;
+mod mux;
 mod noop;
 mod pre_state;
 
@@ -110,10 +112,14 @@ pub enum GethTrace {
     Default(DefaultFrame),
     /// The response for call tracer
     CallTracer(CallFrame),
+    /// The response for flat call tracer
+    FlatCallTracer(FlatCallFrame),
     /// The response for four byte tracer
     FourByteTracer(FourByteFrame),
     /// The response for pre-state byte tracer
     PreStateTracer(PreStateFrame),
+    /// The response for mux tracer
+    MuxTracer(MuxFrame),
     /// An empty json response
     NoopTracer(NoopFrame),
     /// Any other trace response, such as custom javascript response objects
@@ -144,6 +150,18 @@ impl From<PreStateFrame> for GethTrace {
     }
 }
 
+impl From<FlatCallFrame> for GethTrace {
+    fn from(value: FlatCallFrame) -> Self {
+        GethTrace::FlatCallTracer(value)
+    }
+}
+
+impl From<MuxFrame> for GethTrace {
+    fn from(value: MuxFrame) -> Self {
+        GethTrace::MuxTracer(value)
+    }
+}
+
 impl From<NoopFrame> for GethTrace {
     fn from(value: NoopFrame) -> Self {
         GethTrace::NoopTracer(value)
@@ -153,7 +171,7 @@ impl From<NoopFrame> for GethTrace {
 /// Available built-in tracers
 ///
 /// See <https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers>
-#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Deserialize, Serialize)]
 pub enum GethDebugBuiltInTracerType {
     /// The 4byteTracer collects the function selectors of every function executed in the lifetime
     /// of a transaction, along with the size of the supplied call data. The result is a
@@ -166,6 +184,10 @@ pub enum GethDebugBuiltInTracerType {
     /// with the top-level call at root and sub-calls as children of the higher levels.
     #[serde(rename = "callTracer")]
     CallTracer,
+    /// The flatCallTracer tracks all the call frames executed during a transaction like the
+    /// callTracer, but returns them as a flat list of Parity style traces.
+    #[serde(rename = "flatCallTracer")]
+    FlatCallTracer,
     /// The prestate tracer has two modes: prestate and diff. The prestate mode returns the
     /// accounts necessary to execute a given transaction. diff mode returns the differences
     /// between the transaction's pre and post-state (i.e. what changed because the transaction
@@ -179,6 +201,10 @@ pub enum GethDebugBuiltInTracerType {
     /// This tracer is noop. It returns an empty object and is only meant for testing the setup.
     #[serde(rename = "noopTracer")]
     NoopTracer,
+    /// The muxTracer runs several built-in tracers in a single execution of the transaction. The
+    /// result is an object that maps each tracer to its result.
+    #[serde(rename = "muxTracer")]
+    MuxTracer,
 }
 
 /// Available tracers
@@ -240,6 +266,22 @@ impl GethDebugTracerConfig {
         }
         self.from_value()
     }
+
+    /// Returns the [FlatCallConfig] if it is a flat call config.
+    pub fn into_flat_call_config(self) -> Result<FlatCallConfig, serde_json::Error> {
+        if self.0.is_null() {
+            return Ok(Default::default())
+        }
+        self.from_value()
+    }
+
+    /// Returns the [MuxConfig] if it is a mux config.
+    pub fn into_mux_config(self) -> Result<MuxConfig, serde_json::Error> {
+        if self.0.is_null() {
+            return Ok(Default::default())
+        }
+        self.from_value()
+    }
 }
 
 impl From<serde_json::Value> for GethDebugTracerConfig {
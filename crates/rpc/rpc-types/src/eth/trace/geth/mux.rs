@@ -0,0 +1,64 @@
+use crate::trace::geth::{GethDebugBuiltInTracerType, GethDebugTracerConfig, GethTrace};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The configuration for the mux tracer, which maps each tracer that should be run to its config.
+///
+/// <https://github.com/ethereum/go-ethereum/blob/0a2f33946b95989e8ce36e72a88138adceab6a23/eth/tracers/native/mux.go>
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MuxConfig(pub HashMap<GethDebugBuiltInTracerType, Option<GethDebugTracerConfig>>);
+
+/// The response object for `debug_traceTransaction` with `"tracer": "muxTracer"`, which maps each
+/// configured tracer to its result.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MuxFrame(pub HashMap<GethDebugBuiltInTracerType, GethTrace>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace::geth::*;
+
+    #[test]
+    fn test_serialize_mux_tracer_config() {
+        let mut opts = GethDebugTracingCallOptions::default();
+        opts.tracing_options.tracer =
+            Some(GethDebugTracerType::BuiltInTracer(GethDebugBuiltInTracerType::MuxTracer));
+        opts.tracing_options.tracer_config = serde_json::to_value(MuxConfig(HashMap::from([(
+            GethDebugBuiltInTracerType::CallTracer,
+            Some(GethDebugTracerConfig(serde_json::json!({ "onlyTopCall": true }))),
+        )])))
+        .unwrap()
+        .into();
+
+        assert_eq!(
+            serde_json::to_string(&opts).unwrap(),
+            r#"{"tracer":"muxTracer","tracerConfig":{"callTracer":{"onlyTopCall":true}}}"#
+        );
+    }
+
+    #[test]
+    fn test_deserialize_mux_tracer_config() {
+        let config: MuxConfig =
+            serde_json::from_str(r#"{"4byteTracer":null,"callTracer":{"withLog":true}}"#).unwrap();
+        assert_eq!(config.0.len(), 2);
+        assert_eq!(config.0[&GethDebugBuiltInTracerType::FourByteTracer], None);
+        let call_config = config.0[&GethDebugBuiltInTracerType::CallTracer]
+            .clone()
+            .unwrap()
+            .into_call_config()
+            .unwrap();
+        assert_eq!(call_config.with_log, Some(true));
+    }
+
+    #[test]
+    fn test_deserialize_mux_frame() {
+        let s = r#"{"4byteTracer":{"0x27dc297e-128":1},"noopTracer":{}}"#;
+        let trace: GethTrace = serde_json::from_str(s).unwrap();
+        let GethTrace::MuxTracer(frame) = trace else { panic!("expected mux frame") };
+        assert_eq!(frame.0.len(), 2);
+        assert!(matches!(
+            frame.0[&GethDebugBuiltInTracerType::FourByteTracer],
+            GethTrace::FourByteTracer(_)
+        ));
+    }
+}
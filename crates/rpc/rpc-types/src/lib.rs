@@ -13,6 +13,7 @@
 
 mod admin;
 pub mod beacon;
+mod debug;
 mod eth;
 mod mev;
 mod net;
@@ -23,6 +24,7 @@ mod rpc;
 pub mod serde_helpers;
 
 pub use admin::*;
+pub use debug::*;
 pub use eth::*;
 pub use mev::*;
 pub use net::*;
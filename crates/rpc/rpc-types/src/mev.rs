@@ -79,6 +79,11 @@ pub enum BundleItem {
         /// If true, the transaction can revert without the bundle being considered invalid.
         can_revert: bool,
     },
+    /// A nested bundle request.
+    Bundle {
+        /// Bundle request.
+        bundle: SendBundleRequest,
+    },
 }
 
 /// Requirements for the bundle to be included in the block.
@@ -330,11 +335,11 @@ pub struct SimBundleResponse {
     /// The block number of the simulated block.
     pub state_block: U64,
     /// The gas price of the simulated block.
-    pub mev_gas_price: U64,
+    pub mev_gas_price: U256,
     /// The profit of the simulated block.
-    pub profit: U64,
+    pub profit: U256,
     /// The refundable value of the simulated block.
-    pub refundable_value: U64,
+    pub refundable_value: U256,
     /// The gas used by the simulated block.
     pub gas_used: U64,
     /// Logs returned by mev_simBundle.
@@ -846,6 +851,45 @@ mod tests {
         assert!(actual.success);
     }
 
+    #[test]
+    fn can_deserialize_nested_bundle() {
+        let str = r#"
+        {
+            "version": "v0.1",
+            "inclusion": {
+                "block": "0x1",
+                "maxBlock": "0x5"
+            },
+            "body": [{
+                "bundle": {
+                    "version": "v0.1",
+                    "inclusion": {
+                        "block": "0x1"
+                    },
+                    "body": [{
+                        "hash": "0x05cc8f2f9f2ad5e3ac5b4fda6fc7af7ab8f6bbc6b3d7a7c0a0b1b1e1e0f0a0b0"
+                    }]
+                }
+            }, {
+                "tx": "0x02f86b0180843b9aca00852ecc889a0082520894c87037874aed04e51c29f582394217a0a2b89d808080c080a0a463985c616dd8ee17d7ef9112af4e6e06a27b071525b42182fe7b0b5c8b4925a00af5ca177ffef2ff28449292505d41be578bebb77110dfc09361d2fb56998260",
+                "canRevert": true
+            }],
+            "validity": {
+                "refund": [{
+                    "bodyIdx": 0,
+                    "percent": 90
+                }]
+            }
+        }
+        "#;
+        let bundle: SendBundleRequest = serde_json::from_str(str).unwrap();
+        assert!(matches!(
+            &bundle.bundle_body[0],
+            BundleItem::Bundle { bundle } if matches!(bundle.bundle_body[0], BundleItem::Hash { .. })
+        ));
+        assert!(matches!(bundle.bundle_body[1], BundleItem::Tx { can_revert: true, .. }));
+    }
+
     #[test]
     fn can_deserialize_eth_call_resp() {
         let s = r#"{
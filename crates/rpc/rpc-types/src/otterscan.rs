@@ -1,9 +1,11 @@
 use crate::{Block, BlockTransactions, Rich, Transaction, TransactionReceipt};
 use alloy_primitives::{Address, Bytes, U256};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// Operation type enum for `InternalOperation` struct
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+///
+/// Serialized as its numeric discriminant, as expected by Otterscan.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum OperationType {
     /// Operation Transfer
     OpTransfer = 0,
@@ -15,24 +17,60 @@ pub enum OperationType {
     OpCreate2 = 3,
 }
 
+impl Serialize for OperationType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u8(*self as u8)
+    }
+}
+
+impl<'de> Deserialize<'de> for OperationType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match u8::deserialize(deserializer)? {
+            0 => Ok(OperationType::OpTransfer),
+            1 => Ok(OperationType::OpSelfDestruct),
+            2 => Ok(OperationType::OpCreate),
+            3 => Ok(OperationType::OpCreate2),
+            ty => Err(serde::de::Error::custom(format!("invalid operation type {ty}"))),
+        }
+    }
+}
+
 /// Custom struct for otterscan `getInternalOperations` RPC response
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct InternalOperation {
-    r#type: OperationType,
-    from: Address,
-    to: Address,
-    value: u128,
+    /// The type of the operation
+    pub r#type: OperationType,
+    /// The sender of the value or the contract that performs the operation
+    pub from: Address,
+    /// The recipient of the value or the created contract
+    pub to: Address,
+    /// The value that was transferred
+    pub value: U256,
 }
 
 /// Custom struct for otterscan `traceTransaction` RPC response
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct TraceEntry {
-    r#type: String,
-    depth: u32,
-    from: Address,
-    to: Address,
-    value: u128,
-    input: Bytes,
+    /// The type of the call, e.g. `CALL`, `DELEGATECALL`, `CREATE2` or `SELFDESTRUCT`
+    pub r#type: String,
+    /// The depth of the call, the top level call has depth 0
+    pub depth: u32,
+    /// The caller
+    pub from: Address,
+    /// The callee or the created contract
+    pub to: Address,
+    /// The value that was transferred, `None` for calls that can't transfer value
+    pub value: Option<U256>,
+    /// The calldata or the init code
+    pub input: Bytes,
+    /// The returned data or the deployed code
+    pub output: Bytes,
 }
 
 /// Internal issuance struct for `BlockDetails` struct
@@ -48,9 +86,11 @@ pub struct InternalIssuance {
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OtsBlock {
+    /// The block
     #[serde(flatten)]
-    block: Block,
-    transaction_count: usize,
+    pub block: Block,
+    /// The number of transactions in the block
+    pub transaction_count: usize,
 }
 
 /// Custom struct for otterscan `getBlockDetails` RPC response
@@ -66,16 +106,20 @@ pub struct BlockDetails {
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OtsTransactionReceipt {
+    /// The receipt
     #[serde(flatten)]
-    receipt: TransactionReceipt,
-    timestamp: u64,
+    pub receipt: TransactionReceipt,
+    /// The timestamp of the block the transaction was included in
+    pub timestamp: u64,
 }
 
 /// Custom struct for otterscan `getBlockTransactions` RPC response
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct OtsBlockTransactions {
-    fullblock: OtsBlock,
-    receipts: Vec<OtsTransactionReceipt>,
+    /// The block with only the transactions of the requested page
+    pub fullblock: OtsBlock,
+    /// The receipts of the transactions of the requested page
+    pub receipts: Vec<OtsTransactionReceipt>,
 }
 
 /// Custom struct for otterscan `searchTransactionsAfter`and `searchTransactionsBefore` RPC
@@ -83,10 +127,14 @@ pub struct OtsBlockTransactions {
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionsWithReceipts {
-    txs: Vec<Transaction>,
-    receipts: Vec<OtsTransactionReceipt>,
-    first_page: bool,
-    last_page: bool,
+    /// The matched transactions, most recent first
+    pub txs: Vec<Transaction>,
+    /// The receipts of the matched transactions
+    pub receipts: Vec<OtsTransactionReceipt>,
+    /// Whether this page contains the most recent matching transactions
+    pub first_page: bool,
+    /// Whether this page contains the oldest matching transactions
+    pub last_page: bool,
 }
 
 /// Custom struct for otterscan `getContractCreator` RPC responses
@@ -117,3 +165,19 @@ impl From<Rich<Block>> for BlockDetails {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_internal_operation() {
+        let s = r#"{"type":2,"from":"0x5f5d8ca40c7be2ae15cbcd2d4bae9e4b81c3f06f","to":"0x1d9c2d34fc5edfb1e5f1b0a8b6ef1d3a84d2b2a3","value":"0xde0b6b3a7640000"}"#;
+        let op: InternalOperation = serde_json::from_str(s).unwrap();
+        assert_eq!(op.r#type, OperationType::OpCreate);
+        assert_eq!(op.value, U256::from(1_000_000_000_000_000_000u64));
+        let serialized = serde_json::to_string(&op).unwrap();
+        assert!(serialized.contains(r#""type":2"#));
+        assert_eq!(serde_json::from_str::<InternalOperation>(&serialized).unwrap(), op);
+    }
+}
@@ -0,0 +1,272 @@
+use crate::{
+    eth::{
+        error::{EthApiError, EthResult, SignError},
+        revm_utils::transact,
+        EthTransactions,
+    },
+    EthApi,
+};
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use parking_lot::RwLock;
+use reth_auto_seal_consensus::{AccountStateChange, AutoSealError, AutoSealHandle};
+use reth_interfaces::RethError;
+use reth_network_api::NetworkInfo;
+use reth_primitives::{
+    revm::env::tx_env_with_recovered, Address, BlockId, BlockNumberOrTag, Bytes, Signature,
+    TransactionSigned, B256, U256, U64,
+};
+use reth_provider::{BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderFactory};
+use reth_revm::database::StateProviderDatabase;
+use reth_rpc_api::AnvilApiServer;
+use reth_rpc_types::TransactionRequest;
+use reth_rpc_types_compat::transaction::to_primitive_transaction;
+use reth_transaction_pool::TransactionPool;
+use revm::{db::CacheDB, primitives::Env};
+use std::{collections::HashSet, sync::Arc};
+use tracing::trace;
+
+/// The maximum number of blocks that can be mined with a single `anvil_mine` request.
+const MAX_MINE_BLOCKS: usize = 10_000;
+
+/// `anvil` API implementation.
+///
+/// This type provides the anvil compatible `evm_` and `anvil_` methods to control a dev chain that
+/// is sealed by the auto-seal consensus.
+pub struct AnvilApi<Provider, Pool, Network> {
+    inner: Arc<AnvilApiInner<Provider, Pool, Network>>,
+}
+
+// === impl AnvilApi ===
+
+impl<Provider, Pool, Network> AnvilApi<Provider, Pool, Network> {
+    /// Creates a new instance of `AnvilApi`.
+    pub fn new(eth_api: EthApi<Provider, Pool, Network>, handle: AutoSealHandle) -> Self {
+        let inner = AnvilApiInner { eth_api, handle, impersonated: Default::default() };
+        Self { inner: Arc::new(inner) }
+    }
+
+    /// Returns `true` if the account is impersonated.
+    fn is_impersonated(&self, address: &Address) -> bool {
+        self.inner.impersonated.read().contains(address)
+    }
+}
+
+impl<Provider, Pool, Network> AnvilApi<Provider, Pool, Network>
+where
+    Pool: TransactionPool + Clone + 'static,
+    Provider:
+        BlockReaderIdExt + ChainSpecProvider + StateProviderFactory + EvmEnvProvider + 'static,
+    Network: NetworkInfo + Send + Sync + 'static,
+{
+    /// Mines the given number of blocks, one by default and at most `MAX_MINE_BLOCKS`.
+    ///
+    /// If an interval is given, the timestamps of the blocks are spaced by the interval, starting
+    /// from the latest block.
+    pub async fn mine(&self, blocks: Option<U256>, interval: Option<U256>) -> EthResult<()> {
+        let count: usize = blocks.unwrap_or(U256::from(1)).saturating_to();
+        if count > MAX_MINE_BLOCKS {
+            return Err(EthApiError::InvalidParams(format!(
+                "cannot mine more than {MAX_MINE_BLOCKS} blocks at once"
+            )))
+        }
+        let timestamps = match interval {
+            Some(interval) => {
+                let interval: u64 = interval.saturating_to();
+                let latest = self
+                    .inner
+                    .eth_api
+                    .provider()
+                    .latest_header()?
+                    .ok_or(EthApiError::UnknownBlockNumber)?;
+                (1..=count as u64)
+                    .map(|i| latest.timestamp.saturating_add(interval.saturating_mul(i)))
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        self.inner.handle.mine_blocks(count, timestamps).await?;
+        Ok(())
+    }
+
+    /// Mines a transaction of an impersonated account in a new block, together with the ready
+    /// transactions of the pool.
+    ///
+    /// The transaction is signed with an empty signature and included with the impersonated
+    /// account as its sender. Returns the hash of the transaction, whose receipt is available
+    /// once the block is mined.
+    pub async fn send_unsigned_transaction(&self, request: TransactionRequest) -> EthResult<B256> {
+        let from = request.from.ok_or(SignError::NoAccount)?;
+        if !self.is_impersonated(&from) {
+            return Err(EthApiError::InvalidParams(format!("account {from} is not impersonated")))
+        }
+
+        let eth_api = &self.inner.eth_api;
+        let request = eth_api.fill_transaction_request(from, request).await?;
+        let transaction =
+            to_primitive_transaction(request).ok_or(EthApiError::ConflictingFeeFieldsInRequest)?;
+        let transaction =
+            TransactionSigned::from_transaction_and_signature(transaction, Signature::default())
+                .with_signer(from);
+        let hash = transaction.hash();
+
+        // check that the transaction is valid, so it does not fail the block it is mined in
+        let at = BlockId::Number(BlockNumberOrTag::Latest);
+        let (cfg, block, at) = eth_api.evm_env_at(at).await?;
+        let env = Env { cfg, block, tx: tx_env_with_recovered(&transaction) };
+        eth_api
+            .spawn_with_state_at_block(at, move |state| {
+                let mut db = CacheDB::new(StateProviderDatabase::new(state));
+                transact(&mut db, env)?;
+                Ok(())
+            })
+            .await?;
+
+        trace!(target: "rpc::anvil", ?hash, ?from, "Mining unsigned transaction");
+        self.inner.handle.mine_transactions(vec![transaction]).await?;
+
+        Ok(hash)
+    }
+
+    /// Handler for `eth_sendTransaction` on a dev chain.
+    ///
+    /// Transactions of impersonated accounts are mined right away, see
+    /// [Self::send_unsigned_transaction]. All other transactions are signed and submitted to the
+    /// pool.
+    pub async fn send_transaction(&self, request: TransactionRequest) -> EthResult<B256> {
+        match request.from {
+            Some(from) if self.is_impersonated(&from) => {
+                self.send_unsigned_transaction(request).await
+            }
+            _ => self.inner.eth_api.send_transaction(request).await,
+        }
+    }
+}
+
+#[async_trait]
+impl<Provider, Pool, Network> AnvilApiServer for AnvilApi<Provider, Pool, Network>
+where
+    Pool: TransactionPool + Clone + 'static,
+    Provider:
+        BlockReaderIdExt + ChainSpecProvider + StateProviderFactory + EvmEnvProvider + 'static,
+    Network: NetworkInfo + Send + Sync + 'static,
+{
+    /// Handler for `evm_mine`
+    async fn evm_mine(&self) -> RpcResult<String> {
+        self.inner.handle.mine().await.map_err(EthApiError::from)?;
+        Ok("0x0".to_string())
+    }
+
+    /// Handler for `anvil_mine`
+    async fn anvil_mine(&self, blocks: Option<U256>, interval: Option<U256>) -> RpcResult<()> {
+        Ok(self.mine(blocks, interval).await?)
+    }
+
+    /// Handler for `evm_increaseTime`
+    async fn evm_increase_time(&self, seconds: U256) -> RpcResult<i64> {
+        Ok(self
+            .inner
+            .handle
+            .increase_time(seconds.saturating_to())
+            .await
+            .map_err(EthApiError::from)?)
+    }
+
+    /// Handler for `evm_setNextBlockTimestamp`
+    async fn evm_set_next_block_timestamp(&self, timestamp: U64) -> RpcResult<()> {
+        Ok(self
+            .inner
+            .handle
+            .set_next_block_timestamp(timestamp.saturating_to())
+            .await
+            .map_err(EthApiError::from)?)
+    }
+
+    /// Handler for `evm_snapshot`
+    async fn evm_snapshot(&self) -> RpcResult<U256> {
+        Ok(self.inner.handle.snapshot().await.map_err(EthApiError::from)?)
+    }
+
+    /// Handler for `evm_revert`
+    async fn evm_revert(&self, id: U256) -> RpcResult<bool> {
+        Ok(self.inner.handle.revert(id).await.map_err(EthApiError::from)?)
+    }
+
+    /// Handler for `anvil_setBalance`
+    async fn anvil_set_balance(&self, address: Address, balance: U256) -> RpcResult<()> {
+        let change = AccountStateChange { address, balance: Some(balance), ..Default::default() };
+        Ok(self.inner.handle.modify_state(vec![change]).await.map_err(EthApiError::from)?)
+    }
+
+    /// Handler for `anvil_setCode`
+    async fn anvil_set_code(&self, address: Address, code: Bytes) -> RpcResult<()> {
+        let change = AccountStateChange { address, code: Some(code), ..Default::default() };
+        Ok(self.inner.handle.modify_state(vec![change]).await.map_err(EthApiError::from)?)
+    }
+
+    /// Handler for `anvil_setStorageAt`
+    async fn anvil_set_storage_at(
+        &self,
+        address: Address,
+        slot: U256,
+        value: B256,
+    ) -> RpcResult<bool> {
+        let change = AccountStateChange {
+            address,
+            storage: vec![(B256::from(slot), U256::from_be_bytes(value.0))],
+            ..Default::default()
+        };
+        self.inner.handle.modify_state(vec![change]).await.map_err(EthApiError::from)?;
+        Ok(true)
+    }
+
+    /// Handler for `anvil_impersonateAccount`
+    async fn anvil_impersonate_account(&self, address: Address) -> RpcResult<()> {
+        self.inner.impersonated.write().insert(address);
+        Ok(())
+    }
+
+    /// Handler for `anvil_stopImpersonatingAccount`
+    async fn anvil_stop_impersonating_account(&self, address: Address) -> RpcResult<()> {
+        self.inner.impersonated.write().remove(&address);
+        Ok(())
+    }
+
+    /// Handler for `eth_sendUnsignedTransaction`
+    async fn eth_send_unsigned_transaction(&self, request: TransactionRequest) -> RpcResult<B256> {
+        Ok(self.send_unsigned_transaction(request).await?)
+    }
+}
+
+impl<Provider, Pool, Network> std::fmt::Debug for AnvilApi<Provider, Pool, Network> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnvilApi").finish_non_exhaustive()
+    }
+}
+
+impl<Provider, Pool, Network> Clone for AnvilApi<Provider, Pool, Network> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+struct AnvilApiInner<Provider, Pool, Network> {
+    /// The eth api that is used to prepare and execute transactions.
+    eth_api: EthApi<Provider, Pool, Network>,
+    /// Handle to the mining task of the dev chain.
+    handle: AutoSealHandle,
+    /// Accounts that can send transactions without a signature.
+    impersonated: RwLock<HashSet<Address>>,
+}
+
+impl From<AutoSealError> for EthApiError {
+    fn from(err: AutoSealError) -> Self {
+        match err {
+            err @ AutoSealError::InvalidTimestamp { .. } => {
+                EthApiError::InvalidParams(err.to_string())
+            }
+            AutoSealError::Reth(err) => EthApiError::Internal(err),
+            err => EthApiError::Internal(RethError::Custom(err.to_string())),
+        }
+    }
+}
@@ -7,23 +7,37 @@ use crate::{
         },
         EthTransactions, TransactionSource,
     },
-    result::{internal_rpc_err, ToRpcResult},
+    profiling::{self, CpuProfiler},
+    result::{internal_rpc_err, rpc_error_with_code, ToRpcResult},
     BlockingTaskGuard, EthApiSpec,
 };
 use alloy_rlp::{Decodable, Encodable};
 use async_trait::async_trait;
-use jsonrpsee::core::RpcResult;
+use futures::{stream, Stream, StreamExt};
+use jsonrpsee::{
+    core::{RpcResult, SubscriptionResult},
+    server::SubscriptionMessage,
+    types::error::METHOD_NOT_FOUND_CODE,
+    PendingSubscriptionSink, SubscriptionSink,
+};
+use reth_interfaces::blockchain_tree::InvalidBlock;
 use reth_primitives::{
-    revm::env::tx_env_with_recovered,
+    keccak256,
+    revm::env::{fill_cfg_and_block_env, tx_env_with_recovered},
     revm_primitives::{
         db::{DatabaseCommit, DatabaseRef},
-        BlockEnv, CfgEnv,
+        BlockEnv, CfgEnv, SpecId,
     },
-    Address, Block, BlockId, BlockNumberOrTag, Bytes, TransactionSigned, B256,
+    Address, Block, BlockId, BlockNumber, BlockNumberOrTag, BlockWithSenders, Bytes, Receipts,
+    TransactionSigned, B256, U256,
+};
+use reth_provider::{
+    BlockIdReader, BlockNumReader, BlockReaderIdExt, BundleStateWithReceipts, ChainSpecProvider,
+    HeaderProvider, InvalidBlockReader, PlainStateReader, StateProviderBox, TransactionVariant,
 };
-use reth_provider::{BlockReaderIdExt, HeaderProvider, StateProviderBox, TransactionVariant};
 use reth_revm::{
     database::{StateProviderDatabase, SubState},
+    state_change::apply_beacon_root_contract_call,
     tracing::{
         js::{JsDbRequest, JsInspector},
         FourByteInspector, TracingInspector, TracingInspectorConfig,
@@ -32,19 +46,50 @@ use reth_revm::{
 use reth_rpc_api::DebugApiServer;
 use reth_rpc_types::{
     trace::geth::{
-        BlockTraceResult, FourByteFrame, GethDebugBuiltInTracerType, GethDebugTracerType,
-        GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace, NoopFrame, TraceResult,
+        BlockTraceResult, CallConfig, FlatCallConfig, FourByteFrame, GethDebugBuiltInTracerType,
+        GethDebugTracerType, GethDebugTracingCallOptions, GethDebugTracingOptions,
+        GethDefaultTracingOptions, GethTrace, MuxConfig, MuxFrame, NoopFrame, PreStateConfig,
+        TraceResult,
     },
-    BlockError, Bundle, CallRequest, RichBlock, StateContext,
+    AccountRangeResult, BadBlock, BlockError, BlockTransactionsKind, Bundle, CallRequest,
+    DumpAccount, MemStats, StateContext, TransactionInfo,
 };
+use reth_rpc_types_compat::block::{from_block, from_block_with_tx_hashes};
 use reth_tasks::TaskSpawner;
 use revm::{
-    db::{CacheDB, EmptyDB},
+    db::{states::bundle_state::BundleRetention, CacheDB, EmptyDB},
     primitives::Env,
+    State,
 };
-use std::sync::Arc;
-use tokio::sync::{mpsc, AcquireError, OwnedSemaphorePermit};
-use tokio_stream::{wrappers::ReceiverStream, StreamExt};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::{Bound, RangeInclusive},
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::{mpsc, oneshot, AcquireError, OwnedSemaphorePermit};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::debug;
+
+/// The maximum number of blocks that are traced concurrently by a `debug_traceChain` subscription.
+const MAX_CONCURRENT_TRACE_CHAIN_BLOCKS: usize = 8;
+
+/// The maximum number of blocks that are traced by a `debug_traceChain` subscription.
+const MAX_TRACE_CHAIN_BLOCKS: u64 = 100;
+
+/// The maximum number of accounts returned by a `debug_accountRange` request.
+const ACCOUNT_RANGE_MAX_RESULTS: u64 = 256;
+
+/// The number of plain state accounts that are read at once by a `debug_accountRange` request.
+const ACCOUNT_RANGE_PAGE_SIZE: usize = 256;
+
+/// The maximum number of blocks whose changesets are read by a single `debug_accountRange`,
+/// `debug_intermediateRoots` or `debug_getModifiedAccountsBy*` request.
+///
+/// The historical state of these requests is derived from the changesets between the requested
+/// block and the tip, so the distance to the tip is limited as well.
+const MAX_STATE_HISTORY_BLOCKS: u64 = 256;
 
 /// `debug` API implementation.
 ///
@@ -57,23 +102,95 @@ pub struct DebugApi<Provider, Eth> {
 
 impl<Provider, Eth> DebugApi<Provider, Eth> {
     /// Create a new instance of the [DebugApi]
+    ///
+    /// The profiling methods that write files are rejected, see [DebugApi::with_profiles_dir].
     pub fn new(
         provider: Provider,
         eth: Eth,
         task_spawner: Box<dyn TaskSpawner>,
         blocking_task_guard: BlockingTaskGuard,
     ) -> Self {
-        let inner =
-            Arc::new(DebugApiInner { provider, eth_api: eth, task_spawner, blocking_task_guard });
+        Self::with_profiles_dir(provider, eth, task_spawner, blocking_task_guard, None)
+    }
+
+    /// Create a new instance of the [DebugApi] that writes the profiles requested by
+    /// `debug_writeMemProfile`, `debug_cpuProfile` and `debug_startCPUProfile` to the given
+    /// directory.
+    ///
+    /// If no directory is given, these methods are rejected.
+    pub fn with_profiles_dir(
+        provider: Provider,
+        eth: Eth,
+        task_spawner: Box<dyn TaskSpawner>,
+        blocking_task_guard: BlockingTaskGuard,
+        profiles_dir: Option<PathBuf>,
+    ) -> Self {
+        let inner = Arc::new(DebugApiInner {
+            provider,
+            eth_api: eth,
+            task_spawner,
+            blocking_task_guard,
+            profiles_dir,
+            cpu_profiler: Default::default(),
+        });
         Self { inner }
     }
+
+    /// Executes the closure on a new task that is allowed to block.
+    async fn on_blocking_task<F, R>(&self, f: F) -> EthResult<R>
+    where
+        F: FnOnce(Self) -> EthResult<R> + Send + 'static,
+        R: Send + 'static,
+        Provider: Send + Sync + 'static,
+        Eth: Send + Sync + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        let this = self.clone();
+        self.inner.task_spawner.spawn_blocking(Box::pin(async move {
+            let _ = tx.send(f(this));
+        }));
+        rx.await.map_err(|_| EthApiError::InternalEthError)?
+    }
+
+    /// Returns the path in the profiles directory that a profile with the given file name is
+    /// written to.
+    fn profile_path(&self, file: &str) -> EthResult<PathBuf> {
+        profiling::profile_path(self.inner.profiles_dir.as_deref(), file)
+    }
+
+    /// Collects a CPU profile for the given number of seconds and writes it to the given file in
+    /// the profiles directory.
+    ///
+    /// The duration is limited to [MAX_CPU_PROFILE_DURATION](profiling::MAX_CPU_PROFILE_DURATION).
+    pub async fn cpu_profile(&self, file: &str, seconds: u64) -> EthResult<()>
+    where
+        Provider: Send + Sync + 'static,
+        Eth: Send + Sync + 'static,
+    {
+        let duration = Duration::from_secs(seconds);
+        if duration > profiling::MAX_CPU_PROFILE_DURATION {
+            return Err(EthApiError::InvalidParams(format!(
+                "CPU profiles are limited to {} seconds",
+                profiling::MAX_CPU_PROFILE_DURATION.as_secs()
+            )))
+        }
+
+        self.inner.cpu_profiler.start(self.profile_path(file)?)?;
+        tokio::time::sleep(duration).await;
+        self.on_blocking_task(|this| this.inner.cpu_profiler.stop()).await
+    }
 }
 
 // === impl DebugApi ===
 
 impl<Provider, Eth> DebugApi<Provider, Eth>
 where
-    Provider: BlockReaderIdExt + HeaderProvider + 'static,
+    Provider: BlockReaderIdExt
+        + HeaderProvider
+        + ChainSpecProvider
+        + InvalidBlockReader
+        + PlainStateReader
+        + 'static,
     Eth: EthTransactions + 'static,
 {
     /// Acquires a permit to execute a tracing call.
@@ -88,6 +205,7 @@ where
         transactions: Vec<TransactionSigned>,
         cfg: CfgEnv,
         block_env: BlockEnv,
+        block_hash: B256,
         opts: GethDebugTracingOptions,
     ) -> EthResult<Vec<TraceResult>> {
         // replay all transactions of the block
@@ -98,13 +216,20 @@ where
                 let mut results = Vec::with_capacity(transactions.len());
                 let mut db = CacheDB::new(StateProviderDatabase::new(state));
 
-                let mut transactions = transactions.into_iter().peekable();
-                while let Some(tx) = transactions.next() {
+                let mut transactions = transactions.into_iter().enumerate().peekable();
+                while let Some((index, tx)) = transactions.next() {
                     let tx = tx.into_ecrecovered().ok_or(BlockError::InvalidSignature)?;
+                    let tx_info = TransactionInfo {
+                        hash: Some(tx.hash()),
+                        index: Some(index as u64),
+                        block_hash: Some(block_hash),
+                        block_number: Some(block_env.number.saturating_to()),
+                        base_fee: Some(block_env.basefee.saturating_to()),
+                    };
                     let tx = tx_env_with_recovered(&tx);
                     let env = Env { cfg: cfg.clone(), block: block_env.clone(), tx };
                     let (result, state_changes) =
-                        this.trace_transaction(opts.clone(), env, at, &mut db)?;
+                        this.trace_transaction(opts.clone(), env, at, &mut db, tx_info)?;
                     results.push(TraceResult::Success { result });
 
                     if transactions.peek().is_some() {
@@ -119,6 +244,405 @@ where
             .await
     }
 
+    /// Resolves the `(start, end]` block range of a `debug_traceChain` request.
+    fn trace_chain_range(
+        &self,
+        start_exclusive: BlockNumberOrTag,
+        end_inclusive: BlockNumberOrTag,
+    ) -> EthResult<RangeInclusive<BlockNumber>> {
+        let start = self
+            .inner
+            .provider
+            .convert_block_number(start_exclusive)?
+            .ok_or(EthApiError::UnknownBlockNumber)?;
+        let end = self
+            .inner
+            .provider
+            .convert_block_number(end_inclusive)?
+            .ok_or(EthApiError::UnknownBlockNumber)?;
+        if start >= end {
+            return Err(EthApiError::InvalidParams(format!(
+                "end block (#{end}) needs to come after start block (#{start})"
+            )))
+        }
+        // every block holds a tracing permit while it's traced, so a single subscription must not
+        // be able to keep the tracing pool busy indefinitely
+        if end - start > MAX_TRACE_CHAIN_BLOCKS {
+            return Err(EthApiError::InvalidParams(format!(
+                "Block range too large; currently limited to {MAX_TRACE_CHAIN_BLOCKS} blocks"
+            )))
+        }
+        Ok(start + 1..=end)
+    }
+
+    /// Traces a single block of a `debug_traceChain` request.
+    async fn trace_chain_block(
+        &self,
+        number: BlockNumber,
+        opts: GethDebugTracingOptions,
+    ) -> EthResult<BlockTraceResult> {
+        let _permit = self.acquire_trace_permit().await;
+        let block = self
+            .inner
+            .eth_api
+            .block_by_id(number.into())
+            .await?
+            .ok_or(EthApiError::UnknownBlockNumber)?;
+        let (cfg, block_env, _) = self.inner.eth_api.evm_env_at(block.hash.into()).await?;
+        let hash = block.hash;
+
+        // we trace on top the block's parent block
+        let traces = self
+            .trace_block_with(block.parent_hash.into(), block.body, cfg, block_env, hash, opts)
+            .await?;
+
+        Ok(BlockTraceResult { block: U256::from(number), hash, traces })
+    }
+
+    /// Returns a stream that traces all blocks in the given range.
+    ///
+    /// Blocks are traced concurrently on the tracing pool, but the results are yielded in block
+    /// order. Blocks are only traced once there's capacity to buffer their results, so a slow
+    /// consumer holds back the tracing.
+    pub fn trace_chain_stream(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        opts: GethDebugTracingOptions,
+    ) -> impl Stream<Item = EthResult<BlockTraceResult>> + Send + 'static {
+        let this = self.clone();
+        stream::iter(range)
+            .map(move |number| {
+                let this = this.clone();
+                let opts = opts.clone();
+                async move { this.trace_chain_block(number, opts).await }
+            })
+            .buffered(MAX_CONCURRENT_TRACE_CHAIN_BLOCKS)
+    }
+
+    /// Returns the most recent blocks that were rejected because they failed validation or
+    /// execution, ordered from oldest to newest.
+    pub fn bad_blocks(&self) -> EthResult<Vec<BadBlock>> {
+        let invalid_blocks = self.inner.provider.recent_invalid_blocks()?;
+        let mut bad_blocks = Vec::with_capacity(invalid_blocks.len());
+        for InvalidBlock { block, error } in invalid_blocks {
+            let hash = block.hash;
+            let total_difficulty = self
+                .inner
+                .provider
+                .header_td(&block.parent_hash)?
+                .map(|td| td + block.difficulty)
+                .unwrap_or_default();
+
+            let mut rlp = Vec::new();
+            block.clone().unseal().encode(&mut rlp);
+
+            // the block may have been rejected because of invalid signatures, in which case we
+            // can only return the transaction hashes
+            let rpc_block = match block.try_seal_with_senders() {
+                Ok(block) => from_block(
+                    block.unseal(),
+                    total_difficulty,
+                    BlockTransactionsKind::Full,
+                    Some(hash),
+                )?,
+                Err(block) => from_block_with_tx_hashes(
+                    BlockWithSenders { block: block.unseal(), senders: Vec::new() },
+                    total_difficulty,
+                    Some(hash),
+                ),
+            };
+
+            bad_blocks.push(BadBlock {
+                hash,
+                block: rpc_block.into(),
+                rlp: rlp.into(),
+                reason: error,
+            });
+        }
+        Ok(bad_blocks)
+    }
+
+    /// Returns an error if the state at the given block is too far behind the tip to be derived
+    /// from the changesets, see [MAX_STATE_HISTORY_BLOCKS].
+    fn ensure_state_history_available(&self, block_number: BlockNumber) -> EthResult<()> {
+        let best_number = self.inner.provider.best_block_number()?;
+        if best_number.saturating_sub(block_number) > MAX_STATE_HISTORY_BLOCKS {
+            return Err(EthApiError::InvalidParams(format!(
+                "block #{block_number} is more than {MAX_STATE_HISTORY_BLOCKS} blocks behind the \
+                 tip (#{best_number})"
+            )))
+        }
+        Ok(())
+    }
+
+    /// Returns all addresses whose account or storage was modified in the given block range.
+    ///
+    /// The range may span at most [MAX_STATE_HISTORY_BLOCKS] blocks.
+    pub async fn modified_accounts(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> EthResult<Vec<Address>> {
+        if range.end().saturating_sub(*range.start()) >= MAX_STATE_HISTORY_BLOCKS {
+            return Err(EthApiError::InvalidParams(format!(
+                "block range exceeds {MAX_STATE_HISTORY_BLOCKS} blocks"
+            )))
+        }
+
+        self.on_blocking_task(move |this| {
+            let provider = &this.inner.provider;
+            for number in [*range.start(), *range.end()] {
+                if provider.header_by_number(number)?.is_none() {
+                    return Err(EthApiError::UnknownBlockNumber)
+                }
+            }
+            Ok(provider.changed_state_with_range(range)?.into_keys().collect())
+        })
+        .await
+    }
+
+    /// Resolves the block range of a `debug_getModifiedAccountsBy*` request.
+    ///
+    /// Without an end block, this is the start block itself. Otherwise the range is `(start,
+    /// end]`.
+    fn modified_accounts_range(
+        start: BlockNumber,
+        end: Option<BlockNumber>,
+    ) -> EthResult<RangeInclusive<BlockNumber>> {
+        match end {
+            None => Ok(start..=start),
+            Some(end) if end <= start => Err(EthApiError::InvalidParams(format!(
+                "end block (#{end}) needs to come after start block (#{start})"
+            ))),
+            Some(end) => Ok(start + 1..=end),
+        }
+    }
+
+    /// Returns a page of the state at the end of the given block, starting at the account with
+    /// the given address (prefix).
+    ///
+    /// The plain state only reflects the latest block, so for historical blocks the accounts that
+    /// were changed since then are merged in and all values are read from the historical state.
+    /// The block may be at most [MAX_STATE_HISTORY_BLOCKS] blocks behind the tip.
+    pub async fn account_range(
+        &self,
+        block_number: BlockNumberOrTag,
+        start: Bytes,
+        max_results: u64,
+        nocode: bool,
+        nostorage: bool,
+    ) -> EthResult<AccountRangeResult> {
+        let mut start_address = Address::ZERO;
+        if start.len() > start_address.len() {
+            return Err(EthApiError::InvalidParams(format!(
+                "start key exceeds {} bytes",
+                start_address.len()
+            )))
+        }
+        start_address[..start.len()].copy_from_slice(&start);
+
+        let max_results = if max_results == 0 || max_results > ACCOUNT_RANGE_MAX_RESULTS {
+            ACCOUNT_RANGE_MAX_RESULTS
+        } else {
+            max_results
+        } as usize;
+
+        let number = self
+            .inner
+            .provider
+            .convert_block_number(block_number)?
+            .ok_or(EthApiError::UnknownBlockNumber)?;
+        let header =
+            self.inner.provider.sealed_header(number)?.ok_or(EthApiError::UnknownBlockNumber)?;
+        self.ensure_state_history_available(number)?;
+
+        let _permit = self.acquire_trace_permit().await;
+        let this = self.clone();
+        self.inner
+            .eth_api
+            .spawn_with_state_at_block(header.hash.into(), move |state| {
+                let provider = &this.inner.provider;
+                let best_number = provider.best_block_number()?;
+                // the storage roots below are proven against the historical state, which reverts
+                // the changesets up to the tip only once for the whole request
+                let changed = if number < best_number {
+                    provider.changed_state_with_range(number + 1..=best_number)?
+                } else {
+                    BTreeMap::new()
+                };
+
+                let mut accounts = BTreeMap::new();
+                let mut next = None;
+                let mut from = start_address;
+                'pages: loop {
+                    let mut page =
+                        provider.plain_accounts_from(from, ACCOUNT_RANGE_PAGE_SIZE + 1)?;
+                    // the first address of the next page, if any
+                    let upper = (page.len() > ACCOUNT_RANGE_PAGE_SIZE)
+                        .then(|| page.pop().map(|(address, _)| address))
+                        .flatten();
+
+                    let mut candidates =
+                        page.into_iter().map(|(address, _)| address).collect::<BTreeSet<_>>();
+                    let upper_bound = upper.map_or(Bound::Unbounded, Bound::Excluded);
+                    candidates.extend(
+                        changed.range((Bound::Included(from), upper_bound)).map(|(a, _)| *a),
+                    );
+
+                    for address in candidates {
+                        // the account may not have existed at the requested block
+                        let Some(account) = state.basic_account(address)? else { continue };
+                        if accounts.len() == max_results {
+                            next = Some(Bytes::copy_from_slice(address.as_slice()));
+                            break 'pages
+                        }
+
+                        let code = if nocode {
+                            None
+                        } else {
+                            Some(
+                                state
+                                    .account_code(address)?
+                                    .map(|code| code.original_bytes())
+                                    .unwrap_or_default(),
+                            )
+                        };
+
+                        let storage = if nostorage {
+                            None
+                        } else {
+                            let mut keys = provider
+                                .plain_account_storage(address)?
+                                .into_iter()
+                                .map(|entry| entry.key)
+                                .collect::<BTreeSet<_>>();
+                            keys.extend(changed.get(&address).into_iter().flatten().copied());
+
+                            let mut storage = BTreeMap::new();
+                            for key in keys {
+                                let value = state.storage(address, key)?.unwrap_or_default();
+                                if !value.is_zero() {
+                                    storage.insert(key, value);
+                                }
+                            }
+                            Some(storage)
+                        };
+
+                        accounts.insert(
+                            address,
+                            DumpAccount {
+                                balance: account.balance.to_string(),
+                                nonce: account.nonce,
+                                root: state.proof(address, &[])?.storage_root,
+                                code_hash: account.get_bytecode_hash(),
+                                code,
+                                storage,
+                                address: Some(address),
+                                address_hash: keccak256(address),
+                            },
+                        );
+                    }
+
+                    match upper {
+                        Some(upper) => from = upper,
+                        None => break,
+                    }
+                }
+
+                Ok(AccountRangeResult { root: header.state_root, accounts, next })
+            })
+            .await
+    }
+
+    /// Executes the block with the given hash on top of its parent and returns the state root
+    /// after each transaction.
+    ///
+    /// The block can be canonical, part of a side chain or a recently rejected block, and its
+    /// parent may be at most [MAX_STATE_HISTORY_BLOCKS] blocks behind the tip.
+    pub async fn intermediate_roots(&self, block_hash: B256) -> EthResult<Vec<B256>> {
+        let block = match self.inner.eth_api.block_by_id(block_hash.into()).await? {
+            Some(block) => block,
+            None => self
+                .inner
+                .provider
+                .recent_invalid_blocks()?
+                .into_iter()
+                .find(|invalid| invalid.block.hash == block_hash)
+                .map(|invalid| invalid.block)
+                .ok_or(EthApiError::UnknownBlockNumber)?,
+        };
+        self.ensure_state_history_available(block.number.saturating_sub(1))?;
+
+        let chain_spec = self.inner.provider.chain_spec();
+        let total_difficulty = self
+            .inner
+            .provider
+            .header_td(&block.parent_hash)?
+            .ok_or(EthApiError::UnknownBlockNumber)? +
+            block.difficulty;
+        let mut cfg = CfgEnv::default();
+        let mut block_env = BlockEnv::default();
+        fill_cfg_and_block_env(
+            &mut cfg,
+            &mut block_env,
+            &chain_spec,
+            &block.header,
+            total_difficulty,
+        );
+
+        let _permit = self.acquire_trace_permit().await;
+        self.inner
+            .eth_api
+            .spawn_with_state_at_block(block.parent_hash.into(), move |state| {
+                let mut db = State::builder()
+                    .with_database(StateProviderDatabase::new(&state))
+                    .with_bundle_update()
+                    .build();
+                db.set_state_clear_flag(cfg.spec_id >= SpecId::SPURIOUS_DRAGON);
+
+                // apply the pre-block EIP-4788 contract call
+                let mut evm = revm::EVM::with_env(Env {
+                    cfg: cfg.clone(),
+                    block: block_env.clone(),
+                    ..Default::default()
+                });
+                evm.database(&mut db);
+                apply_beacon_root_contract_call(
+                    &chain_spec,
+                    block.timestamp,
+                    block.number,
+                    block.parent_beacon_block_root,
+                    &mut evm,
+                )
+                .map_err(|err| EthApiError::Internal(err.into()))?;
+
+                let block_number = block.number;
+                let mut roots = Vec::with_capacity(block.body.len());
+                for tx in block.body {
+                    let tx = tx.into_ecrecovered().ok_or(BlockError::InvalidSignature)?;
+                    let env = Env {
+                        cfg: cfg.clone(),
+                        block: block_env.clone(),
+                        tx: tx_env_with_recovered(&tx),
+                    };
+                    let (res, _) = transact(&mut db, env)?;
+                    db.commit(res.state);
+                    db.merge_transitions(BundleRetention::PlainState);
+
+                    // temporarily move the bundle out of the database to avoid cloning it
+                    let bundle_state = BundleStateWithReceipts::new(
+                        std::mem::take(&mut db.bundle_state),
+                        Receipts::new(),
+                        block_number,
+                    );
+                    roots.push(state.state_root(&bundle_state)?);
+                    db.bundle_state = bundle_state.into_state();
+                }
+
+                Ok(roots)
+            })
+            .await
+    }
+
     /// Replays the given block and returns the trace of each transaction.
     ///
     /// This expects a rlp encoded block
@@ -136,7 +660,8 @@ where
 
         // we trace on top the block's parent block
         let parent = block.parent_hash;
-        self.trace_block_with(parent.into(), block.body, cfg, block_env, opts).await
+        let block_hash = block.header.hash_slow();
+        self.trace_block_with(parent.into(), block.body, cfg, block_env, block_hash, opts).await
     }
 
     /// Replays a block and returns the trace of each transaction.
@@ -161,7 +686,7 @@ where
         // its parent block's state
         let state_at = block.parent_hash;
 
-        self.trace_block_with(state_at.into(), block.body, cfg, block_env, opts).await
+        self.trace_block_with(state_at.into(), block.body, cfg, block_env, block.hash, opts).await
     }
 
     /// Trace the transaction according to the provided options.
@@ -188,7 +713,7 @@ where
             .eth_api
             .spawn_with_state_at_block(state_at, move |state| {
                 // configure env for the target transaction
-                let tx = transaction.into_recovered();
+                let (tx, tx_info) = transaction.split();
 
                 let mut db = CacheDB::new(StateProviderDatabase::new(state));
                 // replay all transactions prior to the targeted transaction
@@ -201,7 +726,8 @@ where
                 )?;
 
                 let env = Env { cfg, block: block_env, tx: tx_env_with_recovered(&tx) };
-                this.trace_transaction(opts, env, state_at, &mut db).map(|(trace, _)| trace)
+                this.trace_transaction(opts, env, state_at, &mut db, tx_info)
+                    .map(|(trace, _)| trace)
             })
             .await
     }
@@ -284,6 +810,24 @@ where
                         return Ok(frame.into())
                     }
                     GethDebugBuiltInTracerType::NoopTracer => Ok(NoopFrame::default().into()),
+                    tracer @ (GethDebugBuiltInTracerType::FlatCallTracer |
+                    GethDebugBuiltInTracerType::MuxTracer) => {
+                        let opts = GethDebugTracingOptions {
+                            config,
+                            tracer: Some(tracer.into()),
+                            tracer_config,
+                            timeout: None,
+                        };
+                        let this = self.clone();
+                        let (frame, _) = self
+                            .inner
+                            .eth_api
+                            .spawn_with_call_at(call, at, overrides, move |mut db, env| {
+                                this.trace_transaction(opts, env, at, &mut db, Default::default())
+                            })
+                            .await?;
+                        Ok(frame)
+                    }
                 },
                 GethDebugTracerType::JsTracer(code) => {
                     let config = tracer_config.into_json();
@@ -429,6 +973,7 @@ where
                             env,
                             target_block,
                             &mut db,
+                            Default::default(),
                         )?;
 
                         // If there is more transactions, commit the database
@@ -450,6 +995,8 @@ where
     ///
     /// Returns the trace frame and the state that got updated after executing the transaction.
     ///
+    /// The transaction info is only used to localize the traces of the `flatCallTracer`.
+    ///
     /// Note: this does not apply any state overrides if they're configured in the `opts`.
     ///
     /// Caution: this is blocking and should be performed on a blocking task.
@@ -459,6 +1006,7 @@ where
         env: Env,
         at: BlockId,
         db: &mut SubState<StateProviderBox>,
+        tx_info: TransactionInfo,
     ) -> EthResult<(GethTrace, revm_primitives::State)> {
         let GethDebugTracingOptions { config, tracer, tracer_config, .. } = opts;
 
@@ -509,6 +1057,31 @@ where
 
                         return Ok((frame.into(), res.state))
                     }
+                    GethDebugBuiltInTracerType::FlatCallTracer => {
+                        let flat_call_config = tracer_config
+                            .into_flat_call_config()
+                            .map_err(|_| EthApiError::InvalidTracerConfig)?;
+                        ensure_flat_call_config(&flat_call_config)?;
+
+                        let mut inspector =
+                            TracingInspector::new(TracingInspectorConfig::default_parity());
+                        let (res, _) = inspect(db, env, &mut inspector)?;
+
+                        let frame = inspector
+                            .with_transaction_gas_used(res.result.gas_used())
+                            .into_parity_builder()
+                            .into_localized_transaction_traces(tx_info);
+
+                        return Ok((frame.into(), res.state))
+                    }
+                    GethDebugBuiltInTracerType::MuxTracer => {
+                        let mux_config = tracer_config
+                            .into_mux_config()
+                            .map_err(|_| EthApiError::InvalidTracerConfig)?;
+
+                        let (frame, state) = trace_mux(&config, mux_config, env, db, tx_info)?;
+                        return Ok((frame.into(), state))
+                    }
                     GethDebugBuiltInTracerType::NoopTracer => {
                         Ok((NoopFrame::default().into(), Default::default()))
                     }
@@ -632,7 +1205,12 @@ where
 #[async_trait]
 impl<Provider, Eth> DebugApiServer for DebugApi<Provider, Eth>
 where
-    Provider: BlockReaderIdExt + HeaderProvider + 'static,
+    Provider: BlockReaderIdExt
+        + HeaderProvider
+        + ChainSpecProvider
+        + InvalidBlockReader
+        + PlainStateReader
+        + 'static,
     Eth: EthApiSpec + 'static,
 {
     /// Handler for `debug_getRawHeader`
@@ -659,63 +1237,66 @@ where
     }
 
     async fn debug_backtrace_at(&self, _location: &str) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
+    /// Handler for `debug_accountRange`
     async fn debug_account_range(
         &self,
-        _block_number: BlockNumberOrTag,
-        _start: Bytes,
-        _max_results: u64,
-        _nocode: bool,
-        _nostorage: bool,
+        block_number: BlockNumberOrTag,
+        start: Bytes,
+        max_results: u64,
+        nocode: bool,
+        nostorage: bool,
         _incompletes: bool,
-    ) -> RpcResult<()> {
-        Ok(())
+    ) -> RpcResult<AccountRangeResult> {
+        Ok(DebugApi::account_range(self, block_number, start, max_results, nocode, nostorage)
+            .await?)
     }
 
     async fn debug_block_profile(&self, _file: String, _seconds: u64) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_chaindb_compact(&self) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_chaindb_property(&self, _property: String) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
-    async fn debug_cpu_profile(&self, _file: String, _seconds: u64) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_cpuProfile`
+    async fn debug_cpu_profile(&self, file: String, seconds: u64) -> RpcResult<()> {
+        Ok(DebugApi::cpu_profile(self, &file, seconds).await?)
     }
 
     async fn debug_db_ancient(&self, _kind: String, _number: u64) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_db_ancients(&self) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_db_get(&self, _key: String) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_dump_block(&self, _number: BlockId) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_free_os_memory(&self) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_freeze_client(&self, _node: String) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_gc_stats(&self) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_get_accessible_state(
@@ -723,79 +1304,90 @@ where
         _from: BlockNumberOrTag,
         _to: BlockNumberOrTag,
     ) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
+    /// Handler for `debug_getModifiedAccountsByHash`
     async fn debug_get_modified_accounts_by_hash(
         &self,
-        _start_hash: B256,
-        _end_hash: B256,
-    ) -> RpcResult<()> {
-        Ok(())
+        start_hash: B256,
+        end_hash: Option<B256>,
+    ) -> RpcResult<Vec<Address>> {
+        let block_number = |hash| -> EthResult<BlockNumber> {
+            self.inner.provider.block_number(hash)?.ok_or(EthApiError::UnknownBlockNumber)
+        };
+        let start = block_number(start_hash)?;
+        let end = end_hash.map(block_number).transpose()?;
+        let range = Self::modified_accounts_range(start, end)?;
+        Ok(DebugApi::modified_accounts(self, range).await?)
     }
 
+    /// Handler for `debug_getModifiedAccountsByNumber`
     async fn debug_get_modified_accounts_by_number(
         &self,
-        _start_number: u64,
-        _end_number: u64,
-    ) -> RpcResult<()> {
-        Ok(())
+        start_number: u64,
+        end_number: Option<u64>,
+    ) -> RpcResult<Vec<Address>> {
+        let range = Self::modified_accounts_range(start_number, end_number)?;
+        Ok(DebugApi::modified_accounts(self, range).await?)
     }
 
     async fn debug_go_trace(&self, _file: String, _seconds: u64) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
+    /// Handler for `debug_intermediateRoots`
     async fn debug_intermediate_roots(
         &self,
-        _block_hash: B256,
+        block_hash: B256,
         _opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()> {
-        Ok(())
+    ) -> RpcResult<Vec<B256>> {
+        Ok(DebugApi::intermediate_roots(self, block_hash).await?)
     }
 
-    async fn debug_mem_stats(&self) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_memStats`
+    async fn debug_mem_stats(&self) -> RpcResult<MemStats> {
+        Ok(profiling::mem_stats()?)
     }
 
     async fn debug_mutex_profile(&self, _file: String, _nsec: u64) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_preimage(&self, _hash: B256) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_print_block(&self, _number: u64) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_seed_hash(&self, _number: u64) -> RpcResult<B256> {
-        Ok(Default::default())
+        method_not_supported()
     }
 
     async fn debug_set_block_profile_rate(&self, _rate: u64) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_set_gc_percent(&self, _v: i32) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_set_head(&self, _number: u64) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_set_mutex_profile_fraction(&self, _rate: i32) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_set_trie_flush_interval(&self, _interval: String) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_stacks(&self) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_standard_trace_bad_block_to_file(
@@ -803,7 +1395,7 @@ where
         _block: BlockNumberOrTag,
         _opts: Option<GethDebugTracingCallOptions>,
     ) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_standard_trace_block_to_file(
@@ -811,23 +1403,25 @@ where
         _block: BlockNumberOrTag,
         _opts: Option<GethDebugTracingCallOptions>,
     ) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
-    async fn debug_start_cpu_profile(&self, _file: String) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_startCPUProfile`
+    async fn debug_start_cpu_profile(&self, file: String) -> RpcResult<()> {
+        Ok(self.inner.cpu_profiler.start(self.profile_path(&file)?)?)
     }
 
     async fn debug_start_go_trace(&self, _file: String) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
+    /// Handler for `debug_stopCPUProfile`
     async fn debug_stop_cpu_profile(&self) -> RpcResult<()> {
-        Ok(())
+        Ok(self.on_blocking_task(|this| this.inner.cpu_profiler.stop()).await?)
     }
 
     async fn debug_stop_go_trace(&self) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_storage_range_at(
@@ -838,7 +1432,7 @@ where
         _key_start: B256,
         _max_result: u64,
     ) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_trace_bad_block(
@@ -846,27 +1440,29 @@ where
         _block_hash: B256,
         _opts: Option<GethDebugTracingCallOptions>,
     ) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_verbosity(&self, _level: usize) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_vmodule(&self, _pattern: String) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     async fn debug_write_block_profile(&self, _file: String) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
-    async fn debug_write_mem_profile(&self, _file: String) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_writeMemProfile`
+    async fn debug_write_mem_profile(&self, file: String) -> RpcResult<()> {
+        let file = self.profile_path(&file)?;
+        Ok(self.on_blocking_task(move |_| profiling::write_mem_profile(file)).await?)
     }
 
     async fn debug_write_mutex_profile(&self, _file: String) -> RpcResult<()> {
-        Ok(())
+        method_not_supported()
     }
 
     /// Handler for `debug_getRawBlock`
@@ -924,17 +1520,32 @@ where
     }
 
     /// Handler for `debug_getBadBlocks`
-    async fn bad_blocks(&self) -> RpcResult<Vec<RichBlock>> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn bad_blocks(&self) -> RpcResult<Vec<BadBlock>> {
+        Ok(DebugApi::bad_blocks(self)?)
     }
 
     /// Handler for `debug_traceChain`
     async fn debug_trace_chain(
         &self,
-        _start_exclusive: BlockNumberOrTag,
-        _end_inclusive: BlockNumberOrTag,
-    ) -> RpcResult<Vec<BlockTraceResult>> {
-        Err(internal_rpc_err("unimplemented"))
+        pending: PendingSubscriptionSink,
+        start_exclusive: BlockNumberOrTag,
+        end_inclusive: BlockNumberOrTag,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> SubscriptionResult {
+        let range = match self.trace_chain_range(start_exclusive, end_inclusive) {
+            Ok(range) => range,
+            Err(err) => {
+                pending.reject(err).await;
+                return Ok(())
+            }
+        };
+
+        let sink = pending.accept().await?;
+        let stream = self.trace_chain_stream(range, opts.unwrap_or_default());
+
+        // the subscription future is driven by the server, an error returned after the
+        // subscription was accepted is sent to the subscriber as the final notification
+        pipe_trace_chain(sink, stream).await
     }
 
     /// Handler for `debug_traceBlock`
@@ -1006,6 +1617,150 @@ impl<Provider, Eth> std::fmt::Debug for DebugApi<Provider, Eth> {
     }
 }
 
+/// Forwards the traced blocks of a `debug_traceChain` subscription to the sink.
+///
+/// This stops if the subscription was dropped or tracing a block failed, in which case the error
+/// is returned so that it is sent to the subscriber before the subscription is closed.
+async fn pipe_trace_chain<St>(sink: SubscriptionSink, stream: St) -> SubscriptionResult
+where
+    St: Stream<Item = EthResult<BlockTraceResult>>,
+{
+    futures::pin_mut!(stream);
+    loop {
+        tokio::select! {
+            _ = sink.closed() => {
+                // connection dropped
+                break Ok(())
+            },
+            maybe_item = stream.next() => {
+                let item = match maybe_item {
+                    Some(Ok(item)) => item,
+                    Some(Err(err)) => {
+                        debug!(target: "rpc::debug", %err, "Failed to trace block, ending traceChain subscription");
+                        break Err(err.to_string().into())
+                    }
+                    None => {
+                        // all blocks traced
+                        break Ok(())
+                    },
+                };
+                let msg = SubscriptionMessage::from_json(&item)?;
+                if sink.send(msg).await.is_err() {
+                    break Ok(())
+                }
+            }
+        }
+    }
+}
+
+/// Ensures the given `flatCallTracer` config can be served.
+///
+/// Flat call traces are built by the [ParityTraceBuilder](reth_revm::tracing::ParityTraceBuilder),
+/// which never includes calls to precompiles.
+fn ensure_flat_call_config(config: &FlatCallConfig) -> EthResult<()> {
+    if config.include_precompiles.unwrap_or_default() {
+        return Err(EthApiError::InvalidParams(
+            "flatCallTracer does not support includePrecompiles".to_string(),
+        ))
+    }
+    Ok(())
+}
+
+/// A built-in tracer of a `muxTracer` request with its parsed config.
+enum MuxTracerKind {
+    FourByte,
+    Call(CallConfig),
+    FlatCall,
+    PreState(PreStateConfig),
+    Noop,
+}
+
+/// Runs all tracers of the `muxTracer` config against a single execution of the transaction.
+///
+/// The [TracingInspector] records everything the configured tracers need, and every tracer result
+/// is then built from the same recorded traces.
+fn trace_mux(
+    config: &GethDefaultTracingOptions,
+    mux_config: MuxConfig,
+    env: Env,
+    db: &mut SubState<StateProviderBox>,
+    tx_info: TransactionInfo,
+) -> EthResult<(MuxFrame, revm_primitives::State)> {
+    let mut inspector_config = TracingInspectorConfig::from_geth_config(config);
+    let mut tracers = Vec::with_capacity(mux_config.0.len());
+    for (tracer, tracer_config) in mux_config.0 {
+        let tracer_config = tracer_config.unwrap_or_default();
+        let kind = match tracer {
+            GethDebugBuiltInTracerType::FourByteTracer => MuxTracerKind::FourByte,
+            GethDebugBuiltInTracerType::CallTracer => {
+                let call_config = tracer_config
+                    .into_call_config()
+                    .map_err(|_| EthApiError::InvalidTracerConfig)?;
+                if call_config.with_log.unwrap_or_default() {
+                    inspector_config = inspector_config.set_record_logs(true);
+                }
+                MuxTracerKind::Call(call_config)
+            }
+            GethDebugBuiltInTracerType::FlatCallTracer => {
+                let flat_call_config = tracer_config
+                    .into_flat_call_config()
+                    .map_err(|_| EthApiError::InvalidTracerConfig)?;
+                ensure_flat_call_config(&flat_call_config)?;
+                MuxTracerKind::FlatCall
+            }
+            GethDebugBuiltInTracerType::PreStateTracer => {
+                let prestate_config = tracer_config
+                    .into_pre_state_config()
+                    .map_err(|_| EthApiError::InvalidTracerConfig)?;
+                if prestate_config.is_default_mode() {
+                    inspector_config = inspector_config.set_steps_and_state_diffs(true);
+                }
+                MuxTracerKind::PreState(prestate_config)
+            }
+            GethDebugBuiltInTracerType::NoopTracer => MuxTracerKind::Noop,
+            GethDebugBuiltInTracerType::MuxTracer => return Err(EthApiError::InvalidTracerConfig),
+        };
+        tracers.push((tracer, kind));
+    }
+
+    let mut inspector = TracingInspector::new(inspector_config);
+    let (res, _) = inspect(&mut *db, env, &mut inspector)?;
+    let gas_used = res.result.gas_used();
+
+    let parity_builder = tracers
+        .iter()
+        .any(|(_, kind)| matches!(kind, MuxTracerKind::FlatCall))
+        .then(|| inspector.clone().with_transaction_gas_used(gas_used).into_parity_builder());
+    let geth_builder = inspector.into_geth_builder();
+
+    let mut frame = MuxFrame::default();
+    for (tracer, kind) in tracers {
+        let trace = match kind {
+            MuxTracerKind::FourByte => geth_builder.geth_four_byte_traces().into(),
+            MuxTracerKind::Call(call_config) => {
+                geth_builder.geth_call_traces(call_config, gas_used).into()
+            }
+            MuxTracerKind::FlatCall => parity_builder
+                .clone()
+                .expect("exists if a flat call tracer is configured")
+                .into_localized_transaction_traces(tx_info)
+                .into(),
+            MuxTracerKind::PreState(prestate_config) => {
+                geth_builder.geth_prestate_traces(&res, prestate_config, &*db)?.into()
+            }
+            MuxTracerKind::Noop => NoopFrame::default().into(),
+        };
+        frame.0.insert(tracer, trace);
+    }
+
+    Ok((frame, res.state))
+}
+
+/// Returns the error for `debug` methods that are not supported.
+fn method_not_supported<T>() -> RpcResult<T> {
+    Err(rpc_error_with_code(METHOD_NOT_FOUND_CODE, "method not supported"))
+}
+
 impl<Provider, Eth> Clone for DebugApi<Provider, Eth> {
     fn clone(&self) -> Self {
         Self { inner: Arc::clone(&self.inner) }
@@ -1021,4 +1776,8 @@ struct DebugApiInner<Provider, Eth> {
     blocking_task_guard: BlockingTaskGuard,
     /// The type that can spawn tasks which would otherwise block.
     task_spawner: Box<dyn TaskSpawner>,
+    /// The directory profiles are written to, if profiles can be written.
+    profiles_dir: Option<PathBuf>,
+    /// The CPU profile started by `debug_startCPUProfile`, if any.
+    cpu_profiler: CpuProfiler,
 }
@@ -90,6 +90,7 @@ where
                 .zip(receipts.clone())
                 .enumerate()
                 .map(|(idx, (tx, receipt))| {
+                    let signer = self.mined_transaction_signer(&tx)?;
                     let meta = TransactionMeta {
                         tx_hash: tx.hash,
                         index: idx as u64,
@@ -104,7 +105,7 @@ where
                         self.build_op_tx_meta(&tx, l1_block_info.clone(), block_timestamp)?;
 
                     build_transaction_receipt_with_block_receipts(
-                        tx,
+                        tx.with_signer(signer),
                         meta,
                         receipt,
                         &receipts,
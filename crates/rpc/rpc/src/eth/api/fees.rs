@@ -41,6 +41,19 @@ where
             .map(U256::from)
     }
 
+    /// Returns a suggestion for the max fee per gas of EIP-1559 transactions with the given
+    /// priority fee.
+    ///
+    /// This leaves room for the base fee to double before the transaction becomes unexecutable.
+    pub(crate) async fn suggested_max_fee_per_gas(
+        &self,
+        max_priority_fee_per_gas: U256,
+    ) -> EthResult<U256> {
+        let header = self.block(BlockNumberOrTag::Latest).await?;
+        let base_fee = header.and_then(|h| h.base_fee_per_gas).unwrap_or_default();
+        Ok(U256::from(base_fee) * U256::from(2) + max_priority_fee_per_gas)
+    }
+
     /// Returns a suggestion for the priority fee (the tip)
     pub(crate) async fn suggested_priority_fee(&self) -> EthResult<U256> {
         self.gas_oracle().suggest_tip_cap().await
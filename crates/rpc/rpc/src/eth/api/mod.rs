@@ -44,6 +44,7 @@ mod optimism;
 mod pending_block;
 mod server;
 mod sign;
+mod simulate;
 mod state;
 mod transactions;
 
@@ -100,6 +101,7 @@ where
         eth_cache: EthStateCache,
         gas_oracle: GasPriceOracle<Provider>,
         gas_cap: impl Into<GasCap>,
+        eth_proof_window: u64,
         blocking_task_pool: BlockingTaskPool,
         fee_history_cache: FeeHistoryCache,
     ) -> Self {
@@ -110,6 +112,7 @@ where
             eth_cache,
             gas_oracle,
             gas_cap.into().into(),
+            eth_proof_window,
             Box::<TokioTaskExecutor>::default(),
             blocking_task_pool,
             fee_history_cache,
@@ -125,6 +128,7 @@ where
         eth_cache: EthStateCache,
         gas_oracle: GasPriceOracle<Provider>,
         gas_cap: u64,
+        eth_proof_window: u64,
         task_spawner: Box<dyn TaskSpawner>,
         blocking_task_pool: BlockingTaskPool,
         fee_history_cache: FeeHistoryCache,
@@ -145,6 +149,7 @@ where
             eth_cache,
             gas_oracle,
             gas_cap,
+            eth_proof_window,
             starting_block: U256::from(latest_block),
             task_spawner,
             pending_block: Default::default(),
@@ -192,6 +197,11 @@ where
         self.inner.gas_cap
     }
 
+    /// Returns the maximum number of blocks behind the tip for which `eth_getProof` is served
+    pub fn eth_proof_window(&self) -> u64 {
+        self.inner.eth_proof_window
+    }
+
     /// Returns the inner `Provider`
     pub fn provider(&self) -> &Provider {
         &self.inner.provider
@@ -386,7 +396,7 @@ where
     }
 
     fn accounts(&self) -> Vec<Address> {
-        self.inner.signers.iter().flat_map(|s| s.accounts()).collect()
+        self.inner.signers.read().iter().flat_map(|s| s.accounts()).collect()
     }
 
     fn is_syncing(&self) -> bool {
@@ -451,13 +461,15 @@ struct EthApiInner<Provider, Pool, Network> {
     /// An interface to interact with the network
     network: Network,
     /// All configured Signers
-    signers: Vec<Box<dyn EthSigner>>,
+    signers: parking_lot::RwLock<Vec<Arc<dyn EthSigner>>>,
     /// The async cache frontend for eth related data
     eth_cache: EthStateCache,
     /// The async gas oracle frontend for gas price suggestions
     gas_oracle: GasPriceOracle<Provider>,
     /// Maximum gas limit for `eth_call` and call tracing RPC methods.
     gas_cap: u64,
+    /// The maximum number of blocks behind the tip for which `eth_getProof` is served.
+    eth_proof_window: u64,
     /// The block number at which the node started
     starting_block: U256,
     /// The type that can spawn tasks which would otherwise block.
@@ -2,14 +2,14 @@
 use crate::{
     eth::{
         error::{EthResult, SignError},
-        signer::EthSigner,
+        signer::{EthSigner, KeystoreSigner},
     },
     EthApi,
 };
 use alloy_dyn_abi::TypedData;
 use reth_primitives::{Address, Bytes};
 use serde_json::Value;
-use std::ops::Deref;
+use std::sync::Arc;
 
 impl<Provider, Pool, Network> EthApi<Provider, Pool, Network> {
     pub(crate) async fn sign(&self, account: Address, message: Bytes) -> EthResult<Bytes> {
@@ -26,15 +26,19 @@ impl<Provider, Pool, Network> EthApi<Provider, Pool, Network> {
         Ok(signature.to_hex_bytes())
     }
 
-    pub(crate) fn find_signer(
-        &self,
-        account: &Address,
-    ) -> Result<&(dyn EthSigner + 'static), SignError> {
+    pub(crate) fn find_signer(&self, account: &Address) -> Result<Arc<dyn EthSigner>, SignError> {
         self.inner
             .signers
+            .read()
             .iter()
             .find(|signer| signer.is_signer_for(account))
-            .map(|signer| signer.deref())
+            .cloned()
             .ok_or(SignError::NoAccount)
     }
+
+    /// Adds the accounts of the given keystore to the accounts that can be used to sign, e.g. in
+    /// `eth_sendTransaction`.
+    pub fn add_keystore_signer(&self, signer: KeystoreSigner) {
+        self.inner.signers.write().push(Arc::new(signer));
+    }
 }
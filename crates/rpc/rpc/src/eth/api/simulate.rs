@@ -0,0 +1,440 @@
+//! Contains the `eth_simulateV1` implementation, which executes calls in a sequence of simulated
+//! blocks on top of an existing block.
+
+use crate::{
+    eth::{
+        error::{EthApiError, EthResult, RevertError, RpcInvalidTransactionError},
+        revm_utils::{
+            apply_block_overrides, apply_state_overrides, build_call_evm_env, inspect, transact,
+        },
+        EthTransactions,
+    },
+    EthApi,
+};
+use reth_network_api::NetworkInfo;
+use reth_primitives::{
+    basefee::calculate_next_block_base_fee, revm::compat::into_reth_log, BlockId, BlockNumberOrTag,
+    Header, U256, U64,
+};
+use reth_provider::{BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderFactory};
+use reth_revm::{
+    database::StateProviderDatabase,
+    tracing::{TracingInspector, TracingInspectorConfig},
+};
+use reth_rpc_types::{
+    simulate::{
+        SimBlock, SimCallResult, SimulateError, SimulatePayload, SimulatedBlock,
+        MAX_SIMULATE_BLOCKS, SIMULATE_REVERT_ERROR_CODE, SIMULATE_VM_ERROR_CODE,
+    },
+    trace::geth::{CallConfig, CallFrame},
+    Log,
+};
+use reth_transaction_pool::TransactionPool;
+use revm::{
+    db::{CacheDB, DatabaseRef},
+    primitives::{ExecutionResult, ResultAndState},
+    DatabaseCommit,
+};
+
+/// The default time between two simulated blocks, if the timestamp is not overridden.
+const SIMULATED_BLOCK_TIME: u64 = 12;
+
+impl<Provider, Pool, Network> EthApi<Provider, Pool, Network>
+where
+    Pool: TransactionPool + Clone + 'static,
+    Provider:
+        BlockReaderIdExt + ChainSpecProvider + StateProviderFactory + EvmEnvProvider + 'static,
+    Network: NetworkInfo + Send + Sync + 'static,
+{
+    /// Simulates the blocks of the payload on top of the given block (`eth_simulateV1`).
+    ///
+    /// Each simulated block starts from the state of the previous one. Unless overridden, a block
+    /// inherits the gas limit, coinbase and prevrandao of its parent, and its number and timestamp
+    /// are incremented. The base fee is derived from the parent if validation is enabled and zero
+    /// otherwise.
+    ///
+    /// The calls of all blocks share a total gas budget of the configured gas cap, and the gas
+    /// limit of every call is capped by what's left of it.
+    pub async fn simulate_v1(
+        &self,
+        payload: SimulatePayload,
+        block_number: Option<BlockId>,
+    ) -> EthResult<Vec<SimulatedBlock>> {
+        let SimulatePayload { block_state_calls, trace_calls, validation } = payload;
+        if block_state_calls.is_empty() {
+            return Err(EthApiError::InvalidParams("blockStateCalls are empty".to_string()))
+        }
+        if block_state_calls.len() > MAX_SIMULATE_BLOCKS {
+            return Err(EthApiError::InvalidParams(format!(
+                "too many blocks, at most {MAX_SIMULATE_BLOCKS} blocks can be simulated"
+            )))
+        }
+
+        let target_block = block_number.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
+        let block = self.block(target_block).await?.ok_or(EthApiError::UnknownBlockNumber)?;
+        let (mut cfg, base_block_env, _) = self.evm_env_at(block.hash.into()).await?;
+
+        // same relaxations as `eth_call`, unless the calls should be validated like transactions
+        cfg.disable_block_gas_limit = !validation;
+        cfg.disable_eip3607 = !validation;
+        cfg.disable_base_fee = !validation;
+
+        let chain_spec = self.provider().chain_spec();
+        let gas_cap = self.inner.gas_cap;
+        let mut gas_budget = gas_cap;
+        let mut parent = block.header.clone().unseal();
+        let mut parent_hash = block.hash;
+
+        self.spawn_with_state_at_block(block.hash.into(), move |state| {
+            let mut db = CacheDB::new(StateProviderDatabase::new(state));
+            let mut blocks = Vec::with_capacity(block_state_calls.len());
+
+            for SimBlock { block_overrides, state_overrides, calls } in block_state_calls {
+                let mut block_env = base_block_env.clone();
+                block_env.number = U256::from(parent.number + 1);
+                block_env.timestamp = U256::from(parent.timestamp + SIMULATED_BLOCK_TIME);
+                block_env.gas_limit = U256::from(parent.gas_limit);
+                block_env.basefee = match parent.base_fee_per_gas {
+                    Some(base_fee) if validation => U256::from(calculate_next_block_base_fee(
+                        parent.gas_used,
+                        parent.gas_limit,
+                        base_fee,
+                        chain_spec.base_fee_params(parent.timestamp + SIMULATED_BLOCK_TIME),
+                    )),
+                    _ => U256::ZERO,
+                };
+
+                if let Some(mut block_overrides) = block_overrides {
+                    if let Some(block_hashes) = block_overrides.block_hash.take() {
+                        db.block_hashes.extend(
+                            block_hashes.into_iter().map(|(num, hash)| (U256::from(num), hash)),
+                        )
+                    }
+                    apply_block_overrides(block_overrides, &mut block_env);
+                }
+
+                let number: u64 = block_env.number.saturating_to();
+                let timestamp: u64 = block_env.timestamp.saturating_to();
+                if number <= parent.number {
+                    return Err(EthApiError::InvalidParams(format!(
+                        "block number {number} must be greater than {}",
+                        parent.number
+                    )))
+                }
+                if timestamp <= parent.timestamp {
+                    return Err(EthApiError::InvalidParams(format!(
+                        "block timestamp {timestamp} must be greater than {}",
+                        parent.timestamp
+                    )))
+                }
+
+                if let Some(state_overrides) = state_overrides {
+                    apply_state_overrides(state_overrides, &mut db)?;
+                }
+
+                let block_gas_limit: u64 = block_env.gas_limit.saturating_to();
+                let mut gas_used = 0u64;
+                let mut results = Vec::with_capacity(calls.len());
+                let mut log_index = 0usize;
+
+                for (call_index, call) in calls.into_iter().enumerate() {
+                    let remaining_gas = block_gas_limit.saturating_sub(gas_used);
+                    let request_gas = call.gas;
+                    let mut env = build_call_evm_env(cfg.clone(), block_env.clone(), call)?;
+
+                    if request_gas.is_none() {
+                        env.tx.gas_limit = remaining_gas;
+                    } else if env.tx.gas_limit > remaining_gas {
+                        return Err(EthApiError::InvalidParams(format!(
+                            "call {call_index} exceeds the remaining block gas of {remaining_gas}"
+                        )))
+                    }
+
+                    if gas_budget == 0 {
+                        return Err(EthApiError::InvalidParams(format!(
+                            "call {call_index} of block {number} exceeds the gas cap of {gas_cap}"
+                        )))
+                    }
+                    env.tx.gas_limit = env.tx.gas_limit.min(gas_budget);
+
+                    if validation && env.tx.nonce.is_none() {
+                        // use the next nonce of the sender, so that calls can be chained
+                        let account = DatabaseRef::basic_ref(&db, env.tx.caller)?;
+                        env.tx.nonce = Some(account.map(|acc| acc.nonce).unwrap_or_default());
+                    }
+
+                    let (ResultAndState { result, state }, trace) = if trace_calls {
+                        let mut inspector = TracingInspector::new(
+                            TracingInspectorConfig::default_geth().set_record_logs(true),
+                        );
+                        let (res, _) = inspect(&mut db, env, &mut inspector)?;
+                        let frame = inspector.into_geth_builder().geth_call_traces(
+                            CallConfig::default().with_log(),
+                            res.result.gas_used(),
+                        );
+                        (res, Some(frame))
+                    } else {
+                        let (res, _) = transact(&mut db, env)?;
+                        (res, None)
+                    };
+
+                    gas_used += result.gas_used();
+                    gas_budget = gas_budget.saturating_sub(result.gas_used());
+                    db.commit(state);
+
+                    results.push(sim_call_result(
+                        result,
+                        number,
+                        call_index,
+                        &mut log_index,
+                        trace,
+                    ));
+                }
+
+                let header = Header {
+                    parent_hash,
+                    beneficiary: block_env.coinbase,
+                    number,
+                    gas_limit: block_gas_limit,
+                    gas_used,
+                    timestamp,
+                    mix_hash: block_env.prevrandao.unwrap_or_default(),
+                    difficulty: block_env.difficulty,
+                    base_fee_per_gas: parent
+                        .base_fee_per_gas
+                        .map(|_| block_env.basefee.saturating_to()),
+                    ..Default::default()
+                };
+                let hash = header.hash_slow();
+
+                // the hash is only known once all calls are executed
+                for log in results.iter_mut().flat_map(|res| res.logs.iter_mut()) {
+                    log.block_hash = Some(hash);
+                }
+
+                // make the simulated block available to the `BLOCKHASH` opcode of later blocks
+                db.block_hashes.insert(U256::from(number), hash);
+
+                blocks.push(SimulatedBlock {
+                    number: U256::from(number),
+                    hash,
+                    parent_hash,
+                    timestamp: U64::from(timestamp),
+                    gas_limit: U64::from(block_gas_limit),
+                    gas_used: U64::from(gas_used),
+                    fee_recipient: block_env.coinbase,
+                    base_fee_per_gas: block_env.basefee,
+                    prev_randao: block_env.prevrandao.unwrap_or_default(),
+                    calls: results,
+                });
+
+                parent = header;
+                parent_hash = hash;
+            }
+
+            Ok(blocks)
+        })
+        .await
+    }
+}
+
+/// Converts the result of a simulated call into its RPC representation.
+///
+/// The logs are numbered consecutively within the block, starting at `log_index`. Their block
+/// hash is filled in once the block is complete.
+fn sim_call_result(
+    result: ExecutionResult,
+    block_number: u64,
+    call_index: usize,
+    log_index: &mut usize,
+    trace: Option<CallFrame>,
+) -> SimCallResult {
+    let gas_used = U64::from(result.gas_used());
+    match result {
+        ExecutionResult::Success { output, logs, .. } => {
+            let logs = logs
+                .into_iter()
+                .map(into_reth_log)
+                .map(|log| {
+                    let log = Log {
+                        address: log.address,
+                        topics: log.topics,
+                        data: log.data,
+                        block_hash: None,
+                        block_number: Some(U256::from(block_number)),
+                        transaction_hash: None,
+                        transaction_index: Some(U256::from(call_index)),
+                        log_index: Some(U256::from(*log_index)),
+                        removed: false,
+                    };
+                    *log_index += 1;
+                    log
+                })
+                .collect();
+            SimCallResult {
+                return_data: output.into_data(),
+                logs,
+                gas_used,
+                status: U64::from(1),
+                error: None,
+                trace,
+            }
+        }
+        ExecutionResult::Revert { output, .. } => SimCallResult {
+            error: Some(SimulateError {
+                code: SIMULATE_REVERT_ERROR_CODE,
+                message: RevertError::new(output.clone()).to_string(),
+            }),
+            return_data: output,
+            logs: Vec::new(),
+            gas_used,
+            status: U64::ZERO,
+            trace,
+        },
+        ExecutionResult::Halt { reason, gas_used: halt_gas } => SimCallResult {
+            return_data: Default::default(),
+            logs: Vec::new(),
+            gas_used,
+            status: U64::ZERO,
+            error: Some(SimulateError {
+                code: SIMULATE_VM_ERROR_CODE,
+                message: RpcInvalidTransactionError::halt(reason, halt_gas).to_string(),
+            }),
+            trace,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        eth::{
+            cache::EthStateCache, gas_oracle::GasPriceOracle, FeeHistoryCache,
+            FeeHistoryCacheConfig,
+        },
+        BlockingTaskPool,
+    };
+    use assert_matches::assert_matches;
+    use reth_network_api::noop::NoopNetwork;
+    use reth_primitives::{Address, Block, Bytes, B256};
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_rpc_types::CallRequest;
+    use reth_transaction_pool::test_utils::{testing_pool, TestPool};
+
+    /// Account with a balance that sends the calls.
+    const SENDER: Address = Address::with_last_byte(1);
+    /// Contract that consumes all gas of a call with the `INVALID` opcode.
+    const BURNER: Address = Address::with_last_byte(2);
+
+    /// Returns an [EthApi] with the given gas cap on top of a block at height 1.
+    fn build_test_eth_api(gas_cap: u64) -> (EthApi<MockEthProvider, TestPool, NoopNetwork>, B256) {
+        let provider = MockEthProvider::default();
+        let header =
+            Header { number: 1, gas_limit: 30_000_000, timestamp: 1_000, ..Default::default() };
+        let hash = header.hash_slow();
+        provider.add_block(hash, Block { header, ..Default::default() });
+        provider.add_account(SENDER, ExtendedAccount::new(0, U256::from(u64::MAX)));
+        provider.add_account(
+            BURNER,
+            ExtendedAccount::new(0, U256::ZERO).with_bytecode(Bytes::from_static(&[0xfe])),
+        );
+
+        let cache = EthStateCache::spawn(provider.clone(), Default::default());
+        let eth_api = EthApi::new(
+            provider.clone(),
+            testing_pool(),
+            NoopNetwork::default(),
+            cache.clone(),
+            GasPriceOracle::new(provider, Default::default(), cache.clone()),
+            gas_cap,
+            0,
+            BlockingTaskPool::build().expect("failed to build tracing pool"),
+            FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default()),
+        );
+        (eth_api, hash)
+    }
+
+    fn transfer() -> CallRequest {
+        CallRequest {
+            from: Some(SENDER),
+            to: Some(Address::with_last_byte(3)),
+            value: Some(U256::from(1)),
+            ..Default::default()
+        }
+    }
+
+    fn burn(gas: Option<u64>) -> CallRequest {
+        CallRequest {
+            from: Some(SENDER),
+            to: Some(BURNER),
+            gas: gas.map(U256::from),
+            ..Default::default()
+        }
+    }
+
+    fn payload(blocks: impl IntoIterator<Item = Vec<CallRequest>>) -> SimulatePayload {
+        SimulatePayload {
+            block_state_calls: blocks
+                .into_iter()
+                .map(|calls| SimBlock { calls, ..Default::default() })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn simulate_chains_blocks() {
+        let (eth_api, parent_hash) = build_test_eth_api(30_000_000);
+        let at = Some(parent_hash.into());
+
+        let blocks = eth_api
+            .simulate_v1(payload([vec![transfer()], vec![transfer(), transfer()]]), at)
+            .await
+            .unwrap();
+
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].parent_hash, parent_hash);
+        assert_eq!(blocks[1].parent_hash, blocks[0].hash);
+        assert_eq!(blocks[0].number, U256::from(2));
+        assert_eq!(blocks[1].number, U256::from(3));
+        assert_eq!(blocks[0].timestamp, U64::from(1_000 + SIMULATED_BLOCK_TIME));
+        assert_eq!(blocks[1].timestamp, U64::from(1_000 + 2 * SIMULATED_BLOCK_TIME));
+        assert_eq!(blocks[0].gas_used, U64::from(21_000));
+        assert_eq!(blocks[1].gas_used, U64::from(42_000));
+        assert!(blocks
+            .iter()
+            .flat_map(|block| &block.calls)
+            .all(|call| call.status == U64::from(1)));
+    }
+
+    #[tokio::test]
+    async fn simulate_caps_call_gas() {
+        let (eth_api, parent_hash) = build_test_eth_api(100_000);
+        let at = Some(parent_hash.into());
+
+        let blocks = eth_api.simulate_v1(payload([vec![burn(Some(1_000_000))]]), at).await.unwrap();
+
+        let call = &blocks[0].calls[0];
+        assert_eq!(call.status, U64::ZERO);
+        assert_eq!(call.gas_used, U64::from(100_000));
+        assert_eq!(call.error.as_ref().map(|err| err.code), Some(SIMULATE_VM_ERROR_CODE));
+    }
+
+    #[tokio::test]
+    async fn simulate_shares_gas_cap_between_blocks() {
+        let (eth_api, parent_hash) = build_test_eth_api(100_000);
+        let at = Some(parent_hash.into());
+
+        let blocks =
+            eth_api.simulate_v1(payload([vec![transfer()], vec![burn(None)]]), at).await.unwrap();
+        assert_eq!(blocks[0].gas_used, U64::from(21_000));
+        assert_eq!(blocks[1].gas_used, U64::from(79_000));
+
+        // the budget is used up by the first two blocks
+        let res = eth_api
+            .simulate_v1(payload([vec![transfer()], vec![burn(None)], vec![transfer()]]), at)
+            .await;
+        assert_matches!(res, Err(EthApiError::InvalidParams(_)));
+    }
+}
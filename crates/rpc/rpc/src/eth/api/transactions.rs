@@ -144,6 +144,14 @@ pub trait EthTransactions: Send + Sync {
     /// Returns the hash of the signed transaction.
     async fn send_transaction(&self, request: TransactionRequest) -> EthResult<B256>;
 
+    /// Signs transaction with a matching signer, if any, without submitting it to the pool.
+    ///
+    /// Missing nonce, gas limit and fee fields are filled like in
+    /// [send_transaction](EthTransactions::send_transaction).
+    ///
+    /// Returns the EIP-2718 encoded signed transaction.
+    async fn sign_transaction(&self, request: TransactionRequest) -> EthResult<Bytes>;
+
     /// Prepares the state and env for the given [CallRequest] at the given [BlockId] and executes
     /// the closure on a new task returning the result of the closure.
     async fn spawn_with_call_at<F, R>(
@@ -379,9 +387,8 @@ where
                 match this.provider().transaction_by_hash_with_meta(hash)? {
                     None => Ok(None),
                     Some((tx, meta)) => {
-                        let transaction = tx
-                            .into_ecrecovered()
-                            .ok_or(EthApiError::InvalidTransactionSignature)?;
+                        let signer = this.mined_transaction_signer(&tx)?;
+                        let transaction = tx.with_signer(signer);
 
                         let tx = TransactionSource::Block {
                             transaction,
@@ -465,7 +472,8 @@ where
                     None => return Ok(None),
                 };
 
-                Ok(Some((tx, meta, receipt)))
+                let signer = this.mined_transaction_signer(&tx)?;
+                Ok(Some((tx.with_signer(signer), meta, receipt)))
             })
             .await?;
 
@@ -492,81 +500,9 @@ where
         Ok(hash)
     }
 
-    async fn send_transaction(&self, mut request: TransactionRequest) -> EthResult<B256> {
-        let from = match request.from {
-            Some(from) => from,
-            None => return Err(SignError::NoAccount.into()),
-        };
-
-        // set nonce if not already set before
-        if request.nonce.is_none() {
-            let nonce =
-                self.get_transaction_count(from, Some(BlockId::Number(BlockNumberOrTag::Pending)))?;
-            // note: `.to()` can't panic because the nonce is constructed from a `u64`
-            request.nonce = Some(U64::from(nonce.to::<u64>()));
-        }
-
-        let chain_id = self.chain_id();
-        // TODO: we need an oracle to fetch the gas price of the current chain
-        let gas_price = request.gas_price.unwrap_or_default();
-        let max_fee_per_gas = request.max_fee_per_gas.unwrap_or_default();
-
-        let estimated_gas = self
-            .estimate_gas_at(
-                CallRequest {
-                    from: Some(from),
-                    to: request.to,
-                    gas: request.gas,
-                    gas_price: Some(U256::from(gas_price)),
-                    max_fee_per_gas: Some(U256::from(max_fee_per_gas)),
-                    value: request.value,
-                    input: request.input.clone().into(),
-                    nonce: request.nonce,
-                    chain_id: Some(chain_id),
-                    access_list: request.access_list.clone(),
-                    max_priority_fee_per_gas: Some(U256::from(max_fee_per_gas)),
-                    transaction_type: None,
-                    blob_versioned_hashes: None,
-                    max_fee_per_blob_gas: None,
-                },
-                BlockId::Number(BlockNumberOrTag::Pending),
-                None,
-            )
-            .await?;
-        let gas_limit = estimated_gas;
-
-        let transaction = match request.into_typed_request() {
-            Some(TypedTransactionRequest::Legacy(mut m)) => {
-                m.chain_id = Some(chain_id.to());
-                m.gas_limit = gas_limit;
-                m.gas_price = gas_price;
-
-                TypedTransactionRequest::Legacy(m)
-            }
-            Some(TypedTransactionRequest::EIP2930(mut m)) => {
-                m.chain_id = chain_id.to();
-                m.gas_limit = gas_limit;
-                m.gas_price = gas_price;
-
-                TypedTransactionRequest::EIP2930(m)
-            }
-            Some(TypedTransactionRequest::EIP1559(mut m)) => {
-                m.chain_id = chain_id.to();
-                m.gas_limit = gas_limit;
-                m.max_fee_per_gas = max_fee_per_gas;
-
-                TypedTransactionRequest::EIP1559(m)
-            }
-            Some(TypedTransactionRequest::EIP4844(mut m)) => {
-                m.chain_id = chain_id.to();
-                m.gas_limit = gas_limit;
-                m.max_fee_per_gas = max_fee_per_gas;
-
-                TypedTransactionRequest::EIP4844(m)
-            }
-            None => return Err(EthApiError::ConflictingFeeFieldsInRequest),
-        };
-
+    async fn send_transaction(&self, request: TransactionRequest) -> EthResult<B256> {
+        let from = request.from.ok_or(SignError::NoAccount)?;
+        let transaction = self.fill_transaction_request(from, request).await?;
         let signed_tx = self.sign_request(&from, transaction)?;
 
         let recovered =
@@ -581,6 +517,13 @@ where
         Ok(hash)
     }
 
+    async fn sign_transaction(&self, request: TransactionRequest) -> EthResult<Bytes> {
+        let from = request.from.ok_or(SignError::NoAccount)?;
+        let transaction = self.fill_transaction_request(from, request).await?;
+        let signed_tx = self.sign_request(&from, transaction)?;
+        Ok(signed_tx.envelope_encoded())
+    }
+
     async fn spawn_with_call_at<F, R>(
         &self,
         request: CallRequest,
@@ -874,13 +817,30 @@ where
         BlockReaderIdExt + ChainSpecProvider + StateProviderFactory + EvmEnvProvider + 'static,
     Network: NetworkInfo + 'static,
 {
+    /// Returns the signer of a mined transaction.
+    ///
+    /// Transactions that were included without a valid signature, like the transactions of
+    /// impersonated accounts on a dev chain, fall back to the sender stored for them.
+    pub(crate) fn mined_transaction_signer(&self, tx: &TransactionSigned) -> EthResult<Address> {
+        if let Some(signer) = tx.recover_signer_unchecked() {
+            return Ok(signer)
+        }
+        let provider = self.provider();
+        provider
+            .transaction_id(tx.hash)?
+            .map(|id| provider.transaction_sender(id))
+            .transpose()?
+            .flatten()
+            .ok_or(EthApiError::InvalidTransactionSignature)
+    }
+
     /// Helper function for `eth_getTransactionReceipt`
     ///
     /// Returns the receipt
     #[cfg(not(feature = "optimism"))]
     pub(crate) async fn build_transaction_receipt(
         &self,
-        tx: TransactionSigned,
+        tx: TransactionSignedEcRecovered,
         meta: TransactionMeta,
         receipt: Receipt,
     ) -> EthResult<TransactionReceipt> {
@@ -898,7 +858,7 @@ where
     #[cfg(feature = "optimism")]
     pub(crate) async fn build_transaction_receipt(
         &self,
-        tx: TransactionSigned,
+        tx: TransactionSignedEcRecovered,
         meta: TransactionMeta,
         receipt: Receipt,
     ) -> EthResult<TransactionReceipt> {
@@ -1007,6 +967,106 @@ where
     }
 }
 
+impl<Provider, Pool, Network> EthApi<Provider, Pool, Network>
+where
+    Pool: TransactionPool + Clone + 'static,
+    Provider:
+        BlockReaderIdExt + ChainSpecProvider + StateProviderFactory + EvmEnvProvider + 'static,
+    Network: NetworkInfo + Send + Sync + 'static,
+{
+    /// Fills the nonce, gas limit and fee fields that are missing in the request and converts it
+    /// into a typed request for the current chain.
+    ///
+    /// The nonce is the sender's pending nonce, the gas limit is estimated against the pending
+    /// block and the fees are the suggestions that are also returned by `eth_gasPrice` and
+    /// `eth_maxPriorityFeePerGas`.
+    pub(crate) async fn fill_transaction_request(
+        &self,
+        from: Address,
+        mut request: TransactionRequest,
+    ) -> EthResult<TypedTransactionRequest> {
+        let chain_id = self.chain_id();
+
+        // set nonce if not already set before
+        if request.nonce.is_none() {
+            let nonce =
+                self.get_transaction_count(from, Some(BlockId::Number(BlockNumberOrTag::Pending)))?;
+            // note: `.to()` can't panic because the nonce is constructed from a `u64`
+            request.nonce = Some(U64::from(nonce.to::<u64>()));
+        }
+
+        if request.gas.is_none() {
+            let estimated_gas = self
+                .estimate_gas_at(
+                    CallRequest {
+                        from: Some(from),
+                        to: request.to,
+                        value: request.value,
+                        input: request.input.clone().into(),
+                        nonce: request.nonce,
+                        chain_id: Some(chain_id),
+                        access_list: request.access_list.clone(),
+                        ..Default::default()
+                    },
+                    BlockId::Number(BlockNumberOrTag::Pending),
+                    None,
+                )
+                .await?;
+            request.gas = Some(estimated_gas);
+        }
+
+        let has_gas_price = request.gas_price.is_some();
+        let has_max_fee = request.max_fee_per_gas.is_some();
+        let has_priority_fee = request.max_priority_fee_per_gas.is_some();
+
+        let transaction = match request.into_typed_request() {
+            Some(TypedTransactionRequest::Legacy(mut m)) => {
+                m.chain_id = Some(chain_id.to());
+                if !has_gas_price {
+                    m.gas_price = self.gas_price().await?.to();
+                }
+
+                TypedTransactionRequest::Legacy(m)
+            }
+            Some(TypedTransactionRequest::EIP2930(mut m)) => {
+                m.chain_id = chain_id.to();
+                if !has_gas_price {
+                    m.gas_price = self.gas_price().await?.to();
+                }
+
+                TypedTransactionRequest::EIP2930(m)
+            }
+            Some(TypedTransactionRequest::EIP1559(mut m)) => {
+                m.chain_id = chain_id.to();
+                if !has_priority_fee {
+                    m.max_priority_fee_per_gas = self.suggested_priority_fee().await?.to();
+                }
+                if !has_max_fee {
+                    m.max_fee_per_gas =
+                        self.suggested_max_fee_per_gas(m.max_priority_fee_per_gas.to()).await?.to();
+                }
+
+                TypedTransactionRequest::EIP1559(m)
+            }
+            Some(TypedTransactionRequest::EIP4844(mut m)) => {
+                m.chain_id = chain_id.to();
+                if !has_priority_fee {
+                    m.max_priority_fee_per_gas = self.suggested_priority_fee().await?.to();
+                }
+                if !has_max_fee {
+                    m.max_fee_per_gas =
+                        self.suggested_max_fee_per_gas(m.max_priority_fee_per_gas.to()).await?.to();
+                }
+
+                TypedTransactionRequest::EIP4844(m)
+            }
+            None => return Err(EthApiError::ConflictingFeeFieldsInRequest),
+        };
+
+        Ok(transaction)
+    }
+}
+
 impl<Provider, Pool, Network> EthApi<Provider, Pool, Network>
 where
     Pool: TransactionPool + 'static,
@@ -1019,15 +1079,8 @@ where
         from: &Address,
         request: TypedTransactionRequest,
     ) -> EthResult<TransactionSigned> {
-        for signer in self.inner.signers.iter() {
-            if signer.is_signer_for(from) {
-                return match signer.sign_transaction(request, from) {
-                    Ok(tx) => Ok(tx),
-                    Err(e) => Err(e.into()),
-                }
-            }
-        }
-        Err(EthApiError::InvalidTransactionSignature)
+        let signer = self.find_signer(from)?;
+        Ok(signer.sign_transaction(request, from)?)
     }
 
     /// Get Transaction by [BlockId] and the index of the transaction within that Block.
@@ -1150,16 +1203,13 @@ impl From<TransactionSource> for Transaction {
 /// Note: This requires _all_ block receipts because we need to calculate the gas used by the
 /// transaction.
 pub(crate) fn build_transaction_receipt_with_block_receipts(
-    transaction: TransactionSigned,
+    transaction: TransactionSignedEcRecovered,
     meta: TransactionMeta,
     receipt: Receipt,
     all_receipts: &[Receipt],
     #[cfg(feature = "optimism")] optimism_tx_meta: OptimismTxMeta,
 ) -> EthResult<TransactionReceipt> {
-    // Note: we assume this transaction is valid, because it's mined (or part of pending block) and
-    // we don't need to check for pre EIP-2
-    let from =
-        transaction.recover_signer_unchecked().ok_or(EthApiError::InvalidTransactionSignature)?;
+    let from = transaction.signer();
 
     // get the previous transaction cumulative gas used
     let gas_used = if meta.index == 0 {
@@ -1276,6 +1326,7 @@ mod tests {
             cache.clone(),
             GasPriceOracle::new(noop_provider, Default::default(), cache.clone()),
             ETHEREUM_BLOCK_GAS_LIMIT,
+            0,
             BlockingTaskPool::build().expect("failed to build tracing pool"),
             fee_history_cache,
         );
@@ -22,3 +22,4 @@ pub use bundle::EthBundle;
 pub use filter::{EthFilter, EthFilterConfig};
 pub use id_provider::EthSubscriptionIdProvider;
 pub use pubsub::EthPubSub;
+pub use signer::{KeystoreError, KeystoreSigner};
@@ -484,7 +484,7 @@ impl CallFees {
 }
 
 /// Applies the given block overrides to the env
-fn apply_block_overrides(overrides: BlockOverrides, env: &mut BlockEnv) {
+pub(crate) fn apply_block_overrides(overrides: BlockOverrides, env: &mut BlockEnv) {
     let BlockOverrides {
         number,
         difficulty,
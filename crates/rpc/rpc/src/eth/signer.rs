@@ -3,13 +3,18 @@
 use crate::eth::error::SignError;
 use alloy_dyn_abi::TypedData;
 use reth_primitives::{
-    eip191_hash_message, sign_message, Address, Signature, TransactionSigned, B256,
+    eip191_hash_message,
+    fs::{self, FsPathError},
+    public_key_to_address, sign_message, Address, Signature, TransactionSigned, B256,
 };
 use reth_rpc_types::TypedTransactionRequest;
 
 use reth_rpc_types_compat::transaction::to_primitive_transaction;
-use secp256k1::SecretKey;
-use std::collections::HashMap;
+use secp256k1::{PublicKey, SecretKey, SECP256K1};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 type Result<T> = std::result::Result<T, SignError>;
 
@@ -45,6 +50,19 @@ pub(crate) struct DevSigner {
 }
 
 impl DevSigner {
+    /// Creates a signer for the given keys.
+    pub(crate) fn from_keys(keys: impl IntoIterator<Item = SecretKey>) -> Self {
+        let mut addresses = Vec::new();
+        let mut accounts = HashMap::new();
+        for key in keys {
+            let address = public_key_to_address(PublicKey::from_secret_key(SECP256K1, &key));
+            if accounts.insert(address, key).is_none() {
+                addresses.push(address);
+            }
+        }
+        Self { addresses, accounts }
+    }
+
     fn get_key(&self, account: Address) -> Result<&SecretKey> {
         self.accounts.get(&account).ok_or(SignError::NoAccount)
     }
@@ -94,6 +112,105 @@ impl EthSigner for DevSigner {
     }
 }
 
+/// Errors that can occur when loading a keystore.
+#[derive(Debug, thiserror::Error)]
+pub enum KeystoreError {
+    /// The keystore directory or a key file could not be read.
+    #[error(transparent)]
+    Fs(#[from] FsPathError),
+    /// A key file could not be decrypted with the given password.
+    #[error("failed to decrypt key file {path:?}: {source}")]
+    Decrypt {
+        /// The path of the key file.
+        path: PathBuf,
+        /// The decryption error.
+        source: eth_keystore::KeystoreError,
+    },
+    /// A key file does not contain a valid secp256k1 secret key.
+    #[error("invalid secret key in key file {0:?}")]
+    InvalidKey(PathBuf),
+}
+
+/// Signs with the keys of encrypted JSON keystore (v3) files.
+///
+/// All key files in the keystore directory are decrypted with the same password when the signer
+/// is loaded, and the keys are kept in memory afterwards.
+pub struct KeystoreSigner {
+    inner: DevSigner,
+}
+
+impl KeystoreSigner {
+    /// Decrypts all key files in the given directory with the given password.
+    ///
+    /// Hidden files and subdirectories are ignored.
+    pub fn load(dir: impl AsRef<Path>, password: &str) -> std::result::Result<Self, KeystoreError> {
+        let dir = dir.as_ref();
+        let mut paths = fs::read_dir(dir)?
+            .map(|entry| {
+                entry.map(|entry| entry.path()).map_err(|err| FsPathError::read_dir(err, dir))
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        // load the keys in a deterministic order
+        paths.sort();
+
+        let mut keys = Vec::with_capacity(paths.len());
+        for path in paths {
+            let is_hidden = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map_or(true, |name| name.starts_with('.'));
+            if !path.is_file() || is_hidden {
+                continue
+            }
+            let secret = eth_keystore::decrypt_key(&path, password)
+                .map_err(|source| KeystoreError::Decrypt { path: path.clone(), source })?;
+            let key =
+                SecretKey::from_slice(&secret).map_err(|_| KeystoreError::InvalidKey(path))?;
+            keys.push(key);
+        }
+
+        Ok(Self { inner: DevSigner::from_keys(keys) })
+    }
+
+    /// Returns the addresses of the loaded keys.
+    pub fn addresses(&self) -> &[Address] {
+        &self.inner.addresses
+    }
+}
+
+impl std::fmt::Debug for KeystoreSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeystoreSigner").field("addresses", &self.inner.addresses).finish()
+    }
+}
+
+#[async_trait::async_trait]
+impl EthSigner for KeystoreSigner {
+    fn accounts(&self) -> Vec<Address> {
+        self.inner.accounts()
+    }
+
+    fn is_signer_for(&self, addr: &Address) -> bool {
+        self.inner.is_signer_for(addr)
+    }
+
+    async fn sign(&self, address: Address, message: &[u8]) -> Result<Signature> {
+        self.inner.sign(address, message).await
+    }
+
+    fn sign_transaction(
+        &self,
+        request: TypedTransactionRequest,
+        address: &Address,
+    ) -> Result<TransactionSigned> {
+        self.inner.sign_transaction(request, address)
+    }
+
+    fn sign_typed_data(&self, address: Address, payload: &TypedData) -> Result<Signature> {
+        self.inner.sign_typed_data(address, payload)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -215,4 +332,30 @@ mod test {
         };
         assert_eq!(sig, expected)
     }
+
+    #[test]
+    fn test_keystore_signer() {
+        let dir = tempfile::tempdir().unwrap();
+        let secret =
+            SecretKey::from_str("4646464646464646464646464646464646464646464646464646464646464646")
+                .unwrap();
+        eth_keystore::encrypt_key(
+            dir.path(),
+            &mut rand::thread_rng(),
+            secret.secret_bytes(),
+            "password",
+            Some("key"),
+        )
+        .unwrap();
+        // hidden files are ignored
+        fs::write(dir.path().join(".lock"), b"").unwrap();
+
+        let signer = KeystoreSigner::load(dir.path(), "password").unwrap();
+        let address = public_key_to_address(PublicKey::from_secret_key(SECP256K1, &secret));
+        assert_eq!(signer.addresses(), &[address]);
+        assert!(signer.is_signer_for(&address));
+
+        let err = KeystoreSigner::load(dir.path(), "wrong").unwrap_err();
+        assert!(matches!(err, KeystoreError::Decrypt { .. }));
+    }
 }
@@ -27,23 +27,28 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 mod admin;
+mod anvil;
 mod debug;
 mod engine;
 pub mod eth;
 mod layers;
+mod mev;
 mod net;
 mod otterscan;
+mod profiling;
 mod reth;
 mod rpc;
 mod trace;
 mod txpool;
 mod web3;
 pub use admin::AdminApi;
+pub use anvil::AnvilApi;
 pub use blocking_pool::{BlockingTaskGuard, BlockingTaskPool};
 pub use debug::DebugApi;
 pub use engine::{EngineApi, EngineEthApi};
 pub use eth::{EthApi, EthApiSpec, EthFilter, EthPubSub, EthSubscriptionIdProvider};
 pub use layers::{AuthLayer, AuthValidator, Claims, JwtAuthValidator, JwtError, JwtSecret};
+pub use mev::MevApi;
 pub use net::NetApi;
 pub use otterscan::OtterscanApi;
 pub use reth::RethApi;
@@ -0,0 +1,720 @@
+//! `mev` namespace implementation for simulating MEV-Share bundles.
+
+use crate::{
+    eth::{
+        error::{EthApiError, EthResult, RpcInvalidTransactionError},
+        revm_utils::{transact, FillableTransaction},
+        utils::recover_raw_transaction,
+        EthTransactions,
+    },
+    BlockingTaskGuard,
+};
+use jsonrpsee::core::RpcResult;
+use reth_primitives::{
+    revm::compat::into_reth_log, Address, BlockId, BlockNumberOrTag, TransactionSignedEcRecovered,
+    B256, U256, U64,
+};
+use reth_revm::database::StateProviderDatabase;
+use reth_rpc_api::MevApiServer;
+use reth_rpc_types::{
+    BundleItem, Log, Refund, RefundConfig, SendBundleRequest, SendBundleResponse, SimBundleLogs,
+    SimBundleOverrides, SimBundleResponse, Validity,
+};
+use revm::{
+    db::{CacheDB, DatabaseRef},
+    primitives::{Env, ResultAndState, TxEnv},
+    DatabaseCommit,
+};
+use std::{
+    ops::RangeInclusive,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// Maximum number of blocks the inclusion range of a bundle can span.
+const MAX_BLOCK_RANGE: u64 = 30;
+
+/// Maximum number of items in a bundle, including the items of its nested bundles.
+const MAX_BODY_SIZE: usize = 50;
+
+/// Maximum depth of nested bundles.
+const MAX_NESTING_DEPTH: usize = 5;
+
+/// Default timeout of a bundle simulation.
+const DEFAULT_SIM_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Maximum timeout of a bundle simulation.
+const MAX_SIM_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Gas charged for every refund payout to a recipient.
+const REFUND_PAYOUT_GAS: u64 = 21_000;
+
+/// The time between the parent block and the simulated block, if the timestamp is not overridden.
+const SIMULATED_BLOCK_TIME: u64 = 12;
+
+/// `mev` API implementation.
+///
+/// This node is not a matchmaker, hence bundles can only be simulated and not submitted.
+pub struct MevApi<Eth> {
+    /// All nested fields bundled together.
+    inner: Arc<MevApiInner<Eth>>,
+}
+
+impl<Eth> MevApi<Eth> {
+    /// Create a new `MevApi` instance.
+    pub fn new(eth_api: Eth, blocking_task_guard: BlockingTaskGuard) -> Self {
+        Self { inner: Arc::new(MevApiInner { eth_api, blocking_task_guard }) }
+    }
+}
+
+impl<Eth> MevApi<Eth>
+where
+    Eth: EthTransactions + 'static,
+{
+    /// Simulates a fully matched bundle, including its nested bundles, on top of the state of the
+    /// parent block.
+    ///
+    /// The header of the simulated block is derived from the parent block, unless overridden. The
+    /// refunds of the bundle are paid out from the coinbase, like a matchmaker would do. All
+    /// transactions of the bundle have to fit into the gas limit of the simulated block, and the
+    /// simulation is aborted once the timeout is reached.
+    ///
+    /// Returns an error if the bundle is malformed. A bundle that can't be included in the
+    /// simulated block results in an unsuccessful [SimBundleResponse] instead.
+    pub async fn sim_bundle(
+        &self,
+        request: SendBundleRequest,
+        overrides: SimBundleOverrides,
+    ) -> EthResult<SimBundleResponse> {
+        let bundle = SimBundle::try_from_request(request, 0, &mut 0)?;
+
+        let SimBundleOverrides {
+            parent_block,
+            block_number,
+            coinbase,
+            timestamp,
+            gas_limit,
+            base_fee,
+            timeout,
+        } = overrides;
+        let timeout = timeout
+            .map(|timeout| Duration::from_secs(timeout.to()))
+            .unwrap_or(DEFAULT_SIM_TIMEOUT)
+            .min(MAX_SIM_TIMEOUT);
+        let deadline = Instant::now() + timeout;
+
+        let parent_block = parent_block.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
+        let (cfg, mut block_env, at) = self.inner.eth_api.evm_env_at(parent_block).await?;
+
+        let state_block = block_env.number.to::<u64>();
+        block_env.number = U256::from(block_number.map(|n| n.to()).unwrap_or(state_block + 1));
+        block_env.timestamp = timestamp
+            .map(|timestamp| U256::from(timestamp.to::<u64>()))
+            .unwrap_or(block_env.timestamp + U256::from(SIMULATED_BLOCK_TIME));
+        if let Some(coinbase) = coinbase {
+            block_env.coinbase = coinbase;
+        }
+        if let Some(gas_limit) = gas_limit {
+            block_env.gas_limit = U256::from(gas_limit.to::<u64>());
+        }
+        if let Some(base_fee) = base_fee {
+            block_env.basefee = U256::from(base_fee.to::<u64>());
+        }
+
+        // restrict the number of concurrent simulations
+        let _permit = self.inner.blocking_task_guard.clone().acquire_owned().await;
+
+        self.inner
+            .eth_api
+            .spawn_with_state_at_block(at, move |state| {
+                let mut simulator = BundleSimulator {
+                    db: CacheDB::new(StateProviderDatabase::new(state)),
+                    env: Env { cfg, block: block_env, tx: TxEnv::default() },
+                    deadline,
+                    timeout,
+                    block_gas_used: 0,
+                };
+
+                let response = match simulator.simulate(&bundle)? {
+                    Ok(output) => SimBundleResponse {
+                        success: true,
+                        error: None,
+                        state_block: U64::from(state_block),
+                        mev_gas_price: output
+                            .profit
+                            .checked_div(U256::from(output.gas_used))
+                            .unwrap_or_default(),
+                        profit: output.profit,
+                        refundable_value: output.refundable_value,
+                        gas_used: U64::from(output.gas_used),
+                        logs: Some(output.logs),
+                    },
+                    Err(err) => SimBundleResponse {
+                        success: false,
+                        error: Some(err.to_string()),
+                        state_block: U64::from(state_block),
+                        mev_gas_price: U256::ZERO,
+                        profit: U256::ZERO,
+                        refundable_value: U256::ZERO,
+                        gas_used: U64::ZERO,
+                        logs: None,
+                    },
+                };
+
+                Ok(response)
+            })
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl<Eth> MevApiServer for MevApi<Eth>
+where
+    Eth: EthTransactions + 'static,
+{
+    async fn send_bundle(&self, _request: SendBundleRequest) -> RpcResult<SendBundleResponse> {
+        Err(EthApiError::Unsupported("mev_sendBundle is only supported by matchmakers").into())
+    }
+
+    async fn sim_bundle(
+        &self,
+        request: SendBundleRequest,
+        sim_overrides: SimBundleOverrides,
+    ) -> RpcResult<SimBundleResponse> {
+        Ok(MevApi::sim_bundle(self, request, sim_overrides).await?)
+    }
+}
+
+/// Container type for `MevApi` internals
+#[derive(Debug)]
+struct MevApiInner<Eth> {
+    /// Access to commonly used code of the `eth` namespace
+    eth_api: Eth,
+    // restrict the number of concurrent simulations.
+    blocking_task_guard: BlockingTaskGuard,
+}
+
+impl<Eth> std::fmt::Debug for MevApi<Eth> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MevApi").finish_non_exhaustive()
+    }
+}
+
+impl<Eth> Clone for MevApi<Eth> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+/// A validated bundle, with its transactions decoded and ready to be simulated.
+#[derive(Debug)]
+struct SimBundle {
+    /// Blocks the bundle can be included in.
+    inclusion: RangeInclusive<u64>,
+    /// Transactions and nested bundles of the bundle.
+    body: Vec<SimBundleItem>,
+    /// Refunds to the items of the body, see [Validity::refund].
+    refunds: Vec<Refund>,
+    /// Recipients of a refund to this bundle, see [Validity::refund_config].
+    refund_config: Vec<RefundConfig>,
+}
+
+impl SimBundle {
+    /// Validates the [SendBundleRequest] and recovers the signers of its transactions.
+    ///
+    /// `body_size` is the number of items of all the bundles validated so far, which is limited to
+    /// [MAX_BODY_SIZE].
+    fn try_from_request(
+        request: SendBundleRequest,
+        depth: usize,
+        body_size: &mut usize,
+    ) -> EthResult<Self> {
+        let SendBundleRequest { inclusion, bundle_body, validity, .. } = request;
+
+        if depth > MAX_NESTING_DEPTH {
+            return Err(MevBundleError::MaxNestingDepthExceeded.into())
+        }
+        if bundle_body.is_empty() {
+            return Err(MevBundleError::EmptyBundleBody.into())
+        }
+
+        let min_block = inclusion.block_number();
+        let max_block = inclusion.max_block_number().unwrap_or(min_block);
+        if max_block < min_block || max_block - min_block > MAX_BLOCK_RANGE {
+            return Err(MevBundleError::InvalidInclusion.into())
+        }
+
+        let body = bundle_body
+            .into_iter()
+            .map(|item| {
+                *body_size += 1;
+                if *body_size > MAX_BODY_SIZE {
+                    return Err(MevBundleError::BundleTooLarge.into())
+                }
+
+                match item {
+                    BundleItem::Hash { .. } => Err(MevBundleError::UnmatchedBundle.into()),
+                    BundleItem::Tx { tx, can_revert } => Ok(SimBundleItem::Tx {
+                        tx: recover_raw_transaction(tx)?.into_ecrecovered_transaction(),
+                        can_revert,
+                    }),
+                    BundleItem::Bundle { bundle } => Ok(SimBundleItem::Bundle(
+                        Self::try_from_request(bundle, depth + 1, body_size)?,
+                    )),
+                }
+            })
+            .collect::<EthResult<Vec<_>>>()?;
+
+        let Validity { refund, refund_config } = validity.unwrap_or_default();
+        let refunds = refund.unwrap_or_default();
+        let refund_config = refund_config.unwrap_or_default();
+
+        if let Some(refund) = refunds.iter().find(|refund| refund.body_idx as usize >= body.len()) {
+            return Err(MevBundleError::InvalidRefundIndex(refund.body_idx).into())
+        }
+        if exceeds_full_refund(refunds.iter().map(|refund| refund.percent)) ||
+            exceeds_full_refund(refund_config.iter().map(|config| config.percent))
+        {
+            return Err(MevBundleError::InvalidRefundPercent.into())
+        }
+
+        Ok(Self { inclusion: min_block..=max_block, body, refunds, refund_config })
+    }
+}
+
+/// Returns true if any of the caller-supplied percentages, or their sum, exceeds 100.
+fn exceeds_full_refund(mut percents: impl Iterator<Item = u64>) -> bool {
+    percents
+        .try_fold(0u64, |total, percent| total.checked_add(percent).filter(|total| *total <= 100))
+        .is_none()
+}
+
+/// An item of a [SimBundle].
+#[derive(Debug)]
+enum SimBundleItem {
+    /// A signed transaction.
+    Tx {
+        /// The transaction with its recovered signer.
+        tx: TransactionSignedEcRecovered,
+        /// Whether the transaction can revert without invalidating the bundle.
+        can_revert: bool,
+    },
+    /// A nested bundle.
+    Bundle(SimBundle),
+}
+
+impl SimBundleItem {
+    /// Returns the recipients of a refund to this item.
+    ///
+    /// The refund of a transaction goes to its signer. The refund of a bundle goes to its
+    /// configured recipients, or to the recipients of its first item if none are configured.
+    fn refund_config(&self) -> Vec<RefundConfig> {
+        match self {
+            Self::Tx { tx, .. } => vec![RefundConfig { address: tx.signer(), percent: 100 }],
+            Self::Bundle(bundle) if bundle.refund_config.is_empty() => {
+                bundle.body[0].refund_config()
+            }
+            Self::Bundle(bundle) => bundle.refund_config.clone(),
+        }
+    }
+}
+
+/// The outcome of a successful bundle simulation.
+#[derive(Debug, Default)]
+struct SimBundleOutput {
+    /// Gas used by all transactions of the bundle.
+    gas_used: u64,
+    /// Value paid to the coinbase, minus the refunds paid out.
+    profit: U256,
+    /// Value paid to the coinbase by the items of the body which are not refunded themselves.
+    refundable_value: U256,
+    /// Logs of every item of the body.
+    logs: Vec<SimBundleLogs>,
+}
+
+/// Executes [SimBundle]s on top of a state, committing the changes of every transaction.
+struct BundleSimulator<DB> {
+    db: CacheDB<DB>,
+    /// The environment of the simulated block.
+    env: Env,
+    /// The simulation is aborted once this is reached.
+    deadline: Instant,
+    /// The configured timeout, for the error message.
+    timeout: Duration,
+    /// Gas used by all transactions simulated so far, which is limited by the block gas limit.
+    block_gas_used: u64,
+}
+
+impl<DB> BundleSimulator<DB>
+where
+    DB: DatabaseRef,
+    EthApiError: From<<DB as DatabaseRef>::Error>,
+{
+    /// Simulates the bundle and its nested bundles, then pays out the refunds of its body.
+    ///
+    /// Returns the reason of the failure if the bundle can't be included in the simulated block.
+    fn simulate(
+        &mut self,
+        bundle: &SimBundle,
+    ) -> EthResult<Result<SimBundleOutput, SimBundleError>> {
+        let block_number = self.env.block.number.to::<u64>();
+        if !bundle.inclusion.contains(&block_number) {
+            return Ok(Err(SimBundleError::BlockNotInInclusionRange(block_number)))
+        }
+
+        let coinbase = self.env.block.coinbase;
+        let mut output = SimBundleOutput::default();
+
+        for (idx, item) in bundle.body.iter().enumerate() {
+            if Instant::now() > self.deadline {
+                return Err(EthApiError::ExecutionTimedOut(self.timeout))
+            }
+
+            let coinbase_balance_before = self.balance(coinbase)?;
+
+            match item {
+                SimBundleItem::Tx { tx, can_revert } => {
+                    let block_gas_limit = self.env.block.gas_limit.saturating_to::<u64>();
+                    if tx.gas_limit() > block_gas_limit.saturating_sub(self.block_gas_used) {
+                        return Ok(Err(SimBundleError::BlockGasLimitExceeded(tx.hash())))
+                    }
+
+                    let mut env = self.env.clone();
+                    tx.try_fill_tx_env(&mut env.tx)?;
+                    let ResultAndState { result, state } = match transact(&mut self.db, env) {
+                        Ok((res, _)) => res,
+                        Err(EthApiError::InvalidTransaction(err)) => {
+                            return Ok(Err(SimBundleError::InvalidTransaction(tx.hash(), err)))
+                        }
+                        Err(err) => return Err(err),
+                    };
+                    if !result.is_success() && !*can_revert {
+                        return Ok(Err(SimBundleError::TransactionReverted(tx.hash())))
+                    }
+
+                    self.block_gas_used += result.gas_used();
+                    output.gas_used += result.gas_used();
+                    let logs = result
+                        .into_logs()
+                        .into_iter()
+                        .map(into_reth_log)
+                        .map(|log| Log {
+                            address: log.address,
+                            topics: log.topics,
+                            data: log.data,
+                            block_hash: None,
+                            block_number: None,
+                            transaction_hash: Some(tx.hash()),
+                            transaction_index: None,
+                            log_index: None,
+                            removed: false,
+                        })
+                        .collect();
+                    output.logs.push(SimBundleLogs { tx_logs: Some(logs), bundle_logs: None });
+
+                    self.db.commit(state);
+                }
+                SimBundleItem::Bundle(nested) => {
+                    let nested = match self.simulate(nested)? {
+                        Ok(nested) => nested,
+                        Err(err) => return Ok(Err(err)),
+                    };
+                    output.gas_used += nested.gas_used;
+                    output
+                        .logs
+                        .push(SimBundleLogs { tx_logs: None, bundle_logs: Some(nested.logs) });
+                }
+            }
+
+            let profit = self.balance(coinbase)?.saturating_sub(coinbase_balance_before);
+            output.profit += profit;
+            if !bundle.refunds.iter().any(|refund| refund.body_idx as usize == idx) {
+                output.refundable_value += profit;
+            }
+        }
+
+        for refund in &bundle.refunds {
+            let recipients = bundle.body[refund.body_idx as usize].refund_config();
+
+            let max_profit = output.refundable_value * U256::from(refund.percent) / U256::from(100);
+            let payout_cost =
+                self.env.block.basefee * U256::from(REFUND_PAYOUT_GAS * recipients.len() as u64);
+            if max_profit <= payout_cost {
+                return Ok(Err(SimBundleError::NegativeRefundProfit(refund.body_idx)))
+            }
+
+            let max_payout = max_profit - payout_cost;
+            for recipient in recipients {
+                let value = max_payout * U256::from(recipient.percent) / U256::from(100);
+                self.transfer(coinbase, recipient.address, value)?;
+            }
+            output.profit = output.profit.saturating_sub(max_profit);
+        }
+
+        Ok(Ok(output))
+    }
+
+    /// Returns the current balance of the account.
+    fn balance(&self, address: Address) -> EthResult<U256> {
+        Ok(DatabaseRef::basic_ref(&self.db, address)?.map(|acc| acc.balance).unwrap_or_default())
+    }
+
+    /// Moves `value` from one account to the other, without executing a transaction.
+    fn transfer(&mut self, from: Address, to: Address, value: U256) -> EthResult<()> {
+        // we need to fetch the accounts via the `DatabaseRef` to not update their state
+        let mut from_info = DatabaseRef::basic_ref(&self.db, from)?.unwrap_or_default();
+        from_info.balance = from_info.balance.saturating_sub(value);
+        self.db.insert_account_info(from, from_info);
+
+        let mut to_info = DatabaseRef::basic_ref(&self.db, to)?.unwrap_or_default();
+        to_info.balance = to_info.balance.saturating_add(value);
+        self.db.insert_account_info(to, to_info);
+
+        Ok(())
+    }
+}
+
+/// Errors of malformed `mev_simBundle` requests.
+#[derive(Debug, thiserror::Error)]
+enum MevBundleError {
+    /// Thrown if a bundle doesn't contain any items.
+    #[error("bundle body is empty")]
+    EmptyBundleBody,
+    /// Thrown if the bundle contains more than [MAX_BODY_SIZE] items, nested ones included.
+    #[error("bundle contains more than {MAX_BODY_SIZE} items")]
+    BundleTooLarge,
+    /// Thrown if bundles are nested deeper than [MAX_NESTING_DEPTH].
+    #[error("bundle nesting depth exceeds {MAX_NESTING_DEPTH}")]
+    MaxNestingDepthExceeded,
+    /// Thrown if the inclusion range of a bundle is empty or spans more than [MAX_BLOCK_RANGE]
+    /// blocks.
+    #[error("invalid inclusion range")]
+    InvalidInclusion,
+    /// Thrown if the bundle backruns a transaction or bundle by its hash.
+    #[error("only fully matched bundles can be simulated")]
+    UnmatchedBundle,
+    /// Thrown if a refund refers to an item which is not part of the body.
+    #[error("refund refers to missing body item {0}")]
+    InvalidRefundIndex(u64),
+    /// Thrown if the percentages of the refunds or refund recipients add up to more than 100.
+    #[error("refund percentages exceed 100")]
+    InvalidRefundPercent,
+}
+
+impl From<MevBundleError> for EthApiError {
+    fn from(err: MevBundleError) -> Self {
+        EthApiError::InvalidParams(err.to_string())
+    }
+}
+
+/// Reasons why a bundle can't be included in the simulated block.
+#[derive(Debug, thiserror::Error)]
+enum SimBundleError {
+    /// The simulated block is not in the inclusion range of a bundle.
+    #[error("bundle can't be included in block {0}")]
+    BlockNotInInclusionRange(u64),
+    /// A transaction can't be executed.
+    #[error("transaction {0} is invalid: {1}")]
+    InvalidTransaction(B256, RpcInvalidTransactionError),
+    /// The gas limit of a transaction exceeds the gas left in the simulated block.
+    #[error("transaction {0} exceeds the block gas limit")]
+    BlockGasLimitExceeded(B256),
+    /// A transaction which isn't allowed to revert reverted.
+    #[error("transaction {0} reverted")]
+    TransactionReverted(B256),
+    /// The refundable value doesn't cover the cost of paying out a refund.
+    #[error("refund of body item {0} doesn't cover its payout cost")]
+    NegativeRefundProfit(u64),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use reth_primitives::{
+        public_key_to_address, sign_message, Transaction, TransactionKind, TransactionSigned,
+        TxLegacy,
+    };
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use secp256k1::{PublicKey, SecretKey, SECP256K1};
+
+    const COINBASE: Address = Address::with_last_byte(0xc0);
+    const BASE_FEE: u64 = 1_000_000_000;
+    const ETHER: u128 = 1_000_000_000_000_000_000;
+
+    /// A funded account that signs transactions.
+    struct Signer {
+        key: SecretKey,
+        address: Address,
+        nonce: u64,
+    }
+
+    impl Signer {
+        fn new(provider: &MockEthProvider, seed: u8) -> Self {
+            let key = SecretKey::from_slice(&[seed; 32]).unwrap();
+            let address = public_key_to_address(PublicKey::from_secret_key(SECP256K1, &key));
+            provider.add_account(address, ExtendedAccount::new(0, U256::from(100 * ETHER)));
+            Self { key, address, nonce: 0 }
+        }
+
+        /// Signs a transfer that pays the base fee and no priority fee.
+        fn transfer(&mut self, to: Address, value: u128, gas_limit: u64) -> SimBundleItem {
+            let transaction = Transaction::Legacy(TxLegacy {
+                chain_id: Some(1),
+                nonce: self.nonce,
+                gas_price: BASE_FEE as u128,
+                gas_limit,
+                to: TransactionKind::Call(to),
+                value: value.into(),
+                input: Default::default(),
+            });
+            self.nonce += 1;
+            let signature =
+                sign_message(B256::from_slice(self.key.as_ref()), transaction.signature_hash())
+                    .unwrap();
+            let tx = TransactionSigned::from_transaction_and_signature(transaction, signature)
+                .into_ecrecovered()
+                .unwrap();
+            SimBundleItem::Tx { tx, can_revert: false }
+        }
+    }
+
+    fn bundle(body: Vec<SimBundleItem>, refunds: Vec<Refund>) -> SimBundle {
+        SimBundle { inclusion: 1..=1, body, refunds, refund_config: Vec::new() }
+    }
+
+    fn simulator(
+        provider: MockEthProvider,
+        gas_limit: u64,
+    ) -> BundleSimulator<StateProviderDatabase<MockEthProvider>> {
+        let mut env = Env::default();
+        env.block.number = U256::from(1);
+        env.block.coinbase = COINBASE;
+        env.block.gas_limit = U256::from(gas_limit);
+        env.block.basefee = U256::from(BASE_FEE);
+        BundleSimulator {
+            db: CacheDB::new(StateProviderDatabase::new(provider)),
+            env,
+            deadline: Instant::now() + DEFAULT_SIM_TIMEOUT,
+            timeout: DEFAULT_SIM_TIMEOUT,
+            block_gas_used: 0,
+        }
+    }
+
+    #[test]
+    fn refund_percentages_are_bounded() {
+        assert!(!exceeds_full_refund([].into_iter()));
+        assert!(!exceeds_full_refund([60, 40].into_iter()));
+        assert!(exceeds_full_refund([60, 41].into_iter()));
+        assert!(exceeds_full_refund([101].into_iter()));
+        // the sum wraps around to 1
+        assert!(exceeds_full_refund([u64::MAX, 2].into_iter()));
+    }
+
+    #[test]
+    fn sim_bundle_pays_refund() {
+        let provider = MockEthProvider::default();
+        let mut user = Signer::new(&provider, 1);
+        let mut searcher = Signer::new(&provider, 2);
+        let user_address = user.address;
+
+        let bundle = bundle(
+            vec![
+                user.transfer(Address::with_last_byte(3), 0, 21_000),
+                searcher.transfer(COINBASE, ETHER, 21_000),
+            ],
+            vec![Refund { body_idx: 0, percent: 90 }],
+        );
+        let mut simulator = simulator(provider, 30_000_000);
+        let output = simulator.simulate(&bundle).unwrap().unwrap();
+
+        let max_profit = U256::from(ETHER * 9 / 10);
+        let payout = max_profit - U256::from(BASE_FEE * REFUND_PAYOUT_GAS);
+        assert_eq!(output.gas_used, 42_000);
+        assert_eq!(output.refundable_value, U256::from(ETHER));
+        assert_eq!(output.profit, U256::from(ETHER) - max_profit);
+        assert_eq!(simulator.balance(COINBASE).unwrap(), U256::from(ETHER) - payout);
+        assert_eq!(
+            simulator.balance(user_address).unwrap(),
+            U256::from(100 * ETHER) - U256::from(BASE_FEE * 21_000) + payout
+        );
+    }
+
+    #[test]
+    fn sim_bundle_rejects_unprofitable_refund() {
+        let provider = MockEthProvider::default();
+        let mut user = Signer::new(&provider, 1);
+        let mut searcher = Signer::new(&provider, 2);
+
+        // the backrun doesn't cover the cost of the refund payout
+        let bundle = bundle(
+            vec![
+                user.transfer(Address::with_last_byte(3), 0, 21_000),
+                searcher.transfer(COINBASE, 1_000, 21_000),
+            ],
+            vec![Refund { body_idx: 0, percent: 90 }],
+        );
+        let result = simulator(provider, 30_000_000).simulate(&bundle).unwrap();
+        assert_matches!(result, Err(SimBundleError::NegativeRefundProfit(0)));
+    }
+
+    #[test]
+    fn sim_bundle_refunds_nested_bundle_recipients() {
+        let provider = MockEthProvider::default();
+        let mut user = Signer::new(&provider, 1);
+        let mut searcher = Signer::new(&provider, 2);
+        let (first, second) = (Address::with_last_byte(4), Address::with_last_byte(5));
+
+        let mut nested = bundle(vec![user.transfer(Address::with_last_byte(3), 0, 21_000)], vec![]);
+        nested.refund_config = vec![
+            RefundConfig { address: first, percent: 60 },
+            RefundConfig { address: second, percent: 40 },
+        ];
+        let bundle = bundle(
+            vec![SimBundleItem::Bundle(nested), searcher.transfer(COINBASE, ETHER, 21_000)],
+            vec![Refund { body_idx: 0, percent: 50 }],
+        );
+        let mut simulator = simulator(provider, 30_000_000);
+        let output = simulator.simulate(&bundle).unwrap().unwrap();
+
+        let max_profit = U256::from(ETHER / 2);
+        let payout = max_profit - U256::from(BASE_FEE * REFUND_PAYOUT_GAS * 2);
+        assert_eq!(output.gas_used, 42_000);
+        assert_eq!(output.profit, U256::from(ETHER) - max_profit);
+        assert_eq!(simulator.balance(first).unwrap(), payout * U256::from(60) / U256::from(100));
+        assert_eq!(simulator.balance(second).unwrap(), payout * U256::from(40) / U256::from(100));
+
+        assert_eq!(output.logs.len(), 2);
+        assert_matches!(&output.logs[0].bundle_logs, Some(logs) if logs.len() == 1);
+        assert_matches!(&output.logs[1].tx_logs, Some(_));
+    }
+
+    #[test]
+    fn sim_bundle_respects_block_gas_limit() {
+        let provider = MockEthProvider::default();
+        let mut searcher = Signer::new(&provider, 2);
+
+        // the second transaction only fits if the gas used by the nested bundle is counted
+        let nested = bundle(vec![searcher.transfer(COINBASE, 0, 21_000)], vec![]);
+        let second = searcher.transfer(COINBASE, 0, 30_000);
+        let hash = match &second {
+            SimBundleItem::Tx { tx, .. } => tx.hash(),
+            SimBundleItem::Bundle(_) => unreachable!(),
+        };
+        let bundle = bundle(vec![SimBundleItem::Bundle(nested), second], vec![]);
+
+        let result = simulator(provider, 50_000).simulate(&bundle).unwrap();
+        assert_matches!(result, Err(SimBundleError::BlockGasLimitExceeded(h)) if h == hash);
+    }
+
+    #[test]
+    fn sim_bundle_times_out() {
+        let provider = MockEthProvider::default();
+        let mut searcher = Signer::new(&provider, 2);
+        let bundle = bundle(vec![searcher.transfer(COINBASE, 0, 21_000)], vec![]);
+
+        let mut simulator = simulator(provider, 30_000_000);
+        simulator.deadline = Instant::now() - Duration::from_secs(1);
+        assert_matches!(simulator.simulate(&bundle), Err(EthApiError::ExecutionTimedOut(_)));
+    }
+}
@@ -1,37 +1,222 @@
-#![allow(dead_code, unused_variables)]
-use crate::result::internal_rpc_err;
+use crate::{
+    eth::{
+        error::{EthApiError, EthResult},
+        EthTransactions,
+    },
+    result::internal_rpc_err,
+};
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
-use reth_primitives::{Address, BlockId, BlockNumberOrTag, TxHash, B256};
+use reth_primitives::{
+    Address, BlockId, BlockNumber, BlockNumberOrTag, Bloom, Bytes, TxHash, B256, U256,
+};
+use reth_provider::{BlockNumReader, HistoryReader};
+use reth_revm::tracing::{
+    types::{CallKind, CallTraceNode},
+    TracingInspectorConfig,
+};
 use reth_rpc_api::{EthApiServer, OtterscanServer};
 use reth_rpc_types::{
-    BlockDetails, ContractCreator, InternalOperation, OtsBlockTransactions, TraceEntry,
-    Transaction, TransactionsWithReceipts,
+    BlockDetails, BlockTransactions, ContractCreator, InternalOperation, OperationType, OtsBlock,
+    OtsBlockTransactions, OtsTransactionReceipt, TraceEntry, Transaction, TransactionsWithReceipts,
 };
+use reth_tasks::TaskSpawner;
+use revm::primitives::ExecutionResult;
+use std::{future::Future, sync::Arc};
+use tokio::sync::oneshot;
 
 const API_LEVEL: u64 = 8;
 
 /// Otterscan Api
-#[derive(Debug)]
-pub struct OtterscanApi<Eth> {
-    eth: Eth,
+pub struct OtterscanApi<Provider, Eth> {
+    inner: Arc<OtterscanApiInner<Provider, Eth>>,
 }
 
-impl<Eth> OtterscanApi<Eth> {
+// === impl OtterscanApi ===
+
+impl<Provider, Eth> OtterscanApi<Provider, Eth> {
     /// Creates a new instance of `Otterscan`.
-    pub fn new(eth: Eth) -> Self {
-        Self { eth }
+    pub fn new(provider: Provider, eth: Eth, task_spawner: Box<dyn TaskSpawner>) -> Self {
+        let inner = Arc::new(OtterscanApiInner { provider, eth, task_spawner });
+        Self { inner }
+    }
+
+    /// The provider that can interact with the chain.
+    pub fn provider(&self) -> &Provider {
+        &self.inner.provider
+    }
+}
+
+impl<Provider, Eth> OtterscanApi<Provider, Eth>
+where
+    Provider: BlockNumReader + HistoryReader + 'static,
+    Eth: EthApiServer + EthTransactions + 'static,
+{
+    /// Executes the future on a new blocking task.
+    async fn on_blocking_task<C, F, R>(&self, c: C) -> EthResult<R>
+    where
+        C: FnOnce(Self) -> F,
+        F: Future<Output = EthResult<R>> + Send + 'static,
+        R: Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        let this = self.clone();
+        let f = c(this);
+        self.inner.task_spawner.spawn_blocking(Box::pin(async move {
+            let res = f.await;
+            let _ = tx.send(res);
+        }));
+        rx.await.map_err(|_| EthApiError::InternalEthError)?
+    }
+
+    /// Returns up to `limit` blocks in the given range in which the account or its storage
+    /// changed, walking backwards from the end of the range if `reverse` is set.
+    ///
+    /// The end of the range defaults to and is capped at the best block.
+    async fn history_blocks(
+        &self,
+        address: Address,
+        start: BlockNumber,
+        end: Option<BlockNumber>,
+        limit: usize,
+        reverse: bool,
+    ) -> EthResult<Vec<BlockNumber>> {
+        self.on_blocking_task(|this| async move {
+            let best = this.provider().best_block_number()?;
+            let end = end.map_or(best, |end| end.min(best));
+            if start > end {
+                return Ok(Vec::new())
+            }
+            Ok(this.provider().account_history_blocks(address, start..=end, limit, reverse)?)
+        })
+        .await
+    }
+
+    /// Collects the transactions touching the address from the blocks of the given range in which
+    /// the account or its storage changed, until at least `page_size` transactions are found.
+    ///
+    /// The blocks are searched from the start of the range, or from its end if `reverse` is set,
+    /// and fetched from the history indices one page at a time. A block is never split across
+    /// pages. Returns the transactions in the searched order, and whether the range contains more
+    /// blocks to search.
+    async fn search_transactions(
+        &self,
+        address: Address,
+        mut start: BlockNumber,
+        mut end: Option<BlockNumber>,
+        page_size: usize,
+        reverse: bool,
+    ) -> RpcResult<(Vec<Transaction>, Vec<OtsTransactionReceipt>, bool)> {
+        let limit = page_size.max(1);
+        let mut txs = Vec::new();
+        let mut receipts = Vec::new();
+
+        loop {
+            let blocks = self.history_blocks(address, start, end, limit, reverse).await?;
+            let fetched = blocks.len();
+            let mut blocks = blocks.into_iter();
+            while let Some(block) = blocks.next() {
+                let (block_txs, block_receipts) =
+                    self.block_transactions_touching(address, block).await?;
+                if reverse {
+                    txs.extend(block_txs.into_iter().rev());
+                    receipts.extend(block_receipts.into_iter().rev());
+                } else {
+                    txs.extend(block_txs);
+                    receipts.extend(block_receipts);
+                }
+
+                // narrow the range to the blocks that haven't been searched yet
+                let rest = if reverse {
+                    block.checked_sub(1).filter(|end| *end >= start).map(|end| (start, Some(end)))
+                } else {
+                    block.checked_add(1).map(|start| (start, end))
+                };
+                let Some(rest) = rest else { return Ok((txs, receipts, false)) };
+                (start, end) = rest;
+
+                if txs.len() >= page_size {
+                    let has_more = blocks.len() > 0 ||
+                        !self.history_blocks(address, start, end, 1, reverse).await?.is_empty();
+                    return Ok((txs, receipts, has_more))
+                }
+            }
+
+            if fetched < limit {
+                return Ok((txs, receipts, false))
+            }
+        }
+    }
+
+    /// Replays the given block and returns all transactions of the block that touch the given
+    /// address in any of their call frames, in the order they appear in the block, together with
+    /// their receipts.
+    async fn block_transactions_touching(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+    ) -> RpcResult<(Vec<Transaction>, Vec<OtsTransactionReceipt>)> {
+        let block_id = BlockId::Number(block_number.into());
+        let indices = self
+            .inner
+            .eth
+            .trace_block_with(
+                block_id,
+                TracingInspectorConfig::default_parity(),
+                move |tx_info, inspector, _, _, _| {
+                    Ok(is_touched(inspector.get_traces().nodes(), address)
+                        .then_some(tx_info.index)
+                        .flatten())
+                },
+            )
+            .await?
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        if indices.is_empty() {
+            return Ok(Default::default())
+        }
+
+        let (block, receipts) = futures::try_join!(
+            self.inner.eth.block_by_number(block_number.into(), true),
+            self.inner.eth.block_receipts(block_id),
+        )?;
+        let block = block.ok_or(EthApiError::UnknownBlockNumber)?;
+        let mut receipts = receipts.ok_or(EthApiError::UnknownBlockNumber)?;
+        let timestamp = block.header.timestamp.saturating_to::<u64>();
+        let BlockTransactions::Full(mut transactions) = block.inner.transactions else {
+            return Err(internal_rpc_err("block transactions are not available"))
+        };
+
+        let mut txs = Vec::with_capacity(indices.len());
+        let mut tx_receipts = Vec::with_capacity(indices.len());
+        for index in indices {
+            let index = index as usize;
+            if index >= transactions.len() || index >= receipts.len() {
+                return Err(EthApiError::UnknownBlockOrTxIndex.into())
+            }
+            txs.push(std::mem::take(&mut transactions[index]));
+            tx_receipts.push(OtsTransactionReceipt {
+                receipt: std::mem::take(&mut receipts[index]),
+                timestamp,
+            });
+        }
+
+        Ok((txs, tx_receipts))
     }
 }
 
 #[async_trait]
-impl<Eth> OtterscanServer for OtterscanApi<Eth>
+impl<Provider, Eth> OtterscanServer for OtterscanApi<Provider, Eth>
 where
-    Eth: EthApiServer,
+    Provider: BlockNumReader + HistoryReader + 'static,
+    Eth: EthApiServer + EthTransactions + 'static,
 {
     /// Handler for `ots_hasCode`
     async fn has_code(&self, address: Address, block_number: Option<BlockId>) -> RpcResult<bool> {
-        self.eth.get_code(address, block_number).await.map(|code| !code.is_empty())
+        self.inner.eth.get_code(address, block_number).await.map(|code| !code.is_empty())
     }
 
     /// Handler for `ots_getApiLevel`
@@ -41,17 +226,52 @@ where
 
     /// Handler for `ots_getInternalOperations`
     async fn get_internal_operations(&self, tx_hash: TxHash) -> RpcResult<Vec<InternalOperation>> {
-        Err(internal_rpc_err("unimplemented"))
+        let operations = self
+            .inner
+            .eth
+            .spawn_trace_transaction_in_block(
+                tx_hash,
+                TracingInspectorConfig::default_parity(),
+                move |_, inspector, _, _| Ok(internal_operations(inspector.get_traces().nodes())),
+            )
+            .await?
+            .ok_or(EthApiError::TransactionNotFound)?;
+        Ok(operations)
     }
 
     /// Handler for `ots_getTransactionError`
-    async fn get_transaction_error(&self, tx_hash: TxHash) -> RpcResult<String> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn get_transaction_error(&self, tx_hash: TxHash) -> RpcResult<Bytes> {
+        let output = self
+            .inner
+            .eth
+            .spawn_trace_transaction_in_block(
+                tx_hash,
+                TracingInspectorConfig::default_parity(),
+                move |_, _, res, _| {
+                    Ok(match res.result {
+                        ExecutionResult::Revert { output, .. } => output,
+                        _ => Bytes::default(),
+                    })
+                },
+            )
+            .await?
+            .ok_or(EthApiError::TransactionNotFound)?;
+        Ok(output)
     }
 
     /// Handler for `ots_traceTransaction`
-    async fn trace_transaction(&self, tx_hash: TxHash) -> RpcResult<TraceEntry> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn trace_transaction(&self, tx_hash: TxHash) -> RpcResult<Vec<TraceEntry>> {
+        let entries = self
+            .inner
+            .eth
+            .spawn_trace_transaction_in_block(
+                tx_hash,
+                TracingInspectorConfig::default_parity().set_exclude_precompile_calls(false),
+                move |_, inspector, _, _| Ok(trace_entries(inspector.get_traces().nodes())),
+            )
+            .await?
+            .ok_or(EthApiError::TransactionNotFound)?;
+        Ok(entries)
     }
 
     /// Handler for `ots_getBlockDetails`
@@ -59,13 +279,13 @@ where
         &self,
         block_number: BlockNumberOrTag,
     ) -> RpcResult<Option<BlockDetails>> {
-        let block = self.eth.block_by_number(block_number, true).await?;
+        let block = self.inner.eth.block_by_number(block_number, true).await?;
         Ok(block.map(Into::into))
     }
 
     /// Handler for `getBlockDetailsByHash`
     async fn get_block_details_by_hash(&self, block_hash: B256) -> RpcResult<Option<BlockDetails>> {
-        let block = self.eth.block_by_hash(block_hash, true).await?;
+        let block = self.inner.eth.block_by_hash(block_hash, true).await?;
         Ok(block.map(Into::into))
     }
 
@@ -76,7 +296,43 @@ where
         page_number: usize,
         page_size: usize,
     ) -> RpcResult<OtsBlockTransactions> {
-        Err(internal_rpc_err("unimplemented"))
+        let (block, receipts) = futures::try_join!(
+            self.inner.eth.block_by_number(block_number, true),
+            self.inner.eth.block_receipts(BlockId::Number(block_number)),
+        )?;
+        let mut block = block.ok_or(EthApiError::UnknownBlockNumber)?.inner;
+        let mut receipts = receipts.ok_or(EthApiError::UnknownBlockNumber)?;
+        let timestamp = block.header.timestamp.saturating_to::<u64>();
+        let BlockTransactions::Full(transactions) = &mut block.transactions else {
+            return Err(internal_rpc_err("block transactions are not available"))
+        };
+        let transaction_count = transactions.len();
+
+        // pages are counted from the end of the block, so the first page contains the last
+        // transactions
+        let page_end = transaction_count.saturating_sub(page_number.saturating_mul(page_size));
+        let page_start = page_end.saturating_sub(page_size);
+
+        *transactions = transactions
+            .drain(page_start..page_end)
+            .map(|mut tx| {
+                // only the selector is of interest
+                tx.input.truncate(4);
+                tx
+            })
+            .collect();
+        // receipts may be missing, e.g. if they were pruned
+        let receipts_end = page_end.min(receipts.len());
+        let receipts = receipts
+            .drain(page_start.min(receipts_end)..receipts_end)
+            .map(|mut receipt| {
+                receipt.logs = Vec::new();
+                receipt.logs_bloom = Bloom::default();
+                OtsTransactionReceipt { receipt, timestamp }
+            })
+            .collect();
+
+        Ok(OtsBlockTransactions { fullblock: OtsBlock { block, transaction_count }, receipts })
     }
 
     /// Handler for `searchTransactionsBefore`
@@ -86,7 +342,13 @@ where
         block_number: BlockNumberOrTag,
         page_size: usize,
     ) -> RpcResult<TransactionsWithReceipts> {
-        Err(internal_rpc_err("unimplemented"))
+        let block_number = search_block_number(block_number);
+        let first_page = block_number == 0;
+        let end = if first_page { None } else { Some(block_number - 1) };
+        let (txs, receipts, has_more) =
+            self.search_transactions(address, 0, end, page_size, true).await?;
+
+        Ok(TransactionsWithReceipts { txs, receipts, first_page, last_page: !has_more })
     }
 
     /// Handler for `searchTransactionsAfter`
@@ -96,20 +358,242 @@ where
         block_number: BlockNumberOrTag,
         page_size: usize,
     ) -> RpcResult<TransactionsWithReceipts> {
-        Err(internal_rpc_err("unimplemented"))
+        let block_number = search_block_number(block_number);
+        let last_page = block_number == 0;
+        let (mut txs, mut receipts, has_more) = self
+            .search_transactions(address, block_number.saturating_add(1), None, page_size, false)
+            .await?;
+
+        // most recent transactions first
+        txs.reverse();
+        receipts.reverse();
+
+        Ok(TransactionsWithReceipts { txs, receipts, first_page: !has_more, last_page })
     }
 
     /// Handler for `getTransactionBySenderAndNonce`
     async fn get_transaction_by_sender_and_nonce(
         &self,
-        sender: Address,
-        nonce: u64,
+        _sender: Address,
+        _nonce: u64,
     ) -> RpcResult<Option<Transaction>> {
         Err(internal_rpc_err("unimplemented"))
     }
 
     /// Handler for `getContractCreator`
-    async fn get_contract_creator(&self, address: Address) -> RpcResult<Option<ContractCreator>> {
+    async fn get_contract_creator(&self, _address: Address) -> RpcResult<Option<ContractCreator>> {
         Err(internal_rpc_err("unimplemented"))
     }
 }
+
+impl<Provider, Eth> std::fmt::Debug for OtterscanApi<Provider, Eth> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OtterscanApi").finish_non_exhaustive()
+    }
+}
+
+impl<Provider, Eth> Clone for OtterscanApi<Provider, Eth> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+struct OtterscanApiInner<Provider, Eth> {
+    /// The provider that can interact with the chain.
+    provider: Provider,
+    /// The implementation of `eth` API
+    eth: Eth,
+    /// The type that can spawn tasks which would otherwise block.
+    task_spawner: Box<dyn TaskSpawner>,
+}
+
+/// Converts the block number argument of the search endpoints.
+///
+/// Like Erigon, block 0 is used as the sentinel for "from the tip" or "from genesis", which is also
+/// what any block tag resolves to.
+fn search_block_number(block_number: BlockNumberOrTag) -> BlockNumber {
+    match block_number {
+        BlockNumberOrTag::Number(number) => number,
+        _ => 0,
+    }
+}
+
+/// Returns true if the address is the caller, the callee or the selfdestruct beneficiary in any of
+/// the recorded call frames.
+fn is_touched(nodes: &[CallTraceNode], address: Address) -> bool {
+    nodes.iter().any(|node| {
+        node.trace.caller == address ||
+            node.trace.address == address ||
+            node.trace.selfdestruct_refund_target == Some(address)
+    })
+}
+
+/// Returns the indices of the nodes in the order the calls were entered, paired with a flag that
+/// is set once all children of the node have been visited.
+///
+/// This is used to emit selfdestructs after all calls of the selfdestructing frame.
+fn walk_nodes(nodes: &[CallTraceNode]) -> Vec<(usize, bool)> {
+    let mut walked = Vec::with_capacity(nodes.len() * 2);
+    let mut stack = Vec::new();
+    if !nodes.is_empty() {
+        stack.push((0, false));
+    }
+    while let Some((idx, exit)) = stack.pop() {
+        walked.push((idx, exit));
+        if !exit {
+            stack.push((idx, true));
+            stack.extend(nodes[idx].children.iter().rev().map(|child| (*child, false)));
+        }
+    }
+    walked
+}
+
+/// Builds the internal value transfers, contract creations and selfdestructs of a transaction
+/// from the recorded call frames.
+fn internal_operations(nodes: &[CallTraceNode]) -> Vec<InternalOperation> {
+    let mut operations = Vec::new();
+    for (idx, exit) in walk_nodes(nodes) {
+        let node = &nodes[idx];
+        let trace = &node.trace;
+        if exit {
+            if node.is_selfdestruct() {
+                operations.push(InternalOperation {
+                    r#type: OperationType::OpSelfDestruct,
+                    from: trace.address,
+                    to: trace.selfdestruct_refund_target.unwrap_or_default(),
+                    value: trace.value,
+                });
+            }
+            continue
+        }
+
+        // the top level call is the transaction itself
+        if node.parent.is_none() {
+            continue
+        }
+        let r#type = match trace.kind {
+            CallKind::Call if trace.value > U256::ZERO => OperationType::OpTransfer,
+            CallKind::Create => OperationType::OpCreate,
+            CallKind::Create2 => OperationType::OpCreate2,
+            _ => continue,
+        };
+        operations.push(InternalOperation {
+            r#type,
+            from: trace.caller,
+            to: trace.address,
+            value: trace.value,
+        });
+    }
+    operations
+}
+
+/// Builds the flattened call tree of a transaction from the recorded call frames.
+fn trace_entries(nodes: &[CallTraceNode]) -> Vec<TraceEntry> {
+    let mut entries = Vec::with_capacity(nodes.len());
+    for (idx, exit) in walk_nodes(nodes) {
+        let node = &nodes[idx];
+        let trace = &node.trace;
+        if exit {
+            if node.is_selfdestruct() {
+                entries.push(TraceEntry {
+                    r#type: "SELFDESTRUCT".to_string(),
+                    depth: trace.depth as u32 + 1,
+                    from: trace.address,
+                    to: trace.selfdestruct_refund_target.unwrap_or_default(),
+                    value: Some(trace.value),
+                    input: Bytes::default(),
+                    output: Bytes::default(),
+                });
+            }
+            continue
+        }
+
+        let value = match trace.kind {
+            CallKind::StaticCall | CallKind::DelegateCall => None,
+            _ => Some(trace.value),
+        };
+        entries.push(TraceEntry {
+            r#type: trace.kind.to_string(),
+            depth: trace.depth as u32,
+            from: trace.caller,
+            to: trace.address,
+            value,
+            input: trace.data.clone(),
+            output: trace.output.clone(),
+        });
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_revm::tracing::types::CallTrace;
+    use revm::interpreter::InstructionResult;
+
+    fn node(
+        idx: usize,
+        parent: Option<usize>,
+        children: Vec<usize>,
+        kind: CallKind,
+        value: u64,
+    ) -> CallTraceNode {
+        CallTraceNode {
+            parent,
+            children,
+            idx,
+            trace: CallTrace {
+                depth: parent.map_or(0, |_| 1),
+                caller: Address::with_last_byte(idx as u8),
+                address: Address::with_last_byte(idx as u8 + 1),
+                kind,
+                value: U256::from(value),
+                status: InstructionResult::Return,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn internal_operations_from_nodes() {
+        let mut root = node(0, None, vec![1, 2, 3], CallKind::Call, 5);
+        root.trace.status = InstructionResult::SelfDestruct;
+        root.trace.selfdestruct_refund_target = Some(Address::with_last_byte(9));
+        let nodes = vec![
+            root,
+            node(1, Some(0), vec![], CallKind::Call, 1),
+            node(2, Some(0), vec![], CallKind::StaticCall, 0),
+            node(3, Some(0), vec![], CallKind::Create2, 0),
+        ];
+
+        let operations = internal_operations(&nodes);
+        let types = operations.iter().map(|op| op.r#type).collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            vec![
+                OperationType::OpTransfer,
+                OperationType::OpCreate2,
+                OperationType::OpSelfDestruct
+            ]
+        );
+        assert_eq!(operations[2].to, Address::with_last_byte(9));
+
+        let entries = trace_entries(&nodes);
+        let types = entries.iter().map(|entry| entry.r#type.as_str()).collect::<Vec<_>>();
+        assert_eq!(types, vec!["CALL", "CALL", "STATICCALL", "CREATE2", "SELFDESTRUCT"]);
+        assert_eq!(entries[2].value, None);
+        assert_eq!(entries[4].depth, 1);
+    }
+
+    #[test]
+    fn touched_addresses() {
+        let nodes = vec![
+            node(0, None, vec![1], CallKind::Call, 0),
+            node(1, Some(0), vec![], CallKind::Call, 0),
+        ];
+        assert!(is_touched(&nodes, Address::with_last_byte(0)));
+        assert!(is_touched(&nodes, Address::with_last_byte(2)));
+        assert!(!is_touched(&nodes, Address::with_last_byte(3)));
+    }
+}
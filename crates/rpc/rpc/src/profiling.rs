@@ -0,0 +1,244 @@
+//! Memory and CPU profiling support for the `debug` namespace.
+//!
+//! Memory statistics and heap profiles are read from jemalloc and require the `jemalloc` feature.
+//! CPU profiles are collected by an in-process sampler and require the `cpu-profiling` feature.
+//!
+//! Profiles are only written to the configured profiles directory, callers can only choose the
+//! file name.
+
+use crate::eth::error::{EthApiError, EthResult};
+use reth_rpc_types::MemStats;
+use std::{
+    path::{Component, Path, PathBuf},
+    time::Duration,
+};
+
+/// The maximum duration of a CPU profile collected by `debug_cpuProfile`.
+pub(crate) const MAX_CPU_PROFILE_DURATION: Duration = Duration::from_secs(5 * 60);
+
+/// Returns the path in the profiles directory that a profile with the given file name is written
+/// to.
+///
+/// The file must be a plain file name, absolute paths and paths that leave the profiles directory
+/// are rejected. The profiles directory is created if it doesn't exist.
+pub(crate) fn profile_path(profiles_dir: Option<&Path>, file: &str) -> EthResult<PathBuf> {
+    let Some(profiles_dir) = profiles_dir else {
+        return Err(unavailable("no profiles directory is configured"))
+    };
+
+    let mut components = Path::new(file).components();
+    let (Some(Component::Normal(file_name)), None) = (components.next(), components.next()) else {
+        return Err(EthApiError::InvalidParams(format!(
+            "profile file must be a file name inside the profiles directory, got {file:?}"
+        )))
+    };
+
+    reth_primitives::fs::create_dir_all(profiles_dir).map_err(|err| {
+        EthApiError::Internal(reth_interfaces::RethError::Custom(err.to_string()))
+    })?;
+    Ok(profiles_dir.join(file_name))
+}
+
+/// Returns the statistics of the jemalloc allocator.
+#[cfg(all(feature = "jemalloc", unix))]
+pub(crate) fn mem_stats() -> EthResult<MemStats> {
+    use jemalloc_ctl::{epoch, stats};
+
+    // the statistics are cached and only refreshed when the epoch is advanced
+    epoch::advance().map_err(jemalloc_err)?;
+
+    Ok(MemStats {
+        allocated: stats::allocated::read().map_err(jemalloc_err)? as u64,
+        active: stats::active::read().map_err(jemalloc_err)? as u64,
+        metadata: stats::metadata::read().map_err(jemalloc_err)? as u64,
+        resident: stats::resident::read().map_err(jemalloc_err)? as u64,
+        mapped: stats::mapped::read().map_err(jemalloc_err)? as u64,
+        retained: stats::retained::read().map_err(jemalloc_err)? as u64,
+    })
+}
+
+/// Returns the statistics of the jemalloc allocator.
+#[cfg(not(all(feature = "jemalloc", unix)))]
+pub(crate) fn mem_stats() -> EthResult<MemStats> {
+    Err(unavailable("memory statistics require the jemalloc allocator"))
+}
+
+/// Dumps a jemalloc heap profile to the given file.
+///
+/// Heap profiling must be enabled when the node is started, e.g. with
+/// `_RJEM_MALLOC_CONF=prof:true` and the `jemalloc-prof` feature.
+#[cfg(all(feature = "jemalloc", unix))]
+pub(crate) fn write_mem_profile(file: PathBuf) -> EthResult<()> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    // SAFETY: `opt.prof` is a boolean option.
+    let enabled: bool = unsafe { jemalloc_ctl::raw::read(b"opt.prof\0") }.map_err(jemalloc_err)?;
+    if !enabled {
+        return Err(unavailable("heap profiling is not enabled"))
+    }
+
+    let path = CString::new(file.as_os_str().as_bytes())
+        .map_err(|_| EthApiError::InvalidParams("invalid profile file path".to_string()))?;
+    // SAFETY: `prof.dump` expects a pointer to a nul-terminated file path, which outlives the call.
+    unsafe { jemalloc_ctl::raw::write(b"prof.dump\0", path.as_ptr()) }.map_err(jemalloc_err)
+}
+
+/// Dumps a jemalloc heap profile to the given file.
+#[cfg(not(all(feature = "jemalloc", unix)))]
+pub(crate) fn write_mem_profile(_file: PathBuf) -> EthResult<()> {
+    Err(unavailable("heap profiles require the jemalloc allocator"))
+}
+
+/// Returns the error of a profiling request that can't be served by this node.
+///
+/// This is an internal error rather than [EthApiError::Unsupported], because the method exists but
+/// the node wasn't built or started with the required support.
+fn unavailable(reason: &str) -> EthApiError {
+    EthApiError::Internal(reth_interfaces::RethError::Custom(reason.to_string()))
+}
+
+#[cfg(all(feature = "jemalloc", unix))]
+fn jemalloc_err(err: jemalloc_ctl::Error) -> EthApiError {
+    EthApiError::Internal(reth_interfaces::RethError::Custom(format!("jemalloc: {err}")))
+}
+
+/// The sampling frequency of CPU profiles, in Hz.
+#[cfg(all(feature = "cpu-profiling", unix))]
+const CPU_PROFILE_FREQUENCY: i32 = 100;
+
+/// Keeps track of the CPU profile that is currently being collected, if any.
+///
+/// Only a single CPU profile can be collected at a time.
+#[derive(Default)]
+pub(crate) struct CpuProfiler {
+    /// The running profiler and the file the profile is written to once it is stopped.
+    #[cfg(all(feature = "cpu-profiling", unix))]
+    active: std::sync::Mutex<Option<(pprof::ProfilerGuard<'static>, PathBuf)>>,
+}
+
+#[cfg(all(feature = "cpu-profiling", unix))]
+impl CpuProfiler {
+    /// Starts collecting a CPU profile that is written to the given file once it is stopped.
+    pub(crate) fn start(&self, file: PathBuf) -> EthResult<()> {
+        let mut active = self.active.lock().expect("not poisoned");
+        if active.is_some() {
+            return Err(EthApiError::InvalidParams("CPU profiling already in progress".to_string()))
+        }
+
+        let guard = pprof::ProfilerGuardBuilder::default()
+            .frequency(CPU_PROFILE_FREQUENCY)
+            .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+            .build()
+            .map_err(pprof_err)?;
+        *active = Some((guard, file));
+        Ok(())
+    }
+
+    /// Stops collecting the current CPU profile and writes it to its file in the pprof format.
+    ///
+    /// Note: this symbolizes the collected samples and should be called on a blocking task.
+    pub(crate) fn stop(&self) -> EthResult<()> {
+        use pprof::protos::Message;
+
+        let (guard, file) = self.active.lock().expect("not poisoned").take().ok_or_else(|| {
+            EthApiError::InvalidParams("CPU profiling not in progress".to_string())
+        })?;
+
+        let profile = guard.report().build().map_err(pprof_err)?.pprof().map_err(pprof_err)?;
+        let mut content = Vec::new();
+        profile.encode(&mut content).map_err(pprof_err)?;
+        reth_primitives::fs::write(file, content).map_err(|err| {
+            EthApiError::Internal(reth_interfaces::RethError::Custom(err.to_string()))
+        })
+    }
+}
+
+#[cfg(not(all(feature = "cpu-profiling", unix)))]
+impl CpuProfiler {
+    /// Starts collecting a CPU profile that is written to the given file once it is stopped.
+    pub(crate) fn start(&self, _file: PathBuf) -> EthResult<()> {
+        Err(unavailable("CPU profiles require the cpu-profiling feature"))
+    }
+
+    /// Stops collecting the current CPU profile and writes it to its file in the pprof format.
+    pub(crate) fn stop(&self) -> EthResult<()> {
+        Err(unavailable("CPU profiles require the cpu-profiling feature"))
+    }
+}
+
+#[cfg(all(feature = "cpu-profiling", unix))]
+fn pprof_err(err: impl std::fmt::Display) -> EthApiError {
+    EthApiError::Internal(reth_interfaces::RethError::Custom(format!("pprof: {err}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpsee::types::{error::INTERNAL_ERROR_CODE, ErrorObject};
+
+    fn error_code(err: EthApiError) -> i32 {
+        ErrorObject::from(err).code()
+    }
+
+    #[test]
+    fn profiles_are_written_to_profiles_dir() {
+        use jsonrpsee::types::error::INVALID_PARAMS_CODE;
+
+        let dir = tempfile::tempdir().unwrap();
+        let profiles_dir = dir.path().join("profiles");
+
+        assert_eq!(
+            profile_path(Some(&profiles_dir), "cpu.prof").unwrap(),
+            profiles_dir.join("cpu.prof")
+        );
+        assert!(profiles_dir.is_dir());
+
+        for file in ["", "/etc/passwd", "../cpu.prof", "./cpu.prof", "nested/cpu.prof", ".."] {
+            assert_eq!(
+                profile_path(Some(&profiles_dir), file).map_err(error_code),
+                Err(INVALID_PARAMS_CODE),
+                "{file}"
+            );
+        }
+        assert_eq!(profile_path(None, "cpu.prof").map_err(error_code), Err(INTERNAL_ERROR_CODE));
+    }
+
+    #[test]
+    #[cfg(not(all(feature = "jemalloc", unix)))]
+    fn memory_profiling_requires_jemalloc() {
+        assert_eq!(mem_stats().map_err(error_code), Err(INTERNAL_ERROR_CODE));
+        assert_eq!(
+            write_mem_profile(PathBuf::from("heap.prof")).map_err(error_code),
+            Err(INTERNAL_ERROR_CODE)
+        );
+    }
+
+    #[test]
+    #[cfg(not(all(feature = "cpu-profiling", unix)))]
+    fn cpu_profiling_requires_feature() {
+        let profiler = CpuProfiler::default();
+        assert_eq!(
+            profiler.start(PathBuf::from("cpu.prof")).map_err(error_code),
+            Err(INTERNAL_ERROR_CODE)
+        );
+        assert_eq!(profiler.stop().map_err(error_code), Err(INTERNAL_ERROR_CODE));
+    }
+
+    #[test]
+    #[cfg(all(feature = "cpu-profiling", unix))]
+    fn cpu_profiler_collects_one_profile_at_a_time() {
+        use jsonrpsee::types::error::INVALID_PARAMS_CODE;
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("cpu.prof");
+        let profiler = CpuProfiler::default();
+
+        assert_eq!(profiler.stop().map_err(error_code), Err(INVALID_PARAMS_CODE));
+        profiler.start(file.clone()).unwrap();
+        assert_eq!(profiler.start(file.clone()).map_err(error_code), Err(INVALID_PARAMS_CODE));
+
+        profiler.stop().unwrap();
+        assert!(file.exists());
+        assert_eq!(profiler.stop().map_err(error_code), Err(INVALID_PARAMS_CODE));
+    }
+}
@@ -0,0 +1,95 @@
+use crate::segments::{prepare_jar, Column, Segment};
+use reth_db::{
+    cursor::DbCursorRO, database::Database, models::BlockAccountChangeSet, table::Compress, tables,
+    transaction::DbTx, DatabaseError,
+};
+use reth_interfaces::provider::ProviderResult;
+use reth_primitives::{
+    snapshot::{Compression, Filters, SegmentConfig},
+    BlockNumber, SnapshotSegment,
+};
+use reth_provider::DatabaseProviderRO;
+use std::{ops::RangeInclusive, path::Path};
+
+/// Snapshot segment responsible for [SnapshotSegment::AccountChangeSets] part of data.
+///
+/// Every row holds the [`BlockAccountChangeSet`] of one block, including the blocks which didn't
+/// change any account.
+#[derive(Debug)]
+pub struct AccountChangeSets {
+    config: SegmentConfig,
+}
+
+impl AccountChangeSets {
+    /// Creates new instance of [AccountChangeSets] snapshot segment.
+    pub fn new(compression: Compression, filters: Filters) -> Self {
+        Self { config: SegmentConfig { compression, filters } }
+    }
+
+    /// Walks the account changesets of `range` and calls `f` with the compressed
+    /// [`BlockAccountChangeSet`] of every block.
+    fn for_each_block<DB: Database>(
+        provider: &DatabaseProviderRO<DB>,
+        range: RangeInclusive<BlockNumber>,
+        f: impl FnOnce(&mut Column<'_>) -> ProviderResult<()>,
+    ) -> ProviderResult<()> {
+        let mut cursor = provider.tx_ref().cursor_read::<tables::AccountChangeSet>()?;
+        let mut walker = cursor.walk_range(range.clone())?.peekable();
+
+        let mut column = range.map(|block| -> Result<_, DatabaseError> {
+            let mut changes = Vec::new();
+            while let Some(entry) =
+                walker.next_if(|entry| entry.as_ref().map_or(true, |(number, _)| *number == block))
+            {
+                changes.push(entry?.1);
+            }
+            Ok(BlockAccountChangeSet(changes).compress())
+        });
+
+        f(&mut column)
+    }
+}
+
+impl Default for AccountChangeSets {
+    fn default() -> Self {
+        Self { config: SnapshotSegment::AccountChangeSets.config() }
+    }
+}
+
+impl Segment for AccountChangeSets {
+    fn segment(&self) -> SnapshotSegment {
+        SnapshotSegment::AccountChangeSets
+    }
+
+    fn snapshot<DB: Database>(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        directory: impl AsRef<Path>,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<()> {
+        let range_len = range.clone().count();
+        let mut jar = prepare_jar::<DB, 1>(
+            provider,
+            directory,
+            self.segment(),
+            self.config,
+            range.clone(),
+            range_len,
+            || {
+                // Trains the dictionary with the most recent blocks (at most 1000).
+                let dataset_range =
+                    range.end().saturating_sub(999).max(*range.start())..=*range.end();
+                let mut dataset = Vec::new();
+                Self::for_each_block(provider, dataset_range, |column| {
+                    dataset = column.collect::<Result<_, _>>()?;
+                    Ok(())
+                })?;
+                Ok([dataset])
+            },
+        )?;
+
+        Self::for_each_block(provider, range, |column| {
+            Ok(jar.freeze(vec![column.map(|row| row.map_err(Into::into))], range_len as u64)?)
+        })
+    }
+}
@@ -9,8 +9,15 @@ pub use headers::Headers;
 mod receipts;
 pub use receipts::Receipts;
 
+mod account_changesets;
+pub use account_changesets::AccountChangeSets;
+
+mod storage_changesets;
+pub use storage_changesets::StorageChangeSets;
+
 use reth_db::{
-    cursor::DbCursorRO, database::Database, table::Table, transaction::DbTx, RawKey, RawTable,
+    cursor::DbCursorRO, database::Database, table::Table, transaction::DbTx, DatabaseError, RawKey,
+    RawTable,
 };
 use reth_interfaces::provider::ProviderResult;
 use reth_nippy_jar::NippyJar;
@@ -25,6 +32,9 @@ use std::{ops::RangeInclusive, path::Path};
 
 pub(crate) type Rows<const COLUMNS: usize> = [Vec<Vec<u8>>; COLUMNS];
 
+/// Values of a column which are generated while being read from the database.
+pub(crate) type Column<'a> = dyn Iterator<Item = Result<Vec<u8>, DatabaseError>> + 'a;
+
 /// A segment represents a snapshotting of some portion of the data.
 pub trait Segment: Default {
     /// Snapshot data using the provided range. The `directory` parameter determines the snapshot
@@ -98,3 +108,242 @@ pub(crate) fn prepare_jar<DB: Database, const COLUMNS: usize>(
 
     Ok(nippy_jar)
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::segments::{AccountChangeSets, Headers, Segment, StorageChangeSets, Transactions};
+    use reth_db::{
+        models::{BlockNumberAddress, StorageBeforeTx},
+        tables,
+    };
+    use reth_interfaces::test_utils::{
+        generators,
+        generators::{random_block_range, random_changeset_range, random_eoa_account_range},
+    };
+    use reth_primitives::{snapshot::HighestSnapshots, B256, MAINNET};
+    use reth_provider::{
+        providers::SnapshotProvider, AccountExtReader, AccountReader, BundleStateWithReceipts,
+        ChangeSetReader, HeaderProvider, ProviderFactory, StateProvider, StateRootProvider,
+        StorageReader, TransactionsProvider, TransactionsProviderExt,
+    };
+    use reth_stages::test_utils::TestStageDB;
+    use std::collections::BTreeMap;
+    use tokio::sync::watch;
+
+    #[test]
+    fn changesets() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(&mut rng, 0..=9, B256::ZERO, 0..2);
+        db.insert_blocks(blocks.iter(), None).expect("insert blocks");
+
+        let accounts =
+            random_eoa_account_range(&mut rng, 0..3).into_iter().collect::<BTreeMap<_, _>>();
+        let (changesets, _) = random_changeset_range(
+            &mut rng,
+            blocks.iter(),
+            accounts.into_iter().map(|(addr, acc)| (addr, (acc, Vec::new()))),
+            0..3,
+            0..10,
+        );
+        db.insert_changesets(changesets, None).expect("insert changesets");
+
+        let snapshots_dir = tempfile::tempdir().unwrap();
+        let provider = db.factory.provider().unwrap();
+        AccountChangeSets::default().snapshot(&provider, snapshots_dir.path(), 0..=9).unwrap();
+        StorageChangeSets::default().snapshot(&provider, snapshots_dir.path(), 0..=9).unwrap();
+
+        let snapshot_provider = SnapshotProvider::new(snapshots_dir.path());
+        snapshot_provider.update_index().unwrap();
+
+        let storage_changesets = db.table::<tables::StorageChangeSet>().unwrap();
+        for block in 0..=9 {
+            assert_eq!(
+                snapshot_provider.account_block_changeset(block).unwrap(),
+                provider.account_block_changeset(block).unwrap()
+            );
+
+            let storage_changeset = storage_changesets
+                .iter()
+                .filter(|(key, _)| key.block_number() == block)
+                .map(|(key, entry)| StorageBeforeTx { address: key.address(), entry: *entry })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                snapshot_provider.storage_block_changeset(block).unwrap(),
+                storage_changeset
+            );
+        }
+    }
+
+    #[test]
+    fn historical_state_from_snapshots() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(&mut rng, 0..=19, B256::ZERO, 0..2);
+        db.insert_blocks(blocks.iter(), None).expect("insert blocks");
+
+        let accounts =
+            random_eoa_account_range(&mut rng, 0..3).into_iter().collect::<BTreeMap<_, _>>();
+        let (changesets, _) = random_changeset_range(
+            &mut rng,
+            blocks.iter(),
+            accounts.into_iter().map(|(addr, acc)| (addr, (acc, Vec::new()))),
+            0..3,
+            0..10,
+        );
+        db.insert_changesets(changesets.clone(), None).expect("insert changesets");
+        db.insert_history(changesets.clone(), None).expect("insert history");
+
+        let slots = changesets
+            .iter()
+            .flatten()
+            .flat_map(|(address, _, storage)| storage.iter().map(|entry| (*address, entry.key)))
+            .collect::<Vec<_>>();
+        let addresses = slots.iter().map(|(address, _)| *address).collect::<Vec<_>>();
+
+        // Reads the state of every historical block, as well as the accounts and storage slots
+        // that changed in the blocks which get snapshotted.
+        let read_state = |factory: &ProviderFactory<_>| {
+            let states = (1..19)
+                .map(|block| {
+                    let state = factory.history_by_block_number(block).unwrap();
+                    let accounts = addresses
+                        .iter()
+                        .map(|address| state.basic_account(*address).unwrap())
+                        .collect::<Vec<_>>();
+                    let storage = slots
+                        .iter()
+                        .map(|(address, key)| state.storage(*address, *key).unwrap())
+                        .collect::<Vec<_>>();
+                    let state_root = state.state_root(&BundleStateWithReceipts::default()).unwrap();
+                    (accounts, storage, state_root)
+                })
+                .collect::<Vec<_>>();
+
+            let provider = factory.provider().unwrap();
+            let changed_accounts = provider.changed_accounts_with_range(5..=14).unwrap();
+            let changed_storages = provider.changed_storages_with_range(5..=14).unwrap();
+            (states, changed_accounts, changed_storages)
+        };
+        let expected = read_state(&db.factory);
+
+        let snapshots_dir = tempfile::tempdir().unwrap();
+        let provider = db.factory.provider().unwrap();
+        AccountChangeSets::default().snapshot(&provider, snapshots_dir.path(), 0..=9).unwrap();
+        StorageChangeSets::default().snapshot(&provider, snapshots_dir.path(), 0..=9).unwrap();
+        drop(provider);
+
+        // Prune the snapshotted changesets from the database, keeping the history indices.
+        let provider = db.factory.provider_rw().unwrap();
+        provider
+            .prune_table_with_range::<tables::AccountChangeSet>(..=9, usize::MAX, |_| false, |_| {})
+            .unwrap();
+        provider
+            .prune_table_with_range::<tables::StorageChangeSet>(
+                BlockNumberAddress::range(0..=9),
+                usize::MAX,
+                |_| false,
+                |_| {},
+            )
+            .unwrap();
+        provider.commit().unwrap();
+        assert!(db
+            .table::<tables::AccountChangeSet>()
+            .unwrap()
+            .iter()
+            .all(|(block, _)| *block > 9));
+
+        let (_, highest_snapshots) = watch::channel(Some(HighestSnapshots {
+            account_changesets: Some(9),
+            storage_changesets: Some(9),
+            ..Default::default()
+        }));
+        let factory = ProviderFactory::new(db.factory.db_ref().clone(), MAINNET.clone())
+            .with_snapshots(snapshots_dir.path().to_path_buf(), highest_snapshots);
+        assert_eq!(read_state(&factory), expected);
+    }
+
+    #[test]
+    fn headers_and_transactions_from_snapshots() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(&mut rng, 0..=19, B256::ZERO, 1..3);
+        db.insert_blocks(blocks.iter(), None).expect("insert blocks");
+        db.insert_headers_with_td(blocks.iter().map(|block| &block.header))
+            .expect("insert headers");
+
+        let provider = db.factory.provider().unwrap();
+        let headers = provider.sealed_headers_while(0..20, |_| true).unwrap();
+        let snapshotted_txs = provider.transaction_range_by_block_range(0..=9).unwrap();
+        let txs = provider.transaction_range_by_block_range(5..=14).unwrap();
+        let txs = *txs.start()..*txs.end() + 1;
+        let transactions = provider.transactions_by_tx_range(txs.clone()).unwrap();
+        let mut tx_hashes = provider.transaction_hashes_by_range(txs.clone()).unwrap();
+        tx_hashes.sort();
+
+        let snapshots_dir = tempfile::tempdir().unwrap();
+        Headers::default().snapshot(&provider, snapshots_dir.path(), 0..=9).unwrap();
+        Transactions::default().snapshot(&provider, snapshots_dir.path(), 0..=9).unwrap();
+        drop(provider);
+
+        // Delete the snapshotted data from the database, so it can only be read from snapshots.
+        let provider = db.factory.provider_rw().unwrap();
+        provider
+            .prune_table_with_range::<tables::Headers>(..=9, usize::MAX, |_| false, |_| {})
+            .unwrap();
+        provider
+            .prune_table_with_range::<tables::CanonicalHeaders>(..=9, usize::MAX, |_| false, |_| {})
+            .unwrap();
+        provider
+            .prune_table_with_range::<tables::Transactions>(
+                snapshotted_txs,
+                usize::MAX,
+                |_| false,
+                |_| {},
+            )
+            .unwrap();
+        provider.commit().unwrap();
+
+        let (_, highest_snapshots) = watch::channel(Some(HighestSnapshots {
+            headers: Some(9),
+            transactions: Some(9),
+            ..Default::default()
+        }));
+        let factory = ProviderFactory::new(db.factory.db_ref().clone(), MAINNET.clone())
+            .with_snapshots(snapshots_dir.path().to_path_buf(), highest_snapshots);
+        let provider = factory.provider().unwrap();
+
+        // Single values on both sides of the highest snapshot.
+        assert_eq!(provider.header_by_number(9).unwrap().as_ref(), Some(&headers[9].header));
+        assert_eq!(provider.header_by_number(10).unwrap().as_ref(), Some(&headers[10].header));
+
+        // Ranges that straddle the highest snapshot.
+        assert_eq!(
+            provider.headers_range(5..15).unwrap(),
+            headers[5..15].iter().map(|header| header.header.clone()).collect::<Vec<_>>()
+        );
+        assert_eq!(provider.transactions_by_tx_range(txs.clone()).unwrap(), transactions);
+        let mut snapshot_tx_hashes = provider.transaction_hashes_by_range(txs).unwrap();
+        snapshot_tx_hashes.sort();
+        assert_eq!(snapshot_tx_hashes, tx_hashes);
+
+        // A predicate that stops inside the snapshots doesn't query the database.
+        let mut calls = 0;
+        let sealed_headers = provider
+            .sealed_headers_while(0..20, |header| {
+                calls += 1;
+                header.number < 7
+            })
+            .unwrap();
+        assert_eq!(sealed_headers, headers[..7]);
+        assert_eq!(calls, 8);
+
+        // A predicate that stops inside the database.
+        let sealed_headers =
+            provider.sealed_headers_while(0..20, |header| header.number < 12).unwrap();
+        assert_eq!(sealed_headers, headers[..12]);
+    }
+}
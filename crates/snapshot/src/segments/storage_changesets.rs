@@ -0,0 +1,101 @@
+use crate::segments::{prepare_jar, Column, Segment};
+use reth_db::{
+    cursor::DbCursorRO,
+    database::Database,
+    models::{BlockNumberAddress, BlockStorageChangeSet, StorageBeforeTx},
+    table::Compress,
+    tables,
+    transaction::DbTx,
+    DatabaseError,
+};
+use reth_interfaces::provider::ProviderResult;
+use reth_primitives::{
+    snapshot::{Compression, Filters, SegmentConfig},
+    BlockNumber, SnapshotSegment,
+};
+use reth_provider::DatabaseProviderRO;
+use std::{ops::RangeInclusive, path::Path};
+
+/// Snapshot segment responsible for [SnapshotSegment::StorageChangeSets] part of data.
+///
+/// Every row holds the [`BlockStorageChangeSet`] of one block, including the blocks which didn't
+/// change any storage slot.
+#[derive(Debug)]
+pub struct StorageChangeSets {
+    config: SegmentConfig,
+}
+
+impl StorageChangeSets {
+    /// Creates new instance of [StorageChangeSets] snapshot segment.
+    pub fn new(compression: Compression, filters: Filters) -> Self {
+        Self { config: SegmentConfig { compression, filters } }
+    }
+
+    /// Walks the storage changesets of `range` and calls `f` with the compressed
+    /// [`BlockStorageChangeSet`] of every block.
+    fn for_each_block<DB: Database>(
+        provider: &DatabaseProviderRO<DB>,
+        range: RangeInclusive<BlockNumber>,
+        f: impl FnOnce(&mut Column<'_>) -> ProviderResult<()>,
+    ) -> ProviderResult<()> {
+        let mut cursor = provider.tx_ref().cursor_read::<tables::StorageChangeSet>()?;
+        let mut walker = cursor.walk_range(BlockNumberAddress::range(range.clone()))?.peekable();
+
+        let mut column = range.map(|block| -> Result<_, DatabaseError> {
+            let mut changes = Vec::new();
+            while let Some(entry) = walker.next_if(|entry| {
+                entry.as_ref().map_or(true, |(key, _)| key.block_number() == block)
+            }) {
+                let (key, entry) = entry?;
+                changes.push(StorageBeforeTx { address: key.address(), entry });
+            }
+            Ok(BlockStorageChangeSet(changes).compress())
+        });
+
+        f(&mut column)
+    }
+}
+
+impl Default for StorageChangeSets {
+    fn default() -> Self {
+        Self { config: SnapshotSegment::StorageChangeSets.config() }
+    }
+}
+
+impl Segment for StorageChangeSets {
+    fn segment(&self) -> SnapshotSegment {
+        SnapshotSegment::StorageChangeSets
+    }
+
+    fn snapshot<DB: Database>(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        directory: impl AsRef<Path>,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<()> {
+        let range_len = range.clone().count();
+        let mut jar = prepare_jar::<DB, 1>(
+            provider,
+            directory,
+            self.segment(),
+            self.config,
+            range.clone(),
+            range_len,
+            || {
+                // Trains the dictionary with the most recent blocks (at most 1000).
+                let dataset_range =
+                    range.end().saturating_sub(999).max(*range.start())..=*range.end();
+                let mut dataset = Vec::new();
+                Self::for_each_block(provider, dataset_range, |column| {
+                    dataset = column.collect::<Result<_, _>>()?;
+                    Ok(())
+                })?;
+                Ok([dataset])
+            },
+        )?;
+
+        Self::for_each_block(provider, range, |column| {
+            Ok(jar.freeze(vec![column.map(|row| row.map_err(Into::into))], range_len as u64)?)
+        })
+    }
+}
@@ -15,7 +15,7 @@ use reth_primitives::{
 use reth_provider::{
     DatabaseProviderRW, HeaderProvider, ProviderError, StageCheckpointReader, StageCheckpointWriter,
 };
-use reth_trie::{IntermediateStateRootState, StateRoot, StateRootProgress};
+use reth_trie::{IntermediateStateRootState, ParallelStateRoot, StateRoot, StateRootProgress};
 use std::fmt::Debug;
 use tracing::*;
 
@@ -51,6 +51,12 @@ pub enum MerkleStage {
         /// The threshold (in number of blocks) for switching from incremental trie building
         /// of changes to whole rebuild.
         clean_threshold: u64,
+        /// Whether to compute the storage roots of changed accounts in parallel when updating
+        /// the trie incrementally.
+        ///
+        /// The parallel computation reads the hashed state through separate read-only
+        /// transactions, so the hashing stages must have been committed before this stage runs.
+        parallel: bool,
     },
     /// The unwind portion of the merkle stage.
     Unwind,
@@ -58,13 +64,13 @@ pub enum MerkleStage {
     /// Able to execute and unwind. Used for tests
     #[cfg(any(test, feature = "test-utils"))]
     #[allow(missing_docs)]
-    Both { clean_threshold: u64 },
+    Both { clean_threshold: u64, parallel: bool },
 }
 
 impl MerkleStage {
     /// Stage default for the [MerkleStage::Execution].
     pub fn default_execution() -> Self {
-        Self::Execution { clean_threshold: MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD }
+        Self::Execution { clean_threshold: MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD, parallel: false }
     }
 
     /// Stage default for the [MerkleStage::Unwind].
@@ -73,8 +79,8 @@ impl MerkleStage {
     }
 
     /// Create new instance of [MerkleStage::Execution].
-    pub fn new_execution(clean_threshold: u64) -> Self {
-        Self::Execution { clean_threshold }
+    pub fn new_execution(clean_threshold: u64, parallel: bool) -> Self {
+        Self::Execution { clean_threshold, parallel }
     }
 
     /// Gets the hashing progress
@@ -130,14 +136,14 @@ impl<DB: Database> Stage<DB> for MerkleStage {
         provider: &DatabaseProviderRW<DB>,
         input: ExecInput,
     ) -> Result<ExecOutput, StageError> {
-        let threshold = match self {
+        let (threshold, parallel) = match self {
             MerkleStage::Unwind => {
                 info!(target: "sync::stages::merkle::unwind", "Stage is always skipped");
                 return Ok(ExecOutput::done(StageCheckpoint::new(input.target())))
             }
-            MerkleStage::Execution { clean_threshold } => *clean_threshold,
+            MerkleStage::Execution { clean_threshold, parallel } => (*clean_threshold, *parallel),
             #[cfg(any(test, feature = "test-utils"))]
-            MerkleStage::Both { clean_threshold } => *clean_threshold,
+            MerkleStage::Both { clean_threshold, parallel } => (*clean_threshold, *parallel),
         };
 
         let range = input.next_block_range();
@@ -225,10 +231,13 @@ impl<DB: Database> Stage<DB> for MerkleStage {
                 }
             }
         } else {
-            debug!(target: "sync::stages::merkle::exec", current = ?current_block_number, target = ?to_block, "Updating trie");
-            let (root, updates) =
+            debug!(target: "sync::stages::merkle::exec", current = ?current_block_number, target = ?to_block, parallel, "Updating trie");
+            let (root, updates) = if parallel {
+                ParallelStateRoot::incremental_root_with_updates(provider.db(), range)
+            } else {
                 StateRoot::incremental_root_with_updates(provider.tx_ref(), range)
-                    .map_err(|e| StageError::Fatal(Box::new(e)))?;
+            }
+            .map_err(|e| StageError::Fatal(Box::new(e)))?;
             updates.flush(provider.tx_ref())?;
 
             let total_hashed_entries = (provider.tx_ref().entries::<tables::HashedAccount>()? +
@@ -441,14 +450,55 @@ mod tests {
         assert!(runner.validate_execution(input, result.ok()).is_ok(), "execution validation");
     }
 
+    /// Update small trie, computing the storage roots in parallel
+    #[tokio::test]
+    async fn execute_small_merkle_parallel() {
+        let (previous_stage, stage_progress) = (2, 1);
+
+        // Set up the runner
+        let mut runner = MerkleTestRunner { parallel: true, ..Default::default() };
+        let input = ExecInput {
+            target: Some(previous_stage),
+            checkpoint: Some(StageCheckpoint::new(stage_progress)),
+        };
+
+        runner.seed_execution(input).expect("failed to seed execution");
+
+        let rx = runner.execute(input);
+
+        // Assert the successful result
+        let result = rx.await.unwrap();
+        assert_matches!(
+            result,
+            Ok(ExecOutput {
+                checkpoint: StageCheckpoint {
+                    block_number,
+                    stage_checkpoint: Some(StageUnitCheckpoint::Entities(EntitiesCheckpoint {
+                        processed,
+                        total
+                    }))
+                },
+                done: true
+            }) if block_number == previous_stage && processed == total &&
+                total == (
+                    runner.db.table::<tables::HashedAccount>().unwrap().len() +
+                    runner.db.table::<tables::HashedStorage>().unwrap().len()
+                ) as u64
+        );
+
+        // Validate the stage execution
+        assert!(runner.validate_execution(input, result.ok()).is_ok(), "execution validation");
+    }
+
     struct MerkleTestRunner {
         db: TestStageDB,
         clean_threshold: u64,
+        parallel: bool,
     }
 
     impl Default for MerkleTestRunner {
         fn default() -> Self {
-            Self { db: TestStageDB::default(), clean_threshold: 10000 }
+            Self { db: TestStageDB::default(), clean_threshold: 10000, parallel: false }
         }
     }
 
@@ -460,7 +510,7 @@ mod tests {
         }
 
         fn stage(&self) -> Self::S {
-            Self::S::Both { clean_threshold: self.clean_threshold }
+            Self::S::Both { clean_threshold: self.clean_threshold, parallel: self.parallel }
         }
     }
 
@@ -8,7 +8,7 @@ use crate::{
     DatabaseError,
 };
 use reth_codecs::{derive_arbitrary, Compact};
-use reth_primitives::{Account, Address, BlockNumber, Buf};
+use reth_primitives::{Account, Address, BlockNumber, Buf, StorageEntry};
 use serde::{Deserialize, Serialize};
 
 /// Account as it is saved inside [`AccountChangeSet`][crate::tables::AccountChangeSet].
@@ -56,6 +56,74 @@ impl Compact for AccountBeforeTx {
     }
 }
 
+/// Every [`AccountBeforeTx`] of a block, sorted by address.
+///
+/// Used as the row value of the account changesets snapshot segment.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct BlockAccountChangeSet(pub Vec<AccountBeforeTx>);
+
+impl Compact for BlockAccountChangeSet {
+    fn to_compact<B>(self, buf: &mut B) -> usize
+    where
+        B: bytes::BufMut + AsMut<[u8]>,
+    {
+        self.0.to_compact(buf)
+    }
+
+    fn from_compact(buf: &[u8], len: usize) -> (Self, &[u8]) {
+        let (changes, buf) = Vec::from_compact(buf, len);
+        (Self(changes), buf)
+    }
+}
+
+/// Storage slot as it is saved inside [`StorageChangeSet`][crate::tables::StorageChangeSet],
+/// alongside the [`Address`] of its account which is part of the table key.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct StorageBeforeTx {
+    /// Address of the account owning the storage slot.
+    pub address: Address,
+    /// Storage slot before the transaction.
+    pub entry: StorageEntry,
+}
+
+impl Compact for StorageBeforeTx {
+    fn to_compact<B>(self, buf: &mut B) -> usize
+    where
+        B: bytes::BufMut + AsMut<[u8]>,
+    {
+        buf.put_slice(self.address.as_slice());
+        self.entry.to_compact(buf) + 20
+    }
+
+    fn from_compact(mut buf: &[u8], len: usize) -> (Self, &[u8]) {
+        let address = Address::from_slice(&buf[..20]);
+        buf.advance(20);
+
+        let (entry, buf) = StorageEntry::from_compact(buf, len - 20);
+        (Self { address, entry }, buf)
+    }
+}
+
+/// Every [`StorageBeforeTx`] of a block, sorted by address and storage key.
+///
+/// Used as the row value of the storage changesets snapshot segment.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct BlockStorageChangeSet(pub Vec<StorageBeforeTx>);
+
+impl Compact for BlockStorageChangeSet {
+    fn to_compact<B>(self, buf: &mut B) -> usize
+    where
+        B: bytes::BufMut + AsMut<[u8]>,
+    {
+        self.0.to_compact(buf)
+    }
+
+    fn from_compact(buf: &[u8], len: usize) -> (Self, &[u8]) {
+        let (changes, buf) = Vec::from_compact(buf, len);
+        (Self(changes), buf)
+    }
+}
+
 /// [`BlockNumber`] concatenated with [`Address`]. Used as the key for
 /// [`StorageChangeSet`](crate::tables::StorageChangeSet)
 ///
@@ -126,6 +194,7 @@ impl_fixed_arbitrary!(BlockNumberAddress, 28);
 mod test {
     use super::*;
     use rand::{thread_rng, Rng};
+    use reth_primitives::{B256, U256};
     use std::str::FromStr;
 
     #[test]
@@ -152,4 +221,29 @@ mod test {
         let key = BlockNumberAddress::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
         assert_eq!(bytes, Encode::encode(key));
     }
+
+    #[test]
+    fn test_block_changesets_compact() {
+        let account_changeset = BlockAccountChangeSet(vec![
+            AccountBeforeTx { address: Address::with_last_byte(1), info: None },
+            AccountBeforeTx {
+                address: Address::with_last_byte(2),
+                info: Some(Account { nonce: 1, ..Default::default() }),
+            },
+        ]);
+        let mut buf = Vec::new();
+        account_changeset.clone().to_compact(&mut buf);
+        assert_eq!(BlockAccountChangeSet::from_compact(&buf, buf.len()).0, account_changeset);
+
+        let storage_changeset = BlockStorageChangeSet(vec![
+            StorageBeforeTx { address: Address::with_last_byte(1), entry: StorageEntry::default() },
+            StorageBeforeTx {
+                address: Address::with_last_byte(1),
+                entry: StorageEntry::new(B256::with_last_byte(1), U256::from(2)),
+            },
+        ]);
+        let mut buf = Vec::new();
+        storage_changeset.clone().to_compact(&mut buf);
+        assert_eq!(BlockStorageChangeSet::from_compact(&buf, buf.len()).0, storage_changeset);
+    }
 }
@@ -0,0 +1,77 @@
+use crate::MAX_BLOCKS_PER_ERA1;
+use reth_primitives::{B256, U256};
+use sha2::{Digest, Sha256};
+
+/// Block hash and total difficulty of a block, the leaves of the header accumulator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeaderRecord {
+    /// The hash of the block.
+    pub block_hash: B256,
+    /// The total difficulty of the chain up to and including the block.
+    pub total_difficulty: U256,
+}
+
+impl HeaderRecord {
+    /// Returns the SSZ hash tree root of the record.
+    fn tree_root(&self) -> B256 {
+        hash_pair(&self.block_hash, &B256::from(self.total_difficulty.to_le_bytes::<32>()))
+    }
+}
+
+/// Computes the header accumulator root of the given records, i.e. the SSZ hash tree root of
+/// `List[HeaderRecord, MAX_BLOCKS_PER_ERA1]`.
+///
+/// # Panics
+///
+/// If there are more than [`MAX_BLOCKS_PER_ERA1`] records.
+pub fn accumulator_root(records: &[HeaderRecord]) -> B256 {
+    assert!(records.len() <= MAX_BLOCKS_PER_ERA1, "too many header records");
+
+    let mut layer = records.iter().map(HeaderRecord::tree_root).collect::<Vec<_>>();
+    // Hash of a subtree made of zero leaves at the current depth.
+    let mut zero_hash = B256::ZERO;
+    for _ in 0..MAX_BLOCKS_PER_ERA1.trailing_zeros() {
+        if layer.len() % 2 == 1 {
+            layer.push(zero_hash);
+        }
+        layer = layer.chunks_exact(2).map(|pair| hash_pair(&pair[0], &pair[1])).collect();
+        zero_hash = hash_pair(&zero_hash, &zero_hash);
+    }
+    let root = layer.first().copied().unwrap_or(zero_hash);
+
+    // mix in the length of the list
+    hash_pair(&root, &B256::from(U256::from(records.len()).to_le_bytes::<32>()))
+}
+
+fn hash_pair(left: &B256, right: &B256) -> B256 {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    B256::from_slice(&hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_accumulator() {
+        // zero hash of a tree of depth 13, mixed in with a zero length
+        let mut zero_hash = B256::ZERO;
+        for _ in 0..13 {
+            zero_hash = hash_pair(&zero_hash, &zero_hash);
+        }
+        assert_eq!(accumulator_root(&[]), hash_pair(&zero_hash, &B256::ZERO));
+    }
+
+    #[test]
+    fn accumulator_depends_on_records() {
+        let record = HeaderRecord { block_hash: B256::random(), total_difficulty: U256::from(1) };
+        let other = HeaderRecord { total_difficulty: U256::from(2), ..record };
+
+        let root = accumulator_root(&[record]);
+        assert_eq!(root, accumulator_root(&[record]));
+        assert_ne!(root, accumulator_root(&[other]));
+        assert_ne!(root, accumulator_root(&[record, record]));
+    }
+}
@@ -0,0 +1,219 @@
+//! The [e2store](https://github.com/status-im/nimbus-eth2/blob/stable/docs/e2store.md) format.
+//!
+//! An e2store file is a sequence of entries, each made of an 8 byte header followed by its value.
+//! The header holds the type of the entry (2 bytes), the length of the value (4 bytes) and 2
+//! reserved bytes that must be zero, all little endian.
+
+use crate::EraError;
+use std::io::{self, Read, Write};
+
+/// Length of the header of an entry.
+pub const HEADER_LEN: usize = 8;
+
+/// Maximum length of the value of an entry.
+///
+/// Well above the size of the largest compressed block or list of receipts, it bounds the memory
+/// allocated for an entry of a corrupt file.
+pub const MAX_ENTRY_LEN: usize = 64 * 1024 * 1024;
+
+/// Version entry, the first entry of every file.
+pub const VERSION: u16 = 0x3265;
+/// Snappy compressed rlp encoded header.
+pub const COMPRESSED_HEADER: u16 = 0x03;
+/// Snappy compressed rlp encoded block body.
+pub const COMPRESSED_BODY: u16 = 0x04;
+/// Snappy compressed rlp encoded list of receipts.
+pub const COMPRESSED_RECEIPTS: u16 = 0x05;
+/// Total difficulty of a block, as a little endian 32 byte integer.
+pub const TOTAL_DIFFICULTY: u16 = 0x06;
+/// Header accumulator root of the blocks of an Era1 file.
+pub const ACCUMULATOR: u16 = 0x07;
+/// Index of the blocks of an Era1 file.
+pub const BLOCK_INDEX: u16 = 0x3266;
+
+/// An entry of an e2store file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The type of the entry.
+    pub entry_type: u16,
+    /// The value of the entry.
+    pub value: Vec<u8>,
+}
+
+impl Entry {
+    /// Creates a new entry.
+    pub fn new(entry_type: u16, value: Vec<u8>) -> Self {
+        Self { entry_type, value }
+    }
+
+    /// Returns the value of the entry if it has the expected type.
+    pub fn expect_type(self, expected: u16) -> Result<Vec<u8>, EraError> {
+        if self.entry_type != expected {
+            return Err(EraError::UnexpectedEntry { got: self.entry_type, expected })
+        }
+        Ok(self.value)
+    }
+}
+
+/// Writes entries to an e2store file.
+#[derive(Debug)]
+pub struct E2sWriter<W> {
+    /// The underlying writer.
+    writer: W,
+    /// Number of bytes written so far.
+    written: u64,
+}
+
+impl<W: Write> E2sWriter<W> {
+    /// Creates a new writer.
+    pub fn new(writer: W) -> Self {
+        Self { writer, written: 0 }
+    }
+
+    /// Returns the number of bytes written so far.
+    pub fn written(&self) -> u64 {
+        self.written
+    }
+
+    /// Writes an entry, returning the number of bytes written.
+    ///
+    /// Fails if the value is longer than [`MAX_ENTRY_LEN`].
+    pub fn write_entry(&mut self, entry_type: u16, value: &[u8]) -> Result<u64, EraError> {
+        if value.len() > MAX_ENTRY_LEN {
+            return Err(EraError::InvalidEntryLength { entry_type, len: value.len() })
+        }
+        let len = value.len() as u32;
+
+        let mut header = [0u8; HEADER_LEN];
+        header[..2].copy_from_slice(&entry_type.to_le_bytes());
+        header[2..6].copy_from_slice(&len.to_le_bytes());
+        self.writer.write_all(&header)?;
+        self.writer.write_all(value)?;
+
+        let written = (HEADER_LEN + value.len()) as u64;
+        self.written += written;
+        Ok(written)
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> Result<(), EraError> {
+        Ok(self.writer.flush()?)
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reads entries from an e2store file.
+#[derive(Debug)]
+pub struct E2sReader<R> {
+    /// The underlying reader.
+    reader: R,
+    /// Number of bytes read so far.
+    read: u64,
+}
+
+impl<R: Read> E2sReader<R> {
+    /// Creates a new reader.
+    pub fn new(reader: R) -> Self {
+        Self { reader, read: 0 }
+    }
+
+    /// Returns the number of bytes read so far, i.e. the offset of the next entry.
+    pub fn read(&self) -> u64 {
+        self.read
+    }
+
+    /// Reads the next entry, returning `None` at the end of the file.
+    ///
+    /// Fails if the value of the entry is longer than [`MAX_ENTRY_LEN`].
+    pub fn read_entry(&mut self) -> Result<Option<Entry>, EraError> {
+        let mut header = [0u8; HEADER_LEN];
+        let mut read = 0;
+        while read < HEADER_LEN {
+            match self.reader.read(&mut header[read..]) {
+                Ok(0) if read == 0 => return Ok(None),
+                Ok(0) => return Err(EraError::UnexpectedEof),
+                Ok(n) => read += n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        let entry_type = u16::from_le_bytes([header[0], header[1]]);
+        let len = u32::from_le_bytes([header[2], header[3], header[4], header[5]]) as usize;
+        if header[6..] != [0, 0] {
+            return Err(EraError::NonZeroReserved(entry_type))
+        }
+        if len > MAX_ENTRY_LEN {
+            return Err(EraError::InvalidEntryLength { entry_type, len })
+        }
+
+        // don't trust the length for the allocation, the file may be truncated
+        let mut value = Vec::new();
+        (&mut self.reader).take(len as u64).read_to_end(&mut value)?;
+        if value.len() != len {
+            return Err(EraError::UnexpectedEof)
+        }
+        self.read += (HEADER_LEN + len) as u64;
+
+        Ok(Some(Entry { entry_type, value }))
+    }
+
+    /// Reads the next entry, failing at the end of the file.
+    pub fn next_entry(&mut self) -> Result<Entry, EraError> {
+        self.read_entry()?.ok_or(EraError::UnexpectedEof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entry_roundtrip() {
+        let mut writer = E2sWriter::new(Vec::new());
+        assert_eq!(writer.write_entry(VERSION, &[]).unwrap(), 8);
+        assert_eq!(writer.write_entry(ACCUMULATOR, &[0xab; 32]).unwrap(), 40);
+        assert_eq!(writer.written(), 48);
+
+        let buf = writer.into_inner();
+        // the version entry spells "e2"
+        assert_eq!(&buf[..8], b"e2\0\0\0\0\0\0");
+
+        let mut reader = E2sReader::new(&buf[..]);
+        assert_eq!(reader.read_entry().unwrap(), Some(Entry::new(VERSION, vec![])));
+        assert_eq!(reader.read_entry().unwrap(), Some(Entry::new(ACCUMULATOR, vec![0xab; 32])));
+        assert_eq!(reader.read_entry().unwrap(), None);
+    }
+
+    #[test]
+    fn truncated_entry() {
+        let mut writer = E2sWriter::new(Vec::new());
+        writer.write_entry(ACCUMULATOR, &[0xab; 32]).unwrap();
+        let buf = writer.into_inner();
+
+        assert!(matches!(E2sReader::new(&buf[..4]).read_entry(), Err(EraError::UnexpectedEof)));
+        assert!(matches!(E2sReader::new(&buf[..20]).read_entry(), Err(EraError::UnexpectedEof)));
+    }
+
+    #[test]
+    fn oversized_entry() {
+        let value = vec![0; MAX_ENTRY_LEN + 1];
+        assert!(matches!(
+            E2sWriter::new(Vec::new()).write_entry(ACCUMULATOR, &value),
+            Err(EraError::InvalidEntryLength { entry_type: ACCUMULATOR, .. })
+        ));
+
+        // a header claiming a 4 GiB value is rejected before anything is allocated
+        let mut header = [0u8; HEADER_LEN];
+        header[..2].copy_from_slice(&ACCUMULATOR.to_le_bytes());
+        header[2..6].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            E2sReader::new(&header[..]).read_entry(),
+            Err(EraError::InvalidEntryLength { len, .. }) if len == u32::MAX as usize
+        ));
+    }
+}
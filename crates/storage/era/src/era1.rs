@@ -0,0 +1,379 @@
+use crate::{
+    accumulator_root,
+    e2s::{
+        E2sReader, E2sWriter, ACCUMULATOR, BLOCK_INDEX, COMPRESSED_BODY, COMPRESSED_HEADER,
+        COMPRESSED_RECEIPTS, TOTAL_DIFFICULTY, VERSION,
+    },
+    EraError, HeaderRecord,
+};
+use alloy_rlp::{Decodable, Encodable};
+use reth_primitives::{
+    hex, Block, BlockBody, BlockNumber, Header, Receipt, ReceiptWithBloom, ReceiptWithBloomRef,
+    SealedBlock, B256, U256,
+};
+use std::io::{Read, Write};
+
+/// Maximum number of blocks in an Era1 file.
+pub const MAX_BLOCKS_PER_ERA1: usize = 8192;
+
+/// Returns the canonical name of an Era1 file: `<network>-<epoch>-<short accumulator root>.era1`.
+///
+/// The epoch is derived from the first block of the file.
+pub fn era1_file_name(network: &str, start_block: BlockNumber, accumulator_root: B256) -> String {
+    let epoch = start_block / MAX_BLOCKS_PER_ERA1 as u64;
+    format!("{network}-{epoch:05}-{}.era1", hex::encode(&accumulator_root[..4]))
+}
+
+/// A block stored in an Era1 file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockTuple {
+    /// The header of the block.
+    pub header: Header,
+    /// The body of the block.
+    pub body: BlockBody,
+    /// The receipts of the transactions of the block.
+    pub receipts: Vec<ReceiptWithBloom>,
+    /// The total difficulty of the chain up to and including the block.
+    pub total_difficulty: U256,
+}
+
+impl BlockTuple {
+    /// Returns the block, discarding receipts and total difficulty.
+    pub fn into_block(self) -> Block {
+        Block {
+            header: self.header,
+            body: self.body.transactions,
+            ommers: self.body.ommers,
+            withdrawals: self.body.withdrawals,
+        }
+    }
+}
+
+/// Writes consecutive blocks to an Era1 file.
+///
+/// The file is only complete once [`Era1Writer::finish`] has written the accumulator root and the
+/// block index.
+#[derive(Debug)]
+pub struct Era1Writer<W> {
+    /// The underlying e2store writer.
+    writer: E2sWriter<W>,
+    /// Number of the first block of the file.
+    start_block: Option<BlockNumber>,
+    /// Offsets of the block tuples, from the start of the file.
+    offsets: Vec<u64>,
+    /// Header records of the written blocks.
+    records: Vec<HeaderRecord>,
+}
+
+impl<W: Write> Era1Writer<W> {
+    /// Creates a new writer, writing the version entry.
+    pub fn new(writer: W) -> Result<Self, EraError> {
+        let mut writer = E2sWriter::new(writer);
+        writer.write_entry(VERSION, &[])?;
+        Ok(Self { writer, start_block: None, offsets: Vec::new(), records: Vec::new() })
+    }
+
+    /// Returns the number of blocks written so far.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns `true` if no block has been written.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Appends a block along with its receipts and total difficulty.
+    ///
+    /// Blocks must be appended in order, without gaps, and all blocks of a file must belong to the
+    /// same epoch of [`MAX_BLOCKS_PER_ERA1`] blocks.
+    pub fn append_block(
+        &mut self,
+        block: SealedBlock,
+        receipts: &[Receipt],
+        total_difficulty: U256,
+    ) -> Result<(), EraError> {
+        if self.records.len() >= MAX_BLOCKS_PER_ERA1 {
+            return Err(EraError::TooManyBlocks)
+        }
+        let start_block = *self.start_block.get_or_insert(block.number);
+        let expected = start_block + self.records.len() as u64;
+        if block.number != expected {
+            return Err(EraError::BlockNumberMismatch { got: block.number, expected })
+        }
+        let epoch = start_block / MAX_BLOCKS_PER_ERA1 as u64;
+        if block.number / MAX_BLOCKS_PER_ERA1 as u64 != epoch {
+            return Err(EraError::EpochBoundary { block: block.number, epoch })
+        }
+
+        let (header, body) = block.split_header_body();
+        let receipts = receipts.iter().map(ReceiptWithBloomRef::from).collect::<Vec<_>>();
+
+        self.offsets.push(self.writer.written());
+        self.writer.write_entry(COMPRESSED_HEADER, &compress(&header.header)?)?;
+        self.writer.write_entry(COMPRESSED_BODY, &compress(&body)?)?;
+        self.writer.write_entry(COMPRESSED_RECEIPTS, &compress(&receipts)?)?;
+        self.writer.write_entry(TOTAL_DIFFICULTY, &total_difficulty.to_le_bytes::<32>())?;
+
+        self.records.push(HeaderRecord { block_hash: header.hash, total_difficulty });
+        Ok(())
+    }
+
+    /// Writes the accumulator root and the block index, and returns the underlying writer along
+    /// with the accumulator root.
+    pub fn finish(mut self) -> Result<(W, B256), EraError> {
+        let start_block = self.start_block.ok_or(EraError::Empty)?;
+
+        let root = accumulator_root(&self.records);
+        self.writer.write_entry(ACCUMULATOR, root.as_slice())?;
+
+        // offsets are relative to the start of the block index entry
+        let index_offset = self.writer.written() as i64;
+        let mut index = Vec::with_capacity(16 + self.offsets.len() * 8);
+        index.extend_from_slice(&start_block.to_le_bytes());
+        for offset in &self.offsets {
+            index.extend_from_slice(&(*offset as i64 - index_offset).to_le_bytes());
+        }
+        index.extend_from_slice(&(self.offsets.len() as u64).to_le_bytes());
+        self.writer.write_entry(BLOCK_INDEX, &index)?;
+        self.writer.flush()?;
+
+        Ok((self.writer.into_inner(), root))
+    }
+}
+
+/// Reads the blocks of an Era1 file.
+///
+/// The accumulator root and the block index are verified against the read blocks once the last
+/// block has been read.
+#[derive(Debug)]
+pub struct Era1Reader<R> {
+    /// The underlying e2store reader.
+    reader: E2sReader<R>,
+    /// Header records of the blocks read so far.
+    records: Vec<HeaderRecord>,
+    /// Offsets of the block tuples read so far, from the start of the file.
+    offsets: Vec<u64>,
+    /// Number of the first block of the file.
+    start_block: Option<BlockNumber>,
+    /// Whether the accumulator and block index have been read.
+    finished: bool,
+}
+
+impl<R: Read> Era1Reader<R> {
+    /// Creates a new reader, reading the version entry.
+    pub fn new(reader: R) -> Result<Self, EraError> {
+        let mut reader = E2sReader::new(reader);
+        reader.next_entry()?.expect_type(VERSION)?;
+        Ok(Self {
+            reader,
+            records: Vec::new(),
+            offsets: Vec::new(),
+            start_block: None,
+            finished: false,
+        })
+    }
+
+    /// Reads the next block, returning `None` once all blocks have been read and verified.
+    pub fn next_block(&mut self) -> Result<Option<BlockTuple>, EraError> {
+        while !self.finished {
+            let offset = self.reader.read();
+            let entry = self.reader.next_entry()?;
+            match entry.entry_type {
+                COMPRESSED_HEADER => return self.read_block(offset, entry.value).map(Some),
+                ACCUMULATOR => self.verify(entry.value)?,
+                // other entries can be skipped
+                _ => {}
+            }
+        }
+        Ok(None)
+    }
+
+    fn read_block(&mut self, offset: u64, header: Vec<u8>) -> Result<BlockTuple, EraError> {
+        let header: Header = decompress(&header)?;
+        let body: BlockBody = decompress(&self.reader.next_entry()?.expect_type(COMPRESSED_BODY)?)?;
+        let receipts: Vec<ReceiptWithBloom> =
+            decompress(&self.reader.next_entry()?.expect_type(COMPRESSED_RECEIPTS)?)?;
+
+        let total_difficulty = self.reader.next_entry()?.expect_type(TOTAL_DIFFICULTY)?;
+        if total_difficulty.len() != 32 {
+            return Err(EraError::InvalidEntryLength {
+                entry_type: TOTAL_DIFFICULTY,
+                len: total_difficulty.len(),
+            })
+        }
+        let total_difficulty = U256::from_le_slice(&total_difficulty);
+
+        let start_block = *self.start_block.get_or_insert(header.number);
+        let expected = start_block + self.records.len() as u64;
+        if header.number != expected {
+            return Err(EraError::BlockNumberMismatch { got: header.number, expected })
+        }
+        if self.records.len() >= MAX_BLOCKS_PER_ERA1 {
+            return Err(EraError::TooManyBlocks)
+        }
+        self.records.push(HeaderRecord { block_hash: header.hash_slow(), total_difficulty });
+        self.offsets.push(offset);
+
+        Ok(BlockTuple { header, body, receipts, total_difficulty })
+    }
+
+    /// Verifies the accumulator root and the block index against the read blocks.
+    fn verify(&mut self, accumulator: Vec<u8>) -> Result<(), EraError> {
+        if accumulator.len() != 32 {
+            return Err(EraError::InvalidEntryLength {
+                entry_type: ACCUMULATOR,
+                len: accumulator.len(),
+            })
+        }
+        let expected = B256::from_slice(&accumulator);
+        let got = accumulator_root(&self.records);
+        if got != expected {
+            return Err(EraError::AccumulatorMismatch { got, expected })
+        }
+
+        // offsets are relative to the start of the block index entry
+        let index_offset = self.reader.read() as i64;
+        let index = self.reader.next_entry()?.expect_type(BLOCK_INDEX)?;
+        let count = self.records.len();
+        if index.len() != 16 + count * 8 {
+            return Err(EraError::InvalidEntryLength { entry_type: BLOCK_INDEX, len: index.len() })
+        }
+        let mut words =
+            index.chunks_exact(8).map(|word| u64::from_le_bytes(word.try_into().expect("8 bytes")));
+
+        let start_block = words.next().expect("start block");
+        let expected = self.start_block.ok_or(EraError::Empty)?;
+        if start_block != expected {
+            return Err(EraError::BlockNumberMismatch { got: start_block, expected })
+        }
+        for (idx, (offset, expected)) in words.by_ref().zip(&self.offsets).enumerate() {
+            if offset as i64 != *expected as i64 - index_offset {
+                return Err(EraError::IndexMismatch(start_block + idx as u64))
+            }
+        }
+        let indexed = words.next().expect("block count");
+        if indexed != count as u64 {
+            return Err(EraError::InvalidEntryLength { entry_type: BLOCK_INDEX, len: index.len() })
+        }
+
+        self.finished = true;
+        Ok(())
+    }
+}
+
+impl<R: Read> Iterator for Era1Reader<R> {
+    type Item = Result<BlockTuple, EraError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.next_block().transpose();
+        if matches!(next, Some(Err(_))) {
+            // don't keep on reading a corrupted file
+            self.finished = true;
+        }
+        next
+    }
+}
+
+/// Rlp encodes and snappy compresses the value.
+fn compress<T: Encodable + ?Sized>(value: &T) -> Result<Vec<u8>, EraError> {
+    let mut encoder = snap::write::FrameEncoder::new(Vec::new());
+    encoder.write_all(&alloy_rlp::encode(value))?;
+    encoder.into_inner().map_err(|err| EraError::Io(err.into_error()))
+}
+
+/// Snappy decompresses and rlp decodes the value.
+fn decompress<T: Decodable>(data: &[u8]) -> Result<T, EraError> {
+    let mut buf = Vec::new();
+    snap::read::FrameDecoder::new(data).read_to_end(&mut buf)?;
+    Ok(T::decode(&mut buf.as_slice())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_interfaces::test_utils::{generators, generators::random_block_range};
+    use reth_primitives::{Address, Log};
+
+    #[test]
+    fn era1_roundtrip() {
+        let mut rng = generators::rng();
+        let blocks = random_block_range(&mut rng, 8188..=8192, B256::ZERO, 0..3);
+        let (blocks, next_epoch) = blocks.split_at(4);
+
+        let receipts = |block: &SealedBlock| {
+            block
+                .body
+                .iter()
+                .enumerate()
+                .map(|(idx, tx)| Receipt {
+                    tx_type: tx.tx_type(),
+                    success: idx % 2 == 0,
+                    cumulative_gas_used: 21_000 * (idx as u64 + 1),
+                    logs: vec![Log {
+                        address: Address::with_last_byte(idx as u8),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut writer = Era1Writer::new(Vec::new()).unwrap();
+        for (idx, block) in blocks.iter().enumerate() {
+            writer.append_block(block.clone(), &receipts(block), U256::from(idx)).unwrap();
+        }
+        assert_eq!(writer.len(), blocks.len());
+        assert!(matches!(
+            writer.append_block(next_epoch[0].clone(), &[], U256::ZERO),
+            Err(EraError::EpochBoundary { block: 8192, epoch: 0 })
+        ));
+        let (buf, root) = writer.finish().unwrap();
+
+        let read = Era1Reader::new(buf.as_slice()).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(read.len(), blocks.len());
+        for (idx, (tuple, block)) in read.into_iter().zip(blocks.iter()).enumerate() {
+            assert_eq!(tuple.total_difficulty, U256::from(idx));
+            assert_eq!(
+                tuple.receipts.into_iter().map(ReceiptWithBloom::into_receipt).collect::<Vec<_>>(),
+                receipts(block)
+            );
+            assert_eq!(tuple.into_block(), block.clone().unseal());
+        }
+
+        assert_eq!(
+            era1_file_name("mainnet", 8188, root),
+            format!("mainnet-00000-{}.era1", hex::encode(&root[..4]))
+        );
+    }
+
+    #[test]
+    fn era1_rejects_gaps_and_corruption() {
+        let mut rng = generators::rng();
+        let blocks = random_block_range(&mut rng, 0..=2, B256::ZERO, 0..1);
+
+        let mut writer = Era1Writer::new(Vec::new()).unwrap();
+        writer.append_block(blocks[0].clone(), &[], U256::ZERO).unwrap();
+        assert!(matches!(
+            writer.append_block(blocks[2].clone(), &[], U256::ZERO),
+            Err(EraError::BlockNumberMismatch { got: 2, expected: 1 })
+        ));
+        writer.append_block(blocks[1].clone(), &[], U256::ZERO).unwrap();
+        let (mut buf, root) = writer.finish().unwrap();
+
+        // corrupt the accumulator root
+        let pos = buf.windows(32).position(|w| w == root.as_slice()).unwrap();
+        buf[pos] ^= 1;
+        let err = Era1Reader::new(buf.as_slice()).unwrap().find_map(Result::err).unwrap();
+        assert!(matches!(err, EraError::AccumulatorMismatch { got, .. } if got == root));
+        buf[pos] ^= 1;
+
+        // corrupt the offset of the last block, which precedes the block count
+        let pos = buf.len() - 16;
+        buf[pos] ^= 1;
+        let err = Era1Reader::new(buf.as_slice()).unwrap().find_map(Result::err).unwrap();
+        assert!(matches!(err, EraError::IndexMismatch(1)));
+
+        assert!(matches!(Era1Writer::new(Vec::new()).unwrap().finish(), Err(EraError::Empty)));
+    }
+}
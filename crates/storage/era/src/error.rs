@@ -0,0 +1,68 @@
+use reth_primitives::B256;
+use thiserror::Error;
+
+/// Errors associated with reading and writing [e2store](crate::e2s) and Era1 files.
+#[derive(Debug, Error)]
+pub enum EraError {
+    /// An error occurred when reading or writing the file.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// An error occurred when decoding the rlp of an entry.
+    #[error(transparent)]
+    Rlp(#[from] alloy_rlp::Error),
+    /// The file ended in the middle of an entry.
+    #[error("unexpected end of file")]
+    UnexpectedEof,
+    /// An entry of another type than the expected one was found.
+    #[error("unexpected entry type {got:#06x}, expected {expected:#06x}")]
+    UnexpectedEntry {
+        /// The type of the found entry.
+        got: u16,
+        /// The expected entry type.
+        expected: u16,
+    },
+    /// An entry has an invalid length.
+    #[error("invalid length {len} of entry type {entry_type:#06x}")]
+    InvalidEntryLength {
+        /// The type of the entry.
+        entry_type: u16,
+        /// The length of the entry.
+        len: usize,
+    },
+    /// The header of an entry has non zero reserved bytes.
+    #[error("non zero reserved bytes in header of entry type {0:#06x}")]
+    NonZeroReserved(u16),
+    /// The block numbers of an Era1 file are not consecutive.
+    #[error("expected block {expected}, got block {got}")]
+    BlockNumberMismatch {
+        /// The number of the found block.
+        got: u64,
+        /// The expected block number.
+        expected: u64,
+    },
+    /// The accumulator root of an Era1 file doesn't match its blocks.
+    #[error("accumulator root mismatch: got {got}, expected {expected}")]
+    AccumulatorMismatch {
+        /// The accumulator root computed from the blocks.
+        got: B256,
+        /// The accumulator root stored in the file.
+        expected: B256,
+    },
+    /// The block index of an Era1 file doesn't point to the given block.
+    #[error("block index offset of block {0} doesn't match its position")]
+    IndexMismatch(u64),
+    /// A block of another epoch than the first block of an Era1 file was appended.
+    #[error("block {block} is outside of epoch {epoch}")]
+    EpochBoundary {
+        /// The number of the appended block.
+        block: u64,
+        /// The epoch of the file.
+        epoch: u64,
+    },
+    /// More blocks than an Era1 file can hold were appended.
+    #[error("era1 files can hold at most {} blocks", crate::MAX_BLOCKS_PER_ERA1)]
+    TooManyBlocks,
+    /// An Era1 file was finished without any block.
+    #[error("era1 files must hold at least one block")]
+    Empty,
+}
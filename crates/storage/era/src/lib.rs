@@ -0,0 +1,24 @@
+//! Reading and writing of [Era1](https://github.com/ethereum/go-ethereum/pull/26621) archives.
+//!
+//! Era1 files are [e2store] files holding up to [`MAX_BLOCKS_PER_ERA1`] consecutive blocks along
+//! with their receipts, total difficulty and the header accumulator root of the blocks.
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![warn(missing_debug_implementations, missing_docs, unreachable_pub, rustdoc::all)]
+#![deny(unused_must_use, rust_2018_idioms)]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+pub mod e2s;
+
+mod accumulator;
+pub use accumulator::{accumulator_root, HeaderRecord};
+
+mod era1;
+pub use era1::{era1_file_name, BlockTuple, Era1Reader, Era1Writer, MAX_BLOCKS_PER_ERA1};
+
+mod error;
+pub use error::EraError;
@@ -223,7 +223,7 @@ where
 
         let column_offset_range = if self.jar.offsets.len() == (offset_pos + 1) {
             // It's the last column of the last row
-            value_offset..self.mmap_handle.len()
+            value_offset..self.jar.data_size
         } else {
             let next_value_offset = self.jar.offsets.select(offset_pos + 1).expect("should exist");
             value_offset..next_value_offset
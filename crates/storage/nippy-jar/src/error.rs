@@ -41,4 +41,10 @@ pub enum NippyJarError {
     DictionaryNotLoaded,
     #[error("It's not possible to generate a compressor after loading a dictionary.")]
     CompressorNotAllowed,
+    #[error("data file is smaller than its committed size: {0} < {1}")]
+    DataFileTooSmall(u64, u64),
+    #[error("offsets index has {0} rows, but the jar has {1}")]
+    OutdatedIndex(usize, usize),
+    #[error("unsupported nippy jar version {0}, expected version {1}")]
+    UnsupportedVersion(usize, usize),
 }
@@ -45,7 +45,10 @@ pub use error::NippyJarError;
 mod cursor;
 pub use cursor::NippyJarCursor;
 
-const NIPPY_JAR_VERSION: usize = 1;
+mod writer;
+pub use writer::NippyJarWriter;
+
+const NIPPY_JAR_VERSION: usize = 2;
 
 /// A [`RefRow`] is a list of column value slices pointing to either an internal buffer or a
 /// memory-mapped file.
@@ -76,8 +79,13 @@ pub type ColumnResult<T> = Result<T, Box<dyn StdError + Send + Sync>>;
 /// Note: that the key (eg. BlockHash) passed to a filter and phf does not need to actually be
 /// stored.
 ///
-/// Ultimately, the `freeze` function yields two files: a data file containing both the data and its
-/// configuration, and an index file that houses the offsets and offsets_index.
+/// Ultimately, the `freeze` function yields two files: a data file containing the data, and an
+/// index file that houses the configuration, the offsets and offsets_index. Rows can later be
+/// appended to an existing jar with a [`NippyJarWriter`].
+///
+/// The index file is always replaced atomically and records the size of the committed data.
+/// Readers ignore anything past that size, and [`NippyJarWriter`] truncates it on open, so a crash
+/// in the middle of an append leaves the jar in its last committed state.
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(test, derive(PartialEq))]
 pub struct NippyJar<H = ()> {
@@ -104,6 +112,9 @@ pub struct NippyJar<H = ()> {
     /// resizing of the output buffer.
     #[serde(skip)]
     max_row_size: usize,
+    /// Size in bytes of the committed data in the data file.
+    #[serde(skip)]
+    data_size: usize,
     /// Data path for file. Index file will be `{path}.idx`
     #[serde(skip)]
     path: Option<PathBuf>,
@@ -124,6 +135,7 @@ impl<H: std::fmt::Debug> std::fmt::Debug for NippyJar<H> {
             .field("offsets (size in bytes)", &self.offsets.size_in_bytes())
             .field("path", &self.path)
             .field("max_row_size", &self.max_row_size)
+            .field("data_size", &self.data_size)
             .finish_non_exhaustive()
     }
 }
@@ -156,6 +168,7 @@ where
             user_header,
             columns,
             max_row_size: 0,
+            data_size: 0,
             compressor: None,
             filter: None,
             phf: None,
@@ -230,27 +243,54 @@ where
     ///
     /// **The user must ensure the header type matches the one used during the jar's creation.**
     pub fn load(path: &Path) -> Result<Self, NippyJarError> {
-        // Read [`Self`] located at the data file.
-        let data_file = File::open(path)?;
+        let index_file = File::open(Self::index_path_of(path))?;
 
         // SAFETY: File is read-only and its descriptor is kept alive as long as the mmap handle.
-        let data_reader = unsafe { memmap2::Mmap::map(&data_file)? };
-        let mut obj: Self = bincode::deserialize_from(data_reader.as_ref())?;
+        let mmap = unsafe { memmap2::Mmap::map(&index_file)? };
+        let mut index_reader = mmap.as_ref();
+
+        // Read [`Self`] located at the start of the index file. Jars of another version are not
+        // migrated and have to be rebuilt.
+        let mut obj = match bincode::deserialize_from::<_, Self>(&mut index_reader) {
+            Ok(obj) if obj.version == NIPPY_JAR_VERSION => obj,
+            res => {
+                if Self::is_v1(path) {
+                    return Err(NippyJarError::UnsupportedVersion(1, NIPPY_JAR_VERSION))
+                }
+                // Since version 2, the version is the first field of the index file.
+                let version = match res {
+                    Ok(obj) => obj.version,
+                    Err(err) => match bincode::deserialize::<usize>(mmap.as_ref()) {
+                        Ok(version) if version != NIPPY_JAR_VERSION => version,
+                        _ => return Err(err.into()),
+                    },
+                };
+                return Err(NippyJarError::UnsupportedVersion(version, NIPPY_JAR_VERSION))
+            }
+        };
         obj.path = Some(path.to_path_buf());
 
-        // Read the offsets lists located at the index file.
-        let offsets_file = File::open(obj.index_path())?;
-
-        // SAFETY: File is read-only and its descriptor is kept alive as long as the mmap handle.
-        let mmap = unsafe { memmap2::Mmap::map(&offsets_file)? };
-        let mut offsets_reader = mmap.as_ref();
-        obj.offsets = EliasFano::deserialize_from(&mut offsets_reader)?;
-        obj.offsets_index = PrefixSummedEliasFano::deserialize_from(&mut offsets_reader)?;
-        obj.max_row_size = bincode::deserialize_from(offsets_reader)?;
+        // Read the offsets lists and data size that follow it.
+        obj.offsets = EliasFano::deserialize_from(&mut index_reader)?;
+        obj.offsets_index = PrefixSummedEliasFano::deserialize_from(&mut index_reader)?;
+        obj.max_row_size = bincode::deserialize_from(&mut index_reader)?;
+        obj.data_size = bincode::deserialize_from(index_reader)?;
 
         Ok(obj)
     }
 
+    /// Returns `true` if the data file at `path` starts with the configuration of a version 1 jar.
+    ///
+    /// Version 1 kept the configuration at the start of the data file, and its index file started
+    /// with the offsets instead.
+    fn is_v1(path: &Path) -> bool {
+        let Ok(data_file) = File::open(path) else { return false };
+
+        // SAFETY: File is read-only and its descriptor is kept alive as long as the mmap handle.
+        let Ok(mmap) = (unsafe { memmap2::Mmap::map(&data_file) }) else { return false };
+        bincode::deserialize::<Self>(mmap.as_ref()).is_ok_and(|obj| obj.version == 1)
+    }
+
     /// Returns the path from the data file
     pub fn data_path(&self) -> PathBuf {
         self.path.clone().expect("exists")
@@ -258,13 +298,27 @@ where
 
     /// Returns the path from the index file
     pub fn index_path(&self) -> PathBuf {
-        let data_path = self.data_path();
+        Self::index_path_of(&self.data_path())
+    }
+
+    /// Returns the index file path of the data file at `data_path`.
+    fn index_path_of(data_path: &Path) -> PathBuf {
         data_path
             .parent()
             .expect("exists")
             .join(format!("{}.idx", data_path.file_name().expect("exists").to_string_lossy()))
     }
 
+    /// Returns the number of rows in the jar.
+    pub fn rows(&self) -> usize {
+        self.offsets.len().checked_div(self.columns).unwrap_or_default()
+    }
+
+    /// Returns the size in bytes of the committed data in the data file.
+    pub fn data_size(&self) -> usize {
+        self.data_size
+    }
+
     /// Returns a [`MmapHandle`] of the data file
     pub fn open_data(&self) -> Result<MmapHandle, NippyJarError> {
         MmapHandle::new(self.data_path())
@@ -293,7 +347,19 @@ where
         values: impl IntoIterator<Item = ColumnResult<T>>,
         row_count: usize,
     ) -> Result<(), NippyJarError> {
-        debug!(target: "nippy-jar", ?row_count, "Preparing index.");
+        self.build_index(values, row_count, 0)
+    }
+
+    /// Builds the perfect hashing function and the offsets index over `values`, and adds every
+    /// value from row `filtered_rows` onwards to the filter. Values of earlier rows are expected
+    /// to already be part of it.
+    fn build_index<T: PHFKey>(
+        &mut self,
+        values: impl IntoIterator<Item = ColumnResult<T>>,
+        row_count: usize,
+        filtered_rows: usize,
+    ) -> Result<(), NippyJarError> {
+        debug!(target: "nippy-jar", ?row_count, ?filtered_rows, "Preparing index.");
 
         let values = values.into_iter().collect::<Result<Vec<_>, _>>()?;
 
@@ -316,7 +382,9 @@ where
 
             for (row_num, v) in values.into_iter().enumerate() {
                 if let Some(filter) = self.filter.as_mut() {
-                    filter.add(v.as_ref())?;
+                    if row_num >= filtered_rows {
+                        filter.add(v.as_ref())?;
+                    }
                 }
 
                 if let Some(phf) = self.phf.as_mut() {
@@ -332,14 +400,44 @@ where
         Ok(())
     }
 
-    /// Writes all data and configuration to a file and the offset index to another.
+    /// Writes all data to a file and the configuration and offset index to another.
     pub fn freeze(
         &mut self,
         columns: Vec<impl IntoIterator<Item = ColumnResult<Vec<u8>>>>,
         total_rows: u64,
     ) -> Result<(), NippyJarError> {
         let mut file = self.freeze_check(&columns)?;
-        self.freeze_config(&mut file)?;
+
+        // Write all rows while taking all row start offsets
+        let mut offsets = Vec::with_capacity(total_rows as usize * self.columns);
+        self.max_row_size = self.write_rows(&mut file, columns, total_rows, &mut offsets)?;
+        self.data_size = file.stream_position()? as usize;
+        file.sync_all()?;
+
+        // Write configuration, offsets and offset index to file
+        self.freeze_offsets(&offsets)?;
+        self.freeze_index()?;
+
+        debug!(target: "nippy-jar", jar=?self, "Finished.");
+
+        Ok(())
+    }
+
+    /// Writes `total_rows` rows to the current position of `file`, pushing the start offset of
+    /// each column value to `offsets`.
+    ///
+    /// Returns the maximum uncompressed row size of the written rows.
+    fn write_rows(
+        &self,
+        file: &mut File,
+        columns: Vec<impl IntoIterator<Item = ColumnResult<Vec<u8>>>>,
+        total_rows: u64,
+        offsets: &mut Vec<usize>,
+    ) -> Result<usize, NippyJarError> {
+        let mut max_row_size = 0;
+        if total_rows == 0 {
+            return Ok(max_row_size)
+        }
 
         // Special case for zstd that might use custom dictionaries/compressors per column
         // If any other compression algorithm is added and uses a similar flow, then revisit
@@ -353,9 +451,7 @@ where
         // dict)
         let mut tmp_buf = Vec::with_capacity(1_000_000);
 
-        // Write all rows while taking all row start offsets
         let mut row_number = 0u64;
-        let mut offsets = Vec::with_capacity(total_rows as usize * self.columns);
         let mut column_iterators =
             columns.into_iter().map(|v| v.into_iter()).collect::<Vec<_>>().into_iter();
 
@@ -382,7 +478,7 @@ where
                                 compression::Zstd::compress_with_dictionary(
                                     &value,
                                     &mut tmp_buf,
-                                    &mut file,
+                                    file,
                                     Some(dict_compressors.get_mut(column_number).expect("exists")),
                                 )?;
                             } else {
@@ -408,7 +504,7 @@ where
 
             tmp_buf.clear();
             row_number += 1;
-            self.max_row_size = self.max_row_size.max(uncompressed_row_size);
+            max_row_size = max_row_size.max(uncompressed_row_size);
 
             if row_number == total_rows {
                 break
@@ -417,19 +513,11 @@ where
             column_iterators = iterators.into_iter();
         }
 
-        // drops immutable borrow
-        drop(maybe_zstd_compressors);
-
-        // Write offsets and offset index to file
-        self.freeze_offsets(offsets)?;
-
-        debug!(target: "nippy-jar", jar=?self, "Finished.");
-
-        Ok(())
+        Ok(max_row_size)
     }
 
-    /// Freezes offsets and its own index.
-    fn freeze_offsets(&mut self, offsets: Vec<usize>) -> Result<(), NippyJarError> {
+    /// Encodes the offsets list. Its own index is built by [`NippyJar::prepare_index`].
+    fn freeze_offsets(&mut self, offsets: &[usize]) -> Result<(), NippyJarError> {
         if !offsets.is_empty() {
             debug!(target: "nippy-jar", "Encoding offsets list.");
 
@@ -437,17 +525,34 @@ where
                 EliasFanoBuilder::new(*offsets.last().expect("qed") + 1, offsets.len())?;
 
             for offset in offsets {
-                builder.push(offset)?;
+                builder.push(*offset)?;
             }
             self.offsets = builder.build().enable_rank();
         }
+        Ok(())
+    }
+
+    /// Writes the configuration, offsets, offsets index and data size to the index file.
+    ///
+    /// The index is first written to a temporary file which then replaces the previous one, so
+    /// the index file is never left partially written.
+    fn freeze_index(&self) -> Result<(), NippyJarError> {
+        let index_path = self.index_path();
+        let tmp_path = index_path.with_extension("idx.tmp");
 
-        debug!(target: "nippy-jar", path=?self.index_path(), "Writing offsets and offsets index to file.");
+        debug!(target: "nippy-jar", path=?index_path, "Writing configuration and offsets to file.");
 
-        let mut file = File::create(self.index_path())?;
+        let mut file = File::create(&tmp_path)?;
+        // TODO Split Dictionaries and Bloomfilters Configuration so we dont have to load everything
+        // at once
+        bincode::serialize_into(&mut file, &self)?;
         self.offsets.serialize_into(&mut file)?;
         self.offsets_index.serialize_into(&mut file)?;
-        self.max_row_size.serialize_into(file)?;
+        self.max_row_size.serialize_into(&mut file)?;
+        self.data_size.serialize_into(&mut file)?;
+        file.sync_all()?;
+
+        std::fs::rename(tmp_path, index_path)?;
         Ok(())
     }
 
@@ -456,15 +561,7 @@ where
         &mut self,
         columns: &[impl IntoIterator<Item = ColumnResult<Vec<u8>>>],
     ) -> Result<File, NippyJarError> {
-        if columns.len() != self.columns {
-            return Err(NippyJarError::ColumnLenMismatch(self.columns, columns.len()))
-        }
-
-        if let Some(compression) = &self.compressor {
-            if !compression.is_ready() {
-                return Err(NippyJarError::CompressorNotReady)
-            }
-        }
+        self.write_check(columns.len())?;
 
         // Check `prepare_index` was called.
         if let Some(phf) = &self.phf {
@@ -476,11 +573,19 @@ where
         Ok(File::create(self.data_path())?)
     }
 
-    /// Writes all necessary configuration to file.
-    fn freeze_config(&mut self, handle: &mut File) -> Result<(), NippyJarError> {
-        // TODO Split Dictionaries and Bloomfilters Configuration so we dont have to load everything
-        // at once
-        Ok(bincode::serialize_into(handle, &self)?)
+    /// Checks that `columns` values can be written to the jar.
+    fn write_check(&self, columns: usize) -> Result<(), NippyJarError> {
+        if columns != self.columns {
+            return Err(NippyJarError::ColumnLenMismatch(self.columns, columns))
+        }
+
+        if let Some(compression) = &self.compressor {
+            if !compression.is_ready() {
+                return Err(NippyJarError::CompressorNotReady)
+            }
+        }
+
+        Ok(())
     }
 }
 
@@ -1013,4 +1118,127 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_append_rows() {
+        let (col1, col2) = test_data(None);
+        let num_columns = 2;
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+        let (initial_rows, appended_rows) = (col1.len() / 2, col1.len() - col1.len() / 2);
+
+        // Freeze the first half of the rows
+        {
+            let mut nippy = NippyJar::new_without_header(num_columns, file_path.path())
+                .with_lz4()
+                .with_cuckoo_filter(col1.len())
+                .with_fmph();
+
+            nippy
+                .prepare_index(clone_with_result(&col1[..initial_rows].to_vec()), initial_rows)
+                .unwrap();
+            nippy
+                .freeze(
+                    vec![
+                        clone_with_result(&col1[..initial_rows].to_vec()),
+                        clone_with_result(&col2[..initial_rows].to_vec()),
+                    ],
+                    initial_rows as u64,
+                )
+                .unwrap();
+        }
+
+        // Append the second half of the rows
+        {
+            let mut nippy = NippyJar::load_without_header(file_path.path()).unwrap();
+            assert_eq!(nippy.rows(), initial_rows);
+
+            let mut writer = NippyJarWriter::new(&mut nippy).unwrap();
+            writer
+                .append_rows(
+                    vec![
+                        clone_with_result(&col1[initial_rows..].to_vec()),
+                        clone_with_result(&col2[initial_rows..].to_vec()),
+                    ],
+                    appended_rows as u64,
+                )
+                .unwrap();
+
+            // Index has not been extended to the appended rows
+            assert!(matches!(writer.commit(), Err(NippyJarError::OutdatedIndex(_, _))));
+
+            writer.append_index(clone_with_result(&col1)).unwrap();
+            writer.commit().unwrap();
+        }
+
+        // Read all rows
+        let loaded_nippy = NippyJar::load_without_header(file_path.path()).unwrap();
+        assert_eq!(loaded_nippy.rows(), col1.len());
+
+        let mut cursor = NippyJarCursor::new(&loaded_nippy).unwrap();
+        let mut row_index = 0usize;
+        while let Some(row) = cursor.next_row().unwrap() {
+            assert_eq!((row[0], row[1]), (col1[row_index].as_slice(), col2[row_index].as_slice()));
+            row_index += 1;
+        }
+        assert_eq!(row_index, col1.len());
+
+        for (v0, v1) in col1.iter().zip(col2.iter()) {
+            let row = cursor.row_by_key(v0).unwrap().unwrap();
+            assert_eq!((row[0], row[1]), (v0.as_slice(), v1.as_slice()));
+        }
+    }
+
+    #[test]
+    fn test_append_rows_recovery() {
+        let (col1, col2) = test_data(None);
+        let num_columns = 2;
+        let num_rows = col1.len() as u64;
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        // Freeze all rows
+        let mut nippy = NippyJar::new_without_header(num_columns, file_path.path()).with_lz4();
+        nippy.freeze(vec![clone_with_result(&col1), clone_with_result(&col2)], num_rows).unwrap();
+        let data_size = nippy.data_size();
+
+        // Append rows without committing them, as if the process had crashed
+        {
+            let mut writer = NippyJarWriter::new(&mut nippy).unwrap();
+            writer
+                .append_rows(vec![clone_with_result(&col2), clone_with_result(&col1)], num_rows)
+                .unwrap();
+        }
+        assert!(std::fs::metadata(file_path.path()).unwrap().len() > data_size as u64);
+
+        // Readers ignore the uncommitted data
+        let mut loaded_nippy = NippyJar::load_without_header(file_path.path()).unwrap();
+        assert_eq!(loaded_nippy.rows(), col1.len());
+        assert_eq!(loaded_nippy.data_size(), data_size);
+        {
+            let mut cursor = NippyJarCursor::new(&loaded_nippy).unwrap();
+            let last_row = cursor.row_by_number(col1.len() - 1).unwrap().unwrap();
+            assert_eq!(
+                (last_row[0], last_row[1]),
+                (col1[col1.len() - 1].as_slice(), col2[col2.len() - 1].as_slice())
+            );
+        }
+
+        // Opening a writer truncates the uncommitted data
+        let writer = NippyJarWriter::new(&mut loaded_nippy).unwrap();
+        assert_eq!(writer.rows(), col1.len());
+        assert_eq!(std::fs::metadata(file_path.path()).unwrap().len(), data_size as u64);
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        // A jar frozen by version 1, with 100 rows of two columns. Its index file starts with the
+        // offsets, whose first field is not the version.
+        let file_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/v1.jar");
+        let index = std::fs::read(file_path.with_extension("jar.idx")).unwrap();
+        assert_ne!(&index[..8], 1usize.to_le_bytes());
+
+        assert!(matches!(
+            NippyJar::load_without_header(&file_path),
+            Err(NippyJarError::UnsupportedVersion(1, NIPPY_JAR_VERSION))
+        ));
+    }
 }
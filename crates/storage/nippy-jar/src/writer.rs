@@ -0,0 +1,134 @@
+use crate::{compression::Compressors, ColumnResult, NippyJar, NippyJarError, PHFKey};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{File, OpenOptions},
+    io::{Seek, SeekFrom},
+};
+use tracing::*;
+
+/// Writer to append rows to a [`NippyJar`].
+///
+/// Appended rows only become visible once [`NippyJarWriter::commit`] has rewritten the index file.
+/// Any data written after the last commit (eg. because of a crash) is truncated when the writer is
+/// opened again.
+///
+/// The writer updates the in-memory [`NippyJar`] it was created from as rows are appended.
+#[derive(Debug)]
+pub struct NippyJarWriter<'a, H = ()> {
+    /// [`NippyJar`] to append rows to.
+    jar: &'a mut NippyJar<H>,
+    /// Data file handle, positioned at its end.
+    data_file: File,
+    /// Offsets of every column value in the data file, including the appended ones.
+    offsets: Vec<usize>,
+}
+
+impl<'a, H> NippyJarWriter<'a, H>
+where
+    H: Send + Sync + Serialize + for<'b> Deserialize<'b> + std::fmt::Debug,
+{
+    /// Opens the data file of `jar` for appending, creating it if it doesn't exist yet.
+    ///
+    /// Data past the committed size of the jar is removed from the data file.
+    ///
+    /// Jars compressed with `zstd` dictionaries can only be appended to before being loaded from
+    /// disk, since loading discards the data needed to build the compressors.
+    pub fn new(jar: &'a mut NippyJar<H>) -> Result<Self, NippyJarError> {
+        if let Some(Compressors::Zstd(zstd)) = &jar.compressor {
+            let _ = zstd.compressors()?;
+        }
+
+        // Uncommitted data is truncated below, once the committed size is known.
+        let mut data_file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(jar.data_path())?;
+
+        // Makes sure the data file matches the committed size of the jar.
+        let data_size = jar.data_size as u64;
+        let file_size = data_file.metadata()?.len();
+        if file_size > data_size {
+            warn!(
+                target: "nippy-jar",
+                path = ?jar.data_path(),
+                file_size,
+                data_size,
+                "Truncating uncommitted data."
+            );
+            data_file.set_len(data_size)?;
+            data_file.sync_all()?;
+        } else if file_size < data_size {
+            return Err(NippyJarError::DataFileTooSmall(file_size, data_size))
+        }
+        data_file.seek(SeekFrom::End(0))?;
+
+        let offsets = (0..jar.offsets.len())
+            .map(|pos| jar.offsets.select(pos).expect("should exist"))
+            .collect();
+
+        Ok(Self { jar, data_file, offsets })
+    }
+
+    /// Returns a reference to the [`NippyJar`] being appended to.
+    pub fn jar(&self) -> &NippyJar<H> {
+        self.jar
+    }
+
+    /// Returns the number of rows in the jar, including the uncommitted ones.
+    pub fn rows(&self) -> usize {
+        self.offsets.len() / self.jar.columns
+    }
+
+    /// Appends `num_rows` rows to the data file. They are only persisted by
+    /// [`NippyJarWriter::commit`].
+    pub fn append_rows(
+        &mut self,
+        columns: Vec<impl IntoIterator<Item = ColumnResult<Vec<u8>>>>,
+        num_rows: u64,
+    ) -> Result<(), NippyJarError> {
+        self.jar.write_check(columns.len())?;
+
+        debug!(target: "nippy-jar", num_rows, rows = self.rows(), "Appending rows.");
+
+        let max_row_size =
+            self.jar.write_rows(&mut self.data_file, columns, num_rows, &mut self.offsets)?;
+        self.jar.max_row_size = self.jar.max_row_size.max(max_row_size);
+
+        Ok(())
+    }
+
+    /// Rebuilds the perfect hashing function and the offsets index, and adds the keys of the
+    /// rows appended since the index was last built to the filter.
+    ///
+    /// Since a perfect hashing function can't be extended, `keys` must hold the key of **every**
+    /// row in the jar, sorted in the same way as the data.
+    pub fn append_index<T: PHFKey>(
+        &mut self,
+        keys: impl IntoIterator<Item = ColumnResult<T>>,
+    ) -> Result<(), NippyJarError> {
+        let rows = self.rows();
+        let indexed_rows = self.jar.offsets_index.len();
+        self.jar.build_index(keys, rows, indexed_rows)
+    }
+
+    /// Flushes the appended rows to disk and atomically replaces the index file, making them
+    /// visible to new readers of the jar.
+    pub fn commit(&mut self) -> Result<(), NippyJarError> {
+        let rows = self.rows();
+        if self.jar.phf.is_some() && self.jar.offsets_index.len() != rows {
+            return Err(NippyJarError::OutdatedIndex(self.jar.offsets_index.len(), rows))
+        }
+
+        self.data_file.sync_all()?;
+        self.jar.data_size = self.data_file.stream_position()? as usize;
+
+        self.jar.freeze_offsets(&self.offsets)?;
+        self.jar.freeze_index()?;
+
+        debug!(target: "nippy-jar", jar=?self.jar, "Committed rows.");
+
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{StateChanges, StateReverts};
 use reth_db::{
     cursor::{DbCursorRO, DbCursorRW},
+    database::Database,
     tables,
     transaction::{DbTx, DbTxMut},
 };
@@ -14,7 +15,7 @@ use reth_primitives::{
 use reth_trie::{
     hashed_cursor::{HashedPostState, HashedPostStateCursorFactory, HashedStorage},
     updates::TrieUpdates,
-    StateRoot, StateRootError,
+    ParallelStateRoot, StateRoot, StateRootError,
 };
 use revm::{db::states::BundleState, primitives::AccountInfo};
 use std::collections::HashMap;
@@ -95,6 +96,11 @@ impl BundleStateWithReceipts {
         &self.bundle
     }
 
+    /// Consumes self and returns the underlying bundle state.
+    pub fn into_state(self) -> BundleState {
+        self.bundle
+    }
+
     /// Set first block.
     pub fn set_first_block(&mut self, first_block: BlockNumber) {
         self.first_block = first_block;
@@ -169,6 +175,18 @@ impl BundleStateWithReceipts {
             .with_destroyed_accounts(hashed_post_state.destroyed_accounts())
     }
 
+    /// Returns [ParallelStateRoot] calculator based on database and in-memory state.
+    ///
+    /// Storage roots of the changed accounts are computed in parallel, each on its own read-only
+    /// transaction opened on `db`.
+    pub fn parallel_state_root_calculator<'a, DB: Database>(
+        &self,
+        db: &'a DB,
+        hashed_post_state: &'a HashedPostState,
+    ) -> ParallelStateRoot<'a, DB> {
+        ParallelStateRoot::new(db).with_hashed_state(hashed_post_state)
+    }
+
     /// Calculate the state root for this [BundleState].
     /// Internally, function calls [Self::hash_state_slow] to obtain the [HashedPostState].
     /// Afterwards, it retrieves the prefixsets from the [HashedPostState] and uses them to
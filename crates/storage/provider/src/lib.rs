@@ -22,17 +22,20 @@ pub use traits::{
     CanonStateNotification, CanonStateNotificationSender, CanonStateNotifications,
     CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader, EvmEnvProvider, ExecutorFactory,
     HashingWriter, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider, HeaderSyncMode,
-    HistoryWriter, PrunableBlockExecutor, PruneCheckpointReader, PruneCheckpointWriter,
-    ReceiptProvider, ReceiptProviderIdExt, StageCheckpointReader, StageCheckpointWriter,
-    StateProvider, StateProviderBox, StateProviderFactory, StateRootProvider, StorageReader,
+    HistoryReader, HistoryWriter, InvalidBlockReader, LatestStateChanges, LatestStateWriter,
+    PlainStateReader, PrunableBlockExecutor, PruneCheckpointReader, PruneCheckpointWriter,
+    ReceiptProvider, ReceiptProviderIdExt, RevertStateCache, StageCheckpointReader,
+    StageCheckpointWriter, StateProvider, StateProviderBox, StateProviderFactory,
+    StateRangeProvider, StateRangeProviderBox, StateRangeReader, StateRootProvider, StorageReader,
     TransactionVariant, TransactionsProvider, TransactionsProviderExt, WithdrawalsProvider,
 };
 
 /// Provider trait implementations.
 pub mod providers;
 pub use providers::{
-    DatabaseProvider, DatabaseProviderRO, DatabaseProviderRW, HistoricalStateProvider,
-    HistoricalStateProviderRef, LatestStateProvider, LatestStateProviderRef, ProviderFactory,
+    DatabaseProvider, DatabaseProviderRO, DatabaseProviderRW, HashedStateRangeProvider,
+    HistoricalStateProvider, HistoricalStateProviderRef, LatestStateProvider,
+    LatestStateProviderRef, ProviderFactory,
 };
 
 #[cfg(any(test, feature = "test-utils"))]
@@ -21,6 +21,11 @@ impl<SP: StateProvider, BSDP: BundleStateDataProvider> BundleStateProvider<SP, B
     pub fn new(state_provider: SP, bundle_state_data_provider: BSDP) -> Self {
         Self { state_provider, bundle_state_data_provider }
     }
+
+    /// Returns the bundle state data on top of the inner state provider.
+    pub fn bundle_state_data_provider(&self) -> &BSDP {
+        &self.bundle_state_data_provider
+    }
 }
 
 /* Implement StateProvider traits */
@@ -1,13 +1,17 @@
 use crate::{
     providers::{
-        state::{historical::HistoricalStateProvider, latest::LatestStateProvider},
+        state::{
+            historical::HistoricalStateProvider, latest::LatestStateProvider,
+            range::HashedStateRangeProvider,
+        },
         SnapshotProvider,
     },
     traits::{BlockSource, ReceiptProvider},
     BlockHashReader, BlockNumReader, BlockReader, ChainSpecProvider, EvmEnvProvider,
     HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider, HeaderSyncMode, ProviderError,
-    PruneCheckpointReader, StageCheckpointReader, StateProviderBox, TransactionVariant,
-    TransactionsProvider, WithdrawalsProvider,
+    PruneCheckpointReader, RevertStateCache, StageCheckpointReader, StateProviderBox,
+    StateRangeProviderBox, StateRangeReader, TransactionVariant, TransactionsProvider,
+    WithdrawalsProvider,
 };
 use reth_db::{database::Database, init_db, models::StoredBlockBodyIndices, DatabaseEnv};
 use reth_interfaces::{db::LogLevel, provider::ProviderResult, RethError, RethResult};
@@ -19,6 +23,7 @@ use reth_primitives::{
     SealedHeader, TransactionMeta, TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber,
     Withdrawal, B256, U256,
 };
+use reth_trie::hashed_cursor::HashedPostState;
 use revm::primitives::{BlockEnv, CfgEnv};
 use std::{
     ops::{RangeBounds, RangeInclusive},
@@ -39,17 +44,17 @@ pub use provider::{DatabaseProvider, DatabaseProviderRO, DatabaseProviderRW};
 #[derive(Debug)]
 pub struct ProviderFactory<DB> {
     /// Database
-    db: DB,
+    db: Arc<DB>,
     /// Chain spec
     chain_spec: Arc<ChainSpec>,
     /// Snapshot Provider
     snapshot_provider: Option<Arc<SnapshotProvider>>,
 }
 
-impl<DB: Clone> Clone for ProviderFactory<DB> {
+impl<DB> Clone for ProviderFactory<DB> {
     fn clone(&self) -> Self {
         Self {
-            db: self.db.clone(),
+            db: Arc::clone(&self.db),
             chain_spec: Arc::clone(&self.chain_spec),
             snapshot_provider: self.snapshot_provider.clone(),
         }
@@ -61,7 +66,7 @@ impl<DB: Database> ProviderFactory<DB> {}
 impl<DB> ProviderFactory<DB> {
     /// Create new database provider factory.
     pub fn new(db: DB, chain_spec: Arc<ChainSpec>) -> Self {
-        Self { db, chain_spec, snapshot_provider: None }
+        Self { db: Arc::new(db), chain_spec, snapshot_provider: None }
     }
 
     /// Create new database provider by passing a path. [`ProviderFactory`] will own the database
@@ -72,7 +77,7 @@ impl<DB> ProviderFactory<DB> {
         log_level: Option<LogLevel>,
     ) -> RethResult<ProviderFactory<DatabaseEnv>> {
         Ok(ProviderFactory::<DatabaseEnv> {
-            db: init_db(path, log_level).map_err(|e| RethError::Custom(e.to_string()))?,
+            db: Arc::new(init_db(path, log_level).map_err(|e| RethError::Custom(e.to_string()))?),
             chain_spec,
             snapshot_provider: None,
         })
@@ -122,7 +127,7 @@ impl<DB: Database> ProviderFactory<DB> {
             provider = provider.with_snapshot_provider(snapshot_provider.clone());
         }
 
-        Ok(DatabaseProviderRW(provider))
+        Ok(DatabaseProviderRW(provider, Arc::clone(&self.db)))
     }
 
     /// Storage provider for latest block
@@ -153,6 +158,9 @@ impl<DB: Database> ProviderFactory<DB> {
             provider.get_prune_checkpoint(PruneSegment::StorageHistory)?;
 
         let mut state_provider = HistoricalStateProvider::new(provider.into_tx(), block_number);
+        if let Some(snapshot_provider) = &self.snapshot_provider {
+            state_provider = state_provider.with_snapshot_provider(snapshot_provider.clone());
+        }
 
         // If we pruned account or storage history, we can't return state on every historical block.
         // Instead, we should cap it at the latest prune checkpoint for corresponding prune segment.
@@ -489,17 +497,75 @@ impl<DB: Database> PruneCheckpointReader for ProviderFactory<DB> {
     }
 }
 
+impl<DB: Database> StateRangeReader for ProviderFactory<DB> {
+    fn state_range_by_root(
+        &self,
+        root: B256,
+        max_blocks: u64,
+        revert_states: &RevertStateCache,
+    ) -> ProviderResult<Option<StateRangeProviderBox>> {
+        let provider = self.provider()?;
+        let best_block_number = provider.best_block_number()?;
+        let first_block_number = best_block_number.saturating_sub(max_blocks.saturating_sub(1));
+        let Some(offset) = provider
+            .headers_range(first_block_number..=best_block_number)?
+            .iter()
+            .rposition(|header| header.state_root == root)
+        else {
+            return Ok(None)
+        };
+        let block_number = first_block_number + offset as u64;
+
+        if block_number == best_block_number {
+            return Ok(Some(Box::new(HashedStateRangeProvider::new(provider.into_tx()))))
+        }
+
+        // The state is reverted with the changesets of all later blocks, which must not have been
+        // pruned.
+        for segment in [PruneSegment::AccountHistory, PruneSegment::StorageHistory] {
+            if provider
+                .get_prune_checkpoint(segment)?
+                .and_then(|checkpoint| checkpoint.block_number)
+                .is_some_and(|pruned| pruned > block_number)
+            {
+                return Ok(None)
+            }
+        }
+
+        let best_block_hash = provider
+            .block_hash(best_block_number)?
+            .ok_or_else(|| ProviderError::HeaderNotFound(best_block_number.into()))?;
+        let revert_state = revert_states.get_or_try_insert_with(best_block_hash, root, || {
+            HashedPostState::from_revert_range(
+                provider.tx_ref(),
+                block_number + 1..=best_block_number,
+            )
+        })?;
+        Ok(Some(Box::new(HashedStateRangeProvider::new_with_revert_state(
+            provider.into_tx(),
+            revert_state,
+        ))))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::ProviderFactory;
     use crate::{
         test_utils::create_test_provider_factory, BlockHashReader, BlockNumReader, BlockWriter,
-        HeaderSyncGapProvider, HeaderSyncMode, TransactionsProvider,
+        ChangeSetReader, HeaderSyncGapProvider, HeaderSyncMode, HistoryReader, LatestStateChanges,
+        LatestStateWriter, TransactionsProvider,
     };
     use alloy_rlp::Decodable;
     use assert_matches::assert_matches;
     use rand::Rng;
-    use reth_db::{tables, test_utils::ERROR_TEMPDIR, transaction::DbTxMut, DatabaseEnv};
+    use reth_db::{
+        models::{storage_sharded_key::StorageShardedKey, ShardedKey},
+        tables,
+        test_utils::ERROR_TEMPDIR,
+        transaction::{DbTx, DbTxMut},
+        BlockNumberList, DatabaseEnv,
+    };
     use reth_interfaces::{
         provider::ProviderError,
         test_utils::{
@@ -509,7 +575,8 @@ mod tests {
         RethError,
     };
     use reth_primitives::{
-        hex_literal::hex, ChainSpecBuilder, PruneMode, PruneModes, SealedBlock, TxNumber, B256,
+        hex_literal::hex, keccak256, Account, Address, ChainSpecBuilder, PruneMode, PruneModes,
+        SealedBlock, StorageEntry, TxNumber, B256, U256,
     };
     use std::{ops::RangeInclusive, sync::Arc};
     use tokio::sync::watch;
@@ -698,4 +765,108 @@ mod tests {
             Err(RethError::Provider(ProviderError::InconsistentHeaderGap))
         );
     }
+
+    #[test]
+    fn account_history_blocks_lookup() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+
+        let address = Address::with_last_byte(1);
+        let other = Address::with_last_byte(2);
+        let tx = provider.tx_ref();
+        tx.put::<tables::AccountHistory>(
+            ShardedKey::new(address, 7),
+            BlockNumberList::new([1, 3, 7]).unwrap(),
+        )
+        .unwrap();
+        tx.put::<tables::AccountHistory>(
+            ShardedKey::new(address, u64::MAX),
+            BlockNumberList::new([10, 15]).unwrap(),
+        )
+        .unwrap();
+        tx.put::<tables::AccountHistory>(
+            ShardedKey::new(other, u64::MAX),
+            BlockNumberList::new([2, 4]).unwrap(),
+        )
+        .unwrap();
+        tx.put::<tables::StorageHistory>(
+            StorageShardedKey::new(address, B256::with_last_byte(1), u64::MAX),
+            BlockNumberList::new([3, 5, 12]).unwrap(),
+        )
+        .unwrap();
+        tx.put::<tables::StorageHistory>(
+            StorageShardedKey::new(other, B256::with_last_byte(1), u64::MAX),
+            BlockNumberList::new([6]).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            provider.account_history_blocks(address, 0..=u64::MAX, usize::MAX, false),
+            Ok(vec![1, 3, 5, 7, 10, 12, 15])
+        );
+        assert_eq!(
+            provider.account_history_blocks(address, 3..=10, usize::MAX, false),
+            Ok(vec![3, 5, 7, 10])
+        );
+        assert_eq!(
+            provider.account_history_blocks(address, 16..=20, usize::MAX, false),
+            Ok(vec![])
+        );
+        assert_eq!(
+            provider.account_history_blocks(other, 0..=5, usize::MAX, false),
+            Ok(vec![2, 4])
+        );
+
+        // limited lookups start at the start of the range, or at its end if reversed
+        assert_eq!(
+            provider.account_history_blocks(address, 0..=u64::MAX, 3, false),
+            Ok(vec![1, 3, 5])
+        );
+        assert_eq!(provider.account_history_blocks(address, 2..=11, 2, false), Ok(vec![3, 5]));
+        assert_eq!(
+            provider.account_history_blocks(address, 0..=u64::MAX, 3, true),
+            Ok(vec![15, 12, 10])
+        );
+        assert_eq!(provider.account_history_blocks(address, 2..=11, 3, true), Ok(vec![10, 7, 5]));
+        assert_eq!(
+            provider.account_history_blocks(address, 0..=6, usize::MAX, true),
+            Ok(vec![5, 3, 1])
+        );
+        assert_eq!(provider.account_history_blocks(other, 0..=u64::MAX, 2, true), Ok(vec![6, 4]));
+        assert_eq!(provider.account_history_blocks(address, 0..=u64::MAX, 0, true), Ok(vec![]));
+    }
+
+    #[test]
+    fn latest_state_changes_and_reverts() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+
+        let address = Address::with_last_byte(1);
+        let slot = B256::with_last_byte(1);
+        let account = Account { balance: U256::from(10), ..Default::default() };
+        let mut changes = LatestStateChanges::default();
+        changes.accounts.insert(address, Some(account));
+        changes.storage.insert(address, vec![StorageEntry { key: slot, value: U256::from(1) }]);
+
+        let previous = provider.write_latest_state(changes).unwrap();
+        assert_eq!(previous.accounts.get(&address), Some(&None));
+        assert_eq!(
+            previous.storage.get(&address),
+            Some(&vec![StorageEntry { key: slot, value: U256::ZERO }])
+        );
+
+        let tx = provider.tx_ref();
+        assert_eq!(tx.get::<tables::PlainAccountState>(address).unwrap(), Some(account));
+        assert_eq!(tx.get::<tables::HashedAccount>(keccak256(address)).unwrap(), Some(account));
+
+        provider.write_state_reverts(1, previous).unwrap();
+        let changeset = provider.account_block_changeset(1).unwrap();
+        assert_eq!(changeset.len(), 1);
+        assert_eq!(changeset[0].address, address);
+        assert_eq!(changeset[0].info, None);
+        assert_eq!(
+            provider.account_history_blocks(address, 0..=u64::MAX, usize::MAX, false),
+            Ok(vec![1])
+        );
+    }
 }
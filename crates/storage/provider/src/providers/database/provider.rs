@@ -1,12 +1,13 @@
 use crate::{
     bundle_state::{BundleStateInit, BundleStateWithReceipts, HashedStateChanges, RevertsInit},
-    providers::{database::metrics, SnapshotProvider},
+    providers::{database::metrics, snapshot::to_range, SnapshotProvider},
     traits::{
         AccountExtReader, BlockSource, ChangeSetReader, ReceiptProvider, StageCheckpointWriter,
     },
     AccountReader, BlockExecutionWriter, BlockHashReader, BlockNumReader, BlockReader, BlockWriter,
     Chain, EvmEnvProvider, HashingWriter, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider,
-    HeaderSyncMode, HistoryWriter, OriginalValuesKnown, ProviderError, PruneCheckpointReader,
+    HeaderSyncMode, HistoryReader, HistoryWriter, LatestStateChanges, LatestStateWriter,
+    OriginalValuesKnown, PlainStateReader, ProviderError, PruneCheckpointReader,
     PruneCheckpointWriter, StageCheckpointReader, StorageReader, TransactionVariant,
     TransactionsProvider, TransactionsProviderExt, WithdrawalsProvider,
 };
@@ -17,8 +18,10 @@ use reth_db::{
     database::Database,
     models::{
         sharded_key, storage_sharded_key::StorageShardedKey, AccountBeforeTx, BlockNumberAddress,
-        ShardedKey, StoredBlockBodyIndices, StoredBlockOmmers, StoredBlockWithdrawals,
+        ShardedKey, StorageBeforeTx, StoredBlockBodyIndices, StoredBlockOmmers,
+        StoredBlockWithdrawals,
     },
+    snapshot::ReceiptMask,
     table::{Table, TableRow},
     tables,
     transaction::{DbTx, DbTxMut},
@@ -39,9 +42,9 @@ use reth_primitives::{
     trie::Nibbles,
     Account, Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders,
     ChainInfo, ChainSpec, GotExpected, Hardfork, Head, Header, PruneCheckpoint, PruneModes,
-    PruneSegment, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader, StorageEntry,
-    TransactionMeta, TransactionSigned, TransactionSignedEcRecovered, TransactionSignedNoHash,
-    TxHash, TxNumber, Withdrawal, B256, U256,
+    PruneSegment, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader, SnapshotSegment,
+    StorageEntry, TransactionMeta, TransactionSigned, TransactionSignedEcRecovered,
+    TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, B256, U256,
 };
 use reth_trie::{
     hashed_cursor::HashedPostState, prefix_set::PrefixSetMut, updates::TrieUpdates, StateRoot,
@@ -64,7 +67,10 @@ pub type DatabaseProviderRO<DB> = DatabaseProvider<<DB as Database>::TX>;
 /// Ideally this would be an alias type. However, there's some weird compiler error (<https://github.com/rust-lang/rust/issues/102211>), that forces us to wrap this in a struct instead.
 /// Once that issue is solved, we can probably revert back to being an alias type.
 #[derive(Debug)]
-pub struct DatabaseProviderRW<DB: Database>(pub DatabaseProvider<<DB as Database>::TXMut>);
+pub struct DatabaseProviderRW<DB: Database>(
+    pub DatabaseProvider<<DB as Database>::TXMut>,
+    pub(crate) Arc<DB>,
+);
 
 impl<DB: Database> Deref for DatabaseProviderRW<DB> {
     type Target = DatabaseProvider<<DB as Database>::TXMut>;
@@ -90,6 +96,13 @@ impl<DB: Database> DatabaseProviderRW<DB> {
     pub fn into_tx(self) -> <DB as Database>::TXMut {
         self.0.into_tx()
     }
+
+    /// Returns the database the read-write transaction was opened on.
+    ///
+    /// Read-only transactions opened on it don't observe the uncommitted changes of this provider.
+    pub fn db(&self) -> &DB {
+        &self.1
+    }
 }
 
 /// A provider struct that fetchs data from the database.
@@ -101,7 +114,6 @@ pub struct DatabaseProvider<TX> {
     /// Chain spec
     chain_spec: Arc<ChainSpec>,
     /// Snapshot provider
-    #[allow(unused)]
     snapshot_provider: Option<Arc<SnapshotProvider>>,
 }
 
@@ -172,6 +184,73 @@ impl<TX: DbTx> DatabaseProvider<TX> {
         self
     }
 
+    /// Gets data from the snapshot of `segment` if `number` has already been snapshotted, or from
+    /// the database otherwise.
+    ///
+    /// `number` is a block number for block based segments and a transaction number otherwise.
+    fn get_with_snapshot<T>(
+        &self,
+        segment: SnapshotSegment,
+        number: u64,
+        fetch_from_snapshot: impl FnOnce(&SnapshotProvider) -> ProviderResult<T>,
+        fetch_from_database: impl FnOnce() -> ProviderResult<T>,
+    ) -> ProviderResult<T> {
+        if let Some(snapshot_provider) = &self.snapshot_provider {
+            if snapshot_provider
+                .get_highest_snapshot_number(segment)?
+                .map_or(false, |highest| number <= highest)
+            {
+                return fetch_from_snapshot(snapshot_provider)
+            }
+        }
+        fetch_from_database()
+    }
+
+    /// Gets the data of `range` from the snapshots of `segment` up to the highest snapshot, and
+    /// from the database for the rest of it.
+    ///
+    /// `range` is a block range for block based segments and a transaction range otherwise. The
+    /// database is not queried if `predicate` stops the snapshot query.
+    fn get_range_with_snapshot<T, P>(
+        &self,
+        segment: SnapshotSegment,
+        mut range: Range<u64>,
+        fetch_from_snapshot: impl FnOnce(
+            &SnapshotProvider,
+            Range<u64>,
+            &mut P,
+        ) -> ProviderResult<Vec<T>>,
+        fetch_from_database: impl FnOnce(Range<u64>, P) -> ProviderResult<Vec<T>>,
+        mut predicate: P,
+    ) -> ProviderResult<Vec<T>>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        let mut data = Vec::new();
+
+        if let Some(snapshot_provider) = &self.snapshot_provider {
+            if let Some(highest) = snapshot_provider.get_highest_snapshot_number(segment)? {
+                if range.start <= highest {
+                    let end = range.end.min(highest + 1);
+                    data =
+                        fetch_from_snapshot(snapshot_provider, range.start..end, &mut predicate)?;
+
+                    // The predicate stopped the query before reaching the database.
+                    if (data.len() as u64) < end - range.start {
+                        return Ok(data)
+                    }
+                    range.start = end;
+                }
+            }
+        }
+
+        if !range.is_empty() {
+            data.extend(fetch_from_database(range, predicate)?);
+        }
+
+        Ok(data)
+    }
+
     /// Consume `DbTx` or `DbTxMut`.
     pub fn into_tx(self) -> TX {
         self.tx
@@ -816,13 +895,25 @@ impl<TX: DbTx> AccountExtReader for DatabaseProvider<TX> {
         &self,
         range: impl RangeBounds<BlockNumber>,
     ) -> ProviderResult<BTreeSet<Address>> {
-        self.tx
-            .cursor_read::<tables::AccountChangeSet>()?
-            .walk_range(range)?
-            .map(|entry| {
-                entry.map(|(_, account_before)| account_before.address).map_err(Into::into)
-            })
-            .collect()
+        let changesets = self.get_range_with_snapshot(
+            SnapshotSegment::AccountChangeSets,
+            to_range(range),
+            |snapshot_provider, range, _| {
+                range.map(|block| snapshot_provider.account_block_changeset(block)).collect()
+            },
+            |range, _| {
+                let changeset = self
+                    .tx
+                    .cursor_read::<tables::AccountChangeSet>()?
+                    .walk_range(range)?
+                    .map(|entry| entry.map(|(_, account_before)| account_before))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(vec![changeset])
+            },
+            |_| true,
+        )?;
+
+        Ok(changesets.into_iter().flatten().map(|account_before| account_before.address).collect())
     }
 
     fn basic_accounts(
@@ -860,16 +951,204 @@ impl<TX: DbTx> ChangeSetReader for DatabaseProvider<TX> {
         &self,
         block_number: BlockNumber,
     ) -> ProviderResult<Vec<AccountBeforeTx>> {
-        let range = block_number..=block_number;
+        self.get_with_snapshot(
+            SnapshotSegment::AccountChangeSets,
+            block_number,
+            |snapshot_provider| snapshot_provider.account_block_changeset(block_number),
+            || {
+                let range = block_number..=block_number;
+                self.tx
+                    .cursor_read::<tables::AccountChangeSet>()?
+                    .walk_range(range)?
+                    .map(|result| -> ProviderResult<_> {
+                        let (_, account_before) = result?;
+                        Ok(account_before)
+                    })
+                    .collect()
+            },
+        )
+    }
+}
+
+impl<TX: DbTx> PlainStateReader for DatabaseProvider<TX> {
+    fn plain_accounts_from(
+        &self,
+        start: Address,
+        limit: usize,
+    ) -> ProviderResult<Vec<(Address, Account)>> {
         self.tx
-            .cursor_read::<tables::AccountChangeSet>()?
-            .walk_range(range)?
-            .map(|result| -> ProviderResult<_> {
-                let (_, account_before) = result?;
-                Ok(account_before)
-            })
+            .cursor_read::<tables::PlainAccountState>()?
+            .walk(Some(start))?
+            .take(limit)
+            .map(|entry| entry.map_err(Into::into))
+            .collect()
+    }
+
+    fn plain_account_storage(&self, address: Address) -> ProviderResult<Vec<StorageEntry>> {
+        self.tx
+            .cursor_dup_read::<tables::PlainStorageState>()?
+            .walk_dup(Some(address), None)?
+            .map(|entry| entry.map(|(_, storage_entry)| storage_entry).map_err(Into::into))
             .collect()
     }
+
+    fn changed_state_with_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeMap<Address, BTreeSet<B256>>> {
+        let mut changed = self.changed_storages_with_range(range.clone())?;
+        for address in self.changed_accounts_with_range(range)? {
+            changed.entry(address).or_default();
+        }
+        Ok(changed)
+    }
+}
+
+impl<TX: DbTx> HistoryReader for DatabaseProvider<TX> {
+    fn account_history_blocks(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+        limit: usize,
+        reverse: bool,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        let (start, end) = (*range.start(), *range.end());
+        // Shards are keyed by their highest block number, so walking forward starts at the first
+        // shard that can contain the start of the range, and walking backward at the first shard
+        // that can contain its end.
+        let first_block = if reverse { end } else { start };
+        let mut blocks = BTreeSet::new();
+
+        let mut account_cursor = self.tx.cursor_read::<tables::AccountHistory>()?;
+        let account_key = ShardedKey::new(address, first_block);
+        let account_shards = |entry: Result<TableRow<tables::AccountHistory>, _>| match entry {
+            Ok((key, list)) => (key.key == address).then_some(Ok((key.highest_block_number, list))),
+            Err(err) => Some(Err(err)),
+        };
+        blocks.extend(if reverse {
+            history_shard_blocks(
+                account_cursor.walk_back(Some(account_key))?.map_while(account_shards),
+                &range,
+                limit,
+                reverse,
+            )?
+        } else {
+            history_shard_blocks(
+                account_cursor.walk(Some(account_key))?.map_while(account_shards),
+                &range,
+                limit,
+                reverse,
+            )?
+        });
+
+        // Storage history shards are keyed by the storage slot first, so the shards of every slot
+        // of the account are looked up separately. Once `limit` blocks are found, only blocks
+        // before the last of them are of interest, which narrows the range, so for most slots
+        // only the shard found by the initial seek is read.
+        let mut range = range;
+        let mut storage_cursor = self.tx.cursor_read::<tables::StorageHistory>()?;
+        let mut entry = storage_cursor.seek(StorageShardedKey::new(address, B256::ZERO, 0))?;
+        while let Some((key, _)) = entry.filter(|(key, _)| key.address == address) {
+            range = narrow_history_range(&mut blocks, range, limit, reverse);
+            let slot = key.sharded_key.key;
+            let storage_key = StorageShardedKey::new(address, slot, first_block);
+            let storage_shards = |entry: Result<TableRow<tables::StorageHistory>, _>| match entry {
+                Ok((key, list)) => (key.address == address && key.sharded_key.key == slot)
+                    .then_some(Ok((key.sharded_key.highest_block_number, list))),
+                Err(err) => Some(Err(err)),
+            };
+            blocks.extend(if reverse {
+                history_shard_blocks(
+                    storage_cursor.walk_back(Some(storage_key))?.map_while(storage_shards),
+                    &range,
+                    limit,
+                    reverse,
+                )?
+            } else {
+                history_shard_blocks(
+                    storage_cursor.walk(Some(storage_key))?.map_while(storage_shards),
+                    &range,
+                    limit,
+                    reverse,
+                )?
+            });
+
+            // the last shard of every slot is keyed by `u64::MAX`, the next entry belongs to the
+            // next slot
+            storage_cursor.seek_exact(StorageShardedKey::new(address, slot, u64::MAX))?;
+            entry = storage_cursor.next()?;
+        }
+
+        Ok(if reverse {
+            blocks.into_iter().rev().take(limit).collect()
+        } else {
+            blocks.into_iter().take(limit).collect()
+        })
+    }
+}
+
+/// Drops all but the first `limit` blocks, in the order of the lookup, and narrows the range to
+/// the blocks that can still be among them.
+fn narrow_history_range(
+    blocks: &mut BTreeSet<BlockNumber>,
+    range: RangeInclusive<BlockNumber>,
+    limit: usize,
+    reverse: bool,
+) -> RangeInclusive<BlockNumber> {
+    if limit == 0 || blocks.len() < limit {
+        return range
+    }
+    if reverse {
+        let last = *blocks.iter().rev().nth(limit - 1).expect("enough blocks");
+        blocks.retain(|block| *block >= last);
+        last.max(*range.start())..=*range.end()
+    } else {
+        let last = *blocks.iter().nth(limit - 1).expect("enough blocks");
+        blocks.retain(|block| *block <= last);
+        *range.start()..=last.min(*range.end())
+    }
+}
+
+/// Returns up to `limit` block numbers within the range from the given history shards of a single
+/// account or storage slot.
+///
+/// The shards are expected in ascending order of their highest block number, or in descending order
+/// if `reverse` is set, starting at the first shard that can contain blocks of the range. The
+/// returned block numbers are in the same order, and no further shards are read once `limit`
+/// blocks are found or the range is exhausted.
+fn history_shard_blocks(
+    shards: impl Iterator<Item = Result<(BlockNumber, BlockNumberList), DatabaseError>>,
+    range: &RangeInclusive<BlockNumber>,
+    limit: usize,
+    reverse: bool,
+) -> ProviderResult<Vec<BlockNumber>> {
+    let mut blocks = Vec::new();
+    for shard in shards {
+        if blocks.len() >= limit {
+            break
+        }
+
+        let (highest_block_number, list) = shard?;
+        let shard_blocks = list.iter(0).map(|block| block as BlockNumber).collect::<Vec<_>>();
+        let exhausted = if reverse {
+            shard_blocks.first().map_or(false, |first| first <= range.start())
+        } else {
+            highest_block_number >= *range.end()
+        };
+
+        let in_range = shard_blocks.into_iter().filter(|block| range.contains(block));
+        if reverse {
+            blocks.extend(in_range.rev());
+        } else {
+            blocks.extend(in_range);
+        }
+
+        if exhausted {
+            break
+        }
+    }
+    blocks.truncate(limit);
+    Ok(blocks)
 }
 
 impl<TX: DbTx> HeaderSyncGapProvider for DatabaseProvider<TX> {
@@ -933,7 +1212,12 @@ impl<TX: DbTx> HeaderProvider for DatabaseProvider<TX> {
     }
 
     fn header_by_number(&self, num: BlockNumber) -> ProviderResult<Option<Header>> {
-        Ok(self.tx.get::<tables::Headers>(num)?)
+        self.get_with_snapshot(
+            SnapshotSegment::Headers,
+            num,
+            |snapshot_provider| snapshot_provider.header_by_number(num),
+            || Ok(self.tx.get::<tables::Headers>(num)?),
+        )
     }
 
     fn header_td(&self, block_hash: &BlockHash) -> ProviderResult<Option<U256>> {
@@ -951,15 +1235,28 @@ impl<TX: DbTx> HeaderProvider for DatabaseProvider<TX> {
             return Ok(Some(td))
         }
 
-        Ok(self.tx.get::<tables::HeaderTD>(number)?.map(|td| td.0))
+        self.get_with_snapshot(
+            SnapshotSegment::Headers,
+            number,
+            |snapshot_provider| snapshot_provider.header_td_by_number(number),
+            || Ok(self.tx.get::<tables::HeaderTD>(number)?.map(|td| td.0)),
+        )
     }
 
     fn headers_range(&self, range: impl RangeBounds<BlockNumber>) -> ProviderResult<Vec<Header>> {
-        let mut cursor = self.tx.cursor_read::<tables::Headers>()?;
-        cursor
-            .walk_range(range)?
-            .map(|result| result.map(|(_, header)| header).map_err(Into::into))
-            .collect::<ProviderResult<Vec<_>>>()
+        self.get_range_with_snapshot(
+            SnapshotSegment::Headers,
+            to_range(range),
+            |snapshot_provider, range, _| snapshot_provider.headers_range(range),
+            |range, _| {
+                let mut cursor = self.tx.cursor_read::<tables::Headers>()?;
+                cursor
+                    .walk_range(range)?
+                    .map(|result| result.map(|(_, header)| header).map_err(Into::into))
+                    .collect::<ProviderResult<Vec<_>>>()
+            },
+            |_| true,
+        )
     }
 
     fn sealed_header(&self, number: BlockNumber) -> ProviderResult<Option<SealedHeader>> {
@@ -976,27 +1273,42 @@ impl<TX: DbTx> HeaderProvider for DatabaseProvider<TX> {
     fn sealed_headers_while(
         &self,
         range: impl RangeBounds<BlockNumber>,
-        mut predicate: impl FnMut(&SealedHeader) -> bool,
+        predicate: impl FnMut(&SealedHeader) -> bool,
     ) -> ProviderResult<Vec<SealedHeader>> {
-        let mut headers = vec![];
-        for entry in self.tx.cursor_read::<tables::Headers>()?.walk_range(range)? {
-            let (number, header) = entry?;
-            let hash = self
-                .block_hash(number)?
-                .ok_or_else(|| ProviderError::HeaderNotFound(number.into()))?;
-            let sealed = header.seal(hash);
-            if !predicate(&sealed) {
-                break
-            }
-            headers.push(sealed);
-        }
-        Ok(headers)
+        self.get_range_with_snapshot(
+            SnapshotSegment::Headers,
+            to_range(range),
+            |snapshot_provider, range, predicate| {
+                snapshot_provider.sealed_headers_while(range, predicate)
+            },
+            |range, mut predicate| {
+                let mut headers = vec![];
+                for entry in self.tx.cursor_read::<tables::Headers>()?.walk_range(range)? {
+                    let (number, header) = entry?;
+                    let hash = self
+                        .block_hash(number)?
+                        .ok_or_else(|| ProviderError::HeaderNotFound(number.into()))?;
+                    let sealed = header.seal(hash);
+                    if !predicate(&sealed) {
+                        break
+                    }
+                    headers.push(sealed);
+                }
+                Ok(headers)
+            },
+            predicate,
+        )
     }
 }
 
 impl<TX: DbTx> BlockHashReader for DatabaseProvider<TX> {
     fn block_hash(&self, number: u64) -> ProviderResult<Option<B256>> {
-        Ok(self.tx.get::<tables::CanonicalHeaders>(number)?)
+        self.get_with_snapshot(
+            SnapshotSegment::Headers,
+            number,
+            |snapshot_provider| snapshot_provider.block_hash(number),
+            || Ok(self.tx.get::<tables::CanonicalHeaders>(number)?),
+        )
     }
 
     fn canonical_hashes_range(
@@ -1004,12 +1316,21 @@ impl<TX: DbTx> BlockHashReader for DatabaseProvider<TX> {
         start: BlockNumber,
         end: BlockNumber,
     ) -> ProviderResult<Vec<B256>> {
-        let range = start..end;
-        let mut cursor = self.tx.cursor_read::<tables::CanonicalHeaders>()?;
-        cursor
-            .walk_range(range)?
-            .map(|result| result.map(|(_, hash)| hash).map_err(Into::into))
-            .collect::<ProviderResult<Vec<_>>>()
+        self.get_range_with_snapshot(
+            SnapshotSegment::Headers,
+            start..end,
+            |snapshot_provider, range, _| {
+                snapshot_provider.canonical_hashes_range(range.start, range.end)
+            },
+            |range, _| {
+                let mut cursor = self.tx.cursor_read::<tables::CanonicalHeaders>()?;
+                cursor
+                    .walk_range(range)?
+                    .map(|result| result.map(|(_, hash)| hash).map_err(Into::into))
+                    .collect::<ProviderResult<Vec<_>>>()
+            },
+            |_| true,
+        )
     }
 }
 
@@ -1028,7 +1349,19 @@ impl<TX: DbTx> BlockNumReader for DatabaseProvider<TX> {
     }
 
     fn last_block_number(&self) -> ProviderResult<BlockNumber> {
-        Ok(self.tx.cursor_read::<tables::CanonicalHeaders>()?.last()?.unwrap_or_default().0)
+        // Snapshotted headers might have already been pruned from the database.
+        let highest_snapshot = self
+            .snapshot_provider
+            .as_ref()
+            .and_then(|provider| provider.get_highest_snapshot(SnapshotSegment::Headers));
+
+        Ok(self
+            .tx
+            .cursor_read::<tables::CanonicalHeaders>()?
+            .last()?
+            .map(|(number, _)| number)
+            .max(highest_snapshot)
+            .unwrap_or_default())
     }
 
     fn block_number(&self, hash: B256) -> ProviderResult<Option<BlockNumber>> {
@@ -1164,14 +1497,12 @@ impl<TX: DbTx> BlockReader for DatabaseProvider<TX> {
         let len = range.end().saturating_sub(*range.start()) as usize;
         let mut blocks = Vec::with_capacity(len);
 
-        let mut headers_cursor = self.tx.cursor_read::<tables::Headers>()?;
         let mut ommers_cursor = self.tx.cursor_read::<tables::BlockOmmers>()?;
         let mut withdrawals_cursor = self.tx.cursor_read::<tables::BlockWithdrawals>()?;
         let mut block_body_cursor = self.tx.cursor_read::<tables::BlockBodyIndices>()?;
-        let mut tx_cursor = self.tx.cursor_read::<tables::Transactions>()?;
 
         for num in range {
-            if let Some((_, header)) = headers_cursor.seek_exact(num)? {
+            if let Some(header) = self.header_by_number(num)? {
                 // If the body indices are not found, this means that the transactions either do
                 // not exist in the database yet, or they do exit but are
                 // not indexed. If they exist but are not indexed, we don't
@@ -1182,10 +1513,10 @@ impl<TX: DbTx> BlockReader for DatabaseProvider<TX> {
                     let body = if tx_range.is_empty() {
                         Vec::new()
                     } else {
-                        tx_cursor
-                            .walk_range(tx_range)?
-                            .map(|result| result.map(|(_, tx)| tx.into()))
-                            .collect::<Result<Vec<_>, _>>()?
+                        self.transactions_by_tx_range(tx_range)?
+                            .into_iter()
+                            .map(Into::into)
+                            .collect()
                     };
 
                     // If we are past shanghai, then all blocks should have a withdrawal list,
@@ -1216,9 +1547,30 @@ impl<TX: DbTx> BlockReader for DatabaseProvider<TX> {
 }
 
 impl<TX: DbTx> TransactionsProviderExt for DatabaseProvider<TX> {
+    /// Recovers transaction hashes of the snapshotted part of the range from the snapshots, since
+    /// the pruner may have already deleted them from the `Transactions` table, and the rest by
+    /// walking through the table and calculating them in a parallel manner. Returned unsorted.
+    fn transaction_hashes_by_range(
+        &self,
+        tx_range: Range<TxNumber>,
+    ) -> ProviderResult<Vec<(TxHash, TxNumber)>> {
+        self.get_range_with_snapshot(
+            SnapshotSegment::Transactions,
+            tx_range,
+            |snapshot_provider, range, _| {
+                let transactions = snapshot_provider.transactions_by_tx_range(range.clone())?;
+                Ok(transactions.into_iter().zip(range).map(|(tx, id)| (tx.hash(), id)).collect())
+            },
+            |range, _| self.database_transaction_hashes_by_range(range),
+            |_| true,
+        )
+    }
+}
+
+impl<TX: DbTx> DatabaseProvider<TX> {
     /// Recovers transaction hashes by walking through `Transactions` table and
     /// calculating them in a parallel manner. Returned unsorted.
-    fn transaction_hashes_by_range(
+    fn database_transaction_hashes_by_range(
         &self,
         tx_range: Range<TxNumber>,
     ) -> ProviderResult<Vec<(TxHash, TxNumber)>> {
@@ -1280,14 +1632,24 @@ impl<TX: DbTx> TransactionsProvider for DatabaseProvider<TX> {
     }
 
     fn transaction_by_id(&self, id: TxNumber) -> ProviderResult<Option<TransactionSigned>> {
-        Ok(self.tx.get::<tables::Transactions>(id)?.map(Into::into))
+        self.get_with_snapshot(
+            SnapshotSegment::Transactions,
+            id,
+            |snapshot_provider| snapshot_provider.transaction_by_id(id),
+            || Ok(self.tx.get::<tables::Transactions>(id)?.map(Into::into)),
+        )
     }
 
     fn transaction_by_id_no_hash(
         &self,
         id: TxNumber,
     ) -> ProviderResult<Option<TransactionSignedNoHash>> {
-        Ok(self.tx.get::<tables::Transactions>(id)?)
+        self.get_with_snapshot(
+            SnapshotSegment::Transactions,
+            id,
+            |snapshot_provider| snapshot_provider.transaction_by_id_no_hash(id),
+            || Ok(self.tx.get::<tables::Transactions>(id)?),
+        )
     }
 
     fn transaction_by_hash(&self, hash: TxHash) -> ProviderResult<Option<TransactionSigned>> {
@@ -1355,17 +1717,17 @@ impl<TX: DbTx> TransactionsProvider for DatabaseProvider<TX> {
         &self,
         id: BlockHashOrNumber,
     ) -> ProviderResult<Option<Vec<TransactionSigned>>> {
-        let mut tx_cursor = self.tx.cursor_read::<tables::Transactions>()?;
         if let Some(block_number) = self.convert_hash_or_number(id)? {
             if let Some(body) = self.block_body_indices(block_number)? {
                 let tx_range = body.tx_num_range();
                 return if tx_range.is_empty() {
                     Ok(Some(Vec::new()))
                 } else {
-                    let transactions = tx_cursor
-                        .walk_range(tx_range)?
-                        .map(|result| result.map(|(_, tx)| tx.into()))
-                        .collect::<Result<Vec<_>, _>>()?;
+                    let transactions = self
+                        .transactions_by_tx_range(tx_range)?
+                        .into_iter()
+                        .map(Into::into)
+                        .collect();
                     Ok(Some(transactions))
                 }
             }
@@ -1379,7 +1741,6 @@ impl<TX: DbTx> TransactionsProvider for DatabaseProvider<TX> {
     ) -> ProviderResult<Vec<Vec<TransactionSigned>>> {
         let mut results = Vec::new();
         let mut body_cursor = self.tx.cursor_read::<tables::BlockBodyIndices>()?;
-        let mut tx_cursor = self.tx.cursor_read::<tables::Transactions>()?;
         for entry in body_cursor.walk_range(range)? {
             let (_, body) = entry?;
             let tx_num_range = body.tx_num_range();
@@ -1387,10 +1748,10 @@ impl<TX: DbTx> TransactionsProvider for DatabaseProvider<TX> {
                 results.push(Vec::new());
             } else {
                 results.push(
-                    tx_cursor
-                        .walk_range(tx_num_range)?
-                        .map(|result| result.map(|(_, tx)| tx.into()))
-                        .collect::<Result<Vec<_>, _>>()?,
+                    self.transactions_by_tx_range(tx_num_range)?
+                        .into_iter()
+                        .map(Into::into)
+                        .collect(),
                 );
             }
         }
@@ -1401,12 +1762,20 @@ impl<TX: DbTx> TransactionsProvider for DatabaseProvider<TX> {
         &self,
         range: impl RangeBounds<TxNumber>,
     ) -> ProviderResult<Vec<TransactionSignedNoHash>> {
-        Ok(self
-            .tx
-            .cursor_read::<tables::Transactions>()?
-            .walk_range(range)?
-            .map(|entry| entry.map(|tx| tx.1))
-            .collect::<Result<Vec<_>, _>>()?)
+        self.get_range_with_snapshot(
+            SnapshotSegment::Transactions,
+            to_range(range),
+            |snapshot_provider, range, _| snapshot_provider.transactions_by_tx_range(range),
+            |range, _| {
+                Ok(self
+                    .tx
+                    .cursor_read::<tables::Transactions>()?
+                    .walk_range(range)?
+                    .map(|entry| entry.map(|tx| tx.1))
+                    .collect::<Result<Vec<_>, _>>()?)
+            },
+            |_| true,
+        )
     }
 
     fn senders_by_tx_range(
@@ -1428,7 +1797,12 @@ impl<TX: DbTx> TransactionsProvider for DatabaseProvider<TX> {
 
 impl<TX: DbTx> ReceiptProvider for DatabaseProvider<TX> {
     fn receipt(&self, id: TxNumber) -> ProviderResult<Option<Receipt>> {
-        Ok(self.tx.get::<tables::Receipts>(id)?)
+        self.get_with_snapshot(
+            SnapshotSegment::Receipts,
+            id,
+            |snapshot_provider| snapshot_provider.receipt(id),
+            || Ok(self.tx.get::<tables::Receipts>(id)?),
+        )
     }
 
     fn receipt_by_hash(&self, hash: TxHash) -> ProviderResult<Option<Receipt>> {
@@ -1446,11 +1820,28 @@ impl<TX: DbTx> ReceiptProvider for DatabaseProvider<TX> {
                 return if tx_range.is_empty() {
                     Ok(Some(Vec::new()))
                 } else {
-                    let mut receipts_cursor = self.tx.cursor_read::<tables::Receipts>()?;
-                    let receipts = receipts_cursor
-                        .walk_range(tx_range)?
-                        .map(|result| result.map(|(_, receipt)| receipt))
-                        .collect::<Result<Vec<_>, _>>()?;
+                    let receipts = self.get_range_with_snapshot(
+                        SnapshotSegment::Receipts,
+                        tx_range,
+                        |snapshot_provider, range, _| {
+                            snapshot_provider.fetch_range(
+                                SnapshotSegment::Receipts,
+                                range,
+                                |cursor, number| {
+                                    cursor.get_one::<ReceiptMask<Receipt>>(number.into())
+                                },
+                            )
+                        },
+                        |range, _| {
+                            Ok(self
+                                .tx
+                                .cursor_read::<tables::Receipts>()?
+                                .walk_range(range)?
+                                .map(|result| result.map(|(_, receipt)| receipt))
+                                .collect::<Result<Vec<_>, _>>()?)
+                        },
+                        |_| true,
+                    )?;
                     Ok(Some(receipts))
                 }
             }
@@ -1642,16 +2033,37 @@ impl<TX: DbTx> StorageReader for DatabaseProvider<TX> {
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<BTreeMap<Address, BTreeSet<B256>>> {
-        self.tx
-            .cursor_read::<tables::StorageChangeSet>()?
-            .walk_range(BlockNumberAddress::range(range))?
-            // fold all storages and save its old state so we can remove it from HashedStorage
-            // it is needed as it is dup table.
-            .try_fold(BTreeMap::new(), |mut accounts: BTreeMap<Address, BTreeSet<B256>>, entry| {
-                let (BlockNumberAddress((_, address)), storage_entry) = entry?;
-                accounts.entry(address).or_default().insert(storage_entry.key);
-                Ok(accounts)
-            })
+        let changesets = self.get_range_with_snapshot(
+            SnapshotSegment::StorageChangeSets,
+            to_range(range),
+            |snapshot_provider, range, _| {
+                range.map(|block| snapshot_provider.storage_block_changeset(block)).collect()
+            },
+            |range, _| {
+                let changeset = self
+                    .tx
+                    .cursor_read::<tables::StorageChangeSet>()?
+                    .walk_range(
+                        BlockNumberAddress((range.start, Address::ZERO))..
+                            BlockNumberAddress((range.end, Address::ZERO)),
+                    )?
+                    .map(|entry| {
+                        entry.map(|(BlockNumberAddress((_, address)), entry)| StorageBeforeTx {
+                            address,
+                            entry,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(vec![changeset])
+            },
+            |_| true,
+        )?;
+
+        let mut accounts = BTreeMap::<Address, BTreeSet<B256>>::new();
+        for StorageBeforeTx { address, entry } in changesets.into_iter().flatten() {
+            accounts.entry(address).or_default().insert(entry.key);
+        }
+        Ok(accounts)
     }
 
     fn changed_storages_and_blocks_with_range(
@@ -2050,6 +2462,123 @@ impl<TX: DbTxMut + DbTx> HistoryWriter for DatabaseProvider<TX> {
     }
 }
 
+impl<TX: DbTxMut + DbTx> LatestStateWriter for DatabaseProvider<TX> {
+    fn write_latest_state(
+        &self,
+        changes: LatestStateChanges,
+    ) -> ProviderResult<LatestStateChanges> {
+        let LatestStateChanges { accounts, storage, bytecodes } = changes;
+        let mut previous = LatestStateChanges::default();
+
+        for (hash, bytecode) in bytecodes {
+            self.tx.put::<tables::Bytecodes>(hash, bytecode)?;
+        }
+
+        let mut plain_accounts = self.tx.cursor_write::<tables::PlainAccountState>()?;
+        for (address, account) in &accounts {
+            let old = plain_accounts.seek_exact(*address)?.map(|(_, account)| account);
+            match account {
+                Some(account) => plain_accounts.upsert(*address, *account)?,
+                None if old.is_some() => plain_accounts.delete_current()?,
+                None => {}
+            }
+            previous.accounts.insert(*address, old);
+        }
+
+        let mut plain_storage = self.tx.cursor_dup_write::<tables::PlainStorageState>()?;
+        for (address, entries) in &storage {
+            let previous_entries = previous.storage.entry(*address).or_default();
+            for entry in entries {
+                let old = plain_storage
+                    .seek_by_key_subkey(*address, entry.key)?
+                    .filter(|old| old.key == entry.key);
+                if old.is_some() {
+                    plain_storage.delete_current()?;
+                }
+                if entry.value != U256::ZERO {
+                    plain_storage.upsert(*address, *entry)?;
+                }
+                previous_entries
+                    .push(old.unwrap_or(StorageEntry { key: entry.key, value: U256::ZERO }));
+            }
+        }
+
+        // update the hashed state and the trie
+        let mut account_prefix_set = PrefixSetMut::default();
+        let mut storage_prefix_set: HashMap<B256, PrefixSetMut> = HashMap::default();
+        let mut destroyed_accounts = HashSet::default();
+
+        for (hashed_address, hashed_slots) in self.insert_storage_for_hashing(storage)? {
+            account_prefix_set.insert(Nibbles::unpack(hashed_address));
+            for slot in hashed_slots {
+                storage_prefix_set.entry(hashed_address).or_default().insert(Nibbles::unpack(slot));
+            }
+        }
+        for (hashed_address, account) in self.insert_account_for_hashing(accounts)? {
+            account_prefix_set.insert(Nibbles::unpack(hashed_address));
+            if account.is_none() {
+                destroyed_accounts.insert(hashed_address);
+            }
+        }
+
+        let (state_root, trie_updates) = StateRoot::new(&self.tx)
+            .with_changed_account_prefixes(account_prefix_set.freeze())
+            .with_changed_storage_prefixes(
+                storage_prefix_set.into_iter().map(|(k, v)| (k, v.freeze())).collect(),
+            )
+            .with_destroyed_accounts(destroyed_accounts)
+            .root_with_updates()
+            .map_err(Into::<reth_db::DatabaseError>::into)?;
+        trie_updates.flush(&self.tx)?;
+
+        debug!(target: "providers::db", ?state_root, "Wrote latest state changes");
+
+        Ok(previous)
+    }
+
+    fn write_state_reverts(
+        &self,
+        block: BlockNumber,
+        reverts: LatestStateChanges,
+    ) -> ProviderResult<()> {
+        let mut account_indices = BTreeMap::new();
+        let mut account_changesets = self.tx.cursor_dup_write::<tables::AccountChangeSet>()?;
+        for (address, info) in reverts.accounts {
+            if account_changesets
+                .seek_by_key_subkey(block, address)?
+                .filter(|entry| entry.address == address)
+                .is_some()
+            {
+                account_changesets.delete_current()?;
+            } else {
+                account_indices.insert(address, vec![block]);
+            }
+            account_changesets.upsert(block, AccountBeforeTx { address, info })?;
+        }
+
+        let mut storage_indices = BTreeMap::new();
+        let mut storage_changesets = self.tx.cursor_dup_write::<tables::StorageChangeSet>()?;
+        for (address, entries) in reverts.storage {
+            let key = BlockNumberAddress((block, address));
+            for entry in entries {
+                if storage_changesets
+                    .seek_by_key_subkey(key, entry.key)?
+                    .filter(|existing| existing.key == entry.key)
+                    .is_some()
+                {
+                    storage_changesets.delete_current()?;
+                } else {
+                    storage_indices.insert((address, entry.key), vec![block]);
+                }
+                storage_changesets.upsert(key, entry)?;
+            }
+        }
+
+        self.insert_account_history_index(account_indices)?;
+        self.insert_storage_history_index(storage_indices)
+    }
+}
+
 impl<TX: DbTxMut + DbTx> BlockExecutionWriter for DatabaseProvider<TX> {
     /// Return range of blocks and its execution result
     fn get_or_take_block_and_execution_range<const TAKE: bool>(
@@ -2,13 +2,15 @@ use crate::{
     AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
     BlockchainTreePendingStateProvider, BundleStateDataProvider, CanonChainTracker,
     CanonStateNotifications, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader,
-    EvmEnvProvider, HeaderProvider, ProviderError, PruneCheckpointReader, ReceiptProvider,
-    ReceiptProviderIdExt, StageCheckpointReader, StateProviderBox, StateProviderFactory,
-    TransactionVariant, TransactionsProvider, WithdrawalsProvider,
+    EvmEnvProvider, HeaderProvider, HistoryReader, InvalidBlockReader, LatestStateChanges,
+    LatestStateWriter, PlainStateReader, ProviderError, PruneCheckpointReader, ReceiptProvider,
+    ReceiptProviderIdExt, RevertStateCache, StageCheckpointReader, StateProviderBox,
+    StateProviderFactory, StateRangeProviderBox, StateRangeReader, TransactionVariant,
+    TransactionsProvider, WithdrawalsProvider,
 };
 use reth_db::{database::Database, models::StoredBlockBodyIndices};
 use reth_interfaces::{
-    blockchain_tree::{BlockchainTreeEngine, BlockchainTreeViewer},
+    blockchain_tree::{BlockchainTreeEngine, BlockchainTreeViewer, InvalidBlock},
     consensus::ForkchoiceState,
     provider::ProviderResult,
     RethError, RethResult,
@@ -17,12 +19,13 @@ use reth_primitives::{
     stage::{StageCheckpoint, StageId},
     Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId, BlockNumHash, BlockNumber,
     BlockNumberOrTag, BlockWithSenders, ChainInfo, ChainSpec, Header, PruneCheckpoint,
-    PruneSegment, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader, TransactionMeta,
-    TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, B256, U256,
+    PruneSegment, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader, StorageEntry,
+    TransactionMeta, TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal,
+    B256, U256,
 };
 use revm::primitives::{BlockEnv, CfgEnv};
 use std::{
-    collections::{BTreeMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashSet},
     ops::{RangeBounds, RangeInclusive},
     sync::Arc,
     time::Instant,
@@ -32,6 +35,7 @@ use tracing::trace;
 pub use state::{
     historical::{HistoricalStateProvider, HistoricalStateProviderRef},
     latest::{LatestStateProvider, LatestStateProviderRef},
+    range::HashedStateRangeProvider,
 };
 
 mod bundle_state_provider;
@@ -622,6 +626,10 @@ where
     fn unwind(&self, unwind_to: BlockNumber) -> RethResult<()> {
         self.tree.unwind(unwind_to)
     }
+
+    fn record_invalid_block(&self, block: SealedBlock, error: String) {
+        self.tree.record_invalid_block(block, error)
+    }
 }
 
 impl<DB, Tree> BlockchainTreeViewer for BlockchainProvider<DB, Tree>
@@ -653,6 +661,10 @@ where
         self.tree.buffered_header_by_hash(block_hash)
     }
 
+    fn invalid_blocks(&self) -> Vec<InvalidBlock> {
+        self.tree.invalid_blocks()
+    }
+
     fn canonical_blocks(&self) -> BTreeMap<BlockNumber, BlockHash> {
         self.tree.canonical_blocks()
     }
@@ -842,6 +854,99 @@ where
     }
 }
 
+impl<DB, Tree> HistoryReader for BlockchainProvider<DB, Tree>
+where
+    DB: Database,
+    Tree: Sync + Send,
+{
+    fn account_history_blocks(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+        limit: usize,
+        reverse: bool,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.database.provider()?.account_history_blocks(address, range, limit, reverse)
+    }
+}
+
+impl<DB, Tree> InvalidBlockReader for BlockchainProvider<DB, Tree>
+where
+    DB: Send + Sync,
+    Tree: BlockchainTreeViewer,
+{
+    fn recent_invalid_blocks(&self) -> ProviderResult<Vec<InvalidBlock>> {
+        Ok(self.tree.invalid_blocks())
+    }
+}
+
+impl<DB, Tree> PlainStateReader for BlockchainProvider<DB, Tree>
+where
+    DB: Database,
+    Tree: Sync + Send,
+{
+    fn plain_accounts_from(
+        &self,
+        start: Address,
+        limit: usize,
+    ) -> ProviderResult<Vec<(Address, Account)>> {
+        self.database.provider()?.plain_accounts_from(start, limit)
+    }
+
+    fn plain_account_storage(&self, address: Address) -> ProviderResult<Vec<StorageEntry>> {
+        self.database.provider()?.plain_account_storage(address)
+    }
+
+    fn changed_state_with_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeMap<Address, BTreeSet<B256>>> {
+        self.database.provider()?.changed_state_with_range(range)
+    }
+}
+
+impl<DB, Tree> StateRangeReader for BlockchainProvider<DB, Tree>
+where
+    DB: Database,
+    Tree: Sync + Send,
+{
+    fn state_range_by_root(
+        &self,
+        root: B256,
+        max_blocks: u64,
+        revert_states: &RevertStateCache,
+    ) -> ProviderResult<Option<StateRangeProviderBox>> {
+        self.database.state_range_by_root(root, max_blocks, revert_states)
+    }
+}
+
+impl<DB, Tree> LatestStateWriter for BlockchainProvider<DB, Tree>
+where
+    DB: Database,
+    Tree: Sync + Send,
+{
+    fn write_latest_state(
+        &self,
+        changes: LatestStateChanges,
+    ) -> ProviderResult<LatestStateChanges> {
+        let provider_rw = self.database.provider_rw()?;
+        let previous = provider_rw.write_latest_state(changes)?;
+        provider_rw.commit()?;
+        Ok(previous)
+    }
+
+    fn write_state_reverts(
+        &self,
+        block: BlockNumber,
+        reverts: LatestStateChanges,
+    ) -> ProviderResult<()> {
+        let provider_rw = self.database.provider_rw()?;
+        provider_rw.write_state_reverts(block, reverts)?;
+        provider_rw.commit()?;
+        Ok(())
+    }
+}
+
 impl<DB, Tree> AccountReader for BlockchainProvider<DB, Tree>
 where
     DB: Database + Sync + Send,
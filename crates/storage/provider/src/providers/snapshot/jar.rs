@@ -1,17 +1,22 @@
-use super::LoadedJarRef;
+use super::{to_range, LoadedJarRef};
 use crate::{
-    BlockHashReader, BlockNumReader, HeaderProvider, ReceiptProvider, TransactionsProvider,
+    BlockHashReader, BlockNumReader, ChangeSetReader, HeaderProvider, ReceiptProvider,
+    TransactionsProvider,
 };
 use reth_db::{
     codecs::CompactU256,
-    snapshot::{HeaderMask, ReceiptMask, SnapshotCursor, TransactionMask},
+    models::{AccountBeforeTx, BlockAccountChangeSet, BlockStorageChangeSet, StorageBeforeTx},
+    snapshot::{
+        AccountChangeSetMask, HeaderMask, ReceiptMask, SnapshotCursor, StorageChangeSetMask,
+        TransactionMask,
+    },
 };
 use reth_interfaces::provider::{ProviderError, ProviderResult};
 use reth_primitives::{
     Address, BlockHash, BlockHashOrNumber, BlockNumber, ChainInfo, Header, Receipt, SealedHeader,
     TransactionMeta, TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, B256, U256,
 };
-use std::ops::{Deref, Range, RangeBounds};
+use std::ops::{Deref, RangeBounds};
 
 /// Provider over a specific `NippyJar` and range.
 #[derive(Debug)]
@@ -49,6 +54,19 @@ impl<'a> SnapshotJarProvider<'a> {
         self.auxiliar_jar = Some(Box::new(auxiliar_jar));
         self
     }
+
+    /// Returns the storage slots changed in `block_number` with their values from before the
+    /// block, sorted by address and storage key.
+    pub fn storage_block_changeset(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Vec<StorageBeforeTx>> {
+        Ok(self
+            .cursor()?
+            .get_one::<StorageChangeSetMask<BlockStorageChangeSet>>(block_number.into())?
+            .map(|changeset| changeset.0)
+            .unwrap_or_default())
+    }
 }
 
 impl<'a> HeaderProvider for SnapshotJarProvider<'a> {
@@ -287,18 +305,15 @@ impl<'a> ReceiptProvider for SnapshotJarProvider<'a> {
     }
 }
 
-fn to_range<R: RangeBounds<u64>>(bounds: R) -> Range<u64> {
-    let start = match bounds.start_bound() {
-        std::ops::Bound::Included(&v) => v,
-        std::ops::Bound::Excluded(&v) => v + 1,
-        std::ops::Bound::Unbounded => 0,
-    };
-
-    let end = match bounds.end_bound() {
-        std::ops::Bound::Included(&v) => v + 1,
-        std::ops::Bound::Excluded(&v) => v,
-        std::ops::Bound::Unbounded => u64::MAX,
-    };
-
-    start..end
+impl<'a> ChangeSetReader for SnapshotJarProvider<'a> {
+    fn account_block_changeset(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Vec<AccountBeforeTx>> {
+        Ok(self
+            .cursor()?
+            .get_one::<AccountChangeSetMask<BlockAccountChangeSet>>(block_number.into())?
+            .map(|changeset| changeset.0)
+            .unwrap_or_default())
+    }
 }
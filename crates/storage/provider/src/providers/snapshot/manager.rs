@@ -1,25 +1,31 @@
-use super::{LoadedJar, SnapshotJarProvider};
-use crate::{BlockHashReader, BlockNumReader, HeaderProvider, TransactionsProvider};
+use super::{to_range, LoadedJar, SnapshotJarProvider};
+use crate::{
+    BlockHashReader, BlockNumReader, ChangeSetReader, HeaderProvider, ReceiptProvider,
+    TransactionsProvider,
+};
 use dashmap::DashMap;
 use parking_lot::RwLock;
 use reth_db::{
     codecs::CompactU256,
-    snapshot::{HeaderMask, TransactionMask},
+    models::{AccountBeforeTx, StorageBeforeTx},
+    snapshot::{HeaderMask, ReceiptMask, SnapshotCursor, TransactionMask},
 };
 use reth_interfaces::provider::{ProviderError, ProviderResult};
 use reth_nippy_jar::NippyJar;
 use reth_primitives::{
-    snapshot::HighestSnapshots, Address, BlockHash, BlockHashOrNumber, BlockNumber, ChainInfo,
-    Header, SealedHeader, SnapshotSegment, TransactionMeta, TransactionSigned,
-    TransactionSignedNoHash, TxHash, TxNumber, B256, U256,
+    snapshot::{iter_snapshots, HighestSnapshots},
+    Address, BlockHash, BlockHashOrNumber, BlockNumber, ChainInfo, Header, Receipt, SealedHeader,
+    SnapshotSegment, TransactionMeta, TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber,
+    B256, U256,
 };
 use revm::primitives::HashMap;
 use std::{
     collections::BTreeMap,
-    ops::{RangeBounds, RangeInclusive},
+    ops::{Range, RangeBounds, RangeInclusive},
     path::{Path, PathBuf},
 };
 use tokio::sync::watch;
+use tracing::warn;
 
 /// Alias type for a map that can be queried for transaction/block ranges from a block/transaction
 /// segment respectively. It uses `BlockNumber` to represent the block end of a snapshot range or
@@ -42,6 +48,8 @@ pub struct SnapshotProvider {
     snapshots_tx_index: RwLock<SegmentRanges>,
     /// Tracks the highest snapshot of every segment.
     highest_tracker: Option<watch::Receiver<Option<HighestSnapshots>>>,
+    /// Highest snapshots at the time the block and transaction indexes were last updated.
+    indexed_highest: RwLock<Option<HighestSnapshots>>,
     /// Directory where snapshots are located
     path: PathBuf,
 }
@@ -54,6 +62,7 @@ impl SnapshotProvider {
             snapshots_block_index: Default::default(),
             snapshots_tx_index: Default::default(),
             highest_tracker: None,
+            indexed_highest: Default::default(),
             path: path.as_ref().to_path_buf(),
         }
     }
@@ -67,6 +76,38 @@ impl SnapshotProvider {
         self
     }
 
+    /// Loads the block and transaction ranges of every snapshot found in the snapshots directory.
+    pub fn update_index(&self) -> ProviderResult<()> {
+        let highest = self.highest_tracker.as_ref().and_then(|tracker| *tracker.borrow());
+
+        let mut block_index = SegmentRanges::default();
+        let mut tx_index = SegmentRanges::default();
+
+        for (segment, block_range, tx_range) in iter_snapshots(&self.path)
+            .map_err(|err| ProviderError::SnapshotsDirectory(self.path.clone(), err.to_string()))?
+        {
+            block_index.entry(segment).or_default().insert(*block_range.end(), tx_range.clone());
+            tx_index.entry(segment).or_default().insert(*tx_range.end(), block_range);
+        }
+
+        *self.snapshots_block_index.write() = block_index;
+        *self.snapshots_tx_index.write() = tx_index;
+        *self.indexed_highest.write() = highest;
+
+        Ok(())
+    }
+
+    /// Updates the block and transaction indexes if the highest snapshots have changed since they
+    /// were last loaded.
+    fn ensure_index(&self) -> ProviderResult<()> {
+        if let Some(tracker) = &self.highest_tracker {
+            if *tracker.borrow() != *self.indexed_highest.read() {
+                self.update_index()?;
+            }
+        }
+        Ok(())
+    }
+
     /// Gets the [`SnapshotJarProvider`] of the requested segment and block.
     pub fn get_segment_provider_from_block(
         &self,
@@ -118,7 +159,10 @@ impl SnapshotProvider {
                     None
                 })
             }
-            None => fn_ranges(),
+            None => {
+                self.ensure_index()?;
+                fn_ranges()
+            }
         };
 
         // Return cached `LoadedJar` or insert it for the first time, and then, return it.
@@ -207,6 +251,106 @@ impl SnapshotProvider {
             .and_then(|tracker| tracker.borrow().and_then(|highest| highest.highest(segment)))
     }
 
+    /// Gets the highest snapshotted block of a block based segment, or the highest snapshotted
+    /// transaction of a transaction based segment, if it exists.
+    pub fn get_highest_snapshot_number(
+        &self,
+        segment: SnapshotSegment,
+    ) -> ProviderResult<Option<u64>> {
+        self.ensure_index()?;
+
+        let index = if segment.is_block_based() {
+            self.snapshots_block_index.read()
+        } else {
+            self.snapshots_tx_index.read()
+        };
+        Ok(index.get(&segment).and_then(|ranges| ranges.last_key_value().map(|(end, _)| *end)))
+    }
+
+    /// Fetches the values of `range` across as many snapshots of `segment` as needed, stopping at
+    /// the first value for which `predicate` returns `false`.
+    ///
+    /// `range` is a block range for block based segments and a transaction range otherwise.
+    pub fn fetch_range_with_predicate<T>(
+        &self,
+        segment: SnapshotSegment,
+        range: Range<u64>,
+        mut get_fn: impl FnMut(&mut SnapshotCursor<'_>, u64) -> ProviderResult<Option<T>>,
+        mut predicate: impl FnMut(&T) -> bool,
+    ) -> ProviderResult<Vec<T>> {
+        // Values past the highest snapshot can't be found in any of them.
+        let Some(highest) = self.get_highest_snapshot_number(segment)? else {
+            return Ok(Vec::new())
+        };
+        let range = range.start..range.end.min(highest + 1);
+        if range.is_empty() {
+            return Ok(Vec::new())
+        }
+
+        let get_provider = |start: u64| {
+            if segment.is_block_based() {
+                self.get_segment_provider_from_block(segment, start, None)
+            } else {
+                self.get_segment_provider_from_transaction(segment, start, None)
+            }
+        };
+
+        let mut result = Vec::with_capacity((range.end - range.start).min(100) as usize);
+        let mut provider = get_provider(range.start)?;
+        let mut cursor = provider.cursor()?;
+
+        'outer: for number in range {
+            // Only retries once per number, so that a value missing from two different snapshots
+            // returns an error instead of looping forever.
+            let mut retrying = false;
+
+            'inner: loop {
+                match get_fn(&mut cursor, number)? {
+                    Some(res) => {
+                        if !predicate(&res) {
+                            break 'outer
+                        }
+                        result.push(res);
+                        break 'inner
+                    }
+                    None => {
+                        if retrying {
+                            warn!(
+                                target: "provider::snapshot",
+                                ?segment,
+                                ?number,
+                                "Could not find value in the snapshots."
+                            );
+                            return Err(if segment.is_block_based() {
+                                ProviderError::MissingSnapshotBlock(segment, number)
+                            } else {
+                                ProviderError::MissingSnapshotTx(segment, number)
+                            })
+                        }
+                        // The value belongs to the next snapshot.
+                        provider = get_provider(number)?;
+                        cursor = provider.cursor()?;
+                        retrying = true;
+                    }
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Fetches the values of `range` across as many snapshots of `segment` as needed.
+    ///
+    /// See [`SnapshotProvider::fetch_range_with_predicate`].
+    pub fn fetch_range<T>(
+        &self,
+        segment: SnapshotSegment,
+        range: Range<u64>,
+        get_fn: impl FnMut(&mut SnapshotCursor<'_>, u64) -> ProviderResult<Option<T>>,
+    ) -> ProviderResult<Vec<T>> {
+        self.fetch_range_with_predicate(segment, range, get_fn, |_| true)
+    }
+
     /// Iterates through segment snapshots in reverse order, executing a function until it returns
     /// some object. Useful for finding objects by [`TxHash`] or [`BlockHash`].
     pub fn find_snapshot<T>(
@@ -214,6 +358,8 @@ impl SnapshotProvider {
         segment: SnapshotSegment,
         func: impl Fn(SnapshotJarProvider<'_>) -> ProviderResult<Option<T>>,
     ) -> ProviderResult<Option<T>> {
+        self.ensure_index()?;
+
         let snapshots = self.snapshots_block_index.read();
         if let Some(segment_snapshots) = snapshots.get(&segment) {
             // It's more probable that the request comes from a newer block height, so we iterate
@@ -238,6 +384,20 @@ impl SnapshotProvider {
 
         Ok(None)
     }
+
+    /// Returns the storage slots changed in `block_number` with their values from before the
+    /// block, sorted by address and storage key.
+    pub fn storage_block_changeset(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Vec<StorageBeforeTx>> {
+        self.get_segment_provider_from_block(
+            SnapshotSegment::StorageChangeSets,
+            block_number,
+            None,
+        )?
+        .storage_block_changeset(block_number)
+    }
 }
 
 impl HeaderProvider for SnapshotProvider {
@@ -274,8 +434,10 @@ impl HeaderProvider for SnapshotProvider {
             .header_td_by_number(num)
     }
 
-    fn headers_range(&self, _range: impl RangeBounds<BlockNumber>) -> ProviderResult<Vec<Header>> {
-        todo!();
+    fn headers_range(&self, range: impl RangeBounds<BlockNumber>) -> ProviderResult<Vec<Header>> {
+        self.fetch_range(SnapshotSegment::Headers, to_range(range), |cursor, number| {
+            cursor.get_one::<HeaderMask<Header>>(number.into())
+        })
     }
 
     fn sealed_header(&self, num: BlockNumber) -> ProviderResult<Option<SealedHeader>> {
@@ -285,10 +447,19 @@ impl HeaderProvider for SnapshotProvider {
 
     fn sealed_headers_while(
         &self,
-        _range: impl RangeBounds<BlockNumber>,
-        _predicate: impl FnMut(&SealedHeader) -> bool,
+        range: impl RangeBounds<BlockNumber>,
+        predicate: impl FnMut(&SealedHeader) -> bool,
     ) -> ProviderResult<Vec<SealedHeader>> {
-        todo!()
+        self.fetch_range_with_predicate(
+            SnapshotSegment::Headers,
+            to_range(range),
+            |cursor, number| {
+                Ok(cursor
+                    .get_two::<HeaderMask<Header, BlockHash>>(number.into())?
+                    .map(|(header, hash)| header.seal(hash)))
+            },
+            predicate,
+        )
     }
 }
 
@@ -299,28 +470,32 @@ impl BlockHashReader for SnapshotProvider {
 
     fn canonical_hashes_range(
         &self,
-        _start: BlockNumber,
-        _end: BlockNumber,
+        start: BlockNumber,
+        end: BlockNumber,
     ) -> ProviderResult<Vec<B256>> {
-        todo!()
+        self.fetch_range(SnapshotSegment::Headers, start..end, |cursor, number| {
+            cursor.get_one::<HeaderMask<BlockHash>>(number.into())
+        })
     }
 }
 
 impl BlockNumReader for SnapshotProvider {
     fn chain_info(&self) -> ProviderResult<ChainInfo> {
-        todo!()
+        // Information on live database
+        Err(ProviderError::UnsupportedProvider)
     }
 
     fn best_block_number(&self) -> ProviderResult<BlockNumber> {
-        todo!()
+        // Information on live database
+        Err(ProviderError::UnsupportedProvider)
     }
 
     fn last_block_number(&self) -> ProviderResult<BlockNumber> {
-        todo!()
+        Ok(self.get_highest_snapshot(SnapshotSegment::Headers).unwrap_or_default())
     }
 
-    fn block_number(&self, _hash: B256) -> ProviderResult<Option<BlockNumber>> {
-        todo!()
+    fn block_number(&self, hash: B256) -> ProviderResult<Option<BlockNumber>> {
+        self.find_snapshot(SnapshotSegment::Headers, |jar_provider| jar_provider.block_number(hash))
     }
 }
 
@@ -367,42 +542,86 @@ impl TransactionsProvider for SnapshotProvider {
         &self,
         _hash: TxHash,
     ) -> ProviderResult<Option<(TransactionSigned, TransactionMeta)>> {
-        todo!()
+        // Information required on indexing table [`tables::TransactionBlock`]
+        Err(ProviderError::UnsupportedProvider)
     }
 
     fn transaction_block(&self, _id: TxNumber) -> ProviderResult<Option<BlockNumber>> {
-        todo!()
+        // Information on indexing table [`tables::TransactionBlock`]
+        Err(ProviderError::UnsupportedProvider)
     }
 
     fn transactions_by_block(
         &self,
         _block_id: BlockHashOrNumber,
     ) -> ProviderResult<Option<Vec<TransactionSigned>>> {
-        todo!()
+        // Related to indexing tables. Live database should get the tx_range and call snapshot
+        // provider with `transactions_by_tx_range` instead.
+        Err(ProviderError::UnsupportedProvider)
     }
 
     fn transactions_by_block_range(
         &self,
         _range: impl RangeBounds<BlockNumber>,
     ) -> ProviderResult<Vec<Vec<TransactionSigned>>> {
-        todo!()
+        // Related to indexing tables. Live database should get the tx_range and call snapshot
+        // provider with `transactions_by_tx_range` instead.
+        Err(ProviderError::UnsupportedProvider)
     }
 
     fn senders_by_tx_range(
         &self,
-        _range: impl RangeBounds<TxNumber>,
+        range: impl RangeBounds<TxNumber>,
     ) -> ProviderResult<Vec<Address>> {
-        todo!()
+        let txs = self.transactions_by_tx_range(range)?;
+        TransactionSignedNoHash::recover_signers(&txs, txs.len())
+            .ok_or(ProviderError::SenderRecoveryError)
     }
 
     fn transactions_by_tx_range(
         &self,
-        _range: impl RangeBounds<TxNumber>,
+        range: impl RangeBounds<TxNumber>,
     ) -> ProviderResult<Vec<reth_primitives::TransactionSignedNoHash>> {
-        todo!()
+        self.fetch_range(SnapshotSegment::Transactions, to_range(range), |cursor, number| {
+            cursor.get_one::<TransactionMask<TransactionSignedNoHash>>(number.into())
+        })
     }
 
     fn transaction_sender(&self, id: TxNumber) -> ProviderResult<Option<Address>> {
         Ok(self.transaction_by_id_no_hash(id)?.and_then(|tx| tx.recover_signer()))
     }
 }
+
+impl ReceiptProvider for SnapshotProvider {
+    fn receipt(&self, num: TxNumber) -> ProviderResult<Option<Receipt>> {
+        self.get_segment_provider_from_transaction(SnapshotSegment::Receipts, num, None)?
+            .receipt(num)
+    }
+
+    fn receipt_by_hash(&self, hash: TxHash) -> ProviderResult<Option<Receipt>> {
+        if let Some(num) = self.transaction_id(hash)? {
+            return self.receipt(num)
+        }
+        Ok(None)
+    }
+
+    fn receipts_by_block(&self, _block: BlockHashOrNumber) -> ProviderResult<Option<Vec<Receipt>>> {
+        // Related to indexing tables. Live database should get the tx_range and call snapshot
+        // provider with `fetch_range` instead.
+        Err(ProviderError::UnsupportedProvider)
+    }
+}
+
+impl ChangeSetReader for SnapshotProvider {
+    fn account_block_changeset(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Vec<AccountBeforeTx>> {
+        self.get_segment_provider_from_block(
+            SnapshotSegment::AccountChangeSets,
+            block_number,
+            None,
+        )?
+        .account_block_changeset(block_number)
+    }
+}
@@ -7,7 +7,7 @@ pub use jar::SnapshotJarProvider;
 use reth_interfaces::provider::ProviderResult;
 use reth_nippy_jar::NippyJar;
 use reth_primitives::{snapshot::SegmentHeader, SnapshotSegment};
-use std::ops::Deref;
+use std::ops::{Deref, Range, RangeBounds};
 
 /// Alias type for each specific `NippyJar`.
 type LoadedJarRef<'a> = dashmap::mapref::one::Ref<'a, (u64, SnapshotSegment), LoadedJar>;
@@ -38,6 +38,23 @@ impl Deref for LoadedJar {
     }
 }
 
+/// Converts `bounds` into a half-open [`Range`].
+pub(crate) fn to_range<R: RangeBounds<u64>>(bounds: R) -> Range<u64> {
+    let start = match bounds.start_bound() {
+        std::ops::Bound::Included(&v) => v,
+        std::ops::Bound::Excluded(&v) => v + 1,
+        std::ops::Bound::Unbounded => 0,
+    };
+
+    let end = match bounds.end_bound() {
+        std::ops::Bound::Included(&v) => v + 1,
+        std::ops::Bound::Excluded(&v) => v,
+        std::ops::Bound::Unbounded => u64::MAX,
+    };
+
+    start..end
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
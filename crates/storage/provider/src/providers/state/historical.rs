@@ -1,10 +1,15 @@
 use crate::{
-    providers::state::macros::delegate_provider_impls, AccountReader, BlockHashReader,
-    BundleStateWithReceipts, ProviderError, StateProvider, StateRootProvider,
+    providers::{state::macros::delegate_provider_impls, SnapshotProvider},
+    AccountReader, BlockHashReader, BundleStateWithReceipts, ChangeSetReader, ProviderError,
+    StateProvider, StateRootProvider,
 };
+use itertools::Itertools;
 use reth_db::{
     cursor::{DbCursorRO, DbDupCursorRO},
-    models::{storage_sharded_key::StorageShardedKey, ShardedKey},
+    models::{
+        storage_sharded_key::StorageShardedKey, AccountBeforeTx, BlockNumberAddress, ShardedKey,
+        StorageBeforeTx,
+    },
     table::Table,
     tables,
     transaction::DbTx,
@@ -12,9 +17,20 @@ use reth_db::{
 };
 use reth_interfaces::provider::ProviderResult;
 use reth_primitives::{
-    trie::AccountProof, Account, Address, BlockNumber, Bytecode, StorageKey, StorageValue, B256,
+    trie::AccountProof, Account, Address, BlockNumber, Bytecode, SnapshotSegment, StorageKey,
+    StorageValue, B256,
+};
+use reth_trie::{
+    hashed_cursor::{HashedPostState, HashedPostStateCursorFactory},
+    proof::Proof,
+    updates::TrieUpdates,
+    StateRoot,
+};
+use std::{
+    borrow::Cow,
+    ops::RangeInclusive,
+    sync::{Arc, OnceLock},
 };
-use reth_trie::updates::TrieUpdates;
 
 /// State provider for a given block number which takes a tx reference.
 ///
@@ -27,6 +43,10 @@ use reth_trie::updates::TrieUpdates;
 /// - [tables::StorageHistory]
 /// - [tables::AccountChangeSet]
 /// - [tables::StorageChangeSet]
+///
+/// If a [`SnapshotProvider`] is set, changesets of snapshotted blocks are read from the
+/// [`SnapshotSegment::AccountChangeSets`] and [`SnapshotSegment::StorageChangeSets`] snapshots
+/// instead.
 #[derive(Debug)]
 pub struct HistoricalStateProviderRef<'b, TX: DbTx> {
     /// Transaction
@@ -35,6 +55,10 @@ pub struct HistoricalStateProviderRef<'b, TX: DbTx> {
     block_number: BlockNumber,
     /// Lowest blocks at which different parts of the state are available.
     lowest_available_blocks: LowestAvailableBlocks,
+    /// Snapshot provider to read the changesets of snapshotted blocks from.
+    snapshot_provider: Option<&'b SnapshotProvider>,
+    /// Cache for the revert state, shared by all references of a [HistoricalStateProvider].
+    revert_state: Option<&'b OnceLock<HashedPostState>>,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -48,7 +72,13 @@ pub enum HistoryInfo {
 impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
     /// Create new StateProvider for historical block number
     pub fn new(tx: &'b TX, block_number: BlockNumber) -> Self {
-        Self { tx, block_number, lowest_available_blocks: Default::default() }
+        Self {
+            tx,
+            block_number,
+            lowest_available_blocks: Default::default(),
+            snapshot_provider: None,
+            revert_state: None,
+        }
     }
 
     /// Create new StateProvider for historical block number and lowest block numbers at which
@@ -58,7 +88,84 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         block_number: BlockNumber,
         lowest_available_blocks: LowestAvailableBlocks,
     ) -> Self {
-        Self { tx, block_number, lowest_available_blocks }
+        Self {
+            tx,
+            block_number,
+            lowest_available_blocks,
+            snapshot_provider: None,
+            revert_state: None,
+        }
+    }
+
+    /// Reads the changesets of snapshotted blocks from the provided [`SnapshotProvider`].
+    pub fn with_snapshot_provider(mut self, snapshot_provider: &'b SnapshotProvider) -> Self {
+        self.snapshot_provider = Some(snapshot_provider);
+        self
+    }
+
+    /// Returns the [`SnapshotProvider`] if `block_number` has been snapshotted for `segment`.
+    fn snapshot_provider_for(
+        &self,
+        segment: SnapshotSegment,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Option<&'b SnapshotProvider>> {
+        let Some(snapshot_provider) = self.snapshot_provider else { return Ok(None) };
+        Ok(snapshot_provider
+            .get_highest_snapshot_number(segment)?
+            .filter(|highest| block_number <= *highest)
+            .map(|_| snapshot_provider))
+    }
+
+    /// Get the account as it was before the changes of `changeset_block_number`.
+    fn account_before(
+        &self,
+        changeset_block_number: BlockNumber,
+        address: Address,
+    ) -> ProviderResult<Option<AccountBeforeTx>> {
+        if let Some(snapshot_provider) =
+            self.snapshot_provider_for(SnapshotSegment::AccountChangeSets, changeset_block_number)?
+        {
+            let mut changeset =
+                snapshot_provider.account_block_changeset(changeset_block_number)?;
+            return Ok(changeset
+                .binary_search_by_key(&address, |account| account.address)
+                .ok()
+                .map(|index| changeset.swap_remove(index)))
+        }
+
+        Ok(self
+            .tx
+            .cursor_dup_read::<tables::AccountChangeSet>()?
+            .seek_by_key_subkey(changeset_block_number, address)?
+            .filter(|acc| acc.address == address))
+    }
+
+    /// Get the storage slot value as it was before the changes of `changeset_block_number`.
+    fn storage_before(
+        &self,
+        changeset_block_number: BlockNumber,
+        address: Address,
+        storage_key: StorageKey,
+    ) -> ProviderResult<Option<StorageValue>> {
+        if let Some(snapshot_provider) =
+            self.snapshot_provider_for(SnapshotSegment::StorageChangeSets, changeset_block_number)?
+        {
+            let changeset = snapshot_provider.storage_block_changeset(changeset_block_number)?;
+            return Ok(changeset
+                .binary_search_by_key(
+                    &(address, storage_key),
+                    |StorageBeforeTx { address, entry }| (*address, entry.key),
+                )
+                .ok()
+                .map(|index| changeset[index].entry.value))
+        }
+
+        Ok(self
+            .tx
+            .cursor_dup_read::<tables::StorageChangeSet>()?
+            .seek_by_key_subkey((changeset_block_number, address).into(), storage_key)?
+            .filter(|entry| entry.key == storage_key)
+            .map(|entry| entry.value))
     }
 
     /// Lookup an account in the AccountHistory table
@@ -95,6 +202,96 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         )
     }
 
+    /// Retrieve the hashed state that reverts the current database state to the state at the
+    /// start of [Self::block_number], using the account and storage changesets.
+    ///
+    /// The revert state is only computed once if this provider was created from a
+    /// [HistoricalStateProvider].
+    fn revert_state(&self) -> ProviderResult<Cow<'b, HashedPostState>> {
+        let Some(cache) = self.revert_state else {
+            return self.compute_revert_state().map(Cow::Owned)
+        };
+        if cache.get().is_none() {
+            // a concurrent caller may have filled the cache in the meantime, which is fine
+            let _ = cache.set(self.compute_revert_state()?);
+        }
+        Ok(Cow::Borrowed(cache.get().expect("revert state is cached")))
+    }
+
+    fn compute_revert_state(&self) -> ProviderResult<HashedPostState> {
+        if !self.lowest_available_blocks.is_account_history_available(self.block_number) ||
+            !self.lowest_available_blocks.is_storage_history_available(self.block_number)
+        {
+            return Err(ProviderError::StateAtBlockPruned(self.block_number))
+        }
+
+        let tip = self
+            .tx
+            .cursor_read::<tables::CanonicalHeaders>()?
+            .last()?
+            .map(|(tip, _)| tip)
+            .unwrap_or_default();
+
+        // The changesets of snapshotted blocks may have been pruned from the database, so they are
+        // read from the snapshots and only the changesets of later blocks from the database.
+        let account_snapshots = self.snapshotted_blocks(SnapshotSegment::AccountChangeSets)?;
+        let storage_snapshots = self.snapshotted_blocks(SnapshotSegment::StorageChangeSets)?;
+        let first_database_block = |snapshots: &Option<(_, RangeInclusive<BlockNumber>)>| {
+            snapshots.as_ref().map_or(self.block_number, |(_, blocks)| blocks.end() + 1)
+        };
+
+        let mut account_changesets_cursor = self.tx.cursor_read::<tables::AccountChangeSet>()?;
+        let account_reverts = account_snapshots
+            .iter()
+            .flat_map(|(snapshot_provider, blocks)| {
+                blocks.clone().map(move |block| snapshot_provider.account_block_changeset(block))
+            })
+            .flatten_ok()
+            .map_ok(|account_before| (account_before.address, account_before.info))
+            .chain(
+                account_changesets_cursor
+                    .walk_range(first_database_block(&account_snapshots)..=tip)?
+                    .map(|entry| -> ProviderResult<_> {
+                        let (_, account_before) = entry?;
+                        Ok((account_before.address, account_before.info))
+                    }),
+            );
+
+        let mut storage_changesets_cursor = self.tx.cursor_read::<tables::StorageChangeSet>()?;
+        let storage_reverts = storage_snapshots
+            .iter()
+            .flat_map(|(snapshot_provider, blocks)| {
+                blocks.clone().map(move |block| snapshot_provider.storage_block_changeset(block))
+            })
+            .flatten_ok()
+            .map_ok(|StorageBeforeTx { address, entry }| (address, entry))
+            .chain(
+                storage_changesets_cursor
+                    .walk_range(BlockNumberAddress::range(
+                        first_database_block(&storage_snapshots)..=tip,
+                    ))?
+                    .map(|entry| -> ProviderResult<_> {
+                        let (BlockNumberAddress((_, address)), storage) = entry?;
+                        Ok((address, storage))
+                    }),
+            );
+
+        HashedPostState::from_reverts(account_reverts, storage_reverts)
+    }
+
+    /// Returns the [`SnapshotProvider`] and the blocks from [Self::block_number] on that have been
+    /// snapshotted for `segment`.
+    fn snapshotted_blocks(
+        &self,
+        segment: SnapshotSegment,
+    ) -> ProviderResult<Option<(&'b SnapshotProvider, RangeInclusive<BlockNumber>)>> {
+        let Some(snapshot_provider) = self.snapshot_provider else { return Ok(None) };
+        Ok(snapshot_provider
+            .get_highest_snapshot_number(segment)?
+            .filter(|highest| self.block_number <= *highest)
+            .map(|highest| (snapshot_provider, self.block_number..=highest)))
+    }
+
     fn history_info<T, K>(
         &self,
         key: K,
@@ -158,10 +355,7 @@ impl<'b, TX: DbTx> AccountReader for HistoricalStateProviderRef<'b, TX> {
         match self.account_history_lookup(address)? {
             HistoryInfo::NotYetWritten => Ok(None),
             HistoryInfo::InChangeset(changeset_block_number) => Ok(self
-                .tx
-                .cursor_dup_read::<tables::AccountChangeSet>()?
-                .seek_by_key_subkey(changeset_block_number, address)?
-                .filter(|acc| acc.address == address)
+                .account_before(changeset_block_number, address)?
                 .ok_or(ProviderError::AccountChangesetNotFound {
                     block_number: changeset_block_number,
                     address,
@@ -199,8 +393,18 @@ impl<'b, TX: DbTx> BlockHashReader for HistoricalStateProviderRef<'b, TX> {
 }
 
 impl<'b, TX: DbTx> StateRootProvider for HistoricalStateProviderRef<'b, TX> {
-    fn state_root(&self, _bundle_state: &BundleStateWithReceipts) -> ProviderResult<B256> {
-        Err(ProviderError::StateRootNotAvailableForHistoricalBlock)
+    fn state_root(&self, bundle_state: &BundleStateWithReceipts) -> ProviderResult<B256> {
+        let mut hashed_state = self.revert_state()?.into_owned();
+        hashed_state.extend(bundle_state.hash_state_slow());
+        let hashed_state = hashed_state.sorted();
+        let (account_prefix_set, storage_prefix_sets) = hashed_state.construct_prefix_sets();
+        StateRoot::new(self.tx)
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(self.tx, &hashed_state))
+            .with_changed_account_prefixes(account_prefix_set)
+            .with_changed_storage_prefixes(storage_prefix_sets)
+            .with_destroyed_accounts(hashed_state.destroyed_accounts())
+            .root()
+            .map_err(|err| ProviderError::Database(err.into()))
     }
 
     fn state_root_with_updates(
@@ -221,16 +425,13 @@ impl<'b, TX: DbTx> StateProvider for HistoricalStateProviderRef<'b, TX> {
         match self.storage_history_lookup(address, storage_key)? {
             HistoryInfo::NotYetWritten => Ok(None),
             HistoryInfo::InChangeset(changeset_block_number) => Ok(Some(
-                self.tx
-                    .cursor_dup_read::<tables::StorageChangeSet>()?
-                    .seek_by_key_subkey((changeset_block_number, address).into(), storage_key)?
-                    .filter(|entry| entry.key == storage_key)
-                    .ok_or_else(|| ProviderError::StorageChangesetNotFound {
+                self.storage_before(changeset_block_number, address, storage_key)?.ok_or_else(
+                    || ProviderError::StorageChangesetNotFound {
                         block_number: changeset_block_number,
                         address,
                         storage_key: Box::new(storage_key),
-                    })?
-                    .value,
+                    },
+                )?,
             )),
             HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState => Ok(self
                 .tx
@@ -248,8 +449,18 @@ impl<'b, TX: DbTx> StateProvider for HistoricalStateProviderRef<'b, TX> {
     }
 
     /// Get account and storage proofs.
-    fn proof(&self, _address: Address, _keys: &[B256]) -> ProviderResult<AccountProof> {
-        Err(ProviderError::StateRootNotAvailableForHistoricalBlock)
+    ///
+    /// The proof is generated by overlaying the reverted state on top of the current hashed state
+    /// and trie tables.
+    fn proof(&self, address: Address, slots: &[B256]) -> ProviderResult<AccountProof> {
+        let revert_state = self.revert_state()?;
+        let (account_prefix_set, storage_prefix_sets) = revert_state.construct_prefix_sets_mut();
+        Ok(Proof::new(self.tx)
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(self.tx, &revert_state))
+            .with_changed_account_prefixes(account_prefix_set)
+            .with_changed_storage_prefixes(storage_prefix_sets)
+            .account_proof(address, slots)
+            .map_err(Into::<reth_db::DatabaseError>::into)?)
     }
 }
 
@@ -263,12 +474,29 @@ pub struct HistoricalStateProvider<TX: DbTx> {
     block_number: BlockNumber,
     /// Lowest blocks at which different parts of the state are available.
     lowest_available_blocks: LowestAvailableBlocks,
+    /// Snapshot provider to read the changesets of snapshotted blocks from.
+    snapshot_provider: Option<Arc<SnapshotProvider>>,
+    /// The revert state, which is computed on first use by [StateProvider::proof] or
+    /// [StateRootProvider::state_root].
+    revert_state: OnceLock<HashedPostState>,
 }
 
 impl<TX: DbTx> HistoricalStateProvider<TX> {
     /// Create new StateProvider for historical block number
     pub fn new(tx: TX, block_number: BlockNumber) -> Self {
-        Self { tx, block_number, lowest_available_blocks: Default::default() }
+        Self {
+            tx,
+            block_number,
+            lowest_available_blocks: Default::default(),
+            snapshot_provider: None,
+            revert_state: OnceLock::new(),
+        }
+    }
+
+    /// Reads the changesets of snapshotted blocks from the provided [`SnapshotProvider`].
+    pub fn with_snapshot_provider(mut self, snapshot_provider: Arc<SnapshotProvider>) -> Self {
+        self.snapshot_provider = Some(snapshot_provider);
+        self
     }
 
     /// Set the lowest block number at which the account history is available.
@@ -292,11 +520,16 @@ impl<TX: DbTx> HistoricalStateProvider<TX> {
     /// Returns a new provider that takes the `TX` as reference
     #[inline(always)]
     fn as_ref(&self) -> HistoricalStateProviderRef<'_, TX> {
-        HistoricalStateProviderRef::new_with_lowest_available_blocks(
+        let mut provider = HistoricalStateProviderRef::new_with_lowest_available_blocks(
             &self.tx,
             self.block_number,
             self.lowest_available_blocks,
-        )
+        );
+        provider.revert_state = Some(&self.revert_state);
+        match &self.snapshot_provider {
+            Some(snapshot_provider) => provider.with_snapshot_provider(snapshot_provider),
+            None => provider,
+        }
     }
 }
 
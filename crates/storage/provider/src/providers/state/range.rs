@@ -0,0 +1,121 @@
+use crate::StateRangeProvider;
+use reth_db::transaction::DbTx;
+use reth_interfaces::provider::ProviderResult;
+use reth_primitives::{
+    trie::{Nibbles, TrieAccount},
+    Bytes, StorageEntry, B256,
+};
+use reth_trie::{
+    hashed_cursor::{
+        HashedAccountCursor, HashedCursorFactory, HashedPostState, HashedPostStateCursorFactory,
+        HashedStorageCursor,
+    },
+    prefix_set::PrefixSetMut,
+    proof::Proof,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+/// [StateRangeProvider] that reads the hashed state and state trie of the database.
+///
+/// The hashed state and trie tables only reflect the latest state. The state of an earlier block
+/// is read by overlaying the hashed state that reverts all changes since then, see
+/// [HashedPostState::from_revert_range].
+#[derive(Debug)]
+pub struct HashedStateRangeProvider<TX> {
+    /// Database transaction
+    tx: TX,
+    /// The hashed state that reverts the latest state to the served state.
+    revert_state: Arc<HashedPostState>,
+    /// The prefixes of the accounts that are changed by [Self::revert_state].
+    account_prefix_set: PrefixSetMut,
+    /// The prefixes of the storage slots that are changed by [Self::revert_state].
+    storage_prefix_sets: HashMap<B256, PrefixSetMut>,
+}
+
+impl<TX: DbTx> HashedStateRangeProvider<TX> {
+    /// Create a new provider for the latest state.
+    pub fn new(tx: TX) -> Self {
+        Self::new_with_revert_state(tx, Arc::default())
+    }
+
+    /// Create a new provider for the state that results from overlaying the given revert state.
+    pub fn new_with_revert_state(tx: TX, revert_state: Arc<HashedPostState>) -> Self {
+        let (account_prefix_set, storage_prefix_sets) = revert_state.construct_prefix_sets_mut();
+        Self { tx, revert_state, account_prefix_set, storage_prefix_sets }
+    }
+
+    /// Returns the factory for cursors over the served hashed state.
+    fn hashed_cursor_factory(&self) -> HashedPostStateCursorFactory<'_, '_, TX> {
+        HashedPostStateCursorFactory::new(&self.tx, &self.revert_state)
+    }
+
+    /// Returns the proof generator for the served state.
+    fn proof(&self) -> Proof<'_, TX, HashedPostStateCursorFactory<'_, '_, TX>> {
+        Proof::new(&self.tx)
+            .with_hashed_cursor_factory(self.hashed_cursor_factory())
+            .with_changed_account_prefixes(self.account_prefix_set.clone())
+            .with_changed_storage_prefixes(self.storage_prefix_sets.clone())
+    }
+}
+
+impl<TX: DbTx> StateRangeProvider for HashedStateRangeProvider<TX> {
+    fn hashed_accounts_range(
+        &self,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, TrieAccount)>> {
+        let proof = self.proof();
+        let mut cursor = self.hashed_cursor_factory().hashed_account_cursor()?;
+
+        let mut accounts = Vec::new();
+        let mut entry = cursor.seek(start)?;
+        while let Some((hashed_address, account)) = entry.filter(|_| accounts.len() < limit) {
+            let storage_root =
+                proof.storage_root(hashed_address).map_err(Into::<reth_db::DatabaseError>::into)?;
+            accounts.push((hashed_address, TrieAccount::from((account, storage_root))));
+            entry = cursor.next()?;
+        }
+        Ok(accounts)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<StorageEntry>> {
+        let mut cursor = self.hashed_cursor_factory().hashed_storage_cursor()?;
+
+        let mut entries = Vec::new();
+        let mut entry = cursor.seek(hashed_address, start)?;
+        while let Some(storage_entry) = entry.filter(|_| entries.len() < limit) {
+            entries.push(storage_entry);
+            entry = cursor.next()?;
+        }
+        Ok(entries)
+    }
+
+    fn account_trie_nodes(
+        &self,
+        targets: Vec<Nibbles>,
+    ) -> ProviderResult<BTreeMap<Nibbles, Bytes>> {
+        Ok(self
+            .proof()
+            .account_trie_nodes(targets)
+            .map_err(Into::<reth_db::DatabaseError>::into)?)
+    }
+
+    fn storage_trie_nodes(
+        &self,
+        hashed_address: B256,
+        targets: Vec<Nibbles>,
+    ) -> ProviderResult<BTreeMap<Nibbles, Bytes>> {
+        Ok(self
+            .proof()
+            .storage_trie_nodes(hashed_address, targets)
+            .map_err(Into::<reth_db::DatabaseError>::into)?)
+    }
+}
@@ -3,22 +3,26 @@ use crate::{
     traits::{BlockSource, ReceiptProvider},
     AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
     BundleStateDataProvider, ChainSpecProvider, ChangeSetReader, EvmEnvProvider, HeaderProvider,
-    ReceiptProviderIdExt, StateProvider, StateProviderBox, StateProviderFactory, StateRootProvider,
-    TransactionVariant, TransactionsProvider, WithdrawalsProvider,
+    HistoryReader, InvalidBlockReader, PlainStateReader, ReceiptProviderIdExt, RevertStateCache,
+    StateProvider, StateProviderBox, StateProviderFactory, StateRangeProviderBox, StateRangeReader,
+    StateRootProvider, TransactionVariant, TransactionsProvider, WithdrawalsProvider,
 };
 use parking_lot::Mutex;
 use reth_db::models::{AccountBeforeTx, StoredBlockBodyIndices};
-use reth_interfaces::provider::{ProviderError, ProviderResult};
+use reth_interfaces::{
+    blockchain_tree::InvalidBlock,
+    provider::{ProviderError, ProviderResult},
+};
 use reth_primitives::{
     keccak256, trie::AccountProof, Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId,
     BlockNumber, BlockWithSenders, Bytecode, Bytes, ChainInfo, ChainSpec, Header, Receipt,
-    SealedBlock, SealedBlockWithSenders, SealedHeader, StorageKey, StorageValue, TransactionMeta,
-    TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, B256, U256,
+    SealedBlock, SealedBlockWithSenders, SealedHeader, StorageEntry, StorageKey, StorageValue,
+    TransactionMeta, TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, B256, U256,
 };
 use reth_trie::updates::TrieUpdates;
 use revm::primitives::{BlockEnv, CfgEnv};
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
     ops::{RangeBounds, RangeInclusive},
     sync::Arc,
 };
@@ -677,3 +681,64 @@ impl ChangeSetReader for MockEthProvider {
         Ok(Vec::default())
     }
 }
+
+impl HistoryReader for MockEthProvider {
+    fn account_history_blocks(
+        &self,
+        _address: Address,
+        _range: RangeInclusive<BlockNumber>,
+        _limit: usize,
+        _reverse: bool,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        Ok(Vec::default())
+    }
+}
+
+impl InvalidBlockReader for MockEthProvider {
+    fn recent_invalid_blocks(&self) -> ProviderResult<Vec<InvalidBlock>> {
+        Ok(Vec::default())
+    }
+}
+
+impl StateRangeReader for MockEthProvider {
+    fn state_range_by_root(
+        &self,
+        _root: B256,
+        _max_blocks: u64,
+        _revert_states: &RevertStateCache,
+    ) -> ProviderResult<Option<StateRangeProviderBox>> {
+        Ok(None)
+    }
+}
+
+impl PlainStateReader for MockEthProvider {
+    fn plain_accounts_from(
+        &self,
+        start: Address,
+        limit: usize,
+    ) -> ProviderResult<Vec<(Address, Account)>> {
+        let lock = self.accounts.lock();
+        let accounts = lock
+            .iter()
+            .filter(|(address, _)| **address >= start)
+            .map(|(address, account)| (*address, account.account))
+            .collect::<BTreeMap<_, _>>();
+        Ok(accounts.into_iter().take(limit).collect())
+    }
+
+    fn plain_account_storage(&self, address: Address) -> ProviderResult<Vec<StorageEntry>> {
+        let lock = self.accounts.lock();
+        let storage = lock
+            .get(&address)
+            .map(|account| account.storage.clone().into_iter().collect::<BTreeMap<_, _>>())
+            .unwrap_or_default();
+        Ok(storage.into_iter().map(|(key, value)| StorageEntry { key, value }).collect())
+    }
+
+    fn changed_state_with_range(
+        &self,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeMap<Address, BTreeSet<B256>>> {
+        Ok(BTreeMap::default())
+    }
+}
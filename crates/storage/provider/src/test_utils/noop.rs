@@ -2,24 +2,26 @@ use crate::{
     bundle_state::BundleStateWithReceipts,
     traits::{BlockSource, ReceiptProvider},
     AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
-    ChainSpecProvider, ChangeSetReader, EvmEnvProvider, HeaderProvider, PruneCheckpointReader,
-    ReceiptProviderIdExt, StageCheckpointReader, StateProvider, StateProviderBox,
-    StateProviderFactory, StateRootProvider, TransactionVariant, TransactionsProvider,
-    WithdrawalsProvider,
+    ChainSpecProvider, ChangeSetReader, EvmEnvProvider, HeaderProvider, HistoryReader,
+    InvalidBlockReader, PlainStateReader, PruneCheckpointReader, ReceiptProviderIdExt,
+    RevertStateCache, StageCheckpointReader, StateProvider, StateProviderBox, StateProviderFactory,
+    StateRangeProviderBox, StateRangeReader, StateRootProvider, TransactionVariant,
+    TransactionsProvider, WithdrawalsProvider,
 };
 use reth_db::models::{AccountBeforeTx, StoredBlockBodyIndices};
-use reth_interfaces::provider::ProviderResult;
+use reth_interfaces::{blockchain_tree::InvalidBlock, provider::ProviderResult};
 use reth_primitives::{
     stage::{StageCheckpoint, StageId},
     trie::AccountProof,
     Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId, BlockNumber, Bytecode,
     ChainInfo, ChainSpec, Header, PruneCheckpoint, PruneSegment, Receipt, SealedBlock,
-    SealedBlockWithSenders, SealedHeader, StorageKey, StorageValue, TransactionMeta,
+    SealedBlockWithSenders, SealedHeader, StorageEntry, StorageKey, StorageValue, TransactionMeta,
     TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, B256, MAINNET, U256,
 };
 use reth_trie::updates::TrieUpdates;
 use revm::primitives::{BlockEnv, CfgEnv};
 use std::{
+    collections::{BTreeMap, BTreeSet},
     ops::{RangeBounds, RangeInclusive},
     sync::Arc,
 };
@@ -275,6 +277,56 @@ impl ChangeSetReader for NoopProvider {
     }
 }
 
+impl HistoryReader for NoopProvider {
+    fn account_history_blocks(
+        &self,
+        _address: Address,
+        _range: RangeInclusive<BlockNumber>,
+        _limit: usize,
+        _reverse: bool,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        Ok(Vec::default())
+    }
+}
+
+impl InvalidBlockReader for NoopProvider {
+    fn recent_invalid_blocks(&self) -> ProviderResult<Vec<InvalidBlock>> {
+        Ok(Vec::default())
+    }
+}
+
+impl PlainStateReader for NoopProvider {
+    fn plain_accounts_from(
+        &self,
+        _start: Address,
+        _limit: usize,
+    ) -> ProviderResult<Vec<(Address, Account)>> {
+        Ok(Vec::default())
+    }
+
+    fn plain_account_storage(&self, _address: Address) -> ProviderResult<Vec<StorageEntry>> {
+        Ok(Vec::default())
+    }
+
+    fn changed_state_with_range(
+        &self,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeMap<Address, BTreeSet<B256>>> {
+        Ok(BTreeMap::default())
+    }
+}
+
+impl StateRangeReader for NoopProvider {
+    fn state_range_by_root(
+        &self,
+        _root: B256,
+        _max_blocks: u64,
+        _revert_states: &RevertStateCache,
+    ) -> ProviderResult<Option<StateRangeProviderBox>> {
+        Ok(None)
+    }
+}
+
 impl StateRootProvider for NoopProvider {
     fn state_root(&self, _state: &BundleStateWithReceipts) -> ProviderResult<B256> {
         Ok(B256::default())
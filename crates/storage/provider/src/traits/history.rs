@@ -7,6 +7,28 @@ use std::{
     ops::{Range, RangeInclusive},
 };
 
+/// History Reader
+#[auto_impl(&, Arc, Box)]
+pub trait HistoryReader: Send + Sync {
+    /// Returns up to `limit` block numbers within the given range at which the account or any of
+    /// its storage slots changed, according to the [reth_db::tables::AccountHistory] and
+    /// [reth_db::tables::StorageHistory] indices.
+    ///
+    /// The returned block numbers are deduplicated and sorted in ascending order, starting at the
+    /// start of the range. If `reverse` is set, they are sorted in descending order instead,
+    /// starting at the end of the range.
+    ///
+    /// Note that the indices only contain the blocks in which the state of the account changed, so
+    /// blocks with transactions that touch the account without changing its state are missing.
+    fn account_history_blocks(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+        limit: usize,
+        reverse: bool,
+    ) -> ProviderResult<Vec<BlockNumber>>;
+}
+
 /// History Writer
 #[auto_impl(&, Arc, Box)]
 pub trait HistoryWriter: Send + Sync {
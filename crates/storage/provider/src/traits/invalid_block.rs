@@ -0,0 +1,10 @@
+use auto_impl::auto_impl;
+use reth_interfaces::{blockchain_tree::InvalidBlock, provider::ProviderResult};
+
+/// Client trait for fetching blocks that were rejected because they failed validation or
+/// execution.
+#[auto_impl(&, Arc, Box)]
+pub trait InvalidBlockReader: Send + Sync {
+    /// Returns the most recent invalid blocks, ordered from oldest to newest.
+    fn recent_invalid_blocks(&self) -> ProviderResult<Vec<InvalidBlock>>;
+}
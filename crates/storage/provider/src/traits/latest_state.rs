@@ -0,0 +1,46 @@
+use auto_impl::auto_impl;
+use reth_interfaces::provider::ProviderResult;
+use reth_primitives::{Account, Address, BlockNumber, Bytecode, StorageEntry, B256};
+use std::collections::BTreeMap;
+
+/// Account, storage and bytecode values of the state.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LatestStateChanges {
+    /// The accounts, `None` if the account does not exist.
+    pub accounts: BTreeMap<Address, Option<Account>>,
+    /// The storage values of an account, a zero value means that the slot is empty.
+    pub storage: BTreeMap<Address, Vec<StorageEntry>>,
+    /// Bytecodes, keyed by their hash.
+    pub bytecodes: Vec<(B256, Bytecode)>,
+}
+
+impl LatestStateChanges {
+    /// Returns `true` if there are no changes.
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty() && self.storage.is_empty() && self.bytecodes.is_empty()
+    }
+}
+
+/// Writer for state changes that are not the result of block execution, e.g. the state
+/// modifications of a dev chain.
+#[auto_impl(&, Arc, Box)]
+pub trait LatestStateWriter: Send + Sync {
+    /// Writes the given changes to the latest state and returns the values they replaced.
+    ///
+    /// The hashed state and the state trie are updated as well, but no changesets are written.
+    /// This means that the state root of the latest header no longer matches the state until the
+    /// changes are included in the next block, see [LatestStateWriter::write_state_reverts].
+    fn write_latest_state(&self, changes: LatestStateChanges)
+        -> ProviderResult<LatestStateChanges>;
+
+    /// Records the given values as the state before the given block in its changesets.
+    ///
+    /// This makes changes that were written with [LatestStateWriter::write_latest_state] before
+    /// the block was executed part of the block, so they are reverted if the block is unwound.
+    /// Existing changeset entries of the block are replaced.
+    fn write_state_reverts(
+        &self,
+        block: BlockNumber,
+        reverts: LatestStateChanges,
+    ) -> ProviderResult<()>;
+}
@@ -64,7 +64,21 @@ mod hashing;
 pub use hashing::HashingWriter;
 
 mod history;
-pub use history::HistoryWriter;
+pub use history::{HistoryReader, HistoryWriter};
+
+mod invalid_block;
+pub use invalid_block::InvalidBlockReader;
+
+mod latest_state;
+pub use latest_state::{LatestStateChanges, LatestStateWriter};
+
+mod plain_state;
+pub use plain_state::PlainStateReader;
+
+mod state_range;
+pub use state_range::{
+    RevertStateCache, StateRangeProvider, StateRangeProviderBox, StateRangeReader,
+};
 
 mod prune_checkpoint;
 pub use prune_checkpoint::{PruneCheckpointReader, PruneCheckpointWriter};
@@ -0,0 +1,29 @@
+use auto_impl::auto_impl;
+use reth_interfaces::provider::ProviderResult;
+use reth_primitives::{Account, Address, BlockNumber, StorageEntry, B256};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::RangeInclusive,
+};
+
+/// Client trait for iterating over the plain state and the changes that were applied to it.
+#[auto_impl(&, Arc, Box)]
+pub trait PlainStateReader: Send + Sync {
+    /// Returns up to `limit` accounts of the current plain state, ordered by address and starting
+    /// at `start` (inclusive).
+    fn plain_accounts_from(
+        &self,
+        start: Address,
+        limit: usize,
+    ) -> ProviderResult<Vec<(Address, Account)>>;
+
+    /// Returns all storage entries of the account in the current plain state, ordered by key.
+    fn plain_account_storage(&self, address: Address) -> ProviderResult<Vec<StorageEntry>>;
+
+    /// Iterates over the account and storage changesets in the given block range and returns all
+    /// changed addresses alongside the storage keys that were changed.
+    fn changed_state_with_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeMap<Address, BTreeSet<B256>>>;
+}
@@ -0,0 +1,129 @@
+use auto_impl::auto_impl;
+use parking_lot::Mutex;
+use reth_interfaces::provider::ProviderResult;
+use reth_primitives::{
+    trie::{Nibbles, TrieAccount},
+    BlockHash, Bytes, StorageEntry, B256,
+};
+use reth_trie::hashed_cursor::HashedPostState;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::Arc,
+};
+
+/// The maximum number of reverted states that are kept by a [RevertStateCache].
+const MAX_CACHED_REVERT_STATES: usize = 4;
+
+/// Type alias of boxed [StateRangeProvider].
+pub type StateRangeProviderBox = Box<dyn StateRangeProvider>;
+
+/// Client trait for reading ranges of the hashed state and nodes of the state trie of recent
+/// blocks, e.g. to serve them to peers that sync the state.
+#[auto_impl(&, Arc, Box)]
+pub trait StateRangeReader: Send + Sync {
+    /// Returns a [StateRangeProvider] for the state with the given root, if it is the state at
+    /// the end of one of the latest `max_blocks` canonical blocks.
+    ///
+    /// The state of an earlier block than the latest one is served by reverting the latest state,
+    /// which is cached in `revert_states`.
+    fn state_range_by_root(
+        &self,
+        root: B256,
+        max_blocks: u64,
+        revert_states: &RevertStateCache,
+    ) -> ProviderResult<Option<StateRangeProviderBox>>;
+}
+
+/// Caches the hashed states that revert the latest state to the state of recent blocks, see
+/// [StateRangeReader::state_range_by_root].
+///
+/// Reverting the state is expensive, while peers that sync the state request many ranges of the
+/// same state. The cache is cleared whenever the latest block changes.
+#[derive(Debug, Default)]
+pub struct RevertStateCache {
+    inner: Mutex<RevertStates>,
+}
+
+/// The cached states of a [RevertStateCache].
+#[derive(Debug, Default)]
+struct RevertStates {
+    /// The hash of the latest block, whose state is reverted.
+    best_block_hash: BlockHash,
+    /// The reverted states by their state root, from least to most recently inserted.
+    states: VecDeque<(B256, Arc<HashedPostState>)>,
+}
+
+impl RevertStateCache {
+    /// Returns the state that reverts the state of the latest block with the given hash to the
+    /// state with the given root, computing it with `f` if it isn't cached yet.
+    ///
+    /// Lookups are serialized, so concurrent lookups of the same state compute it only once.
+    pub fn get_or_try_insert_with<E>(
+        &self,
+        best_block_hash: BlockHash,
+        root: B256,
+        f: impl FnOnce() -> Result<HashedPostState, E>,
+    ) -> Result<Arc<HashedPostState>, E> {
+        let mut inner = self.inner.lock();
+        if inner.best_block_hash != best_block_hash {
+            inner.best_block_hash = best_block_hash;
+            inner.states.clear();
+        }
+        if let Some((_, state)) = inner.states.iter().find(|(state_root, _)| *state_root == root) {
+            return Ok(state.clone())
+        }
+
+        let state = Arc::new(f()?);
+        if inner.states.len() >= MAX_CACHED_REVERT_STATES {
+            inner.states.pop_front();
+        }
+        inner.states.push_back((root, state.clone()));
+        Ok(state)
+    }
+
+    /// Returns the number of cached states.
+    pub fn len(&self) -> usize {
+        self.inner.lock().states.len()
+    }
+
+    /// Returns `true` if no state is cached.
+    pub fn is_empty(&self) -> bool {
+        self.inner.lock().states.is_empty()
+    }
+}
+
+/// Reads ranges of the hashed state and nodes of the state trie of a fixed state.
+#[auto_impl(&, Arc, Box)]
+pub trait StateRangeProvider: Send + Sync {
+    /// Returns up to `limit` accounts of the hashed state together with their storage roots,
+    /// ordered by hashed address and starting at `start` (inclusive).
+    fn hashed_accounts_range(
+        &self,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, TrieAccount)>>;
+
+    /// Returns up to `limit` hashed storage entries of the account, ordered by hashed slot and
+    /// starting at `start` (inclusive).
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<StorageEntry>>;
+
+    /// Returns the nodes of the account trie on the paths to the given targets, keyed by their
+    /// path.
+    ///
+    /// See also [Proof::account_trie_nodes](reth_trie::proof::Proof::account_trie_nodes).
+    fn account_trie_nodes(&self, targets: Vec<Nibbles>)
+        -> ProviderResult<BTreeMap<Nibbles, Bytes>>;
+
+    /// Returns the nodes of the storage trie of the account on the paths to the given targets,
+    /// keyed by their path.
+    fn storage_trie_nodes(
+        &self,
+        hashed_address: B256,
+        targets: Vec<Nibbles>,
+    ) -> ProviderResult<BTreeMap<Nibbles, Bytes>>;
+}
@@ -5,6 +5,7 @@
 //! - [`stdout()`]
 //! - [`file()`]
 //! - [`journald()`]
+//! - `otlp()`, which requires the `otlp` feature
 //!
 //! As well as a simple way to initialize a subscriber: [`init`].
 
@@ -17,8 +18,17 @@
 #![deny(unused_must_use, rust_2018_idioms)]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+#[cfg(feature = "otlp")]
+use opentelemetry::{trace::TracerProvider as _, KeyValue};
+#[cfg(feature = "otlp")]
+use opentelemetry_otlp::WithExportConfig;
+#[cfg(feature = "otlp")]
+use opentelemetry_sdk::{
+    trace::{self as sdktrace, Sampler, TracerProvider},
+    Resource,
+};
 use rolling_file::{RollingConditionBasic, RollingFileAppender};
-use std::path::Path;
+use std::{fmt, path::Path, str::FromStr};
 use tracing::Subscriber;
 use tracing_subscriber::{
     filter::Directive, prelude::*, registry::LookupSpan, EnvFilter, Layer, Registry,
@@ -123,6 +133,153 @@ where
     Ok(tracing_journald::layer()?.with_filter(filter).boxed())
 }
 
+/// The transport used to export spans to an OpenTelemetry collector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OtlpProtocol {
+    /// OTLP over gRPC.
+    Grpc,
+    /// OTLP over HTTP with binary protobuf payloads.
+    Http,
+}
+
+impl fmt::Display for OtlpProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OtlpProtocol::Grpc => write!(f, "grpc"),
+            OtlpProtocol::Http => write!(f, "http"),
+        }
+    }
+}
+
+impl FromStr for OtlpProtocol {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "grpc" => Ok(OtlpProtocol::Grpc),
+            "http" => Ok(OtlpProtocol::Http),
+            _ => Err(format!("unknown OTLP protocol {s}, expected grpc or http")),
+        }
+    }
+}
+
+/// Configuration of the `otlp()` layer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OtlpConfig {
+    /// The endpoint of the collector, e.g. `http://localhost:4317`.
+    ///
+    /// For [`OtlpProtocol::Http`] the `/v1/traces` path is appended to the endpoint.
+    pub endpoint: String,
+    /// The transport used to export spans.
+    pub protocol: OtlpProtocol,
+    /// The `service.name` resource attribute of the exported spans.
+    pub service_name: String,
+    /// The fraction of root spans that are sampled, child spans follow the decision of their
+    /// parent.
+    pub sample_ratio: f64,
+    /// Additional resource attributes of the exported spans.
+    pub resource_attributes: Vec<(String, String)>,
+}
+
+/// The directives that are always added to the filter of the `otlp()` layer.
+///
+/// The spans of the crates used by the exporter itself would otherwise be exported as well, so
+/// every export would produce more spans to export.
+pub const OTLP_EXPORTER_DIRECTIVES: [&str; 5] =
+    ["h2=off", "tonic=off", "tower=off", "hyper=off", "reqwest=off"];
+
+/// Builds a new tracing layer that exports spans to an OpenTelemetry collector over OTLP.
+///
+/// The spans are filtered by `filter` before they are sampled. The spans of the exporter's own
+/// dependencies are always filtered out, see [OTLP_EXPORTER_DIRECTIVES].
+///
+/// The spans are exported in batches from a dedicated runtime, so this does not have to be called
+/// from within a tokio runtime. The boxed layer and a guard is returned. When the guard is dropped
+/// all pending spans are exported and the runtime is shut down.
+#[cfg(feature = "otlp")]
+#[must_use = "tracing guard must be kept alive to export spans"]
+pub fn otlp<S>(
+    filter: EnvFilter,
+    config: OtlpConfig,
+) -> Result<(BoxedLayer<S>, OtlpGuard), opentelemetry::trace::TraceError>
+where
+    S: Subscriber,
+    for<'a> S: LookupSpan<'a>,
+{
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name("otlp-exporter")
+        .enable_all()
+        .build()
+        .map_err(|err| opentelemetry::trace::TraceError::Other(Box::new(err)))?;
+    // the exporter and the batch processor spawn their tasks on the entered runtime
+    let entered = runtime.enter();
+
+    let exporter = match config.protocol {
+        OtlpProtocol::Grpc => opentelemetry_otlp::new_exporter()
+            .tonic()
+            .with_endpoint(config.endpoint)
+            .build_span_exporter()?,
+        OtlpProtocol::Http => opentelemetry_otlp::new_exporter()
+            .http()
+            .with_endpoint(config.endpoint)
+            .build_span_exporter()?,
+    };
+
+    let resource =
+        Resource::new(std::iter::once(KeyValue::new("service.name", config.service_name)).chain(
+            config.resource_attributes.into_iter().map(|(key, value)| KeyValue::new(key, value)),
+        ));
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+        .with_config(
+            sdktrace::config()
+                .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+                    config.sample_ratio,
+                ))))
+                .with_resource(Resource::default().merge(&resource)),
+        )
+        .build();
+
+    let filter = OTLP_EXPORTER_DIRECTIVES.iter().fold(filter, |filter, directive| {
+        filter.add_directive(directive.parse().expect("valid directive"))
+    });
+    let layer = tracing_opentelemetry::layer()
+        .with_tracer(provider.tracer("reth"))
+        .with_filter(filter)
+        .boxed();
+
+    drop(entered);
+    Ok((layer, OtlpGuard { provider: Some(provider), runtime: Some(runtime) }))
+}
+
+/// A guard returned by [`otlp()`].
+///
+/// When the guard is dropped, all spans that have not been exported yet are exported to the
+/// collector.
+#[cfg(feature = "otlp")]
+#[derive(Debug)]
+pub struct OtlpGuard {
+    /// The provider of the tracer of the layer.
+    provider: Option<TracerProvider>,
+    /// The runtime the spans are exported on.
+    runtime: Option<tokio::runtime::Runtime>,
+}
+
+#[cfg(feature = "otlp")]
+impl Drop for OtlpGuard {
+    fn drop(&mut self) {
+        if let Some(provider) = self.provider.take() {
+            let _ = provider.force_flush();
+            // the batch processor is shut down once the last reference to the provider is dropped
+            drop(provider);
+        }
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
 /// Initializes a tracing subscriber for tests.
 ///
 /// The filter is configurable via `RUST_LOG`.
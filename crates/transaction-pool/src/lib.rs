@@ -28,7 +28,15 @@
 //! `TransactionOrdering` type this pool is configured with.
 //!
 //! This is only used in the _pending_ pool to yield the best transactions for block production. The
-//! _base pool_ is ordered by base fee, and the _queued pool_ by current distance.
+//! _base pool_ is ordered by base fee, and the _queued pool_ by current distance. Blob transactions
+//! that become executable are moved to the pending pool and ordered the same way.
+//!
+//! Besides the default `CoinbaseTipOrdering`, the pool ships first in, first out ordering
+//! (`ArrivalTimeOrdering`) and ordering by effective gas price with a capped tip
+//! (`CappedTipOrdering`). `DynTransactionOrdering` selects one of them at runtime or wraps a custom
+//! scoring function.
+//! Orderings that need the pool metadata of a transaction, like the time it was added to the pool,
+//! override `TransactionOrdering::priority_with_metadata`.
 //!
 //! ### Validation
 //!
@@ -160,7 +168,10 @@ pub use crate::{
         TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT, TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
     },
     error::PoolResult,
-    ordering::{CoinbaseTipOrdering, Priority, TransactionOrdering},
+    ordering::{
+        ArrivalTimeOrdering, CappedTipOrdering, CoinbaseTipOrdering, DynTransactionOrdering,
+        FnOrdering, Priority, TransactionOrdering, TransactionOrderingKind,
+    },
     pool::{
         blob_tx_priority, fee_delta, state::SubPool, AllTransactionsEvents, FullTransactionEvent,
         TransactionEvent, TransactionEvents,
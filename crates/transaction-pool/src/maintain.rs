@@ -2,30 +2,40 @@
 
 use crate::{
     blobstore::{BlobStoreCanonTracker, BlobStoreUpdates},
+    error::PoolError,
     metrics::MaintainPoolMetrics,
     traits::{CanonicalStateUpdate, ChangedAccount, TransactionPoolExt},
-    BlockInfo, TransactionPool,
+    BlockInfo, TransactionOrigin, TransactionPool,
 };
+use alloy_rlp::Decodable;
 use futures_util::{
-    future::{BoxFuture, Fuse, FusedFuture},
+    future::{self, BoxFuture, Either, Fuse, FusedFuture},
     FutureExt, Stream, StreamExt,
 };
 use reth_primitives::{
+    fs::{self, FsPathError},
     Address, BlockHash, BlockNumber, BlockNumberOrTag, FromRecoveredPooledTransaction,
-    FromRecoveredTransaction, PooledTransactionsElementEcRecovered,
+    FromRecoveredTransaction, IntoRecoveredTransaction, PooledTransactionsElement,
+    PooledTransactionsElementEcRecovered,
 };
 use reth_provider::{
     BlockReaderIdExt, BundleStateWithReceipts, CanonStateNotification, ChainSpecProvider,
     ProviderError, StateProviderFactory,
 };
-use reth_tasks::TaskSpawner;
+use reth_tasks::{shutdown::GracefulShutdown, TaskSpawner};
 use std::{
     borrow::Borrow,
     collections::HashSet,
     hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    pin::pin,
+    time::Duration,
 };
-use tokio::sync::oneshot;
-use tracing::{debug, trace};
+use tokio::{sync::oneshot, time::Instant};
+use tracing::{debug, error, info, trace, warn};
+
+/// The default interval at which the local transactions are written to the backup file.
+pub const DEFAULT_LOCAL_TRANSACTIONS_BACKUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
 
 /// Additional settings for maintaining the transaction pool
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -414,6 +424,191 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
     }
 }
 
+/// Settings for backing up the pool's local transactions, so they survive restarts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalTransactionBackupConfig {
+    /// The file the local transactions are written to.
+    ///
+    /// If not set, local transactions are not backed up.
+    pub transactions_path: Option<PathBuf>,
+    /// How often the local transactions are written to the file, in addition to on shutdown.
+    ///
+    /// Default: 1 hour
+    pub interval: Duration,
+}
+
+impl LocalTransactionBackupConfig {
+    /// Backs up the local transactions to the given file.
+    pub fn with_local_txs_backup(transactions_path: PathBuf) -> Self {
+        Self { transactions_path: Some(transactions_path), ..Default::default() }
+    }
+
+    /// Sets the interval at which the local transactions are written to the file.
+    ///
+    /// A zero interval disables the periodic backup, the file is then only written on shutdown.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+}
+
+impl Default for LocalTransactionBackupConfig {
+    fn default() -> Self {
+        Self { transactions_path: None, interval: DEFAULT_LOCAL_TRANSACTIONS_BACKUP_INTERVAL }
+    }
+}
+
+/// Errors that can occur while backing up or restoring the pool's local transactions.
+#[derive(Debug, thiserror::Error)]
+pub enum TransactionsBackupError {
+    /// The backup file could not be decoded.
+    #[error("failed to decode local transactions: {0}")]
+    Decode(#[from] alloy_rlp::Error),
+    /// The backup file could not be read or written.
+    #[error(transparent)]
+    FsPath(#[from] FsPathError),
+    /// The restored transactions could not be added to the pool.
+    #[error(transparent)]
+    Pool(#[from] PoolError),
+    /// The blocking task that writes the backup file failed.
+    #[error("local transactions backup task failed: {0}")]
+    Task(String),
+}
+
+/// Restores the pool's local transactions from the backup file and keeps the file up to date.
+///
+/// On startup, the transactions in the file are re-validated and inserted into the pool as
+/// [TransactionOrigin::Local]. Afterwards, the pool's local transactions are written to the file
+/// periodically and once more when the node shuts down.
+///
+/// The file contains the RLP encoded list of transactions in their network format
+/// ([PooledTransactionsElement]), so blob transactions are stored together with their sidecar.
+pub async fn backup_local_transactions_task<P>(
+    mut shutdown: GracefulShutdown,
+    pool: P,
+    config: LocalTransactionBackupConfig,
+) where
+    P: TransactionPool + 'static,
+{
+    let LocalTransactionBackupConfig { transactions_path, interval } = config;
+    let Some(transactions_path) = transactions_path else { return };
+
+    if let Err(err) = load_local_transactions(&pool, &transactions_path).await {
+        error!(target: "txpool", %err, ?transactions_path, "Failed to restore local transactions");
+    }
+
+    let graceful_guard = if interval.is_zero() {
+        // a zero interval means the transactions are only backed up on shutdown
+        shutdown.await
+    } else {
+        let mut interval = tokio::time::interval_at(Instant::now() + interval, interval);
+        loop {
+            match future::select(&mut shutdown, pin!(interval.tick())).await {
+                Either::Left((guard, _)) => break guard,
+                Either::Right(_) => {
+                    if let Err(err) = spawn_save_local_transactions(&pool, &transactions_path).await
+                    {
+                        warn!(target: "txpool", %err, ?transactions_path, "Failed to back up local transactions");
+                    }
+                }
+            }
+        }
+    };
+
+    if let Err(err) = spawn_save_local_transactions(&pool, &transactions_path).await {
+        error!(target: "txpool", %err, ?transactions_path, "Failed to back up local transactions");
+    }
+    drop(graceful_guard)
+}
+
+/// Runs [save_local_transactions] on the blocking thread pool, so the file write does not stall
+/// the async runtime.
+async fn spawn_save_local_transactions<P>(
+    pool: &P,
+    path: &Path,
+) -> Result<(), TransactionsBackupError>
+where
+    P: TransactionPool + 'static,
+{
+    let pool = pool.clone();
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || save_local_transactions(&pool, &path))
+        .await
+        .map_err(|err| TransactionsBackupError::Task(err.to_string()))?
+}
+
+/// Reads the transactions from the backup file and inserts them into the pool as
+/// [TransactionOrigin::Local].
+///
+/// Transactions are validated again, so transactions that were mined or became invalid in the
+/// meantime are discarded.
+async fn load_local_transactions<P>(pool: &P, path: &Path) -> Result<(), TransactionsBackupError>
+where
+    P: TransactionPool,
+{
+    if !path.exists() {
+        return Ok(())
+    }
+
+    let data = fs::read(path)?;
+    let transactions = Vec::<PooledTransactionsElement>::decode(&mut data.as_slice())?
+        .into_iter()
+        .filter_map(|tx| tx.try_into_ecrecovered().ok())
+        .map(<P as TransactionPool>::Transaction::from_recovered_pooled_transaction)
+        .collect::<Vec<_>>();
+    let num_transactions = transactions.len();
+
+    let outcome = pool.add_transactions(TransactionOrigin::Local, transactions).await?;
+    let restored = outcome.iter().filter(|res| res.is_ok()).count();
+    info!(target: "txpool", ?path, restored, discarded = num_transactions - restored, "Restored local transactions");
+
+    Ok(())
+}
+
+/// Writes the pool's local transactions to the backup file.
+///
+/// The file is replaced atomically, and removed if there are no local transactions.
+fn save_local_transactions<P>(pool: &P, path: &Path) -> Result<(), TransactionsBackupError>
+where
+    P: TransactionPool,
+{
+    let transactions = pool
+        .get_local_transactions()
+        .into_iter()
+        .filter_map(|tx| {
+            let tx = tx.to_recovered_transaction().into_signed();
+            if tx.is_eip4844() {
+                // blob transactions can only be validated again with their sidecar
+                let sidecar = pool.get_blob(tx.hash).ok().flatten()?;
+                PooledTransactionsElement::try_from_blob_transaction(tx, sidecar).ok()
+            } else {
+                Some(tx.into())
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if transactions.is_empty() {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        trace!(target: "txpool", ?path, "No local transactions to back up");
+        return Ok(())
+    }
+
+    let mut buf = Vec::new();
+    alloy_rlp::encode_list(&transactions, &mut buf);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, buf)?;
+    fs::rename(&tmp_path, path)?;
+    debug!(target: "txpool", ?path, num_transactions = transactions.len(), "Backed up local transactions");
+
+    Ok(())
+}
+
 struct FinalizedBlockTracker {
     last_finalized_block: Option<BlockNumber>,
 }
@@ -538,6 +733,49 @@ fn changed_accounts_iter(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        blobstore::InMemoryBlobStore, noop::MockTransactionValidator,
+        test_utils::TransactionGenerator, CoinbaseTipOrdering, EthPooledTransaction, Pool,
+        PoolTransaction,
+    };
+
+    fn eth_pool() -> Pool<
+        MockTransactionValidator<EthPooledTransaction>,
+        CoinbaseTipOrdering<EthPooledTransaction>,
+        InMemoryBlobStore,
+    > {
+        Pool::new(
+            MockTransactionValidator::default(),
+            CoinbaseTipOrdering::default(),
+            InMemoryBlobStore::default(),
+            Default::default(),
+        )
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn restores_local_transactions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("txpool-transactions.rlp");
+        let mut gen = TransactionGenerator::new(rand::thread_rng());
+
+        let pool = eth_pool();
+        let local = gen.gen_eip1559_pooled();
+        let local_hash = *local.hash();
+        pool.add_transaction(TransactionOrigin::Local, local).await.unwrap();
+        pool.add_transaction(TransactionOrigin::External, gen.gen_eip1559_pooled()).await.unwrap();
+        save_local_transactions(&pool, &path).unwrap();
+
+        let restarted = eth_pool();
+        load_local_transactions(&restarted, &path).await.unwrap();
+        let restored = restarted.get_local_transactions();
+        assert_eq!(restored.len(), 1);
+        assert_eq!(*restored[0].hash(), local_hash);
+        assert_eq!(restarted.pool_size().total, 1);
+
+        // the file is removed once there are no local transactions left
+        save_local_transactions(&eth_pool(), &path).unwrap();
+        assert!(!path.exists());
+    }
 
     #[test]
     fn changed_acc_entry() {
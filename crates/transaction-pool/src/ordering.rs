@@ -1,6 +1,6 @@
-use crate::traits::PoolTransaction;
+use crate::{traits::PoolTransaction, ValidPoolTransaction};
 use reth_primitives::U256;
-use std::{fmt, marker::PhantomData};
+use std::{fmt, marker::PhantomData, sync::Arc, time::Instant};
 
 /// Priority of the transaction that can be missing.
 ///
@@ -42,6 +42,19 @@ pub trait TransactionOrdering: Send + Sync + 'static {
         transaction: &Self::Transaction,
         base_fee: u64,
     ) -> Priority<Self::PriorityValue>;
+
+    /// Returns the priority score for the given transaction, together with its pool metadata,
+    /// like the time it was added to the pool and its origin.
+    ///
+    /// This is what the pool uses to order transactions. By default, the metadata is ignored and
+    /// the score is [TransactionOrdering::priority].
+    fn priority_with_metadata(
+        &self,
+        transaction: &ValidPoolTransaction<Self::Transaction>,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        self.priority(&transaction.transaction, base_fee)
+    }
 }
 
 /// Default ordering for the pool.
@@ -82,3 +95,351 @@ impl<T> Clone for CoinbaseTipOrdering<T> {
         Self::default()
     }
 }
+
+/// First in, first out ordering.
+///
+/// The transactions are ordered by the time they were added to the pool, regardless of the fees
+/// they pay. Since this uses the time a transaction entered the pool and not the time it became
+/// executable, transactions that were parked in another sub-pool, like blob transactions waiting
+/// for the blob fee to drop, keep their place.
+#[derive(Debug)]
+pub struct ArrivalTimeOrdering<T> {
+    /// The reference point for the arrival times of the transactions.
+    epoch: Instant,
+    _marker: PhantomData<T>,
+}
+
+impl<T> TransactionOrdering for ArrivalTimeOrdering<T>
+where
+    T: PoolTransaction + 'static,
+{
+    type PriorityValue = U256;
+    type Transaction = T;
+
+    /// The arrival time is only known from the pool metadata.
+    fn priority(
+        &self,
+        _transaction: &Self::Transaction,
+        _base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        Priority::None
+    }
+
+    fn priority_with_metadata(
+        &self,
+        transaction: &ValidPoolTransaction<Self::Transaction>,
+        _base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        // earlier transactions have a higher priority
+        let arrival = transaction.timestamp.saturating_duration_since(self.epoch).as_nanos();
+        Priority::Value(U256::MAX - U256::from(arrival))
+    }
+}
+
+impl<T> Default for ArrivalTimeOrdering<T> {
+    fn default() -> Self {
+        Self { epoch: Instant::now(), _marker: Default::default() }
+    }
+}
+
+impl<T> Clone for ArrivalTimeOrdering<T> {
+    fn clone(&self) -> Self {
+        Self { epoch: self.epoch, _marker: Default::default() }
+    }
+}
+
+/// Orders transactions by their effective gas price, with the tip capped at a configured value.
+///
+/// Tips above the cap do not increase the priority of a transaction, so transactions that pay at
+/// least the capped tip are ordered by the time they became executable instead of competing on
+/// fees.
+#[derive(Debug)]
+pub struct CappedTipOrdering<T> {
+    /// The maximum tip per gas that is taken into account.
+    max_tip_per_gas: u128,
+    _marker: PhantomData<T>,
+}
+
+impl<T> CappedTipOrdering<T> {
+    /// Creates a new ordering that ignores tips above `max_tip_per_gas`.
+    pub fn new(max_tip_per_gas: u128) -> Self {
+        Self { max_tip_per_gas, _marker: Default::default() }
+    }
+
+    /// Returns the maximum tip per gas that is taken into account.
+    pub fn max_tip_per_gas(&self) -> u128 {
+        self.max_tip_per_gas
+    }
+}
+
+impl<T> TransactionOrdering for CappedTipOrdering<T>
+where
+    T: PoolTransaction + 'static,
+{
+    type PriorityValue = U256;
+    type Transaction = T;
+
+    fn priority(
+        &self,
+        transaction: &Self::Transaction,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        transaction
+            .effective_tip_per_gas(base_fee)
+            .map(|tip| U256::from(base_fee) + U256::from(tip.min(self.max_tip_per_gas)))
+            .into()
+    }
+}
+
+impl<T> Clone for CappedTipOrdering<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.max_tip_per_gas)
+    }
+}
+
+/// An ordering that scores transactions with a user-provided function.
+///
+/// The function is called with the transaction, including its pool metadata, and the current base
+/// fee.
+pub struct FnOrdering<T, F> {
+    score: F,
+    _marker: PhantomData<T>,
+}
+
+impl<T, F> FnOrdering<T, F> {
+    /// Creates a new ordering that scores transactions with the given function.
+    pub fn new(score: F) -> Self {
+        Self { score, _marker: Default::default() }
+    }
+}
+
+impl<T, F> TransactionOrdering for FnOrdering<T, F>
+where
+    T: PoolTransaction + 'static,
+    F: Fn(&ValidPoolTransaction<T>, u64) -> Priority<U256> + Send + Sync + 'static,
+{
+    type PriorityValue = U256;
+    type Transaction = T;
+
+    /// The scoring function needs the pool metadata.
+    fn priority(
+        &self,
+        _transaction: &Self::Transaction,
+        _base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        Priority::None
+    }
+
+    fn priority_with_metadata(
+        &self,
+        transaction: &ValidPoolTransaction<Self::Transaction>,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        (self.score)(transaction, base_fee)
+    }
+}
+
+impl<T, F: Clone> Clone for FnOrdering<T, F> {
+    fn clone(&self) -> Self {
+        Self::new(self.score.clone())
+    }
+}
+
+impl<T, F> fmt::Debug for FnOrdering<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FnOrdering").finish_non_exhaustive()
+    }
+}
+
+/// The built-in transaction orderings, see [DynTransactionOrdering].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TransactionOrderingKind {
+    /// Order by coinbase tip, see [CoinbaseTipOrdering].
+    #[default]
+    CoinbaseTip,
+    /// Order by the time transactions were added to the pool, see [ArrivalTimeOrdering].
+    ArrivalTime,
+    /// Order by effective gas price with the tip capped at the given value, see
+    /// [CappedTipOrdering].
+    CappedTip {
+        /// The maximum tip per gas that is taken into account.
+        max_tip_per_gas: u128,
+    },
+}
+
+/// A [TransactionOrdering] that is selected at runtime.
+///
+/// This can wrap any ordering that scores transactions with a [U256], so the pool's type does not
+/// depend on the configured ordering. The built-in orderings are dispatched statically, only custom
+/// orderings are called through a trait object.
+pub struct DynTransactionOrdering<T> {
+    inner: DynOrderingInner<T>,
+}
+
+/// The ordering wrapped by a [DynTransactionOrdering].
+enum DynOrderingInner<T> {
+    CoinbaseTip(CoinbaseTipOrdering<T>),
+    ArrivalTime(ArrivalTimeOrdering<T>),
+    CappedTip(CappedTipOrdering<T>),
+    Custom(Arc<dyn TransactionOrdering<PriorityValue = U256, Transaction = T>>),
+}
+
+impl<T: PoolTransaction + 'static> DynTransactionOrdering<T> {
+    /// Wraps the given ordering.
+    pub fn new<O>(ordering: O) -> Self
+    where
+        O: TransactionOrdering<PriorityValue = U256, Transaction = T>,
+    {
+        Self { inner: DynOrderingInner::Custom(Arc::new(ordering)) }
+    }
+
+    /// Creates an ordering that scores transactions with the given function, see [FnOrdering].
+    pub fn from_fn<F>(score: F) -> Self
+    where
+        F: Fn(&ValidPoolTransaction<T>, u64) -> Priority<U256> + Send + Sync + 'static,
+    {
+        Self::new(FnOrdering::new(score))
+    }
+}
+
+impl<T: PoolTransaction + 'static> From<TransactionOrderingKind> for DynTransactionOrdering<T> {
+    fn from(kind: TransactionOrderingKind) -> Self {
+        let inner = match kind {
+            TransactionOrderingKind::CoinbaseTip => {
+                DynOrderingInner::CoinbaseTip(CoinbaseTipOrdering::default())
+            }
+            TransactionOrderingKind::ArrivalTime => {
+                DynOrderingInner::ArrivalTime(ArrivalTimeOrdering::default())
+            }
+            TransactionOrderingKind::CappedTip { max_tip_per_gas } => {
+                DynOrderingInner::CappedTip(CappedTipOrdering::new(max_tip_per_gas))
+            }
+        };
+        Self { inner }
+    }
+}
+
+impl<T: PoolTransaction + 'static> Default for DynTransactionOrdering<T> {
+    fn default() -> Self {
+        TransactionOrderingKind::default().into()
+    }
+}
+
+impl<T> TransactionOrdering for DynTransactionOrdering<T>
+where
+    T: PoolTransaction + 'static,
+{
+    type PriorityValue = U256;
+    type Transaction = T;
+
+    fn priority(
+        &self,
+        transaction: &Self::Transaction,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        match &self.inner {
+            DynOrderingInner::CoinbaseTip(ordering) => ordering.priority(transaction, base_fee),
+            DynOrderingInner::ArrivalTime(ordering) => ordering.priority(transaction, base_fee),
+            DynOrderingInner::CappedTip(ordering) => ordering.priority(transaction, base_fee),
+            DynOrderingInner::Custom(ordering) => ordering.priority(transaction, base_fee),
+        }
+    }
+
+    fn priority_with_metadata(
+        &self,
+        transaction: &ValidPoolTransaction<Self::Transaction>,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        match &self.inner {
+            DynOrderingInner::CoinbaseTip(ordering) => {
+                ordering.priority_with_metadata(transaction, base_fee)
+            }
+            DynOrderingInner::ArrivalTime(ordering) => {
+                ordering.priority_with_metadata(transaction, base_fee)
+            }
+            DynOrderingInner::CappedTip(ordering) => {
+                ordering.priority_with_metadata(transaction, base_fee)
+            }
+            DynOrderingInner::Custom(ordering) => {
+                ordering.priority_with_metadata(transaction, base_fee)
+            }
+        }
+    }
+}
+
+impl<T> Clone for DynTransactionOrdering<T> {
+    fn clone(&self) -> Self {
+        let inner = match &self.inner {
+            DynOrderingInner::CoinbaseTip(ordering) => {
+                DynOrderingInner::CoinbaseTip(ordering.clone())
+            }
+            DynOrderingInner::ArrivalTime(ordering) => {
+                DynOrderingInner::ArrivalTime(ordering.clone())
+            }
+            DynOrderingInner::CappedTip(ordering) => DynOrderingInner::CappedTip(ordering.clone()),
+            DynOrderingInner::Custom(ordering) => DynOrderingInner::Custom(Arc::clone(ordering)),
+        };
+        Self { inner }
+    }
+}
+
+impl<T> fmt::Debug for DynTransactionOrdering<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynTransactionOrdering").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::{MockTransaction, MockTransactionFactory},
+        TransactionOrigin,
+    };
+
+    #[test]
+    fn arrival_time_prefers_older_transactions() {
+        let ordering = ArrivalTimeOrdering::default();
+        let mut f = MockTransactionFactory::default();
+        let old = f.validated(MockTransaction::eip1559());
+        let mut new = f.validated(MockTransaction::eip1559().inc_price_by(100));
+        new.timestamp = old.timestamp + std::time::Duration::from_secs(1);
+        assert!(
+            ordering.priority_with_metadata(&old, 0) > ordering.priority_with_metadata(&new, 0)
+        );
+    }
+
+    #[test]
+    fn capped_tip_ignores_tips_above_cap() {
+        let ordering = CappedTipOrdering::new(10);
+        let tx =
+            |tip: u128| MockTransaction::eip1559().with_max_fee(100 + tip).with_priority_fee(tip);
+        let low = tx(5);
+        let capped = tx(10);
+        let high = tx(50);
+
+        assert!(ordering.priority(&capped, 100) > ordering.priority(&low, 100));
+        assert_eq!(ordering.priority(&capped, 100), ordering.priority(&high, 100));
+        assert_eq!(ordering.priority(&high, 100), Priority::Value(U256::from(110)));
+    }
+
+    #[test]
+    fn dyn_ordering_uses_scoring_fn() {
+        // prefer private order flow, then order by tip
+        let ordering = DynTransactionOrdering::<MockTransaction>::from_fn(|tx, base_fee| {
+            let private = U256::from((tx.origin == TransactionOrigin::Private) as u8) << 128;
+            tx.transaction
+                .effective_tip_per_gas(base_fee)
+                .map(|tip| private + U256::from(tip))
+                .into()
+        });
+        let mut f = MockTransactionFactory::default();
+        let private =
+            f.validated_with_origin(TransactionOrigin::Private, MockTransaction::eip1559());
+        let external = f.validated(MockTransaction::eip1559().inc_price_by(100));
+        assert!(
+            ordering.priority_with_metadata(&private, 0) >
+                ordering.priority_with_metadata(&external, 0)
+        );
+    }
+}
@@ -139,7 +139,7 @@ impl<T: TransactionOrdering> PendingPool<T> {
         for tx in unlocked {
             submission_id += 1;
             debug_assert!(!best.all.contains_key(tx.id()), "transaction already included");
-            let priority = self.ordering.priority(&tx.transaction, base_fee);
+            let priority = self.ordering.priority_with_metadata(&tx, base_fee);
             let tx_id = *tx.id();
             let transaction = PendingTransaction { submission_id, transaction: tx, priority };
             if best.ancestor(&tx_id).is_none() {
@@ -235,7 +235,7 @@ impl<T: TransactionOrdering> PendingPool<T> {
                 }
             } else {
                 // Re-insert the transaction with new priority.
-                tx.priority = self.ordering.priority(&tx.transaction.transaction, base_fee);
+                tx.priority = self.ordering.priority_with_metadata(&tx.transaction, base_fee);
 
                 self.size_of += tx.transaction.size();
                 self.update_independents_and_highest_nonces(&tx, &id);
@@ -298,7 +298,7 @@ impl<T: TransactionOrdering> PendingPool<T> {
         let tx_id = *tx.id();
 
         let submission_id = self.next_id();
-        let priority = self.ordering.priority(&tx.transaction, base_fee);
+        let priority = self.ordering.priority_with_metadata(&tx, base_fee);
         let tx = PendingTransaction { submission_id, transaction: tx, priority };
 
         self.update_independents_and_highest_nonces(&tx, &tx_id);
@@ -571,7 +571,7 @@ mod tests {
     use super::*;
     use crate::{
         test_utils::{MockOrdering, MockTransaction, MockTransactionFactory, MockTransactionSet},
-        PoolTransaction,
+        ArrivalTimeOrdering, PoolTransaction,
     };
 
     #[test]
@@ -632,6 +632,24 @@ mod tests {
         pool.assert_invariants();
     }
 
+    #[test]
+    fn best_honors_arrival_time_ordering() {
+        let mut f = MockTransactionFactory::default();
+        let mut pool = PendingPool::new(ArrivalTimeOrdering::default());
+
+        // the blob transaction arrived first but only becomes pending after a cheaper transaction
+        let blob_tx = f.validated(MockTransaction::eip4844());
+        let mut tx = f.validated(MockTransaction::eip1559().inc_price_by(100));
+        tx.timestamp = blob_tx.timestamp + std::time::Duration::from_secs(1);
+        let blob_tx = Arc::new(blob_tx);
+        let tx = Arc::new(tx);
+        pool.add_transaction(tx.clone(), 0);
+        pool.add_transaction(blob_tx.clone(), 0);
+
+        let best = pool.best().map(|tx| *tx.hash()).collect::<Vec<_>>();
+        assert_eq!(best, vec![*blob_tx.hash(), *tx.hash()]);
+    }
+
     #[test]
     fn evict_worst() {
         let mut f = MockTransactionFactory::default();
@@ -2,11 +2,18 @@ use super::{HashedAccountCursor, HashedCursorFactory, HashedStorageCursor};
 use crate::prefix_set::{PrefixSet, PrefixSetMut};
 use reth_db::{
     cursor::{DbCursorRO, DbDupCursorRO},
+    models::BlockNumberAddress,
     tables,
     transaction::DbTx,
+    DatabaseError,
+};
+use reth_primitives::{
+    keccak256, trie::Nibbles, Account, Address, BlockNumber, StorageEntry, B256, U256,
+};
+use std::{
+    collections::{hash_map, HashMap, HashSet},
+    ops::RangeInclusive,
 };
-use reth_primitives::{trie::Nibbles, Account, StorageEntry, B256, U256};
-use std::collections::{HashMap, HashSet};
 
 /// The post state account storage with hashed slots.
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -64,6 +71,23 @@ impl HashedStorage {
     pub fn insert_zero_valued_slot(&mut self, slot: B256) {
         self.zero_valued_slots.insert(slot);
     }
+
+    /// Extend the storage with the entries of the given storage.
+    ///
+    /// Entries of `other` take precedence. If `other` was wiped, it replaces this storage entirely.
+    pub fn extend(&mut self, other: Self) {
+        if other.wiped {
+            *self = other;
+            return
+        }
+
+        let slots = other.storage_slots().map(|(slot, _)| slot).collect::<HashSet<_>>();
+        self.non_zero_valued_storage.retain(|(slot, _)| !slots.contains(slot));
+        self.zero_valued_slots.retain(|slot| !slots.contains(slot));
+        self.non_zero_valued_storage.extend(other.non_zero_valued_storage);
+        self.zero_valued_slots.extend(other.zero_valued_slots);
+        self.sorted = false;
+    }
 }
 
 /// The post state with hashed addresses as keys.
@@ -91,6 +115,77 @@ impl Default for HashedPostState {
 }
 
 impl HashedPostState {
+    /// Initialize [HashedPostState] from the account and storage changesets in the given block
+    /// range.
+    ///
+    /// Only the oldest changeset entry of each account and storage slot is retained, so overlaying
+    /// the returned state onto the current hashed state yields the state at the start of the first
+    /// block in the range.
+    pub fn from_revert_range<TX: DbTx>(
+        tx: &TX,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Self, DatabaseError> {
+        let mut account_changesets_cursor = tx.cursor_read::<tables::AccountChangeSet>()?;
+        let mut storage_changesets_cursor = tx.cursor_read::<tables::StorageChangeSet>()?;
+        Self::from_reverts(
+            account_changesets_cursor.walk_range(range.clone())?.map(|entry| {
+                entry.map(|(_, account_before)| (account_before.address, account_before.info))
+            }),
+            storage_changesets_cursor.walk_range(BlockNumberAddress::range(range))?.map(|entry| {
+                entry.map(|(BlockNumberAddress((_, address)), storage)| (address, storage))
+            }),
+        )
+    }
+
+    /// Initialize [HashedPostState] from account and storage changeset entries that are ordered
+    /// from the oldest to the newest block.
+    ///
+    /// Like [Self::from_revert_range], only the oldest entry of each account and storage slot is
+    /// retained.
+    pub fn from_reverts<E>(
+        account_reverts: impl IntoIterator<Item = Result<(Address, Option<Account>), E>>,
+        storage_reverts: impl IntoIterator<Item = Result<(Address, StorageEntry), E>>,
+    ) -> Result<Self, E> {
+        // Record the oldest account info for each address.
+        let mut accounts = HashMap::<B256, Option<Account>>::new();
+        for entry in account_reverts {
+            let (address, info) = entry?;
+            accounts.entry(keccak256(address)).or_insert(info);
+        }
+
+        // Record the oldest value for each storage slot.
+        let mut storages = HashMap::<B256, HashMap<B256, U256>>::new();
+        for entry in storage_reverts {
+            let (address, storage) = entry?;
+            if let hash_map::Entry::Vacant(entry) =
+                storages.entry(keccak256(address)).or_default().entry(keccak256(storage.key))
+            {
+                entry.insert(storage.value);
+            }
+        }
+
+        let mut hashed_state = Self::default();
+        for (hashed_address, account) in accounts {
+            match account {
+                Some(account) => hashed_state.insert_account(hashed_address, account),
+                None => hashed_state.insert_destroyed_account(hashed_address),
+            }
+        }
+        for (hashed_address, slots) in storages {
+            let mut hashed_storage = HashedStorage::new(false);
+            for (hashed_slot, value) in slots {
+                if value.is_zero() {
+                    hashed_storage.insert_zero_valued_slot(hashed_slot);
+                } else {
+                    hashed_storage.insert_non_zero_valued_storage(hashed_slot, value);
+                }
+            }
+            hashed_state.insert_hashed_storage(hashed_address, hashed_storage);
+        }
+
+        Ok(hashed_state.sorted())
+    }
+
     /// Sort and return self.
     pub fn sorted(mut self) -> Self {
         self.sort();
@@ -138,6 +233,33 @@ impl HashedPostState {
         self.storages.insert(hashed_address, hashed_storage);
     }
 
+    /// Extend the post state with the given post state.
+    ///
+    /// Accounts and storage entries of `other` take precedence over the existing entries, which
+    /// allows layering the changes of consecutive blocks on top of each other.
+    pub fn extend(&mut self, other: Self) {
+        let accounts = other
+            .accounts
+            .iter()
+            .map(|(hashed_address, _)| *hashed_address)
+            .chain(other.destroyed_accounts.iter().copied())
+            .collect::<HashSet<_>>();
+        self.accounts.retain(|(hashed_address, _)| !accounts.contains(hashed_address));
+        self.destroyed_accounts.retain(|hashed_address| !accounts.contains(hashed_address));
+        self.accounts.extend(other.accounts);
+        self.destroyed_accounts.extend(other.destroyed_accounts);
+
+        for (hashed_address, hashed_storage) in other.storages {
+            match self.storages.entry(hashed_address) {
+                hash_map::Entry::Occupied(mut entry) => entry.get_mut().extend(hashed_storage),
+                hash_map::Entry::Vacant(entry) => {
+                    entry.insert(hashed_storage);
+                }
+            }
+        }
+        self.sorted = false;
+    }
+
     /// Returns all destroyed accounts.
     pub fn destroyed_accounts(&self) -> HashSet<B256> {
         self.destroyed_accounts.clone()
@@ -147,6 +269,16 @@ impl HashedPostState {
     /// The prefix sets contain the hashed account and storage keys that have been changed in the
     /// post state.
     pub fn construct_prefix_sets(&self) -> (PrefixSet, HashMap<B256, PrefixSet>) {
+        let (account_prefix_set, storage_prefix_sets) = self.construct_prefix_sets_mut();
+        (
+            account_prefix_set.freeze(),
+            storage_prefix_sets.into_iter().map(|(k, v)| (k, v.freeze())).collect(),
+        )
+    }
+
+    /// Construct mutable prefix sets from hashed post state.
+    /// See [HashedPostState::construct_prefix_sets] for more info.
+    pub fn construct_prefix_sets_mut(&self) -> (PrefixSetMut, HashMap<B256, PrefixSetMut>) {
         // Initialize prefix sets.
         let mut account_prefix_set = PrefixSetMut::default();
         let mut storage_prefix_set: HashMap<B256, PrefixSetMut> = HashMap::default();
@@ -172,10 +304,7 @@ impl HashedPostState {
             }
         }
 
-        (
-            account_prefix_set.freeze(),
-            storage_prefix_set.into_iter().map(|(k, v)| (k, v.freeze())).collect(),
-        )
+        (account_prefix_set, storage_prefix_set)
     }
 }
 
@@ -1006,6 +1135,92 @@ mod tests {
         assert_storage_cursor_order(&factory, expected);
     }
 
+    #[test]
+    fn revert_range_keeps_oldest_entries() {
+        use reth_db::models::AccountBeforeTx;
+        use reth_primitives::Address;
+
+        let address = Address::with_last_byte(1);
+        let created = Address::with_last_byte(2);
+        let slot = B256::with_last_byte(1);
+        let account_at = |nonce| Account { nonce, ..Default::default() };
+
+        let db = create_test_rw_db();
+        db.update(|tx| {
+            for (block, address, info) in [
+                (1, address, Some(account_at(1))),
+                (2, address, Some(account_at(2))),
+                (3, address, Some(account_at(3))),
+                (2, created, None),
+            ] {
+                tx.put::<tables::AccountChangeSet>(block, AccountBeforeTx { address, info })
+                    .unwrap();
+            }
+            for (block, value) in [(1, 0), (2, 2), (3, 3)] {
+                tx.put::<tables::StorageChangeSet>(
+                    BlockNumberAddress((block, address)),
+                    StorageEntry { key: slot, value: U256::from(value) },
+                )
+                .unwrap();
+            }
+        })
+        .unwrap();
+
+        let tx = db.tx().unwrap();
+        let hashed_address = keccak256(address);
+        let hashed_slot = keccak256(slot);
+
+        let revert_state = HashedPostState::from_revert_range(&tx, 2..=3).unwrap();
+        assert_eq!(
+            revert_state.accounts().collect::<BTreeMap<_, _>>(),
+            BTreeMap::from([(hashed_address, Some(account_at(2))), (keccak256(created), None)])
+        );
+        let storage = revert_state.storages.get(&hashed_address).unwrap();
+        assert_eq!(storage.storage_slots().collect::<Vec<_>>(), vec![(hashed_slot, U256::from(2))]);
+
+        let revert_state = HashedPostState::from_revert_range(&tx, 1..=3).unwrap();
+        let storage = revert_state.storages.get(&hashed_address).unwrap();
+        assert_eq!(storage.storage_slots().collect::<Vec<_>>(), vec![(hashed_slot, U256::ZERO)]);
+    }
+
+    #[test]
+    fn extend_prefers_newer_entries() {
+        let (address, destroyed) = (B256::with_last_byte(1), B256::with_last_byte(2));
+        let (slot, other_slot) = (B256::with_last_byte(1), B256::with_last_byte(2));
+        let account_at = |nonce| Account { nonce, ..Default::default() };
+
+        let mut hashed_state = HashedPostState::default();
+        hashed_state.insert_account(address, account_at(1));
+        hashed_state.insert_account(destroyed, account_at(1));
+        let mut storage = HashedStorage::new(false);
+        storage.insert_non_zero_valued_storage(slot, U256::from(1));
+        storage.insert_zero_valued_slot(other_slot);
+        hashed_state.insert_hashed_storage(address, storage.clone());
+        hashed_state.insert_hashed_storage(destroyed, storage);
+
+        let mut other = HashedPostState::default();
+        other.insert_account(address, account_at(2));
+        other.insert_destroyed_account(destroyed);
+        let mut storage = HashedStorage::new(false);
+        storage.insert_zero_valued_slot(slot);
+        other.insert_hashed_storage(address, storage);
+        other.insert_hashed_storage(destroyed, HashedStorage::new(true));
+
+        hashed_state.extend(other);
+        let hashed_state = hashed_state.sorted();
+        assert_eq!(
+            hashed_state.accounts().collect::<BTreeMap<_, _>>(),
+            BTreeMap::from([(address, Some(account_at(2))), (destroyed, None)])
+        );
+        assert_eq!(
+            hashed_state.storages[&address].storage_slots().collect::<BTreeMap<_, _>>(),
+            BTreeMap::from([(slot, U256::ZERO), (other_slot, U256::ZERO)])
+        );
+        let wiped = &hashed_state.storages[&destroyed];
+        assert!(wiped.wiped());
+        assert_eq!(wiped.storage_slots().count(), 0);
+    }
+
     #[test]
     fn fuzz_hashed_storage_cursor() {
         proptest!(ProptestConfig::with_cases(10),
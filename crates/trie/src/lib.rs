@@ -41,6 +41,10 @@ pub mod proof;
 mod trie;
 pub use trie::{StateRoot, StorageRoot};
 
+/// State root computation with storage roots computed in parallel.
+mod parallel;
+pub use parallel::ParallelStateRoot;
+
 /// Buffer for trie updates.
 pub mod updates;
 
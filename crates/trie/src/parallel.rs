@@ -0,0 +1,345 @@
+use crate::{
+    hashed_cursor::{HashedPostState, HashedPostStateCursorFactory},
+    prefix_set::{PrefixSet, PrefixSetLoader, PrefixSetMut},
+    updates::TrieUpdates,
+    StateRoot, StateRootError, StorageRoot,
+};
+use rayon::prelude::*;
+use reth_db::database::Database;
+use reth_primitives::{BlockNumber, B256};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::RangeInclusive,
+};
+use tracing::debug;
+
+/// ParallelStateRoot computes the root node of a state trie, computing the storage roots of the
+/// accounts with changed storage in parallel.
+///
+/// Every storage root is computed on a thread of the global [rayon] pool, each with its own
+/// read-only transaction, before the account trie is assembled on another read-only transaction.
+/// The result is identical to the one of [StateRoot], as long as all transactions observe the
+/// same state, i.e. the database must not be modified until the root is computed.
+#[derive(Debug)]
+pub struct ParallelStateRoot<'a, DB> {
+    /// The database to open the read-only transactions on.
+    db: &'a DB,
+    /// The hashed post state to overlay on top of the database.
+    hashed_state: Option<&'a HashedPostState>,
+    /// A set of account prefixes that have changed.
+    changed_account_prefixes: PrefixSet,
+    /// A map containing storage changes with the hashed address as key and a set of storage key
+    /// prefixes as the value.
+    changed_storage_prefixes: HashMap<B256, PrefixSet>,
+    /// A map containing keys of accounts that were destroyed.
+    destroyed_accounts: HashSet<B256>,
+}
+
+impl<'a, DB> ParallelStateRoot<'a, DB> {
+    /// Create a new [ParallelStateRoot] instance.
+    pub fn new(db: &'a DB) -> Self {
+        Self {
+            db,
+            hashed_state: None,
+            changed_account_prefixes: PrefixSetMut::default().freeze(),
+            changed_storage_prefixes: HashMap::default(),
+            destroyed_accounts: HashSet::default(),
+        }
+    }
+
+    /// Set the changed account prefixes.
+    pub fn with_changed_account_prefixes(mut self, prefixes: PrefixSet) -> Self {
+        self.changed_account_prefixes = prefixes;
+        self
+    }
+
+    /// Set the changed storage prefixes.
+    pub fn with_changed_storage_prefixes(mut self, prefixes: HashMap<B256, PrefixSet>) -> Self {
+        self.changed_storage_prefixes = prefixes;
+        self
+    }
+
+    /// Set the destroyed accounts.
+    pub fn with_destroyed_accounts(mut self, accounts: HashSet<B256>) -> Self {
+        self.destroyed_accounts = accounts;
+        self
+    }
+
+    /// Set the sorted hashed post state to overlay on top of the database, along with the prefix
+    /// sets and destroyed accounts derived from it.
+    pub fn with_hashed_state(self, hashed_state: &'a HashedPostState) -> Self {
+        let (account_prefix_set, storage_prefix_sets) = hashed_state.construct_prefix_sets();
+        Self { hashed_state: Some(hashed_state), ..self }
+            .with_changed_account_prefixes(account_prefix_set)
+            .with_changed_storage_prefixes(storage_prefix_sets)
+            .with_destroyed_accounts(hashed_state.destroyed_accounts())
+    }
+}
+
+impl<'a, DB: Database> ParallelStateRoot<'a, DB> {
+    /// Given a block number range, identifies all the accounts and storage keys that
+    /// have changed.
+    ///
+    /// # Returns
+    ///
+    /// An instance of parallel state root calculator with account and storage prefixes loaded.
+    pub fn incremental_root_calculator(
+        db: &'a DB,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Self, StateRootError> {
+        let loaded_prefix_sets = PrefixSetLoader::new(&db.tx()?).load(range)?;
+        Ok(Self::new(db)
+            .with_changed_account_prefixes(loaded_prefix_sets.account_prefix_set.freeze())
+            .with_changed_storage_prefixes(
+                loaded_prefix_sets
+                    .storage_prefix_sets
+                    .into_iter()
+                    .map(|(k, v)| (k, v.freeze()))
+                    .collect(),
+            )
+            .with_destroyed_accounts(loaded_prefix_sets.destroyed_accounts))
+    }
+
+    /// Computes the state root of the trie with the changed account and storage prefixes and
+    /// existing trie nodes collecting updates in the process.
+    ///
+    /// See [StateRoot::incremental_root_with_updates].
+    pub fn incremental_root_with_updates(
+        db: &'a DB,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<(B256, TrieUpdates), StateRootError> {
+        debug!(target: "trie::parallel", ?range, "incremental parallel state root");
+        Self::incremental_root_calculator(db, range)?.root_with_updates()
+    }
+
+    /// Computes the state root hash.
+    pub fn root(self) -> Result<B256, StateRootError> {
+        let (root, _) = self.calculate(false)?;
+        Ok(root)
+    }
+
+    /// Computes the state root hash and collects the trie updates.
+    pub fn root_with_updates(self) -> Result<(B256, TrieUpdates), StateRootError> {
+        self.calculate(true)
+    }
+
+    fn calculate(self, retain_updates: bool) -> Result<(B256, TrieUpdates), StateRootError> {
+        let empty_hashed_state = HashedPostState::default();
+        let hashed_state = self.hashed_state.unwrap_or(&empty_hashed_state);
+
+        debug!(
+            target: "trie::parallel",
+            accounts = self.changed_storage_prefixes.len(),
+            "computing storage roots"
+        );
+        let storage_roots = self
+            .changed_storage_prefixes
+            .par_iter()
+            .map_init(
+                || self.db.tx(),
+                |tx, (hashed_address, prefix_set)| -> Result<_, StateRootError> {
+                    let tx = tx.as_ref().map_err(Clone::clone)?;
+                    let storage_root = StorageRoot::new_hashed_with_factory(
+                        tx,
+                        HashedPostStateCursorFactory::new(tx, hashed_state),
+                        *hashed_address,
+                    )
+                    .with_changed_prefixes(prefix_set.clone())
+                    .calculate(retain_updates)?;
+                    Ok((*hashed_address, storage_root))
+                },
+            )
+            .collect::<Result<HashMap<_, _>, StateRootError>>()?;
+
+        let tx = self.db.tx()?;
+        let state_root = StateRoot::new(&tx)
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(&tx, hashed_state))
+            .with_changed_account_prefixes(self.changed_account_prefixes)
+            .with_changed_storage_prefixes(self.changed_storage_prefixes)
+            .with_destroyed_accounts(self.destroyed_accounts)
+            .with_storage_roots(storage_roots);
+
+        if retain_updates {
+            state_root.root_with_updates()
+        } else {
+            Ok((state_root.root()?, TrieUpdates::default()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hashed_cursor::HashedStorage,
+        test_utils::state_root_prehashed,
+        updates::{TrieKey, TrieOp},
+    };
+    use reth_db::{
+        cursor::{DbCursorRW, DbDupCursorRO, DbDupCursorRW},
+        tables,
+        transaction::DbTxMut,
+    };
+    use reth_primitives::{keccak256, trie::Nibbles, Account, StorageEntry, U256};
+    use reth_provider::test_utils::create_test_provider_factory;
+    use std::collections::BTreeMap;
+
+    /// Returns the updates sorted by key, so they can be compared.
+    fn sorted(updates: TrieUpdates) -> Vec<(TrieKey, TrieOp)> {
+        let mut updates = updates.into_iter().collect::<Vec<_>>();
+        updates.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        updates
+    }
+
+    #[test]
+    fn parallel_state_root_matches_state_root() {
+        let factory = create_test_provider_factory();
+
+        // 100 accounts, every other one with 50 storage slots
+        let mut state = (0..100u64)
+            .map(|i| {
+                let account = Account { nonce: i, balance: U256::from(i), bytecode_hash: None };
+                let storage = (0..if i % 2 == 0 { 50u64 } else { 0 })
+                    .map(|slot| (keccak256(B256::from(U256::from(slot))), U256::from(slot + 1)))
+                    .collect::<BTreeMap<_, _>>();
+                (keccak256(B256::from(U256::from(i))), (account, storage))
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        {
+            let provider_rw = factory.provider_rw().unwrap();
+            let tx = provider_rw.tx_ref();
+            for (hashed_address, (account, storage)) in &state {
+                tx.put::<tables::HashedAccount>(*hashed_address, *account).unwrap();
+                for (hashed_slot, value) in storage {
+                    tx.put::<tables::HashedStorage>(
+                        *hashed_address,
+                        StorageEntry { key: *hashed_slot, value: *value },
+                    )
+                    .unwrap();
+                }
+            }
+            let (root, updates) = StateRoot::new(tx).root_with_updates().unwrap();
+            assert_eq!(root, state_root_prehashed(state.clone().into_iter()));
+            updates.flush(tx).unwrap();
+            provider_rw.commit().unwrap();
+        }
+
+        // Change some storage slots, wipe the storage of one account and add storage to another
+        let mut account_prefix_set = PrefixSetMut::default();
+        let mut storage_prefix_sets = HashMap::<B256, PrefixSetMut>::default();
+        {
+            let provider_rw = factory.provider_rw().unwrap();
+            let mut cursor =
+                provider_rw.tx_ref().cursor_dup_write::<tables::HashedStorage>().unwrap();
+            for (idx, (hashed_address, (_, storage))) in state.iter_mut().enumerate() {
+                let changed_slots = match idx {
+                    0 => std::mem::take(storage).into_keys().collect::<Vec<_>>(),
+                    1 => {
+                        storage.insert(B256::with_last_byte(1), U256::from(1));
+                        vec![B256::with_last_byte(1)]
+                    }
+                    idx if idx % 4 == 2 => {
+                        let slot = *storage.keys().nth(idx % 50).unwrap();
+                        storage.insert(slot, U256::from(idx));
+                        vec![slot]
+                    }
+                    _ => continue,
+                };
+
+                account_prefix_set.insert(Nibbles::unpack(hashed_address));
+                let prefix_set = storage_prefix_sets.entry(*hashed_address).or_default();
+                for slot in changed_slots {
+                    prefix_set.insert(Nibbles::unpack(slot));
+                    if cursor.seek_by_key_subkey(*hashed_address, slot).unwrap().is_some() {
+                        cursor.delete_current().unwrap();
+                    }
+                    if let Some(value) = storage.get(&slot) {
+                        cursor
+                            .upsert(*hashed_address, StorageEntry { key: slot, value: *value })
+                            .unwrap();
+                    }
+                }
+            }
+            drop(cursor);
+            provider_rw.commit().unwrap();
+        }
+
+        let account_prefix_set = account_prefix_set.freeze();
+        let storage_prefix_sets = storage_prefix_sets
+            .into_iter()
+            .map(|(hashed_address, prefix_set)| (hashed_address, prefix_set.freeze()))
+            .collect::<HashMap<_, _>>();
+
+        let provider = factory.provider().unwrap();
+        let (expected_root, expected_updates) = StateRoot::new(provider.tx_ref())
+            .with_changed_account_prefixes(account_prefix_set.clone())
+            .with_changed_storage_prefixes(storage_prefix_sets.clone())
+            .root_with_updates()
+            .unwrap();
+        assert_eq!(expected_root, state_root_prehashed(state.into_iter()));
+
+        let (root, updates) = ParallelStateRoot::new(factory.db_ref())
+            .with_changed_account_prefixes(account_prefix_set)
+            .with_changed_storage_prefixes(storage_prefix_sets)
+            .root_with_updates()
+            .unwrap();
+        assert_eq!(root, expected_root);
+        assert_eq!(sorted(updates), sorted(expected_updates));
+    }
+
+    #[test]
+    fn parallel_state_root_with_hashed_state() {
+        let factory = create_test_provider_factory();
+
+        let hashed_address = B256::with_last_byte(1);
+        let account = Account { nonce: 1, balance: U256::from(1), bytecode_hash: None };
+        {
+            let provider_rw = factory.provider_rw().unwrap();
+            let tx = provider_rw.tx_ref();
+            tx.put::<tables::HashedAccount>(hashed_address, account).unwrap();
+            for slot in 0..10u8 {
+                tx.put::<tables::HashedStorage>(
+                    hashed_address,
+                    StorageEntry { key: B256::with_last_byte(slot), value: U256::from(slot + 1) },
+                )
+                .unwrap();
+            }
+            StateRoot::new(tx).root_with_updates().unwrap().1.flush(tx).unwrap();
+            provider_rw.commit().unwrap();
+        }
+
+        let mut hashed_state = HashedPostState::default();
+        let mut hashed_storage = HashedStorage::new(false);
+        hashed_storage.insert_non_zero_valued_storage(B256::with_last_byte(42), U256::from(42));
+        hashed_storage.insert_zero_valued_slot(B256::with_last_byte(0));
+        hashed_state.insert_hashed_storage(hashed_address, hashed_storage);
+        hashed_state.insert_account(B256::with_last_byte(2), account);
+        let hashed_state = hashed_state.sorted();
+
+        let provider = factory.provider().unwrap();
+        let (account_prefix_set, storage_prefix_sets) = hashed_state.construct_prefix_sets();
+        let (expected_root, expected_updates) = StateRoot::new(provider.tx_ref())
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(
+                provider.tx_ref(),
+                &hashed_state,
+            ))
+            .with_changed_account_prefixes(account_prefix_set)
+            .with_changed_storage_prefixes(storage_prefix_sets)
+            .root_with_updates()
+            .unwrap();
+
+        let (root, updates) = ParallelStateRoot::new(factory.db_ref())
+            .with_hashed_state(&hashed_state)
+            .root_with_updates()
+            .unwrap();
+        assert_eq!(root, expected_root);
+        assert_eq!(sorted(updates), sorted(expected_updates));
+
+        let root = ParallelStateRoot::new(factory.db_ref())
+            .with_hashed_state(&hashed_state)
+            .root()
+            .unwrap();
+        assert_eq!(root, expected_root);
+    }
+}
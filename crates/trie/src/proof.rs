@@ -12,26 +12,64 @@ use reth_primitives::{
     constants::EMPTY_ROOT_HASH,
     keccak256,
     trie::{AccountProof, HashBuilder, Nibbles, StorageProof, TrieAccount},
-    Address, B256,
+    Account, Address, Bytes, B256, U256,
 };
+use std::collections::{BTreeMap, HashMap};
 
 /// A struct for generating merkle proofs.
 ///
 /// Proof generator adds the target address and slots to the prefix set, enables the proof retainer
 /// on the hash builder and follows the same algorithm as the state root calculator.
 /// See `StateRoot::root` for more info.
+///
+/// Proofs against historical state can be generated by overlaying the reverted state on top of the
+/// database with a post state hashed cursor factory and marking the reverted keys as changed.
 #[derive(Debug)]
 pub struct Proof<'a, TX, H> {
     /// A reference to the database transaction.
     tx: &'a TX,
     /// The factory for hashed cursors.
     hashed_cursor_factory: H,
+    /// A set of account prefixes that have changed.
+    changed_account_prefixes: PrefixSetMut,
+    /// A map containing storage changes with the hashed address as key and a set of storage key
+    /// prefixes as the value.
+    changed_storage_prefixes: HashMap<B256, PrefixSetMut>,
 }
 
 impl<'a, TX> Proof<'a, TX, &'a TX> {
     /// Create a new [Proof] instance.
     pub fn new(tx: &'a TX) -> Self {
-        Self { tx, hashed_cursor_factory: tx }
+        Self {
+            tx,
+            hashed_cursor_factory: tx,
+            changed_account_prefixes: PrefixSetMut::default(),
+            changed_storage_prefixes: HashMap::default(),
+        }
+    }
+}
+
+impl<'a, TX, H> Proof<'a, TX, H> {
+    /// Set the hashed cursor factory.
+    pub fn with_hashed_cursor_factory<HF>(self, hashed_cursor_factory: HF) -> Proof<'a, TX, HF> {
+        Proof {
+            tx: self.tx,
+            hashed_cursor_factory,
+            changed_account_prefixes: self.changed_account_prefixes,
+            changed_storage_prefixes: self.changed_storage_prefixes,
+        }
+    }
+
+    /// Set the changed account prefixes.
+    pub fn with_changed_account_prefixes(mut self, prefixes: PrefixSetMut) -> Self {
+        self.changed_account_prefixes = prefixes;
+        self
+    }
+
+    /// Set the changed storage prefixes.
+    pub fn with_changed_storage_prefixes(mut self, prefixes: HashMap<B256, PrefixSetMut>) -> Self {
+        self.changed_storage_prefixes = prefixes;
+        self
     }
 }
 
@@ -47,20 +85,61 @@ where
         slots: &[B256],
     ) -> Result<AccountProof, StateRootError> {
         let target_hashed_address = keccak256(address);
-        let target_nibbles = Nibbles::unpack(target_hashed_address);
         let mut account_proof = AccountProof::new(address);
 
+        let proofs = self.account_trie_nodes_with(
+            Vec::from([Nibbles::unpack(target_hashed_address)]),
+            |hashed_address, account| {
+                if hashed_address == target_hashed_address {
+                    let (storage_root, storage_proofs) =
+                        self.storage_root_with_proofs(hashed_address, slots)?;
+                    account_proof.set_account(account, storage_root, storage_proofs);
+                    Ok(storage_root)
+                } else {
+                    self.storage_root(hashed_address)
+                }
+            },
+        )?;
+        account_proof.set_proof(proofs.into_values().collect());
+
+        Ok(account_proof)
+    }
+
+    /// Returns the nodes of the account trie on the paths to the given targets, keyed by their
+    /// path.
+    ///
+    /// The targets can be full hashed addresses, in which case the nodes prove the inclusion or
+    /// exclusion of the account, or partial paths, in which case the node at the exact path is
+    /// included if it exists.
+    pub fn account_trie_nodes(
+        &self,
+        targets: Vec<Nibbles>,
+    ) -> Result<BTreeMap<Nibbles, Bytes>, StateRootError> {
+        self.account_trie_nodes_with(targets, |hashed_address, _| self.storage_root(hashed_address))
+    }
+
+    /// Walks the account trie towards the given targets and retains all nodes on the way.
+    ///
+    /// The storage root of every account leaf that needs to be hashed is computed by
+    /// `storage_root`.
+    fn account_trie_nodes_with<F>(
+        &self,
+        targets: Vec<Nibbles>,
+        mut storage_root: F,
+    ) -> Result<BTreeMap<Nibbles, Bytes>, StateRootError>
+    where
+        F: FnMut(B256, Account) -> Result<B256, StorageRootError>,
+    {
         let hashed_account_cursor = self.hashed_cursor_factory.hashed_account_cursor()?;
         let trie_cursor = AccountTrieCursor::new(self.tx.cursor_read::<tables::AccountsTrie>()?);
 
         // Create the walker.
-        let mut prefix_set = PrefixSetMut::default();
-        prefix_set.insert(target_nibbles.clone());
+        let mut prefix_set = self.changed_account_prefixes.clone();
+        prefix_set.extend(targets.clone());
         let walker = TrieWalker::new(trie_cursor, prefix_set.freeze());
 
         // Create a hash builder to rebuild the root node since it is not available in the database.
-        let mut hash_builder =
-            HashBuilder::default().with_proof_retainer(Vec::from([target_nibbles.clone()]));
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(targets);
 
         let mut account_rlp = Vec::with_capacity(128);
         let mut account_node_iter = AccountNodeIter::new(walker, hashed_account_cursor);
@@ -70,14 +149,7 @@ where
                     hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
                 }
                 AccountNode::Leaf(hashed_address, account) => {
-                    let storage_root = if hashed_address == target_hashed_address {
-                        let (storage_root, storage_proofs) =
-                            self.storage_root_with_proofs(hashed_address, slots)?;
-                        account_proof.set_account(account, storage_root, storage_proofs);
-                        storage_root
-                    } else {
-                        self.storage_root(hashed_address)?
-                    };
+                    let storage_root = storage_root(hashed_address, account)?;
 
                     account_rlp.clear();
                     let account = TrieAccount::from((account, storage_root));
@@ -90,10 +162,7 @@ where
 
         let _ = hash_builder.root();
 
-        let proofs = hash_builder.take_proofs();
-        account_proof.set_proof(proofs.values().cloned().collect());
-
-        Ok(account_proof)
+        Ok(hash_builder.take_proofs())
     }
 
     /// Compute storage root.
@@ -108,24 +177,72 @@ where
         hashed_address: B256,
         slots: &[B256],
     ) -> Result<(B256, Vec<StorageProof>), StorageRootError> {
-        let mut hashed_storage_cursor = self.hashed_cursor_factory.hashed_storage_cursor()?;
-
         let mut proofs = slots.iter().copied().map(StorageProof::new).collect::<Vec<_>>();
+        let target_nibbles = proofs.iter().map(|p| p.nibbles.clone()).collect::<Vec<_>>();
+
+        let (root, all_proof_nodes) =
+            self.storage_trie_nodes_with(hashed_address, target_nibbles, |nibbles, value| {
+                if let Some(proof) = proofs.iter_mut().find(|proof| proof.nibbles == *nibbles) {
+                    proof.set_value(value);
+                }
+            })?;
+
+        for proof in proofs.iter_mut() {
+            // Iterate over all proof nodes and find the matching ones.
+            // The filtered results are guaranteed to be in order.
+            let matching_proof_nodes = all_proof_nodes
+                .iter()
+                .filter(|(path, _)| proof.nibbles.starts_with(path))
+                .map(|(_, node)| node.clone());
+            proof.set_proof(matching_proof_nodes.collect());
+        }
+
+        Ok((root, proofs))
+    }
+
+    /// Returns the nodes of the storage trie of the account on the paths to the given targets,
+    /// keyed by their path.
+    ///
+    /// See [Self::account_trie_nodes] for the supported targets.
+    pub fn storage_trie_nodes(
+        &self,
+        hashed_address: B256,
+        targets: Vec<Nibbles>,
+    ) -> Result<BTreeMap<Nibbles, Bytes>, StorageRootError> {
+        let (_, nodes) = self.storage_trie_nodes_with(hashed_address, targets, |_, _| {})?;
+        Ok(nodes)
+    }
+
+    /// Walks the storage trie of the account towards the given targets and retains all nodes on
+    /// the way.
+    ///
+    /// Every storage leaf that needs to be hashed is passed to `on_leaf`.
+    fn storage_trie_nodes_with<F>(
+        &self,
+        hashed_address: B256,
+        targets: Vec<Nibbles>,
+        mut on_leaf: F,
+    ) -> Result<(B256, BTreeMap<Nibbles, Bytes>), StorageRootError>
+    where
+        F: FnMut(&Nibbles, U256),
+    {
+        let mut hashed_storage_cursor = self.hashed_cursor_factory.hashed_storage_cursor()?;
 
         // short circuit on empty storage
         if hashed_storage_cursor.is_storage_empty(hashed_address)? {
-            return Ok((EMPTY_ROOT_HASH, proofs))
+            return Ok((EMPTY_ROOT_HASH, BTreeMap::new()))
         }
 
-        let target_nibbles = proofs.iter().map(|p| p.nibbles.clone()).collect::<Vec<_>>();
-        let prefix_set = PrefixSetMut::from(target_nibbles.clone()).freeze();
+        let mut prefix_set =
+            self.changed_storage_prefixes.get(&hashed_address).cloned().unwrap_or_default();
+        prefix_set.extend(targets.clone());
         let trie_cursor = StorageTrieCursor::new(
             self.tx.cursor_dup_read::<tables::StoragesTrie>()?,
             hashed_address,
         );
-        let walker = TrieWalker::new(trie_cursor, prefix_set);
+        let walker = TrieWalker::new(trie_cursor, prefix_set.freeze());
 
-        let mut hash_builder = HashBuilder::default().with_proof_retainer(target_nibbles);
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(targets);
         let mut storage_node_iter =
             StorageNodeIter::new(walker, hashed_storage_cursor, hashed_address);
         while let Some(node) = storage_node_iter.try_next()? {
@@ -135,9 +252,7 @@ where
                 }
                 StorageNode::Leaf(hashed_slot, value) => {
                     let nibbles = Nibbles::unpack(hashed_slot);
-                    if let Some(proof) = proofs.iter_mut().find(|proof| proof.nibbles == nibbles) {
-                        proof.set_value(value);
-                    }
+                    on_leaf(&nibbles, value);
                     hash_builder.add_leaf(nibbles, alloy_rlp::encode_fixed_size(&value).as_ref());
                 }
             }
@@ -145,18 +260,7 @@ where
 
         let root = hash_builder.root();
 
-        let all_proof_nodes = hash_builder.take_proofs();
-        for proof in proofs.iter_mut() {
-            // Iterate over all proof nodes and find the matching ones.
-            // The filtered results are guaranteed to be in order.
-            let matching_proof_nodes = all_proof_nodes
-                .iter()
-                .filter(|(path, _)| proof.nibbles.starts_with(path))
-                .map(|(_, node)| node.clone());
-            proof.set_proof(matching_proof_nodes.collect());
-        }
-
-        Ok((root, proofs))
+        Ok((root, hash_builder.take_proofs()))
     }
 }
 
@@ -231,6 +335,35 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn testspec_account_trie_nodes() {
+        // Create test database and insert genesis accounts.
+        let factory = create_test_provider_factory();
+        insert_genesis(&factory, TEST_SPEC.clone()).unwrap();
+
+        let root_node = convert_to_proof([
+            "0xe48200a7a040f916999be583c572cc4dd369ec53b0a99f7de95f13880cf203d98f935ed1b3",
+        ]);
+        let branch_node = convert_to_proof([
+            "0xf87180a04fb9bab4bb88c062f32452b7c94c8f64d07b5851d44a39f1e32ba4b1829fdbfb8080808080a0b61eeb2eb82808b73c4ad14140a2836689f4ab8445d69dd40554eaf1fce34bc080808080808080a0dea230ff2026e65de419288183a340125b04b8405cc61627b3b4137e2260a1e880",
+        ]);
+
+        let provider = factory.provider().unwrap();
+        let proof = Proof::new(provider.tx_ref());
+
+        let nodes = proof.account_trie_nodes(Vec::from([Nibbles::default()])).unwrap();
+        assert_eq!(nodes.into_values().collect::<Vec<_>>(), root_node);
+
+        let path = Nibbles::from_nibbles_unchecked([0xa, 0x7]);
+        let nodes = proof.account_trie_nodes(Vec::from([path.clone()])).unwrap();
+        assert_eq!(nodes.get(&path), branch_node.first());
+
+        // the proof of a missing account ends at the branch node
+        let missing = Nibbles::unpack(B256::with_last_byte(1));
+        let nodes = proof.account_trie_nodes(Vec::from([missing])).unwrap();
+        assert_eq!(nodes.into_values().collect::<Vec<_>>(), root_node);
+    }
+
     #[test]
     fn testspec_proofs() {
         // Create test database and insert genesis accounts.
@@ -36,6 +36,9 @@ pub struct StateRoot<'a, TX, H> {
     pub changed_storage_prefixes: HashMap<B256, PrefixSet>,
     /// A map containing keys of accounts that were destroyed.
     pub destroyed_accounts: HashSet<B256>,
+    /// Storage roots which were computed ahead of time, with the number of storage slots walked
+    /// and the storage trie updates.
+    storage_roots: HashMap<B256, (B256, usize, TrieUpdates)>,
     /// Previous intermediate state.
     previous_state: Option<IntermediateStateRootState>,
     /// The number of updates after which the intermediate progress should be returned.
@@ -79,6 +82,18 @@ impl<'a, TX, H> StateRoot<'a, TX, H> {
         self
     }
 
+    /// Set the storage roots which were computed ahead of time.
+    ///
+    /// They must have been computed with the same changed prefixes and hashed cursor factory,
+    /// retaining the updates if the updates of this state root are retained.
+    pub(crate) fn with_storage_roots(
+        mut self,
+        storage_roots: HashMap<B256, (B256, usize, TrieUpdates)>,
+    ) -> Self {
+        self.storage_roots = storage_roots;
+        self
+    }
+
     /// Set the hashed cursor factory.
     pub fn with_hashed_cursor_factory<HF>(
         self,
@@ -89,6 +104,7 @@ impl<'a, TX, H> StateRoot<'a, TX, H> {
             changed_account_prefixes: self.changed_account_prefixes,
             changed_storage_prefixes: self.changed_storage_prefixes,
             destroyed_accounts: self.destroyed_accounts,
+            storage_roots: self.storage_roots,
             threshold: self.threshold,
             previous_state: self.previous_state,
             hashed_cursor_factory,
@@ -104,6 +120,7 @@ impl<'a, TX: DbTx> StateRoot<'a, TX, &'a TX> {
             changed_account_prefixes: PrefixSetMut::default().freeze(),
             changed_storage_prefixes: HashMap::default(),
             destroyed_accounts: HashSet::default(),
+            storage_roots: HashMap::default(),
             previous_state: None,
             threshold: 100_000,
             hashed_cursor_factory: tx,
@@ -250,6 +267,7 @@ where
         account_node_iter.walker.set_updates(retain_updates);
         hash_builder.set_updates(retain_updates);
 
+        let mut storage_roots = self.storage_roots;
         let mut account_rlp = Vec::with_capacity(128);
         let mut hashed_entries_walked = 0;
         while let Some(node) = account_node_iter.try_next()? {
@@ -267,24 +285,24 @@ where
                     // progress.
                     // TODO: We can consider introducing the TrieProgress::Progress/Complete
                     // abstraction inside StorageRoot, but let's give it a try as-is for now.
-                    let storage_root_calculator = StorageRoot::new_hashed(self.tx, hashed_address)
-                        .with_hashed_cursor_factory(self.hashed_cursor_factory.clone())
-                        .with_changed_prefixes(
-                            self.changed_storage_prefixes
-                                .get(&hashed_address)
-                                .cloned()
-                                .unwrap_or_default(),
-                        );
-
-                    let storage_root = if retain_updates {
-                        let (root, storage_slots_walked, updates) =
-                            storage_root_calculator.root_with_updates()?;
+                    let (storage_root, storage_slots_walked, updates) =
+                        match storage_roots.remove(&hashed_address) {
+                            Some(storage_root) => storage_root,
+                            None => StorageRoot::new_hashed(self.tx, hashed_address)
+                                .with_hashed_cursor_factory(self.hashed_cursor_factory.clone())
+                                .with_changed_prefixes(
+                                    self.changed_storage_prefixes
+                                        .get(&hashed_address)
+                                        .cloned()
+                                        .unwrap_or_default(),
+                                )
+                                .calculate(retain_updates)?,
+                        };
+
+                    if retain_updates {
                         hashed_entries_walked += storage_slots_walked;
                         trie_updates.extend(updates.into_iter());
-                        root
-                    } else {
-                        storage_root_calculator.root()?
-                    };
+                    }
 
                     let account = TrieAccount::from((account, storage_root));
 
@@ -428,7 +446,7 @@ where
         Ok(root)
     }
 
-    fn calculate(
+    pub(crate) fn calculate(
         &self,
         retain_updates: bool,
     ) -> Result<(B256, usize, TrieUpdates), StorageRootError> {